@@ -0,0 +1,131 @@
+//! On-disk cache of API responses that carried an `ETag`, so repeated list
+//! calls can send `If-None-Match` and skip re-downloading unchanged pages.
+
+use std::fs;
+use std::path::PathBuf;
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::storage::cache_dir;
+
+/// A cached response body and the `ETag` it was served with.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub etag: String,
+    pub body: String,
+    /// Epoch millis when the entry was stored; 0 for entries written before
+    /// this was recorded.
+    #[serde(default)]
+    pub stored_at: i64,
+}
+
+/// Hash of the API token, mixed into every cache key so switching accounts
+/// never serves another account's data.
+pub fn token_scope(token: Option<&str>) -> String {
+    format!("{:x}", Sha256::digest(token.unwrap_or_default()))
+}
+
+/// `token_scope`, narrowed to the org requests are pinned to (see
+/// `HttpOptions::org`), since one token can see several orgs' data.
+pub fn request_scope(token: Option<&str>, org: Option<&str>) -> String {
+    org.map_or_else(
+        || token_scope(token),
+        |org| format!("{}:{org}", token_scope(token)),
+    )
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    dir: PathBuf,
+    scope: String,
+}
+
+impl HttpCache {
+    /// Cache under the user cache directory, or `None` if there isn't one.
+    pub fn new(scope: &str) -> Option<Self> {
+        let dir = cache_dir().ok()?.join("http");
+        Some(Self {
+            dir,
+            scope: scope.to_string(),
+        })
+    }
+
+    fn entry_path(&self, url: &Url) -> PathBuf {
+        let key = Sha256::digest(format!("{}\0{url}", self.scope));
+        self.dir.join(format!("{key:x}.json"))
+    }
+
+    /// The cached response for `url`, if any. Unreadable entries count as
+    /// misses.
+    pub fn get(&self, url: &Url) -> Option<CachedResponse> {
+        let contents = fs::read_to_string(self.entry_path(url)).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    /// Store `body` for `url`. Best effort: a cache that can't be written
+    /// just means the next call downloads the page again.
+    pub fn put(&self, url: &Url, etag: &str, body: &[u8]) {
+        let Ok(body) = String::from_utf8(body.to_vec()) else {
+            return;
+        };
+        let entry = CachedResponse {
+            etag: etag.to_string(),
+            body,
+            stored_at: chrono::Utc::now().timestamp_millis(),
+        };
+        let Ok(json) = serde_json::to_string(&entry) else {
+            return;
+        };
+        if fs::create_dir_all(&self.dir).is_ok() {
+            let _ = fs::write(self.entry_path(url), json);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    fn temp_cache(token: &str) -> HttpCache {
+        HttpCache {
+            dir: env::temp_dir().join(format!("detail-cli-http-cache-{}", process::id())),
+            scope: token_scope(Some(token)),
+        }
+    }
+
+    #[test]
+    fn put_then_get_round_trips() {
+        let cache = temp_cache("dtl_a");
+        let url = Url::parse("https://api.detail.dev/public/v1/repos?limit=100&offset=0").unwrap();
+        cache.put(&url, "\"abc\"", br#"{"repos":[],"total":0}"#);
+        let cached = cache.get(&url).unwrap();
+        assert_eq!(cached.etag, "\"abc\"");
+        assert_eq!(cached.body, r#"{"repos":[],"total":0}"#);
+        assert!(cached.stored_at > 0);
+        let _ = fs::remove_dir_all(&cache.dir);
+    }
+
+    #[test]
+    fn entries_are_scoped_to_the_token() {
+        let url = Url::parse("https://api.detail.dev/public/v1/repos").unwrap();
+        assert_ne!(
+            temp_cache("dtl_a").entry_path(&url),
+            temp_cache("dtl_b").entry_path(&url)
+        );
+    }
+
+    #[test]
+    fn pinned_org_narrows_the_scope() {
+        let scope = request_scope(Some("dtl_a"), None);
+        assert_eq!(scope, token_scope(Some("dtl_a")));
+        assert_ne!(request_scope(Some("dtl_a"), Some("org_1")), scope);
+        assert_ne!(
+            request_scope(Some("dtl_a"), Some("org_1")),
+            request_scope(Some("dtl_a"), Some("org_2"))
+        );
+    }
+}
@@ -1,21 +1,113 @@
-use std::fmt::Debug;
+use std::error;
+use std::fmt::{self, Debug};
+use std::fs;
+use std::future::{self, Future};
 use std::num::NonZeroU64;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::{Context, Result};
-use reqwest::header::{HeaderMap, AUTHORIZATION};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, ACCEPT, AUTHORIZATION, ETAG, IF_NONE_MATCH, RETRY_AFTER};
+use reqwest::{Certificate, NoProxy, Proxy, StatusCode, Url};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, sleep_until, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
 
 use progenitor::progenitor_client::{Error as ProgenitorError, ResponseValue};
 
-use super::generated::types::CreateRuleBody;
+use super::cache::{request_scope, HttpCache};
+use super::generated::types::{ApiError, CreateRuleBody};
+use super::signing::{RequestSigner, SigningKey};
+use super::trace;
+use crate::config::storage::{self, Config};
+use crate::daemon;
+use crate::exit::Failure;
+use crate::utils::concurrency;
+use crate::utils::pagination::fetch_all_pages;
+
 use super::types::{
     Bug, BugDismissalReason, BugId, BugReview, BugReviewState, BugsResponse,
     CreatePublicBugReviewBody, CreateRuleInput, CreateRuleResponse,
-    ListPublicBugsWorkflowRequestId, RepoId, ReposResponse, Rule, RuleCreationRequestId, RuleId,
-    RuleRequestStatus, RuleRequestsResponse, RulesResponse, ScansResponse, UserInfo,
+    ListPublicBugsWorkflowRequestId, Repo, RepoId, ReposResponse, Rule, RuleCreationRequestId,
+    RuleId, RuleRequestStatus, RuleRequestsResponse, RulesResponse, ScansResponse, UserInfo,
 };
 
+/// Error for requests that never got an answer from the API (DNS failure,
+/// refused connection, timeout), as opposed to the API rejecting them.
+#[derive(Debug)]
+pub struct NetworkError(String);
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl error::Error for NetworkError {}
+
+/// Whether `err` was caused by the network rather than by the API.
+pub fn is_network_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<NetworkError>())
+}
+
+/// Error for an HTTP error response, keeping the status so callers can
+/// decide whether a retry is worthwhile.
+#[derive(Debug)]
+pub struct StatusError {
+    status: StatusCode,
+    message: String,
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl error::Error for StatusError {}
+
+/// Whether `err` might succeed on retry: a network failure, rate limiting,
+/// or a server-side (5xx) error.
+pub fn is_transient_error(err: &anyhow::Error) -> bool {
+    is_network_error(err)
+        || err.chain().any(|cause| {
+            cause.downcast_ref::<StatusError>().is_some_and(|e| {
+                e.status == StatusCode::TOO_MANY_REQUESTS || e.status.is_server_error()
+            })
+        })
+}
+
+/// Whether the API rejected the request's token (`401 Unauthorized`), i.e.
+/// the token has expired or been revoked.
+pub fn is_unauthorized(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<StatusError>()
+            .is_some_and(|e| e.status == StatusCode::UNAUTHORIZED)
+    })
+}
+
+/// Whether `err` (or anything in its chain) is an API `403 Forbidden`.
+pub fn is_forbidden(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<StatusError>()
+            .is_some_and(|e| e.status == StatusCode::FORBIDDEN)
+    })
+}
+
+/// The HTTP status of the API error response behind `err`, if any.
+pub fn status(err: &anyhow::Error) -> Option<u16> {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<StatusError>())
+        .map(|e| e.status.as_u16())
+}
+
 /// Convert a progenitor client error into a concise anyhow error.
 ///
 /// progenitor's own `Display` for `ErrorResponse` dumps headers and the typed
@@ -42,37 +134,380 @@ fn api_error<E: Debug + Serialize>(e: ProgenitorError<E>) -> anyhow::Error {
             .and_then(serde_json::Value::as_str)
             .filter(|m| !m.is_empty())
             .map(str::to_owned);
-        return msg.map_or_else(
-            || anyhow::anyhow!("{head}"),
-            |m| anyhow::anyhow!("{head}: {m}"),
-        );
+        let message = msg.map_or_else(|| head.clone(), |m| format!("{head}: {m}"));
+        return status_error(status, message);
     }
     if let Some(status) = e.status() {
-        return anyhow::anyhow!(
+        let message = format!(
             "API error: {} {}",
             status.as_u16(),
             status.canonical_reason().unwrap_or("HTTP error"),
         );
+        return status_error(status, message);
+    }
+    if matches!(e, ProgenitorError::CommunicationError(_)) {
+        return anyhow::Error::new(NetworkError(format!("API error: {e}")));
     }
     anyhow::anyhow!("API error: {e}")
 }
 
-fn base_http_client() -> reqwest::ClientBuilder {
-    reqwest::Client::builder()
+/// A 404 maps to the "not found" exit code; other statuses stay API errors.
+fn status_error(status: StatusCode, message: String) -> anyhow::Error {
+    if status == StatusCode::NOT_FOUND {
+        anyhow::Error::new(Failure::not_found(message))
+    } else {
+        anyhow::Error::new(StatusError { status, message })
+    }
+}
+
+/// Times a request is retried after `429 Too Many Requests` before the
+/// error is returned.
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Longest `Retry-After` worth waiting out; a longer one fails right away.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Wait used when a 429 has no usable `Retry-After` header.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Parse a `Retry-After` value: either delay-seconds or an HTTP date.
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    // A date in the past means "retry now".
+    Some(
+        at.with_timezone(&Utc)
+            .signed_duration_since(now)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// How long to wait before retrying a request that failed with `err`, or
+/// `None` if it wasn't rate limited (or the API asked for too long a wait).
+fn rate_limit_wait<E>(err: &ProgenitorError<E>) -> Option<Duration> {
+    let (status, headers) = if let ProgenitorError::ErrorResponse(rv) = err {
+        (rv.status(), rv.headers())
+    } else if let ProgenitorError::UnexpectedResponse(response) = err {
+        (response.status(), response.headers())
+    } else {
+        return None;
+    };
+    if status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+    let wait = headers
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| parse_retry_after(v, Utc::now()))
+        .unwrap_or(DEFAULT_RETRY_AFTER);
+    (wait <= MAX_RETRY_AFTER).then_some(wait)
+}
+
+/// Why a call was abandoned before the API answered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupted {
+    /// The call's `CancellationToken` was cancelled.
+    Cancelled,
+    /// The call's deadline passed.
+    DeadlineExceeded,
+}
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cancelled => f.write_str("Request cancelled"),
+            Self::DeadlineExceeded => f.write_str("Request deadline exceeded"),
+        }
+    }
+}
+
+impl error::Error for Interrupted {}
+
+/// Per-call controls for embedders (TUIs, servers), applied with
+/// `ApiClient::with_request_options`. The client-wide timeout from
+/// `HttpOptions` still bounds each individual HTTP request.
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Give up on the call, retries included, at this instant.
+    pub deadline: Option<Instant>,
+    /// Abandon the call, including any in-flight request, once this is
+    /// cancelled.
+    pub cancel: Option<CancellationToken>,
+    /// Retries after `429 Too Many Requests`, instead of
+    /// `MAX_RATE_LIMIT_RETRIES`.
+    pub max_retries: Option<u32>,
+}
+
+impl RequestOptions {
+    /// Run `call` to completion unless the deadline passes or the token is
+    /// cancelled first, in which case it is dropped and an `Interrupted`
+    /// error returned.
+    async fn run<T>(&self, call: impl Future<Output = Result<T>>) -> Result<T> {
+        let cancelled = async {
+            match &self.cancel {
+                Some(token) => token.cancelled().await,
+                None => future::pending().await,
+            }
+        };
+        let deadline = async {
+            match self.deadline {
+                Some(deadline) => sleep_until(deadline).await,
+                None => future::pending().await,
+            }
+        };
+        tokio::select! {
+            result = call => result,
+            () = cancelled => Err(Interrupted::Cancelled.into()),
+            () = deadline => Err(Interrupted::DeadlineExceeded.into()),
+        }
+    }
+}
+
+/// Send a request built by `request`, sleeping through `429 Too Many
+/// Requests` responses as their `Retry-After` asks, up to
+/// `MAX_RATE_LIMIT_RETRIES` times (or `options.max_retries`).
+async fn send<T, E, F, Fut>(options: &RequestOptions, mut request: F) -> Result<ResponseValue<T>>
+where
+    E: Debug + Serialize,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<ResponseValue<T>, ProgenitorError<E>>>,
+{
+    let max_retries = options.max_retries.unwrap_or(MAX_RATE_LIMIT_RETRIES);
+    options
+        .run(async {
+            let mut retries = 0;
+            loop {
+                let permit = concurrency::acquire().await;
+                let result = request().await;
+                drop(permit);
+                let err = match result {
+                    Ok(response) => return Ok(response),
+                    Err(err) => err,
+                };
+                let Some(wait) = rate_limit_wait(&err).filter(|_| retries < max_retries) else {
+                    return Err(api_error(err));
+                };
+                retries += 1;
+                warn!("rate limited; retrying in {} ms", wait.as_millis());
+                sleep(wait).await;
+            }
+        })
+        .await
+}
+
+/// Rate-limit headers reported by the API, shown by `detail limits`.
+#[derive(Debug, Default, Serialize)]
+pub struct RateLimits {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// As sent by the API: usually seconds until the window resets.
+    pub reset: Option<String>,
+}
+
+impl RateLimits {
+    /// Read `X-RateLimit-*` headers, or the unprefixed `RateLimit-*` ones
+    /// from the IETF draft.
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            [format!("x-ratelimit-{name}"), format!("ratelimit-{name}")]
+                .iter()
+                .find_map(|key| headers.get(key.as_str()))
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.trim().to_string())
+        };
+        Self {
+            limit: header("limit").and_then(|v| v.parse().ok()),
+            remaining: header("remaining").and_then(|v| v.parse().ok()),
+            reset: header("reset"),
+        }
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.limit.is_none() && self.remaining.is_none() && self.reset.is_none()
+    }
+}
+
+/// Request timeout when neither `--timeout` nor `timeout_secs` is set.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Network settings applied to every HTTP client the CLI builds.
+#[derive(Debug, Clone, Default)]
+pub struct HttpOptions {
+    /// Proxy for every request, taking precedence over `HTTPS_PROXY` and
+    /// `HTTP_PROXY`. `NO_PROXY` still applies.
+    pub proxy: Option<String>,
+    /// PEM bundle of extra root certificates to trust, e.g. a corporate
+    /// TLS-intercepting proxy's CA.
+    pub ca_cert: Option<PathBuf>,
+    /// Skip TLS certificate verification entirely (`--insecure`).
+    pub insecure: bool,
+    /// Limit on a whole request, from connecting to reading the body.
+    /// Defaults to `DEFAULT_TIMEOUT`.
+    pub timeout: Option<Duration>,
+    /// Limit on establishing the connection alone. Unlimited by default
+    /// (the overall timeout still applies).
+    pub connect_timeout: Option<Duration>,
+    /// Organization ID sent as `X-Detail-Org` on every request, for
+    /// enterprise deployments that route by org.
+    pub org: Option<String>,
+    /// Gateway key ID API requests are signed with; the secret is read from
+    /// the OS keyring. See `signing`.
+    pub signing_key_id: Option<String>,
+}
+
+/// Header enterprise deployments route requests by.
+pub const ORG_HEADER: &str = "x-detail-org";
+
+impl HttpOptions {
+    /// Options from the config file. `--insecure` is deliberately not a
+    /// config key, so verification can't be switched off for good.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            proxy: config.proxy.clone(),
+            ca_cert: config.ca_cert.clone(),
+            insecure: false,
+            timeout: config.timeout_secs.map(Duration::from_secs),
+            connect_timeout: config.connect_timeout_secs.map(Duration::from_secs),
+            org: config.org.clone(),
+            signing_key_id: config.signing_key_id.clone(),
+        }
+    }
+}
+
+/// Without a configured proxy, reqwest picks one up from `HTTPS_PROXY`,
+/// `HTTP_PROXY` and `NO_PROXY`.
+fn base_http_client(options: &HttpOptions) -> Result<reqwest::ClientBuilder> {
+    let mut builder = reqwest::Client::builder()
         .user_agent(format!("detail-cli/{}", env!("CARGO_PKG_VERSION")))
-        .timeout(Duration::from_secs(30))
+        .timeout(options.timeout.unwrap_or(DEFAULT_TIMEOUT));
+    if let Some(connect_timeout) = options.connect_timeout {
+        builder = builder.connect_timeout(connect_timeout);
+    }
+    if let Some(proxy) = &options.proxy {
+        let proxy = Proxy::all(proxy)
+            .with_context(|| format!("Invalid proxy URL '{proxy}'"))?
+            .no_proxy(NoProxy::from_env());
+        builder = builder.proxy(proxy);
+    }
+    if let Some(path) = &options.ca_cert {
+        let pem = fs::read(path)
+            .with_context(|| format!("Failed to read CA certificate {}", path.display()))?;
+        let certs = Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("Invalid PEM in CA certificate {}", path.display()))?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    if options.insecure {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    if let Some(org) = &options.org {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ORG_HEADER,
+            org.parse()
+                .with_context(|| format!("Invalid organization ID '{org}'"))?,
+        );
+        builder = builder.default_headers(headers);
+    }
+    Ok(builder)
 }
 
+/// Build the review body submitted by `ApiClient::update_bug_close`, so
+/// previews such as `bugs close --dry-run` show exactly what would be sent.
+pub fn bug_review_body(
+    state: BugReviewState,
+    dismissal_reason: Option<BugDismissalReason>,
+    notes: Option<&str>,
+) -> CreatePublicBugReviewBody {
+    CreatePublicBugReviewBody {
+        state,
+        dismissal_reason,
+        notes: notes.map(String::from),
+    }
+}
+
+/// Query parameters for one page of a repository's bugs in `status`,
+/// optionally only those found by scan `scan_id`.
+fn bugs_query(
+    repo_id: &RepoId,
+    status: BugReviewState,
+    limit: u32,
+    offset: u32,
+    scan_id: Option<&ListPublicBugsWorkflowRequestId>,
+) -> Vec<(&'static str, String)> {
+    let mut query = vec![
+        ("repo_id", repo_id.to_string()),
+        ("status", status.to_string()),
+        ("limit", limit.to_string()),
+        ("offset", offset.to_string()),
+    ];
+    if let Some(scan_id) = scan_id {
+        query.push(("workflow_request_id", scan_id.to_string()));
+    }
+    query
+}
+
+/// How `ApiClient::list_all_bugs` and `list_all_repos` walk pages, set with
+/// `ApiClient::with_pagination`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    /// Results requested per page; the API returns at most 100.
+    pub page_size: u32,
+    /// Most results a walk fetches. A larger total is an error rather than
+    /// thousands of requests.
+    pub max_items: usize,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self {
+            page_size: 100,
+            max_items: 100_000,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct ApiClient {
     inner: super::generated::Client,
+    /// The same HTTP client and base URL the generated client uses, for
+    /// the conditional requests it can't express.
+    http: reqwest::Client,
+    /// Signs those requests as the generated client's hook signs its own.
+    signer: RequestSigner,
+    base_url: String,
+    cache: Option<HttpCache>,
+    /// Identifies the token and pinned org for on-disk caches; see
+    /// `request_scope`.
+    cache_scope: String,
+    /// Bypass on-disk caches (`--refresh`).
+    refresh: bool,
+    /// Ask a running `detail daemon` before calling the API.
+    use_daemon: bool,
+    /// Set once a call through this client (or a clone) changed data. Reads
+    /// after that skip the daemon, whose copies may predate the change.
+    wrote: Arc<AtomicBool>,
+    request_options: RequestOptions,
+    pagination: Pagination,
 }
 
 impl ApiClient {
-    pub fn new(base_url: Option<String>, token: Option<String>) -> Result<Self> {
+    pub fn new(
+        base_url: Option<String>,
+        token: Option<String>,
+        options: &HttpOptions,
+    ) -> Result<Self> {
         let base_url = base_url.unwrap_or_else(|| "https://api.detail.dev".into());
 
-        let mut builder = base_http_client();
+        let mut builder = base_http_client(options)?;
 
+        let cache_scope = request_scope(token.as_deref(), options.org.as_deref());
+        let cache = HttpCache::new(&cache_scope);
         if let Some(token) = token {
             let mut headers = HeaderMap::new();
             headers.insert(
@@ -84,18 +519,235 @@ impl ApiClient {
             builder = builder.default_headers(headers);
         }
 
+        let signer = RequestSigner::new(
+            options
+                .signing_key_id
+                .as_deref()
+                .map(|key_id| {
+                    anyhow::Ok(SigningKey {
+                        key_id: key_id.to_string(),
+                        secret: storage::signing_secret(key_id)?,
+                    })
+                })
+                .transpose()?,
+        );
+
         let reqwest_client = builder.build()?;
-        let inner = super::generated::Client::new_with_client(&base_url, reqwest_client);
+        let inner = super::generated::Client::new_with_client(
+            &base_url,
+            reqwest_client.clone(),
+            signer.clone(),
+        );
+
+        Ok(Self {
+            inner,
+            http: reqwest_client,
+            signer,
+            base_url,
+            cache,
+            cache_scope,
+            refresh: false,
+            use_daemon: true,
+            wrote: Arc::new(AtomicBool::new(false)),
+            request_options: RequestOptions::default(),
+            pagination: Pagination::default(),
+        })
+    }
+
+    /// Always call the API, never a running daemon. The daemon's own client
+    /// uses this so it doesn't ask itself.
+    pub const fn without_daemon(mut self) -> Self {
+        self.use_daemon = false;
+        self
+    }
+
+    /// Skip on-disk caches and always fetch fresh data.
+    pub const fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// A copy of this client whose calls follow `options`, e.g. to cancel
+    /// them when the user navigates away.
+    pub fn with_request_options(&self, options: RequestOptions) -> Self {
+        Self {
+            request_options: options,
+            ..self.clone()
+        }
+    }
+
+    /// Walk pages as `pagination` says.
+    pub const fn with_pagination(mut self, pagination: Pagination) -> Self {
+        self.pagination = pagination;
+        self
+    }
+
+    pub const fn pagination(&self) -> Pagination {
+        self.pagination
+    }
+
+    /// Whether callers should bypass their own caches too.
+    pub const fn refresh(&self) -> bool {
+        self.refresh
+    }
+
+    pub fn cache_scope(&self) -> &str {
+        &self.cache_scope
+    }
+
+    /// Whether reads should ask a running daemon first.
+    fn asks_daemon(&self) -> bool {
+        self.use_daemon && !self.refresh && !self.wrote.load(Ordering::Acquire)
+    }
+
+    /// Call after every request that changes data, whether or not it
+    /// succeeded: a failed one may still have been applied. Later reads
+    /// here skip the daemon, and the daemon drops what it holds so other
+    /// processes don't read the old state either.
+    async fn after_write(&self) {
+        self.wrote.store(true, Ordering::Release);
+        if self.use_daemon {
+            daemon::invalidate().await;
+        }
+    }
+
+    /// Whether `url` points at this client's API, so its token may be used
+    /// to fetch it.
+    pub fn serves(&self, url: &str) -> bool {
+        url.strip_prefix(&self.base_url)
+            .is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    /// The URL `list_bugs` fetches for these arguments.
+    pub fn bugs_url(
+        &self,
+        repo_id: &RepoId,
+        status: BugReviewState,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Url> {
+        let query = bugs_query(repo_id, status, limit, offset, None);
+        Ok(Url::parse_with_params(
+            &format!("{}/public/v1/bugs", self.base_url),
+            &query,
+        )?)
+    }
+
+    /// GET `url` and return the body of a successful response as-is.
+    pub async fn get_text(&self, url: &str) -> Result<String> {
+        let mut request = self
+            .http
+            .get(url)
+            .header(ACCEPT, "application/json")
+            .build()
+            .with_context(|| format!("Invalid URL {url}"))?;
+        self.signer.sign(&mut request)?;
+        self.http
+            .execute(request)
+            .await
+            .with_context(|| format!("Failed to reach {url}"))?
+            .error_for_status()
+            .with_context(|| format!("{url} rejected the request"))?
+            .text()
+            .await
+            .with_context(|| format!("Failed to read the response from {url}"))
+    }
+
+    /// GET `path` like a generated call would, but send `If-None-Match` for
+    /// a cached response and reuse its body on `304 Not Modified`. Results
+    /// and errors take the generated shape so `send` can wrap it.
+    async fn get_conditional<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<ResponseValue<T>, ProgenitorError<ApiError>> {
+        let url = Url::parse_with_params(&format!("{}{path}", self.base_url), query)
+            .map_err(|e| ProgenitorError::InvalidRequest(e.to_string()))?;
+        if self.asks_daemon() {
+            if let Some(body) = daemon::get(&self.cache_scope, url.as_str()).await {
+                if let Ok(inner) = serde_json::from_str(&body) {
+                    return Ok(ResponseValue::new(inner, StatusCode::OK, HeaderMap::new()));
+                }
+            }
+        }
+        let cached = self
+            .cache
+            .as_ref()
+            .filter(|_| !self.refresh)
+            .and_then(|cache| cache.get(&url));
+
+        let mut request = self
+            .http
+            .get(url.clone())
+            .header(ACCEPT, "application/json");
+        if let Some(cached) = &cached {
+            request = request.header(IF_NONE_MATCH, &cached.etag);
+        }
+        let mut request = request
+            .build()
+            .map_err(ProgenitorError::CommunicationError)?;
+        trace::on_request(&self.signer, &request);
+        self.signer
+            .sign(&mut request)
+            .map_err(|e| ProgenitorError::PreHookError(e.to_string()))?;
+        let result = self.http.execute(request).await;
+        trace::on_response(&self.signer, &result);
+        let response = result.map_err(ProgenitorError::CommunicationError)?;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        if self.cache.is_some() {
+            trace::cache_lookup(
+                &url,
+                cached.as_ref(),
+                status == StatusCode::NOT_MODIFIED,
+                self.refresh,
+            );
+        }
+        if status == StatusCode::NOT_MODIFIED {
+            let Some(cached) = cached else {
+                return Err(ProgenitorError::UnexpectedResponse(response));
+            };
+            return serde_json::from_str(&cached.body)
+                .map(|inner| ResponseValue::new(inner, status, headers))
+                .map_err(|e| ProgenitorError::InvalidResponsePayload(cached.body.into(), e));
+        }
+        if !(status.is_success() || status.is_client_error() || status.is_server_error()) {
+            return Err(ProgenitorError::UnexpectedResponse(response));
+        }
 
-        Ok(Self { inner })
+        let body = response
+            .bytes()
+            .await
+            .map_err(ProgenitorError::ResponseBodyError)?;
+        if !status.is_success() {
+            let error = serde_json::from_slice(&body)
+                .map_err(|e| ProgenitorError::InvalidResponsePayload(body.clone(), e))?;
+            return Err(ProgenitorError::ErrorResponse(ResponseValue::new(
+                error, status, headers,
+            )));
+        }
+        let inner = serde_json::from_slice(&body)
+            .map_err(|e| ProgenitorError::InvalidResponsePayload(body.clone(), e))?;
+        if let (Some(cache), Some(etag)) = (&self.cache, headers.get(ETAG)) {
+            if let Ok(etag) = etag.to_str() {
+                cache.put(&url, etag, &body);
+            }
+        }
+        Ok(ResponseValue::new(inner, status, headers))
     }
 
     pub async fn get_current_user(&self) -> Result<UserInfo> {
-        self.inner
-            .get_public_user()
+        send(&self.request_options, || self.inner.get_public_user())
             .await
             .map(ResponseValue::into_inner)
-            .map_err(api_error)
+    }
+
+    /// Rate-limit quota for the current token, read from the headers of a
+    /// cheap authenticated request.
+    pub async fn rate_limits(&self) -> Result<RateLimits> {
+        let response = send(&self.request_options, || self.inner.get_public_user()).await?;
+        Ok(RateLimits::from_headers(response.headers()))
     }
 
     pub async fn list_bugs(
@@ -106,25 +758,53 @@ impl ApiClient {
         offset: u32,
         scan_id: Option<&ListPublicBugsWorkflowRequestId>,
     ) -> Result<BugsResponse> {
-        self.inner
-            .list_public_bugs(
-                NonZeroU64::new(limit.into()),
-                Some(offset.into()),
-                repo_id,
-                status,
-                scan_id,
-            )
-            .await
-            .map(ResponseValue::into_inner)
-            .map_err(api_error)
+        let query = bugs_query(repo_id, status, limit, offset, scan_id);
+        send(&self.request_options, || {
+            self.get_conditional("/public/v1/bugs", &query)
+        })
+        .await
+        .map(ResponseValue::into_inner)
+    }
+
+    /// Every bug in `repo_id` with `status`, fetched page by page.
+    pub async fn list_all_bugs(
+        &self,
+        repo_id: &RepoId,
+        status: BugReviewState,
+    ) -> Result<Vec<Bug>> {
+        self.list_all_bugs_with(repo_id, status, None, |_| {}).await
+    }
+
+    /// Like `list_all_bugs`, only from scan `scan_id` when given, calling
+    /// `on_page` with the length of each page as it arrives.
+    pub async fn list_all_bugs_with(
+        &self,
+        repo_id: &RepoId,
+        status: BugReviewState,
+        scan_id: Option<&ListPublicBugsWorkflowRequestId>,
+        on_page: impl Fn(usize),
+    ) -> Result<Vec<Bug>> {
+        let Pagination {
+            page_size,
+            max_items,
+        } = self.pagination;
+        let on_page = &on_page;
+        fetch_all_pages(max_items, |offset| async move {
+            let response = self
+                .list_bugs(repo_id, status, page_size, offset, scan_id)
+                .await?;
+            on_page(response.bugs.len());
+            let total = usize::try_from(response.total.max(0)).unwrap_or(0);
+            Ok((response.bugs, total))
+        })
+        .await
+        .context("Failed to fetch bugs from repository")
     }
 
     pub async fn get_bug(&self, bug_id: &BugId) -> Result<Bug> {
-        self.inner
-            .get_public_bug(bug_id)
+        send(&self.request_options, || self.inner.get_public_bug(bug_id))
             .await
             .map(ResponseValue::into_inner)
-            .map_err(api_error)
     }
 
     pub async fn update_bug_close(
@@ -134,17 +814,15 @@ impl ApiClient {
         dismissal_reason: Option<BugDismissalReason>,
         notes: Option<&str>,
     ) -> Result<BugReview> {
-        let body = CreatePublicBugReviewBody {
-            state,
-            dismissal_reason,
-            notes: notes.map(String::from),
-        };
+        let body = bug_review_body(state, dismissal_reason, notes);
 
-        self.inner
-            .create_public_bug_review(bug_id, &body)
-            .await
-            .map(ResponseValue::into_inner)
-            .map_err(api_error)
+        let result = send(&self.request_options, || {
+            self.inner.create_public_bug_review(bug_id, &body)
+        })
+        .await
+        .map(ResponseValue::into_inner);
+        self.after_write().await;
+        result
     }
 
     pub async fn list_scans(
@@ -153,19 +831,51 @@ impl ApiClient {
         limit: u32,
         offset: u32,
     ) -> Result<ScansResponse> {
-        self.inner
-            .list_public_scans(NonZeroU64::new(limit.into()), Some(offset.into()), repo_id)
-            .await
-            .map(ResponseValue::into_inner)
-            .map_err(api_error)
+        send(&self.request_options, || {
+            self.inner.list_public_scans(
+                NonZeroU64::new(limit.into()),
+                Some(offset.into()),
+                repo_id,
+            )
+        })
+        .await
+        .map(ResponseValue::into_inner)
     }
 
     pub async fn list_repos(&self, limit: u32, offset: u32) -> Result<ReposResponse> {
-        self.inner
-            .list_public_repos(NonZeroU64::new(limit.into()), Some(offset.into()))
-            .await
-            .map(ResponseValue::into_inner)
-            .map_err(api_error)
+        let query = [("limit", limit.to_string()), ("offset", offset.to_string())];
+        send(&self.request_options, || {
+            self.get_conditional("/public/v1/repos", &query)
+        })
+        .await
+        .map(ResponseValue::into_inner)
+    }
+
+    /// Every repository the token can see, fetched page by page.
+    pub async fn list_all_repos(&self) -> Result<Vec<Repo>> {
+        self.list_all_repos_from(None).await
+    }
+
+    /// Like `list_all_repos`, reusing `first` as the first page when it was
+    /// already fetched.
+    pub async fn list_all_repos_from(&self, first: Option<ReposResponse>) -> Result<Vec<Repo>> {
+        let Pagination {
+            page_size,
+            max_items,
+        } = self.pagination;
+        let mut first = first;
+        fetch_all_pages(max_items, |offset| {
+            let fetched = if offset == 0 { first.take() } else { None };
+            async move {
+                let repos = match fetched {
+                    Some(repos) => repos,
+                    None => self.list_repos(page_size, offset).await?,
+                };
+                let total = usize::try_from(repos.total.max(0)).unwrap_or(0);
+                Ok((repos.repos, total))
+            }
+        })
+        .await
     }
 
     pub async fn create_rule(
@@ -177,59 +887,60 @@ impl ApiClient {
             repo_id: repo_id.clone(),
             input,
         };
-        self.inner
-            .create_rule(&body)
+        let result = send(&self.request_options, || self.inner.create_rule(&body))
             .await
-            .map(ResponseValue::into_inner)
-            .map_err(api_error)
+            .map(ResponseValue::into_inner);
+        self.after_write().await;
+        result
     }
 
     pub async fn list_rules(&self, repo_id: &RepoId) -> Result<RulesResponse> {
-        self.inner
-            .list_rules(repo_id)
+        send(&self.request_options, || self.inner.list_rules(repo_id))
             .await
             .map(ResponseValue::into_inner)
-            .map_err(api_error)
     }
 
     pub async fn get_rule(&self, rule_id: &RuleId) -> Result<Rule> {
-        self.inner
-            .get_rule(rule_id)
+        send(&self.request_options, || self.inner.get_rule(rule_id))
             .await
             .map(ResponseValue::into_inner)
-            .map_err(api_error)
     }
 
     pub async fn get_rule_request(
         &self,
         rcr_id: &RuleCreationRequestId,
     ) -> Result<RuleRequestStatus> {
-        self.inner
-            .get_rule_request(rcr_id)
-            .await
-            .map(ResponseValue::into_inner)
-            .map_err(api_error)
+        send(&self.request_options, || {
+            self.inner.get_rule_request(rcr_id)
+        })
+        .await
+        .map(ResponseValue::into_inner)
     }
 
     pub async fn list_rule_requests(&self, repo_id: &RepoId) -> Result<RuleRequestsResponse> {
-        self.inner
-            .list_rule_requests(repo_id)
-            .await
-            .map(ResponseValue::into_inner)
-            .map_err(api_error)
+        send(&self.request_options, || {
+            self.inner.list_rule_requests(repo_id)
+        })
+        .await
+        .map(ResponseValue::into_inner)
     }
 }
 
 /// Exchange a PKCE auth code for an API token.
 /// This is a free function rather than an `ApiClient` method because it runs
 /// before any token exists (the code/verifier pair is the proof of identity)
-pub async fn pkce_token_exchange(api_url: &str, code: &str, code_verifier: &str) -> Result<String> {
+pub async fn pkce_token_exchange(
+    api_url: &str,
+    code: &str,
+    code_verifier: &str,
+    options: &HttpOptions,
+) -> Result<String> {
     #[derive(Deserialize)]
     struct TokenResponse {
         token: String,
     }
 
-    let client = base_http_client().build()?;
+    let client = base_http_client(options)?.build()?;
 
     let resp = client
         .post(format!("{api_url}/api/v1/cli-auth/token"))
@@ -246,25 +957,246 @@ pub async fn pkce_token_exchange(api_url: &str, code: &str, code_verifier: &str)
     Ok(resp.token)
 }
 
+/// POST `body` as JSON to a URL outside the API, such as an alert webhook.
+pub async fn post_json<T: Serialize + ?Sized>(
+    url: &str,
+    body: &T,
+    options: &HttpOptions,
+) -> Result<()> {
+    let client = base_http_client(options)?.build()?;
+    client
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {url}"))?
+        .error_for_status()
+        .with_context(|| format!("{url} rejected the request"))?;
+    Ok(())
+}
+
+/// POST `body` as JSON to another service's API with `authorization` as the
+/// `Authorization` header, and decode its JSON response. Rejections include
+/// the response body, where such APIs say which field was wrong.
+pub async fn post_json_authorized<T: Serialize + ?Sized, R: DeserializeOwned>(
+    url: &str,
+    body: &T,
+    authorization: &str,
+    options: &HttpOptions,
+) -> Result<R> {
+    let client = base_http_client(options)?.build()?;
+    let resp = client
+        .post(url)
+        .header(AUTHORIZATION, authorization)
+        .header(ACCEPT, "application/json")
+        .json(body)
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {url}"))?;
+    let status = resp.status();
+    if !status.is_success() {
+        let text = resp.text().await.unwrap_or_default();
+        bail!("{url} rejected the request ({status}): {}", text.trim());
+    }
+    resp.json()
+        .await
+        .with_context(|| format!("Unexpected response from {url}"))
+}
+
+/// Download a release asset, such as a checksum file, or release metadata
+/// from outside the API.
+/// `None` when the asset doesn't exist.
+pub async fn download_release_asset(url: &str, options: &HttpOptions) -> Result<Option<Vec<u8>>> {
+    let client = base_http_client(options)?.build()?;
+    let resp = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to download {url}"))?;
+    if resp.status() == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let body = resp
+        .error_for_status()
+        .with_context(|| format!("Failed to download {url}"))?
+        .bytes()
+        .await
+        .with_context(|| format!("Failed to download {url}"))?;
+    Ok(Some(body.to_vec()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::exit;
+
+    /// A request that never gets an answer.
+    async fn hang() -> Result<ResponseValue<()>, ProgenitorError<ApiError>> {
+        future::pending().await
+    }
+
+    #[tokio::test]
+    async fn cancelled_call_is_interrupted() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let options = RequestOptions {
+            cancel: Some(token),
+            ..RequestOptions::default()
+        };
+        let err = send(&options, hang).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Interrupted>(),
+            Some(&Interrupted::Cancelled)
+        );
+    }
+
+    #[tokio::test]
+    async fn call_past_its_deadline_is_interrupted() {
+        let options = RequestOptions {
+            deadline: Some(Instant::now()),
+            ..RequestOptions::default()
+        };
+        let err = send(&options, hang).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Interrupted>(),
+            Some(&Interrupted::DeadlineExceeded)
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_after_a_close_skip_the_daemon() {
+        // Nothing listens on the discard port, so the close itself fails;
+        // it may still have reached the server, so the daemon is skipped
+        // all the same, by every clone of the client.
+        let client = ApiClient::new(
+            Some("http://127.0.0.1:9".into()),
+            None,
+            &HttpOptions::default(),
+        )
+        .unwrap();
+        let listing = client.with_request_options(RequestOptions::default());
+        assert!(listing.asks_daemon());
+        let bug_id = BugId::try_from("bug_1").unwrap();
+        let closed = client
+            .update_bug_close(&bug_id, BugReviewState::Resolved, None, None)
+            .await;
+        assert!(closed.is_err());
+        assert!(!client.asks_daemon());
+        assert!(!listing.asks_daemon());
+    }
+
+    #[test]
+    fn not_found_status_maps_to_not_found_exit_code() {
+        let err = status_error(StatusCode::NOT_FOUND, "API error: 404 Not Found".into());
+        assert_eq!(exit::code(&err), exit::NOT_FOUND);
+        assert_eq!(err.to_string(), "API error: 404 Not Found");
+        let err = status_error(
+            StatusCode::UNAUTHORIZED,
+            "API error: 401 Unauthorized".into(),
+        );
+        assert_eq!(exit::code(&err), exit::API_ERROR);
+    }
+
+    #[test]
+    fn network_errors_are_detected_through_context() {
+        let err = anyhow::Error::new(NetworkError("API error: timed out".into()))
+            .context("Failed to close bug");
+        assert!(is_network_error(&err));
+        assert!(!is_network_error(&anyhow::anyhow!(
+            "API error: 404 Not Found"
+        )));
+    }
+
+    #[test]
+    fn transient_errors_are_network_rate_limit_and_server_errors() {
+        let transient = [
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::BAD_GATEWAY,
+        ];
+        for status in transient {
+            let err = status_error(status, format!("API error: {status}")).context("outer");
+            assert!(is_transient_error(&err), "{status} should be transient");
+        }
+        for status in [StatusCode::BAD_REQUEST, StatusCode::NOT_FOUND] {
+            let err = status_error(status, format!("API error: {status}"));
+            assert!(
+                !is_transient_error(&err),
+                "{status} should not be transient"
+            );
+        }
+        let err = anyhow::Error::new(NetworkError("API error: timed out".into()));
+        assert!(is_transient_error(&err));
+    }
+
+    #[test]
+    fn unauthorized_is_detected_through_context() {
+        let err = status_error(
+            StatusCode::UNAUTHORIZED,
+            "API error: 401 Unauthorized".into(),
+        )
+        .context("Failed to list bugs");
+        assert!(is_unauthorized(&err));
+        let err = status_error(StatusCode::FORBIDDEN, "API error: 403 Forbidden".into());
+        assert!(!is_unauthorized(&err));
+        assert!(is_forbidden(&err));
+        assert_eq!(status(&err), Some(403));
+        assert_eq!(status(&anyhow::anyhow!("API error: timed out")), None);
+    }
+
+    #[test]
+    fn retry_after_accepts_seconds_and_http_dates() {
+        let now = DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_retry_after("5", now), Some(Duration::from_secs(5)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn rate_limits_read_prefixed_and_draft_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit", "1000".parse().unwrap());
+        headers.insert("ratelimit-remaining", "998".parse().unwrap());
+        headers.insert("x-ratelimit-reset", "42".parse().unwrap());
+        let limits = RateLimits::from_headers(&headers);
+        assert_eq!(limits.limit, Some(1000));
+        assert_eq!(limits.remaining, Some(998));
+        assert_eq!(limits.reset.as_deref(), Some("42"));
+        assert!(RateLimits::from_headers(&HeaderMap::new()).is_empty());
+    }
 
     #[test]
     fn new_without_base_url_succeeds() {
-        let client = ApiClient::new(None, None);
+        let client = ApiClient::new(None, None, &HttpOptions::default());
         assert!(client.is_ok());
     }
 
     #[test]
     fn new_with_custom_base_url_succeeds() {
-        let client = ApiClient::new(Some("https://custom.api.dev".into()), None);
+        let client = ApiClient::new(
+            Some("https://custom.api.dev".into()),
+            None,
+            &HttpOptions::default(),
+        );
         assert!(client.is_ok());
     }
 
     #[test]
     fn new_with_token_succeeds() {
-        let client = ApiClient::new(None, Some("dtl_live_test_token".into()));
+        let client = ApiClient::new(
+            None,
+            Some("dtl_live_test_token".into()),
+            &HttpOptions::default(),
+        );
         assert!(client.is_ok());
     }
 
@@ -273,7 +1205,66 @@ mod tests {
         let client = ApiClient::new(
             Some("https://custom.api.dev".into()),
             Some("dtl_live_test_token".into()),
+            &HttpOptions::default(),
         );
         assert!(client.is_ok());
     }
+
+    #[test]
+    fn new_with_proxy_succeeds() {
+        let options = HttpOptions {
+            proxy: Some("http://proxy.corp.example:3128".into()),
+            ..HttpOptions::default()
+        };
+        assert!(ApiClient::new(None, None, &options).is_ok());
+    }
+
+    #[test]
+    fn pinned_org_gets_its_own_cache_scope() {
+        let token = Some("dtl_live_test_token".to_string());
+        let unpinned = ApiClient::new(None, token.clone(), &HttpOptions::default()).unwrap();
+        let options = HttpOptions {
+            org: Some("org_123".into()),
+            ..HttpOptions::default()
+        };
+        let pinned = ApiClient::new(None, token, &options).unwrap();
+        assert_ne!(pinned.cache_scope(), unpinned.cache_scope());
+    }
+
+    #[test]
+    fn new_with_invalid_org_fails() {
+        let options = HttpOptions {
+            org: Some("org\n123".into()),
+            ..HttpOptions::default()
+        };
+        assert!(ApiClient::new(None, None, &options).is_err());
+    }
+
+    #[test]
+    fn new_with_invalid_proxy_fails() {
+        let options = HttpOptions {
+            proxy: Some("not a url".into()),
+            ..HttpOptions::default()
+        };
+        assert!(ApiClient::new(None, None, &options).is_err());
+    }
+
+    #[test]
+    fn new_with_missing_ca_cert_fails() {
+        let options = HttpOptions {
+            ca_cert: Some(PathBuf::from("/nonexistent/detail-ca.pem")),
+            ..HttpOptions::default()
+        };
+        let err = ApiClient::new(None, None, &options).err().unwrap();
+        assert!(format!("{err:#}").contains("Failed to read CA certificate"));
+    }
+
+    #[test]
+    fn new_with_insecure_succeeds() {
+        let options = HttpOptions {
+            insecure: true,
+            ..HttpOptions::default()
+        };
+        assert!(ApiClient::new(None, None, &options).is_ok());
+    }
 }
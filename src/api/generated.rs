@@ -1 +1,8 @@
-progenitor::generate_api!("openapi.json");
+progenitor::generate_api!(
+    spec = "openapi.json",
+    inner_type = crate::api::signing::RequestSigner,
+    pre_hook = crate::api::trace::on_request,
+    pre_hook_async = crate::api::signing::on_request,
+    post_hook = crate::api::trace::on_response,
+    derives = [schemars::JsonSchema],
+);
@@ -1,4 +1,7 @@
+pub mod cache;
 pub mod client;
 #[allow(clippy::all, dead_code, reason = "auto-generated API client code")]
 mod generated;
+pub mod signing;
+pub mod trace;
 pub mod types;
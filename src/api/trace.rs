@@ -0,0 +1,277 @@
+//! HTTP tracing for `--verbose`, `--log-file` and `--events-file`, wired
+//! into the generated client as progenitor pre/post hooks.
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::{Method, Request, Response, Url};
+use tracing::{debug, enabled, error, info, trace, warn, Level};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt;
+use tracing_subscriber::prelude::*;
+
+use super::cache::CachedResponse;
+use super::signing::RequestSigner;
+use crate::{events, exit};
+
+/// Response header carrying the API version that served the request.
+const API_VERSION_HEADER: &str = "x-api-version";
+
+/// Target of the structured events written to `--log-file`. Deliberately
+/// outside the crate's own target so `-v` doesn't print them twice.
+const AUDIT_TARGET: &str = "detail::audit";
+
+/// Requests sent but not yet answered, so the post hook can report timing.
+static IN_FLIGHT: Mutex<Vec<(Method, Url, Instant)>> = Mutex::new(Vec::new());
+
+/// Requests sent during this command, for the summary at `-v`.
+static REQUESTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Of those, the ones answered `304 Not Modified` and served from the cache.
+static FROM_CACHE: AtomicUsize = AtomicUsize::new(0);
+
+/// Install the stderr logger for `-v` (requests), `-vv` (plus request
+/// headers), or `-vvv` (plus response headers), and the JSON logger for
+/// `log_file`. With neither, nothing is installed and the hooks' events go
+/// nowhere.
+pub fn init(verbosity: u8, log_file: Option<&Path>) -> Result<()> {
+    let level = match verbosity {
+        0 => None,
+        1 => Some(Level::INFO),
+        2 => Some(Level::DEBUG),
+        _ => Some(Level::TRACE),
+    };
+    if level.is_none() && log_file.is_none() {
+        return Ok(());
+    }
+
+    let stderr = level.map(|level| {
+        fmt::layer()
+            .with_writer(io::stderr)
+            .with_target(false)
+            .without_time()
+            // Only our own events: reqwest/hyper internals would drown them out.
+            .with_filter(Targets::new().with_target(env!("CARGO_CRATE_NAME"), level))
+    });
+    let audit = log_file
+        .map(|path| {
+            let file = File::options()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open log file {}", path.display()))?;
+            anyhow::Ok(
+                fmt::layer()
+                    .json()
+                    .with_writer(Mutex::new(file))
+                    .with_filter(Targets::new().with_target(AUDIT_TARGET, Level::INFO)),
+            )
+        })
+        .transpose()?;
+
+    let _ = tracing_subscriber::registry()
+        .with(stderr)
+        .with(audit)
+        .try_init();
+    Ok(())
+}
+
+/// Requests sent during this command, and how many of them were served
+/// from the response cache.
+pub fn request_counts() -> (usize, usize) {
+    (
+        REQUESTS.load(Ordering::Relaxed),
+        FROM_CACHE.load(Ordering::Relaxed),
+    )
+}
+
+/// Record how a command ended in the `--log-file` log.
+pub fn command_finished(command: &str, started: Instant, result: &Result<()>) {
+    let duration_ms = started.elapsed().as_millis();
+    let (requests, from_cache) = request_counts();
+    if requests > 0 {
+        info!("{requests} requests, {from_cache} from cache");
+    }
+    match result {
+        Ok(()) => info!(
+            target: AUDIT_TARGET,
+            command,
+            exit_code = exit::SUCCESS,
+            duration_ms,
+            requests,
+            from_cache,
+            "command succeeded"
+        ),
+        Err(err) => error!(
+            target: AUDIT_TARGET,
+            command,
+            exit_code = exit::code(err),
+            duration_ms,
+            requests,
+            from_cache,
+            error = format!("{err:#}"),
+            "command failed"
+        ),
+    }
+}
+
+/// Render an age in milliseconds as e.g. `45s`, `3m 12s` or `2h 5m`.
+fn format_age(ms: i64) -> String {
+    let secs = ms.max(0) / 1000;
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// Describe a response-cache lookup for `url`: a miss, a hit (the server
+/// answered `304 Not Modified`), or a stale entry the server replaced.
+pub fn cache_lookup(url: &Url, cached: Option<&CachedResponse>, not_modified: bool, refresh: bool) {
+    if not_modified {
+        FROM_CACHE.fetch_add(1, Ordering::Relaxed);
+    }
+    match cached {
+        None if refresh => info!("cache bypassed (--refresh): {url}"),
+        None => info!("cache miss: {url}"),
+        Some(cached) => {
+            let outcome = if not_modified { "hit" } else { "stale" };
+            let age = if cached.stored_at > 0 {
+                format_age(chrono::Utc::now().timestamp_millis() - cached.stored_at)
+            } else {
+                "unknown".to_string()
+            };
+            info!("cache {outcome}: {url} (ETag {}, age {age})", cached.etag);
+        }
+    }
+}
+
+/// Render a header value for logs, hiding credentials.
+fn display_header(name: &HeaderName, value: &HeaderValue) -> String {
+    if name == AUTHORIZATION {
+        return "[redacted]".to_string();
+    }
+    value.to_str().unwrap_or("<binary>").to_string()
+}
+
+pub fn on_request(_signer: &RequestSigner, request: &Request) {
+    REQUESTS.fetch_add(1, Ordering::Relaxed);
+    if !enabled!(Level::INFO) && !enabled!(target: AUDIT_TARGET, Level::INFO) && !events::enabled()
+    {
+        return;
+    }
+    debug!("→ {} {}", request.method(), request.url());
+    for (name, value) in request.headers() {
+        debug!("  {name}: {}", display_header(name, value));
+    }
+    IN_FLIGHT
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push((
+            request.method().clone(),
+            request.url().clone(),
+            Instant::now(),
+        ));
+}
+
+/// Take the oldest in-flight request for `url`.
+fn finish(url: &Url) -> Option<(Method, Instant)> {
+    let mut in_flight = IN_FLIGHT.lock().unwrap_or_else(PoisonError::into_inner);
+    let index = in_flight.iter().position(|(_, u, _)| u == url)?;
+    let (method, _, started) = in_flight.remove(index);
+    Some((method, started))
+}
+
+pub fn on_response(_signer: &RequestSigner, result: &Result<Response, reqwest::Error>) {
+    if !enabled!(Level::INFO) && !enabled!(target: AUDIT_TARGET, Level::INFO) && !events::enabled()
+    {
+        return;
+    }
+    match result {
+        Ok(response) => {
+            let (method, started) = finish(response.url())
+                .map_or_else(|| (String::new(), None), |(m, s)| (m.to_string(), Some(s)));
+            let elapsed_ms = started.map(|s| s.elapsed().as_millis());
+            events::emit(&events::Event::ApiCall {
+                method: &method,
+                url: response.url().as_str(),
+                status: response.status().as_u16(),
+                elapsed_ms: started.map(events::elapsed_ms),
+            });
+            info!(
+                target: AUDIT_TARGET,
+                method,
+                url = %response.url(),
+                status = response.status().as_u16(),
+                elapsed_ms,
+                "api call"
+            );
+            let elapsed = elapsed_ms.map_or_else(String::new, |ms| format!(" ({ms} ms)"));
+            let api_version = response
+                .headers()
+                .get(API_VERSION_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .map_or_else(String::new, |v| format!(" [{API_VERSION_HEADER}: {v}]"));
+            info!(
+                "{method} {} → {}{elapsed}{api_version}",
+                response.url(),
+                response.status()
+            );
+            for (name, value) in response.headers() {
+                trace!("  {name}: {}", display_header(name, value));
+            }
+        }
+        Err(err) => {
+            let started = err.url().and_then(finish);
+            let elapsed_ms = started.as_ref().map(|(_, s)| s.elapsed().as_millis());
+            events::emit(&events::Event::ApiError {
+                method: started.as_ref().map(|(m, _)| m.as_str()),
+                url: err.url().map(Url::as_str),
+                elapsed_ms: started.as_ref().map(|&(_, s)| events::elapsed_ms(s)),
+                error: err.to_string(),
+            });
+            warn!(
+                target: AUDIT_TARGET,
+                method = started.as_ref().map(|(m, _)| m.to_string()),
+                url = err.url().map(ToString::to_string),
+                elapsed_ms,
+                error = %err,
+                "api call failed"
+            );
+            let elapsed = elapsed_ms.map_or_else(String::new, |ms| format!(" after {ms} ms"));
+            warn!("request failed{elapsed}: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authorization_header_is_redacted() {
+        let value = HeaderValue::from_static("Bearer dtl_live_secret");
+        assert_eq!(display_header(&AUTHORIZATION, &value), "[redacted]");
+    }
+
+    #[test]
+    fn ages_use_the_two_largest_units() {
+        assert_eq!(format_age(45_900), "45s");
+        assert_eq!(format_age(192_000), "3m 12s");
+        assert_eq!(format_age(7_500_000), "2h 5m");
+        assert_eq!(format_age(-5), "0s");
+    }
+
+    #[test]
+    fn other_headers_are_shown() {
+        let name = HeaderName::from_static(API_VERSION_HEADER);
+        let value = HeaderValue::from_static("2025-01-01");
+        assert_eq!(display_header(&name, &value), "2025-01-01");
+    }
+}
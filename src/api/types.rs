@@ -1,7 +1,7 @@
 use clap::builder::PossibleValue;
 
 use crate::output::Formattable;
-use crate::utils::datetime::{format_date, format_datetime};
+use crate::utils::datetime::{format_date, format_datetime, format_relative_or};
 
 // Re-export generated types as the public API for this crate.
 pub use super::generated::types::{
@@ -147,7 +147,7 @@ impl Formattable for Bug {
     fn to_card(&self) -> (String, Vec<(&'static str, String)>) {
         let mut pairs = vec![
             ("Bug ID", self.id.to_string()),
-            ("Created", format_date(self.created_at)),
+            ("Created", format_relative_or(self.created_at, format_date)),
         ];
         // Each remaining field is conditional so non-applicable bugs don't
         // get a forest of "-" rows. Triage threads regularly need file path
@@ -222,7 +222,10 @@ impl Formattable for Scan {
                 .clone()
                 .unwrap_or_else(|| "-".to_string()),
         ));
-        pairs.push(("Created", format_datetime(self.created_at)));
+        pairs.push((
+            "Created",
+            format_relative_or(self.created_at, format_datetime),
+        ));
         (header, pairs)
     }
 }
@@ -241,6 +244,7 @@ mod tests {
     use clap::ValueEnum;
 
     use super::*;
+    use crate::test_support::test_bug;
 
     // ── Display helpers ──────────────────────────────────────────────
 
@@ -311,15 +315,13 @@ mod tests {
     // ── Formattable ──────────────────────────────────────────────────
 
     fn sample_bug() -> Bug {
-        serde_json::from_value(serde_json::json!({
+        test_bug(serde_json::json!({
             "id": "bug_abc123",
             "title": "Null pointer in handler",
             "summary": "Crash when input is empty",
             "createdAt": 1_736_899_200_000_i64,
-            "repoId": "repo_xyz",
-            "linkedIssues": []
+            "repoId": "repo_xyz"
         }))
-        .expect("valid Bug JSON")
     }
 
     fn sample_repo() -> Repo {
@@ -0,0 +1,153 @@
+//! A library API for Rust tools that build on Detail without shelling out
+//! to `detail`. It logs in the way the CLI does and walks paginated results
+//! as streams:
+//!
+//! ```no_run
+//! use detail_cli::client::Detail;
+//! use futures_util::TryStreamExt as _;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let detail = Detail::from_config()?;
+//! let mut bugs = detail.bugs("usedetail/cli").pending().stream();
+//! while let Some(bug) = bugs.try_next().await? {
+//!     println!("{} {}", bug.id, bug.title);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::{Context, Result};
+use futures_util::{stream, Stream, TryStreamExt as _};
+
+use crate::api::client::{ApiClient, Pagination};
+use crate::api::types::{Bug, BugId, BugReviewState, Repo};
+use crate::utils::pagination::paged;
+use crate::utils::repos::resolve_repo_id;
+
+/// The most results the API returns per page.
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// An authenticated handle on the Detail API.
+pub struct Detail {
+    client: ApiClient,
+}
+
+impl Detail {
+    /// Log in as `detail` would: with `DETAIL_API_TOKEN`, or the login
+    /// saved by `detail auth login` (`DETAIL_PROFILE` picks a profile).
+    pub fn from_config() -> Result<Self> {
+        Ok(Self::new(crate::create_client()?))
+    }
+
+    /// Use `client`, e.g. one set up `with_pagination` for a different
+    /// page size or cap.
+    pub const fn new(client: ApiClient) -> Self {
+        Self { client }
+    }
+
+    /// The underlying client, for calls this API doesn't wrap.
+    pub const fn api(&self) -> &ApiClient {
+        &self.client
+    }
+
+    /// One bug, by ID.
+    pub async fn bug(&self, bug_id: &str) -> Result<Bug> {
+        let id: BugId = bug_id
+            .try_into()
+            .context("Invalid bug ID format (expected bug_...)")?;
+        self.client.get_bug(&id).await
+    }
+
+    /// Bugs in `repo`: owner/repo (e.g. usedetail/cli), repo (e.g. cli), or
+    /// an `@name` shortcut for a single repo. Pending bugs unless the
+    /// query says otherwise.
+    pub fn bugs(&self, repo: impl Into<String>) -> BugQuery<'_> {
+        BugQuery {
+            client: &self.client,
+            repo: repo.into(),
+            status: BugReviewState::Pending,
+            page_size: self.client.pagination().page_size,
+        }
+    }
+
+    /// Every repository the token can see.
+    pub fn repos(&self) -> impl Stream<Item = Result<Repo>> + '_ {
+        let client = &self.client;
+        let page_size = client.pagination().page_size;
+        paged(move |offset| async move {
+            let page = client.list_repos(page_size, offset).await?;
+            let total = usize::try_from(page.total.max(0)).unwrap_or(0);
+            anyhow::Ok((page.repos, total))
+        })
+    }
+}
+
+/// Bugs in one repository, built up by [`Detail::bugs`].
+#[must_use]
+pub struct BugQuery<'a> {
+    client: &'a ApiClient,
+    repo: String,
+    status: BugReviewState,
+    page_size: u32,
+}
+
+impl<'a> BugQuery<'a> {
+    pub const fn pending(self) -> Self {
+        self.status(BugReviewState::Pending)
+    }
+
+    pub const fn resolved(self) -> Self {
+        self.status(BugReviewState::Resolved)
+    }
+
+    pub const fn dismissed(self) -> Self {
+        self.status(BugReviewState::Dismissed)
+    }
+
+    pub const fn status(mut self, status: BugReviewState) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Bugs fetched per request, from 1 to 100.
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+        self
+    }
+
+    /// All the matching bugs at once. Pages after the first are fetched
+    /// concurrently, up to the client's `Pagination::max_items`.
+    pub async fn collect(self) -> Result<Vec<Bug>> {
+        let repo_id = resolve_repo_id(self.client, &self.repo).await?;
+        let client = self.client.clone().with_pagination(Pagination {
+            page_size: self.page_size,
+            ..self.client.pagination()
+        });
+        client.list_all_bugs(&repo_id, self.status).await
+    }
+
+    /// The matching bugs, in the API's order. Pages are fetched as the
+    /// stream is read, so stopping early saves the rest of the requests.
+    pub fn stream(self) -> impl Stream<Item = Result<Bug>> + 'a {
+        let Self {
+            client,
+            repo,
+            status,
+            page_size,
+        } = self;
+        stream::once(async move { resolve_repo_id(client, &repo).await })
+            .map_ok(move |repo_id| {
+                paged(move |offset| {
+                    let repo_id = repo_id.clone();
+                    async move {
+                        let page = client
+                            .list_bugs(&repo_id, status, page_size, offset, None)
+                            .await?;
+                        let total = usize::try_from(page.total.max(0)).unwrap_or(0);
+                        anyhow::Ok((page.bugs, total))
+                    }
+                })
+            })
+            .try_flatten()
+    }
+}
@@ -0,0 +1,327 @@
+//! `detail bugs alert`: compare a repo's pending and vulnerability counts
+//! against thresholds and report only the runs where a threshold is newly
+//! crossed or cleared, so it can run from cron without repeating itself.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use console::style;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::api::client::{post_json, ApiClient};
+use crate::api::types::BugReviewState;
+use crate::config::storage::{cache_dir, load_config};
+use crate::exit::Failure;
+use crate::hooks::shell;
+use crate::notify::{self, Message};
+use crate::output::{stdout, write_structured};
+use crate::utils::repos::resolve_repo_id;
+
+/// A count `bugs alert` watches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Metric {
+    Pending,
+    Vulns,
+}
+
+impl Metric {
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Pending => "pending bugs",
+            Self::Vulns => "pending security vulnerabilities",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Transition {
+    /// The count went above the threshold.
+    Crossed,
+    /// The count fell back to the threshold minus the hysteresis.
+    Cleared,
+}
+
+/// One newly crossed or cleared threshold.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AlertEvent {
+    repo: String,
+    metric: Metric,
+    transition: Transition,
+    count: usize,
+    threshold: usize,
+}
+
+impl AlertEvent {
+    fn text(&self) -> String {
+        match self.transition {
+            Transition::Crossed => format!(
+                "⚠ {}: {} {}, above {}",
+                self.repo,
+                self.count,
+                self.metric.label(),
+                self.threshold
+            ),
+            Transition::Cleared => format!(
+                "✓ {}: {} {}, back within {}",
+                self.repo,
+                self.count,
+                self.metric.label(),
+                self.threshold
+            ),
+        }
+    }
+
+    fn line(&self) -> String {
+        match self.transition {
+            Transition::Crossed => style(self.text()).yellow().to_string(),
+            Transition::Cleared => style(self.text()).green().to_string(),
+        }
+    }
+}
+
+/// Which thresholds were crossed at the end of the previous run.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct AlertState {
+    pending: bool,
+    vulns: bool,
+}
+
+impl AlertState {
+    const fn get_mut(&mut self, metric: Metric) -> &mut bool {
+        match metric {
+            Metric::Pending => &mut self.pending,
+            Metric::Vulns => &mut self.vulns,
+        }
+    }
+}
+
+/// Thresholds and notifications for one `bugs alert` run.
+pub struct AlertOptions<'a> {
+    pub max_pending: Option<usize>,
+    pub max_vulns: Option<usize>,
+    pub hysteresis: usize,
+    pub exec: Option<&'a str>,
+    pub webhook: Option<&'a str>,
+    /// Notification sinks to send the transitions to, by name.
+    pub sinks: &'a [String],
+}
+
+/// Whether a metric is alerting after this run, and the transition to
+/// report, if any. Once crossed, an alert only clears when the count drops
+/// to `max - hysteresis`, so a count hovering at the threshold doesn't flap.
+const fn evaluate(
+    alerting: bool,
+    count: usize,
+    max: usize,
+    hysteresis: usize,
+) -> (bool, Option<Transition>) {
+    if !alerting && count > max {
+        (true, Some(Transition::Crossed))
+    } else if alerting && count <= max.saturating_sub(hysteresis) {
+        (false, Some(Transition::Cleared))
+    } else {
+        (alerting, None)
+    }
+}
+
+/// Apply this run's counts to `state`, returning the transitions.
+fn transitions(
+    repo: &str,
+    state: &mut AlertState,
+    counts: &[(Metric, usize, usize)],
+    hysteresis: usize,
+) -> Vec<AlertEvent> {
+    let mut events = Vec::new();
+    for &(metric, count, max) in counts {
+        let alerting = state.get_mut(metric);
+        let (now_alerting, transition) = evaluate(*alerting, count, max, hysteresis);
+        *alerting = now_alerting;
+        if let Some(transition) = transition {
+            events.push(AlertEvent {
+                repo: repo.to_string(),
+                metric,
+                transition,
+                count,
+                threshold: max,
+            });
+        }
+    }
+    events
+}
+
+fn state_path(repo: &str) -> Result<PathBuf> {
+    let dir = cache_dir()?.join("alerts");
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    Ok(dir.join(format!("{}.json", repo.replace('/', "__"))))
+}
+
+/// Run `command` through the shell once per event, describing the event in
+/// `DETAIL_ALERT_*` environment variables.
+fn run_hook(command: &str, events: &[AlertEvent]) -> Result<()> {
+    for event in events {
+        let metric = match event.metric {
+            Metric::Pending => "pending",
+            Metric::Vulns => "vulns",
+        };
+        let transition = match event.transition {
+            Transition::Crossed => "crossed",
+            Transition::Cleared => "cleared",
+        };
+        let status = shell(command)
+            .env("DETAIL_ALERT_REPO", &event.repo)
+            .env("DETAIL_ALERT_METRIC", metric)
+            .env("DETAIL_ALERT_TRANSITION", transition)
+            .env("DETAIL_ALERT_COUNT", event.count.to_string())
+            .env("DETAIL_ALERT_THRESHOLD", event.threshold.to_string())
+            .status()
+            .with_context(|| format!("Failed to run alert hook `{command}`"))?;
+        if !status.success() {
+            bail!("Alert hook `{command}` exited with {status}");
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle(
+    client: &ApiClient,
+    repo: &str,
+    options: &AlertOptions<'_>,
+    format: &crate::OutputFormat,
+    cli: &crate::Cli,
+) -> Result<()> {
+    let config = load_config()?;
+    let settings = config.repo_settings(repo);
+    let max_pending = options
+        .max_pending
+        .or_else(|| settings.and_then(|s| s.alert_max_pending));
+    let max_vulns = options
+        .max_vulns
+        .or_else(|| settings.and_then(|s| s.alert_max_vulns));
+    if max_pending.is_none() && max_vulns.is_none() {
+        bail!(Failure::usage(format!(
+            "No alert thresholds for {repo}: pass --max-pending or --max-vulns, \
+             or set alert_max_pending or alert_max_vulns under [repos.\"{repo}\"] in config"
+        )));
+    }
+
+    let repo_id = resolve_repo_id(client, repo)
+        .await
+        .context("Failed to resolve repository identifier")?;
+    let bugs = client
+        .list_all_bugs(&repo_id, BugReviewState::Pending)
+        .await?;
+    let vulns = bugs
+        .iter()
+        .filter(|bug| bug.is_security_vulnerability == Some(true))
+        .count();
+    let counts: Vec<(Metric, usize, usize)> = [
+        max_pending.map(|max| (Metric::Pending, bugs.len(), max)),
+        max_vulns.map(|max| (Metric::Vulns, vulns, max)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let path = state_path(repo)?;
+    let mut state: AlertState = fs::read_to_string(&path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default();
+    let events = transitions(repo, &mut state, &counts, options.hysteresis);
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Yaml => {
+            write_structured(&events, format)?;
+        }
+        crate::OutputFormat::Table => {
+            let term = stdout();
+            for event in &events {
+                term.write_line(&event.line())?;
+            }
+        }
+    }
+    if let Some(command) = options.exec {
+        run_hook(command, &events)?;
+    }
+    if let Some(url) = options.webhook {
+        post_json(
+            url,
+            &json!({ "events": events }),
+            &cli.http_options(&config),
+        )
+        .await
+        .context("Failed to call the alert webhook")?;
+    }
+
+    if !options.sinks.is_empty() {
+        let body: Vec<String> = events.iter().map(AlertEvent::text).collect();
+        let message = Message {
+            source: "bugs alert".to_string(),
+            title: format!("Detail alert for {repo}"),
+            body: body.join("\n"),
+        };
+        notify::send(options.sinks, &message, cli).await?;
+    }
+
+    // Saved only once every notification went out, so a failed hook is
+    // retried on the next run rather than lost.
+    fs::write(&path, serde_json::to_string(&state)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alerts_once_when_crossed_and_clears_below_hysteresis() {
+        let mut state = AlertState::default();
+        let runs = [11, 12, 10, 9, 8, 11];
+        let seen: Vec<Option<Transition>> = runs
+            .iter()
+            .map(|&count| {
+                let events =
+                    transitions("acme/api", &mut state, &[(Metric::Pending, count, 10)], 2);
+                events.first().map(|event| event.transition)
+            })
+            .collect();
+        assert_eq!(
+            seen,
+            [
+                Some(Transition::Crossed),
+                None,
+                None,
+                None,
+                Some(Transition::Cleared),
+                Some(Transition::Crossed),
+            ]
+        );
+    }
+
+    #[test]
+    fn metrics_alert_independently() {
+        let mut state = AlertState {
+            pending: true,
+            vulns: false,
+        };
+        let events = transitions(
+            "acme/api",
+            &mut state,
+            &[(Metric::Pending, 20, 10), (Metric::Vulns, 3, 0)],
+            0,
+        );
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].metric, Metric::Vulns);
+        assert!(state.pending && state.vulns);
+    }
+}
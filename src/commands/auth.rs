@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::io::{self, IsTerminal as _};
 use std::str::from_utf8;
 use std::time::Duration;
 
@@ -7,6 +8,7 @@ use anyhow::{bail, Context, Result};
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use clap::Subcommand;
 use console::{style, Term};
+use dialoguer::Confirm;
 use percent_encoding::percent_decode_str;
 use sha2::{Digest, Sha256};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -17,16 +19,26 @@ use tokio::net::{TcpListener, TcpStream};
 const CALLBACK_BUFFER_SIZE: usize = 4096;
 use tokio::time::timeout;
 
-use crate::api::client::{pkce_token_exchange, ApiClient};
+use crate::api::client::{pkce_token_exchange, ApiClient, HttpOptions};
+use crate::api::types::Org;
 use crate::config::storage;
+use crate::exit::Failure;
+use crate::output::stdout;
+use crate::utils::secret::read_hidden_line;
 
 #[derive(Subcommand)]
 pub enum AuthCommands {
     /// Login with your Detail account
     Login {
-        /// API token (`dtl_live_...`) — skips the browser flow
+        /// API token (`dtl_live_...`) — skips the browser flow. Pass `-` to
+        /// type it without echo, or to read it from stdin
         #[arg(long)]
         token: Option<String>,
+
+        /// Pin this login to one of your organizations (name or ID), sent
+        /// with every request for enterprise deployments that route by org
+        #[arg(long, value_name = "ORG")]
+        org: Option<String>,
     },
 
     /// Logout and remove stored credentials
@@ -34,51 +46,42 @@ pub enum AuthCommands {
 
     /// Show current authentication status
     Status,
-}
 
-pub async fn handle(command: &AuthCommands, cli: &crate::Cli) -> Result<()> {
-    match command {
-        AuthCommands::Login { token } => {
-            let config = storage::load_config()
-                .inspect_err(|e| {
-                    let _ = Term::stderr().write_line(&format!(
-                        "Warning: Config file has errors, using default settings: {e}"
-                    ));
-                })
-                .unwrap_or_default();
-            let api_url = config
-                .api_url
-                .as_deref()
-                .unwrap_or("https://api.detail.dev");
-            let app_url = config
-                .app_url
-                .as_deref()
-                .unwrap_or("https://app.detail.dev");
-
-            let token = if let Some(t) = token {
-                if !t.starts_with("dtl_") {
-                    bail!("Invalid token format. Token should start with 'dtl_'");
-                }
-                t.clone()
-            } else {
-                pkce_login(api_url, app_url).await?
-            };
+    /// Print the API token for other tools to reuse, e.g.
+    /// `curl -H "Authorization: Bearer $(detail auth token --reveal)"`
+    Token {
+        /// Print the full token instead of a masked one
+        #[arg(long)]
+        reveal: bool,
+    },
 
-            let client = ApiClient::new(config.api_url, Some(token.clone()))?;
+    /// Sign API requests with a gateway's HMAC key. The secret is read from
+    /// stdin (or prompted for) and kept in the OS keyring
+    SigningKey {
+        /// Key ID issued by the gateway
+        #[arg(required_unless_present = "remove")]
+        key_id: Option<String>,
 
-            let user_info = client
-                .get_current_user()
-                .await
-                .context("Failed to authenticate. Please check your token.")?;
+        /// Stop signing requests
+        #[arg(long, conflicts_with = "key_id")]
+        remove: bool,
+    },
+}
 
-            storage::store_token(&token)?;
+pub async fn handle(command: &AuthCommands, cli: &crate::Cli) -> Result<()> {
+    match command {
+        AuthCommands::Login { token, org } => {
+            let mut term = stdout();
+            let login = login(token.as_deref(), org.as_deref(), cli, &mut term).await?;
 
-            let term = Term::stdout();
             term.write_line(&format!(
                 "{}",
                 style("✓ Successfully authenticated!").green()
             ))?;
-            term.write_line(&format!("Logged in as: {}", user_info.email))?;
+            term.write_line(&format!("Logged in as: {}", login.email))?;
+            if let Some(org) = &login.org {
+                term.write_line(&format!("Organization: {org}"))?;
+            }
             term.write_line("\nExample commands:")?;
             term.write_line("  detail bugs list <owner>/<repo>")?;
             term.write_line("  detail bugs show <bug_id>")?;
@@ -88,36 +91,266 @@ pub async fn handle(command: &AuthCommands, cli: &crate::Cli) -> Result<()> {
 
         AuthCommands::Logout => {
             storage::clear_credentials()?;
-            Term::stdout()
-                .write_line(&format!("{}", style("✓ Logged out successfully").green()))?;
+            stdout().write_line(&format!("{}", style("✓ Logged out successfully").green()))?;
             Ok(())
         }
 
         AuthCommands::Status => {
             if let Ok(client) = cli.create_client() {
-                let term = Term::stdout();
+                let term = stdout();
+                let environment = storage::load_config()
+                    .and_then(|config| storage::api_token(&config))
+                    .ok()
+                    .flatten()
+                    .and_then(|token| token_environment(&token));
                 match client.get_current_user().await {
                     Ok(user) => {
                         term.write_line(&format!("{}", style("✓ Authenticated").green()))?;
                         term.write_line(&format!("Email: {}", user.email))?;
+                        if !user.orgs.is_empty() {
+                            let orgs: Vec<&str> =
+                                user.orgs.iter().map(|org| org.name.as_str()).collect();
+                            term.write_line(&format!("Organizations: {}", orgs.join(", ")))?;
+                        }
+                        if let Some(pinned) = storage::load_config().ok().and_then(|c| c.org) {
+                            let pinned = find_org(&pinned, &user.orgs).map_or_else(
+                                || format!("{pinned} (not one of your organizations)"),
+                                org_label,
+                            );
+                            term.write_line(&format!("Pinned organization: {pinned}"))?;
+                        }
+                        if let Some(profile) = storage::active_profile() {
+                            term.write_line(&format!("Profile: {profile}"))?;
+                        }
+                        if let Some(environment) = environment {
+                            term.write_line(&format!("Environment: {environment}"))?;
+                        }
+                        if storage::env_token().is_some() {
+                            term.write_line(&format!("Token: from {}", storage::TOKEN_ENV))?;
+                        }
                     }
                     Err(e) => {
                         term.write_line(&format!("{}", style("✗ Authentication invalid").red()))?;
                         term.write_line(&format!("Error: {e}"))?;
+                        // A test token against the live API (or the reverse)
+                        // is a common cause.
+                        if let Some(environment) = environment {
+                            term.write_line(&format!("Environment: {environment}"))?;
+                        }
                         term.write_line("\nRun `detail auth login` to re-authenticate")?;
                     }
                 }
             } else {
-                let term = Term::stdout();
+                let term = stdout();
                 term.write_line(&format!("{}", style("✗ Not authenticated").red()))?;
                 term.write_line("\nRun `detail auth login` to authenticate")?;
             }
             Ok(())
         }
+
+        AuthCommands::Token { reveal } => {
+            let token = storage::api_token(&storage::load_config()?)?
+                .with_context(storage::no_token_message)?;
+            if *reveal {
+                stdout().write_line(&token)?;
+            } else {
+                stdout().write_line(&mask_token(&token))?;
+                Term::stderr()
+                    .write_line(&format!(
+                        "{}",
+                        style("Pass --reveal to print the full token").dim()
+                    ))
+                    .ok();
+            }
+            Ok(())
+        }
+
+        AuthCommands::SigningKey { key_id, remove } => {
+            let term = stdout();
+            if *remove {
+                storage::clear_signing_key()?;
+                term.write_line(&format!(
+                    "{}",
+                    style("✓ API requests will no longer be signed").green()
+                ))?;
+                return Ok(());
+            }
+            let Some(key_id) = key_id else {
+                bail!(Failure::usage("Pass a key ID, or --remove"));
+            };
+            let secret = read_secret("Signing secret: ", "signing secret")?;
+            if secret.is_empty() {
+                bail!(Failure::usage("The signing secret is empty"));
+            }
+            storage::store_signing_key(key_id, &secret)?;
+            term.write_line(&format!(
+                "{}",
+                style(format!("✓ API requests will be signed with key {key_id}")).green()
+            ))?;
+            Ok(())
+        }
+    }
+}
+
+/// A secret (`what`, e.g. "signing secret"): typed without echo at a
+/// terminal, otherwise the first line of stdin, so it can be piped from a
+/// secret manager.
+fn read_secret(prompt: &str, what: &str) -> Result<String> {
+    let secret = if io::stdin().is_terminal() {
+        read_hidden_line(prompt)?
+    } else {
+        let mut line = String::new();
+        io::stdin()
+            .read_line(&mut line)
+            .with_context(|| format!("Failed to read the {what} from stdin"))?;
+        line
+    };
+    Ok(secret.trim().to_string())
+}
+
+/// Who a login signed in as.
+struct LoggedIn {
+    email: String,
+    /// The organization requests are pinned to, if any.
+    org: Option<String>,
+}
+
+/// `org` by display name.
+fn org_label(org: &Org) -> String {
+    format!("{} ({})", org.name, org.id)
+}
+
+/// The organization `requested` names, by ID or case-insensitive name.
+fn find_org<'a>(requested: &str, orgs: &'a [Org]) -> Option<&'a Org> {
+    orgs.iter()
+        .find(|org| org.id.as_str() == requested)
+        .or_else(|| {
+            orgs.iter()
+                .find(|org| org.name.eq_ignore_ascii_case(requested))
+        })
+}
+
+/// Like `find_org`, but an error listing the memberships when `requested`
+/// isn't one of them.
+fn resolve_org<'a>(requested: &str, orgs: &'a [Org]) -> Result<&'a Org> {
+    if let Some(org) = find_org(requested, orgs) {
+        return Ok(org);
+    }
+    if orgs.is_empty() {
+        bail!(Failure::usage(format!(
+            "'{requested}' isn't one of your organizations; this account isn't a member of any"
+        )));
+    }
+    let names: Vec<String> = orgs.iter().map(org_label).collect();
+    bail!(Failure::usage(format!(
+        "'{requested}' isn't one of your organizations: {}",
+        names.join(", ")
+    )))
+}
+
+/// Verify and store `token`, or one obtained through the browser flow when
+/// it's `None`. With `org`, pin the login to that organization; without
+/// it, check that any org already pinned still matches. Progress goes to
+/// `term`.
+async fn login(
+    token: Option<&str>,
+    org: Option<&str>,
+    cli: &crate::Cli,
+    term: &mut impl io::Write,
+) -> Result<LoggedIn> {
+    let config = storage::load_config()
+        .inspect_err(|e| {
+            let _ = Term::stderr().write_line(&format!(
+                "Warning: Config file has errors, using default settings: {e}"
+            ));
+        })
+        .unwrap_or_default();
+    let api_url = config
+        .api_url
+        .as_deref()
+        .unwrap_or("https://api.detail.dev");
+    let app_url = config
+        .app_url
+        .as_deref()
+        .unwrap_or("https://app.detail.dev");
+    let mut options = cli.http_options(&config);
+    // Memberships are checked without the pinned org, which may be the one
+    // that no longer matches.
+    let pinned = options.org.take();
+
+    // `--token -` keeps the token out of shell history and `ps`.
+    let entered = if token == Some("-") {
+        Some(read_secret("API token: ", "API token")?)
+    } else {
+        None
+    };
+    let token = entered.as_deref().or(token);
+    let token = if let Some(t) = token {
+        if !t.starts_with("dtl_") {
+            bail!("Invalid token format. Token should start with 'dtl_'");
+        }
+        t.to_string()
+    } else {
+        pkce_login(api_url, app_url, &options, term).await?
+    };
+
+    let client = ApiClient::new(config.api_url, Some(token.clone()), &options)?;
+
+    let user_info = client
+        .get_current_user()
+        .await
+        .context("Failed to authenticate. Please check your token.")?;
+
+    let org = match (org, &pinned) {
+        (Some(requested), _) => Some(resolve_org(requested, &user_info.orgs)?),
+        (None, Some(pinned)) => Some(resolve_org(pinned, &user_info.orgs).with_context(|| {
+            format!(
+                "This login is pinned to organization {pinned}, which no longer matches your \
+                 memberships. Log in with `--org <ORG>` to pin another"
+            )
+        })?),
+        (None, None) => None,
+    };
+
+    storage::store_token(&token)?;
+    if let Some(org) = org {
+        storage::store_org(&org.id.to_string())?;
+    }
+    Ok(LoggedIn {
+        email: user_info.email,
+        org: org.map(org_label),
+    })
+}
+
+/// Offer to log in again after the API rejected the stored token. Returns
+/// whether a new token was stored, so the caller can retry the command.
+/// Everything goes to stderr to keep the retried command's output clean.
+pub async fn offer_relogin(cli: &crate::Cli) -> Result<bool> {
+    let mut term = Term::stderr();
+    let confirmed = Confirm::new()
+        .with_prompt("Token rejected — re-authenticate now?")
+        .default(true)
+        .interact()
+        .context("Failed to read login confirmation")?;
+    if !confirmed {
+        return Ok(false);
     }
+
+    let login = login(None, None, cli, &mut term).await?;
+    term.write_line(&format!(
+        "{}",
+        style(format!("✓ Logged in as {}, retrying", login.email)).green()
+    ))
+    .ok();
+    Ok(true)
 }
 
-async fn pkce_login(api_url: &str, app_url: &str) -> Result<String> {
+async fn pkce_login(
+    api_url: &str,
+    app_url: &str,
+    options: &HttpOptions,
+    term: &mut impl io::Write,
+) -> Result<String> {
     // Generate code_verifier: 32 random bytes → 43-char base64url string (RFC 7636 compliant)
     let verifier_bytes: [u8; 32] = rand::random();
     let code_verifier = URL_SAFE_NO_PAD.encode(verifier_bytes);
@@ -142,22 +375,23 @@ async fn pkce_login(api_url: &str, app_url: &str) -> Result<String> {
         "{app_url}/cli-auth?redirect_uri={encoded_redirect}&state={state}&code_challenge={code_challenge}"
     );
 
-    let term = Term::stdout();
     if open::that(&browser_url).is_ok() {
-        term.write_line("Browser opened for authentication.")?;
+        writeln!(term, "Browser opened for authentication.")?;
     } else {
-        term.write_line("Could not open browser automatically.")?;
+        writeln!(term, "Could not open browser automatically.")?;
     }
-    term.write_line(&format!("  {browser_url}"))?;
-    term.write_line(&format!(
+    writeln!(term, "  {browser_url}")?;
+    writeln!(
+        term,
         "\nTip: You can also generate an API key at {app_url}/cli and authenticate directly:"
-    ))?;
-    term.write_line("  detail auth login --token <your-api-key>")?;
-    term.write_line("\nWaiting for authentication in the browser...")?;
+    )?;
+    writeln!(term, "  detail auth login --token <your-api-key>")?;
+    writeln!(term, "\nWaiting for authentication in the browser...")?;
+    term.flush()?;
 
     let (code, mut stream) = await_pkce_callback(listener, &state, app_url).await?;
 
-    match pkce_token_exchange(api_url, &code, &code_verifier).await {
+    match pkce_token_exchange(api_url, &code, &code_verifier, options).await {
         Ok(token) => {
             redirect_browser(&mut stream, &format!("{app_url}/cli-auth/success")).await;
             Ok(token)
@@ -235,6 +469,33 @@ fn extract_pkce_code(request: &str, expected_state: &str) -> Result<String> {
         .context("No code in callback URL")
 }
 
+/// Which environment a token belongs to, from its `dtl_<env>_` prefix.
+fn token_environment(token: &str) -> Option<&'static str> {
+    if token.starts_with("dtl_live_") {
+        Some("live")
+    } else if token.starts_with("dtl_test_") {
+        Some("test")
+    } else {
+        None
+    }
+}
+
+/// `token` with everything but its `dtl_live_`-style prefix and last four
+/// characters hidden, so it can be told apart without being usable.
+fn mask_token(token: &str) -> String {
+    let prefix = token
+        .rfind('_')
+        .and_then(|i| token.get(..=i))
+        .unwrap_or_default();
+    let rest = token.get(prefix.len()..).unwrap_or_default();
+    // Short tokens would give away too much of themselves.
+    if rest.len() <= 8 {
+        return format!("{prefix}…");
+    }
+    let tail_start = rest.char_indices().rev().nth(3).map_or(0, |(i, _)| i);
+    format!("{prefix}…{}", rest.get(tail_start..).unwrap_or_default())
+}
+
 fn parse_query_string(query: &str) -> HashMap<String, String> {
     query
         .split('&')
@@ -252,6 +513,43 @@ fn parse_query_string(query: &str) -> HashMap<String, String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn token_environment_comes_from_the_prefix() {
+        assert_eq!(token_environment("dtl_live_abc"), Some("live"));
+        assert_eq!(token_environment("dtl_test_abc"), Some("test"));
+        assert_eq!(token_environment("dtl_abc"), None);
+    }
+
+    #[test]
+    fn mask_token_keeps_prefix_and_last_four() {
+        assert_eq!(mask_token("dtl_live_abcdef123456"), "dtl_live_…3456");
+        assert_eq!(mask_token("dtl_live_abc"), "dtl_live_…");
+        assert_eq!(mask_token("opaquetoken123"), "…n123");
+    }
+
+    fn sample_orgs() -> Vec<Org> {
+        serde_json::from_value(serde_json::json!([
+            { "id": "org_1", "name": "Acme" },
+            { "id": "org_2", "name": "Globex" },
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn resolve_org_matches_id_or_name() {
+        let orgs = sample_orgs();
+        assert_eq!(resolve_org("org_2", &orgs).unwrap().name, "Globex");
+        assert_eq!(resolve_org("acme", &orgs).unwrap().id.to_string(), "org_1");
+    }
+
+    #[test]
+    fn resolve_org_lists_memberships_when_missing() {
+        let err = resolve_org("Initech", &sample_orgs()).unwrap_err();
+        assert_eq!(crate::exit::code(&err), crate::exit::USAGE);
+        assert!(err.to_string().contains("Acme (org_1), Globex (org_2)"));
+        assert!(resolve_org("org_1", &[]).is_err());
+    }
+
     #[test]
     fn parse_query_extracts_code_and_state() {
         let params = parse_query_string("code=abc123&state=xyz");
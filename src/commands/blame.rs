@@ -0,0 +1,293 @@
+//! `detail bugs show --blame`: who last touched the code a bug is in, from
+//! `git blame` on the local checkout.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use console::style;
+
+use crate::api::types::Bug;
+use crate::commands::patch::extract_patch;
+use crate::output::{stdout, SectionRenderer};
+use crate::utils::datetime::{format_date, format_relative_or};
+use crate::utils::git::repo_root;
+
+/// How many authors to list.
+const MAX_AUTHORS: usize = 3;
+
+/// The author git reports for lines that aren't committed yet.
+const UNCOMMITTED_AUTHOR: &str = "Not Committed Yet";
+
+/// A line range as `git blame -L` takes it: first line and line count.
+type LineRange = (u32, u32);
+
+/// The lines of `file` a suggested patch changes, from the old side of its
+/// hunks. The API locates bugs by file only, so this is the closest thing
+/// to the affected lines a report carries.
+fn patched_ranges(patch: &str, file: &str) -> Vec<LineRange> {
+    let mut in_file = false;
+    let mut ranges = Vec::new();
+    for line in patch.lines() {
+        if let Some(path) = line.strip_prefix("--- ") {
+            let path = path.split('\t').next().unwrap_or_default().trim();
+            in_file = path.strip_prefix("a/").unwrap_or(path) == file;
+        } else if let Some(hunk) = line.strip_prefix("@@ -").filter(|_| in_file) {
+            let old = hunk.split_whitespace().next().unwrap_or_default();
+            let (start, len) = old.split_once(',').unwrap_or((old, "1"));
+            if let (Ok(start), Ok(len)) = (start.parse::<u32>(), len.parse::<u32>()) {
+                // A pure insertion removes nothing; blame the line it follows.
+                ranges.push((start.max(1), len.max(1)));
+            }
+        }
+    }
+    ranges
+}
+
+/// One blamed line: the commit and who wrote it, and when (epoch seconds).
+#[derive(Debug, PartialEq, Eq)]
+struct BlameLine {
+    sha: String,
+    author: String,
+    author_time: i64,
+}
+
+/// Parse `git blame --line-porcelain` output. Every line comes with its
+/// full commit header, ended by the line's content behind a tab.
+fn parse_porcelain(output: &str) -> Vec<BlameLine> {
+    let mut lines = Vec::new();
+    let mut sha = "";
+    let mut author = "";
+    let mut author_time = 0;
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            lines.push(BlameLine {
+                sha: sha.to_string(),
+                author: author.to_string(),
+                author_time,
+            });
+        } else if let Some(name) = line.strip_prefix("author ") {
+            author = name;
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            author_time = time.parse().unwrap_or(0);
+        } else if let Some(first) = line.split_whitespace().next() {
+            if first.len() == 40 && first.chars().all(|c| c.is_ascii_hexdigit()) {
+                sha = first;
+            }
+        }
+    }
+    lines
+}
+
+/// One author's share of the blamed lines.
+#[derive(Debug, PartialEq, Eq)]
+struct Owner {
+    author: String,
+    lines: usize,
+    /// Their most recent change to these lines, in epoch seconds.
+    last_time: i64,
+    last_sha: String,
+}
+
+/// Group `lines` by author, most lines first, then most recent. Uncommitted
+/// lines are left out: they're the reader's own edits.
+fn owners(lines: &[BlameLine]) -> Vec<Owner> {
+    let mut by_author: BTreeMap<&str, Owner> = BTreeMap::new();
+    for line in lines.iter().filter(|l| l.author != UNCOMMITTED_AUTHOR) {
+        let owner = by_author
+            .entry(line.author.as_str())
+            .or_insert_with(|| Owner {
+                author: line.author.clone(),
+                lines: 0,
+                last_time: line.author_time,
+                last_sha: line.sha.clone(),
+            });
+        owner.lines += 1;
+        if line.author_time > owner.last_time {
+            owner.last_time = line.author_time;
+            owner.last_sha.clone_from(&line.sha);
+        }
+    }
+    let mut owners: Vec<Owner> = by_author.into_values().collect();
+    owners.sort_by_key(|owner| (Reverse(owner.lines), Reverse(owner.last_time)));
+    owners
+}
+
+fn owner_rows(owners: &[Owner]) -> Vec<(&str, String)> {
+    owners
+        .iter()
+        .take(MAX_AUTHORS)
+        .map(|owner| {
+            let lines = if owner.lines == 1 { "line" } else { "lines" };
+            let commit = owner.last_sha.get(..7).unwrap_or(&owner.last_sha);
+            let when = format_relative_or(owner.last_time.saturating_mul(1000), format_date);
+            let row = format!("{} {lines}, last changed {when} ({commit})", owner.lines);
+            (owner.author.as_str(), row)
+        })
+        .collect()
+}
+
+/// Run `git blame` on `file` (limited to `ranges` when there are any) at the
+/// top of the work tree.
+fn blame(file: &str, ranges: &[LineRange]) -> Result<Vec<BlameLine>> {
+    let Some(root) = repo_root() else {
+        bail!("not inside a checkout of the repository");
+    };
+    let mut git = Command::new("git");
+    git.args(["blame", "--line-porcelain"]).current_dir(root);
+    for (start, len) in ranges {
+        git.arg(format!("-L{start},+{len}"));
+    }
+    let output = git
+        .args(["--", file])
+        .output()
+        .context("Failed to run `git blame`")?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(parse_porcelain(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Print who last touched the code `bug` is in. Blame is an aside to the
+/// report already printed, so when it can't be had this says why instead
+/// of failing the command.
+pub fn print_last_touched(bug: &Bug) -> Result<()> {
+    let term = stdout();
+    let Some(file) = bug.file_path.as_deref() else {
+        term.write_line(&format!(
+            "{}",
+            style("No blame: the bug isn't tied to a file").dim()
+        ))?;
+        return Ok(());
+    };
+    let ranges = extract_patch(&bug.summary)
+        .map(|patch| patched_ranges(&patch, file))
+        .unwrap_or_default();
+    // Line ranges from a patch written against another revision can run
+    // past the end of the local file; blame the whole file instead.
+    let (lines, scoped) = match blame(file, &ranges) {
+        Err(_) if !ranges.is_empty() => (blame(file, &[]), false),
+        result => (result, !ranges.is_empty()),
+    };
+    let owners = match lines {
+        Ok(lines) => owners(&lines),
+        Err(e) => {
+            term.write_line(&format!("{}", style(format!("No blame: {e:#}")).dim()))?;
+            return Ok(());
+        }
+    };
+    if owners.is_empty() {
+        term.write_line(&format!(
+            "{}",
+            style(format!("No blame: {file} has no committed lines")).dim()
+        ))?;
+        return Ok(());
+    }
+    let header = if scoped {
+        format!("Last touched by ({file}, patched lines)")
+    } else {
+        format!("Last touched by ({file})")
+    };
+    SectionRenderer::new()
+        .key_value(&header, &owner_rows(&owners))
+        .print()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHA_A: &str = "1111111111111111111111111111111111111111";
+    const SHA_B: &str = "2222222222222222222222222222222222222222";
+
+    fn porcelain(entries: &[(&str, &str, i64)]) -> String {
+        entries
+            .iter()
+            .enumerate()
+            .map(|(i, (sha, author, time))| {
+                let n = i + 1;
+                format!(
+                    "{sha} {n} {n} 1\nauthor {author}\nauthor-mail <x@example.com>\n\
+                     author-time {time}\nauthor-tz +0000\nsummary Change\n\
+                     filename src/handler.rs\n\tlet v = input?;\n"
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ranges_come_from_the_old_side_of_the_files_hunks() {
+        let patch = "\
+--- a/src/other.rs
++++ b/src/other.rs
+@@ -1,2 +1,2 @@
+--- a/src/handler.rs\t2024-01-01
++++ b/src/handler.rs
+@@ -10,4 +10,5 @@ fn handle()
+@@ -42 +43 @@
+@@ -0,0 +1,3 @@
+";
+        assert_eq!(
+            patched_ranges(patch, "src/handler.rs"),
+            [(10, 4), (42, 1), (1, 1)]
+        );
+        assert!(patched_ranges(patch, "src/missing.rs").is_empty());
+    }
+
+    #[test]
+    fn porcelain_yields_one_entry_per_line() {
+        let output = porcelain(&[(SHA_A, "Alice", 100), (SHA_B, "Bob Smith", 200)]);
+        assert_eq!(
+            parse_porcelain(&output),
+            [
+                BlameLine {
+                    sha: SHA_A.into(),
+                    author: "Alice".into(),
+                    author_time: 100,
+                },
+                BlameLine {
+                    sha: SHA_B.into(),
+                    author: "Bob Smith".into(),
+                    author_time: 200,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn owners_rank_by_lines_and_keep_the_latest_change() {
+        let output = porcelain(&[
+            (SHA_A, "Alice", 100),
+            (SHA_B, "Bob", 300),
+            (SHA_B, "Alice", 200),
+            (
+                "0000000000000000000000000000000000000000",
+                UNCOMMITTED_AUTHOR,
+                400,
+            ),
+        ]);
+        let owners = owners(&parse_porcelain(&output));
+        assert_eq!(
+            owners,
+            [
+                Owner {
+                    author: "Alice".into(),
+                    lines: 2,
+                    last_time: 200,
+                    last_sha: SHA_B.into(),
+                },
+                Owner {
+                    author: "Bob".into(),
+                    lines: 1,
+                    last_time: 300,
+                    last_sha: SHA_B.into(),
+                },
+            ]
+        );
+        assert!(owner_rows(&owners)[0]
+            .1
+            .starts_with("2 lines, last changed "));
+        assert!(owner_rows(&owners)[0].1.ends_with("(2222222)"));
+    }
+}
@@ -1,20 +1,63 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::TryInto;
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 
 use anyhow::{bail, Context, Result};
-use clap::Subcommand;
+use clap::{ArgGroup, Subcommand, ValueEnum};
 use console::{style, Term};
 use dialoguer::{Input, Select};
-
-use crate::api::client::ApiClient;
+use futures_util::{stream, StreamExt as _};
+use notify_rust::Notification;
+use rand::rngs::SmallRng;
+use rand::{Rng, RngExt, SeedableRng};
+use serde::{Deserialize, Serialize};
+use tokio::{signal, time::sleep};
+
+use crate::api::client::{bug_review_body, is_network_error, is_transient_error, ApiClient};
 use crate::api::types::{
     dismissal_reason_label, format_introduced_in, format_linked_issue, review_state_label, Bug,
-    BugDismissalReason, BugId, BugReviewState, ListPublicBugsWorkflowRequestId, RepoId,
+    BugDismissalReason, BugId, BugReview, BugReviewState, CreatePublicBugReviewBody,
+    ListPublicBugsWorkflowRequestId, Repo, RepoId, Scan,
+};
+use crate::commands::alert::{self, AlertOptions};
+use crate::commands::export_issue::{self, ExportOptions, Target};
+use crate::commands::patch::{self, PatchMode};
+use crate::commands::queue::offer_to_queue;
+use crate::commands::{blame, diff, digest, fix, reconcile};
+use crate::config::queue::QueuedReview;
+use crate::config::storage::load_config;
+use crate::exit::Failure;
+use crate::export::{self, bug_state, ExportFormat, Redactor, ReportFormat};
+use crate::filter::{self, Filter};
+use crate::hooks;
+use crate::notify;
+use crate::output::{
+    output_grouped_list, output_list, sparkline, stdout, write_stdout, write_structured,
+    Formattable, ListFormat, ListGroup, SectionRenderer,
 };
-use crate::output::{output_list, SectionRenderer};
-use crate::utils::datetime::{format_datetime, parse_time_spec};
-use crate::utils::git::resolve_repo_arg;
+use crate::template::{self, Template};
+use crate::utils::concurrency;
+use crate::utils::datetime::{format_datetime, format_relative_or, parse_time_spec};
+use crate::utils::files::write_atomic;
+use crate::utils::git::{local_author_aliases, repo_prefix, resolve_repo_arg};
 use crate::utils::pagination::page_to_offset;
-use crate::utils::repos::resolve_repo_id;
+use crate::utils::repos::{
+    format_repo_failures, report_repo_failures, resolve_repo_id, resolve_repo_id_from_repos,
+    resolve_repo_ids, resolve_repo_ids_prefetching, RepoFailure,
+};
+use crate::utils::term_caps;
+
+/// Parse the optional `--scan-id` flag into a workflow request ID.
+fn parse_scan_id(scan_id: Option<&str>) -> Result<Option<ListPublicBugsWorkflowRequestId>> {
+    scan_id
+        .map(TryInto::try_into)
+        .transpose()
+        .context(Failure::usage("Invalid scan ID format (expected wr_...)"))
+}
 
 /// Return only bugs where `isSecurityVulnerability` is `true`.
 fn filter_vulns_only(bugs: &[Bug]) -> Vec<Bug> {
@@ -35,7 +78,7 @@ fn resolve_time_flag(
     value.map_or(Ok(None), |s| {
         parse_time_spec(s, now)
             .map(|dt| Some(dt.timestamp_millis()))
-            .map_err(|e| anyhow::anyhow!("invalid {name} value: {e}"))
+            .map_err(|e| Failure::usage(format!("invalid {name} value: {e}")).into())
     })
 }
 
@@ -62,6 +105,71 @@ fn filter_by_introduced_by(bugs: &[Bug], authors: &[String]) -> Vec<Bug> {
         .collect()
 }
 
+/// Apply the client-side `--since`/`--until`, `--vulns` and
+/// `--introduced-by` filters in the same order as `bugs list`.
+fn apply_client_filters(
+    bugs: Vec<Bug>,
+    since_ms: Option<i64>,
+    until_ms: Option<i64>,
+    vulns: bool,
+    introduced_by: &[String],
+) -> Vec<Bug> {
+    let mut filtered = bugs;
+    if since_ms.is_some() || until_ms.is_some() {
+        filtered = filter_by_time_range(&filtered, since_ms, until_ms);
+    }
+    if vulns {
+        filtered = filter_vulns_only(&filtered);
+    }
+    if !introduced_by.is_empty() {
+        filtered = filter_by_introduced_by(&filtered, introduced_by);
+    }
+    filtered
+}
+
+/// The statuses to fetch: every status when `filter` tests `status:`,
+/// otherwise the `--status` ones.
+fn filter_statuses(filter: Option<&Filter>, status: &[BugReviewState]) -> Vec<BugReviewState> {
+    if filter.is_some_and(Filter::mentions_status) {
+        BugReviewState::value_variants().to_vec()
+    } else {
+        status.to_vec()
+    }
+}
+
+/// The IDs in `ids` whose bugs match `filter`, fetching each bug.
+async fn matching_bug_ids(
+    client: &ApiClient,
+    ids: Vec<BugId>,
+    filter: &Filter,
+) -> Result<Vec<BugId>> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let total = ids.len();
+    let mut matching = Vec::with_capacity(total);
+    for id in ids {
+        let bug = client
+            .get_bug(&id)
+            .await
+            .with_context(|| format!("Failed to fetch bug {id}"))?;
+        if filter.matches(&bug, now) {
+            matching.push(id);
+        }
+    }
+    let skipped = total - matching.len();
+    if skipped > 0 {
+        Term::stderr()
+            .write_line(&format!(
+                "{}",
+                style(format!(
+                    "Skipping {skipped} of {total} bugs that don't match --filter"
+                ))
+                .dim()
+            ))
+            .ok();
+    }
+    Ok(matching)
+}
+
 /// Collect the sorted, deduplicated set of authors present in `bugs`.
 fn collect_authors(bugs: &[Bug]) -> Vec<&str> {
     let mut authors: Vec<&str> = bugs
@@ -100,6 +208,234 @@ fn empty_filter_hint(pre_filter: &[Bug], vulns: bool) -> String {
     }
 }
 
+/// How `bugs list --group-by` sections a page of bugs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GroupBy {
+    File,
+    Status,
+    Security,
+}
+
+/// Group name for bugs without a file.
+const NO_FILE_GROUP: &str = "(no file)";
+
+/// Split `bugs` into `--group-by` sections: files alphabetically with
+/// file-less bugs last, statuses in review order, vulnerabilities first.
+/// Bugs keep their order within a section.
+fn group_bugs(bugs: &[Bug], by: GroupBy) -> Vec<ListGroup<'_, Bug>> {
+    let mut groups: BTreeMap<(u8, String), Vec<&Bug>> = BTreeMap::new();
+    for bug in bugs {
+        let key = match by {
+            GroupBy::File => bug
+                .file_path
+                .as_ref()
+                .map_or_else(|| (1, NO_FILE_GROUP.to_string()), |path| (0, path.clone())),
+            GroupBy::Status => {
+                let state = bug_state(bug);
+                let rank = match state {
+                    BugReviewState::Pending => 0,
+                    BugReviewState::Resolved => 1,
+                    BugReviewState::Dismissed => 2,
+                };
+                (rank, review_state_label(&state).to_string())
+            }
+            GroupBy::Security => {
+                if bug.is_security_vulnerability == Some(true) {
+                    (0, "Security vulnerabilities".to_string())
+                } else {
+                    (1, "Other bugs".to_string())
+                }
+            }
+        };
+        groups.entry(key).or_default().push(bug);
+    }
+    groups
+        .into_iter()
+        .map(|((_, name), items)| ListGroup::new(name, items))
+        .collect()
+}
+
+/// One line per bug, printed instead of `--format` output.
+#[derive(Clone, Copy)]
+enum Lines<'a> {
+    /// `--ids-only`
+    Ids,
+    /// `--template`
+    Template(&'a Template),
+}
+
+impl Lines<'_> {
+    fn render(self, bug: &Bug) -> Result<String> {
+        match self {
+            Self::Ids => Ok(bug.id.to_string()),
+            Self::Template(template) => template.render(bug),
+        }
+    }
+}
+
+/// Emit a list of bugs — bare IDs one per line with `--ids-only`, sections
+/// with `--group-by`, otherwise the usual `output_list` view in the chosen
+/// format.
+fn output_bugs(
+    bugs: &[Bug],
+    total: usize,
+    page: u32,
+    limit: u32,
+    format: &ListFormat,
+    lines: Option<Lines<'_>>,
+    group_by: Option<GroupBy>,
+) -> Result<()> {
+    if let Some(lines) = lines {
+        let term = stdout();
+        for bug in bugs {
+            term.write_line(&lines.render(bug)?)?;
+        }
+        return Ok(());
+    }
+    if let Some(by) = group_by {
+        let ListFormat::Output(format) = format else {
+            bail!(Failure::usage(
+                "--group-by works with table, json and yaml output"
+            ));
+        };
+        return output_grouped_list(&group_bugs(bugs, by), total, page, limit, format);
+    }
+    output_list(bugs, total, page, limit, format)
+}
+
+/// Parse newline-separated bug IDs as read from stdin by `bugs close -`.
+/// Blank lines and surrounding whitespace are ignored so the output of
+/// `bugs list --ids-only` can be piped straight in.
+fn parse_bug_ids(input: &str) -> Result<Vec<BugId>> {
+    let ids = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            BugId::try_from(line).with_context(|| {
+                Failure::usage(format!("Invalid bug ID format (expected bug_...): {line}"))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if ids.is_empty() {
+        bail!(Failure::usage("No bug IDs read from stdin"));
+    }
+    Ok(ids)
+}
+
+/// Draw a uniform random sample of `n` bugs (every bug if there are fewer),
+/// keeping them in their original order.
+fn sample_bugs(bugs: Vec<Bug>, n: usize, rng: &mut impl Rng) -> Vec<Bug> {
+    if n >= bugs.len() {
+        return bugs;
+    }
+    // Partial Fisher–Yates: the first `n` slots end up holding a uniform
+    // sample of indices.
+    let mut indices: Vec<usize> = (0..bugs.len()).collect();
+    for i in 0..n {
+        let j = rng.random_range(i..indices.len());
+        indices.swap(i, j);
+    }
+    indices.truncate(n);
+    let chosen: BTreeSet<usize> = indices.into_iter().collect();
+    bugs.into_iter()
+        .enumerate()
+        .filter(|(i, _)| chosen.contains(i))
+        .map(|(_, bug)| bug)
+        .collect()
+}
+
+/// Files listed on a collapsed `--dedupe-titles` row before "+N more".
+const TITLE_GROUP_FILE_PREVIEW: usize = 3;
+
+/// One bug in a `--dedupe-titles` group.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Occurrence {
+    bug_id: BugId,
+    file_path: Option<String>,
+}
+
+/// Bugs in one repo sharing a title, as shown by `bugs list --dedupe-titles`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TitleGroup {
+    title: String,
+    repo_id: RepoId,
+    count: usize,
+    occurrences: Vec<Occurrence>,
+    /// Show every occurrence on the card (`--expand`).
+    #[serde(skip)]
+    expand: bool,
+}
+
+impl Formattable for TitleGroup {
+    fn to_card(&self) -> (String, Vec<(&'static str, String)>) {
+        let mut pairs = vec![("Occurrences", self.count.to_string())];
+        if self.expand {
+            for occurrence in &self.occurrences {
+                pairs.push((
+                    "Bug",
+                    format!(
+                        "{}  {}",
+                        occurrence.bug_id,
+                        occurrence.file_path.as_deref().unwrap_or("-")
+                    ),
+                ));
+            }
+        } else {
+            let files: BTreeSet<&str> = self
+                .occurrences
+                .iter()
+                .filter_map(|o| o.file_path.as_deref())
+                .collect();
+            if !files.is_empty() {
+                let mut preview: Vec<&str> = files
+                    .iter()
+                    .copied()
+                    .take(TITLE_GROUP_FILE_PREVIEW)
+                    .collect();
+                let more = files.len().saturating_sub(TITLE_GROUP_FILE_PREVIEW);
+                let more = (more > 0).then(|| format!("+{more} more"));
+                preview.extend(more.as_deref());
+                pairs.push(("Files", preview.join(", ")));
+            }
+        }
+        (self.title.clone(), pairs)
+    }
+}
+
+/// Group `bugs` by repo and exact title, largest groups first; ties keep
+/// the order in which each title first appeared.
+fn group_by_title(bugs: &[Bug], expand: bool) -> Vec<TitleGroup> {
+    let mut index: BTreeMap<(String, &str), usize> = BTreeMap::new();
+    let mut groups: Vec<TitleGroup> = Vec::new();
+    for bug in bugs {
+        let key = (bug.repo_id.to_string(), bug.title.as_str());
+        let occurrence = Occurrence {
+            bug_id: bug.id.clone(),
+            file_path: bug.file_path.clone(),
+        };
+        if let Some(&i) = index.get(&key) {
+            if let Some(group) = groups.get_mut(i) {
+                group.count += 1;
+                group.occurrences.push(occurrence);
+            }
+        } else {
+            index.insert(key, groups.len());
+            groups.push(TitleGroup {
+                title: bug.title.clone(),
+                repo_id: bug.repo_id.clone(),
+                count: 1,
+                occurrences: vec![occurrence],
+                expand,
+            });
+        }
+    }
+    groups.sort_by(|a, b| b.count.cmp(&a.count));
+    groups
+}
+
 fn paginate_items<T: Clone>(items: &[T], page: u32, limit: u32) -> Vec<T> {
     let offset = usize::try_from(page_to_offset(page, limit)).unwrap_or(0);
     items
@@ -114,7 +450,8 @@ fn paginate_items<T: Clone>(items: &[T], page: u32, limit: u32) -> Vec<T> {
 pub enum BugCommands {
     /// List bugs for a given repository
     List {
-        /// Repository by owner/repo (e.g., usedetail/cli) or repo (e.g., cli).
+        /// Repository by owner/repo (e.g., usedetail/cli), repo (e.g., cli),
+        /// or an @name shortcut from the [shortcuts] config table.
         /// If omitted, inferred from the git remote (origin).
         repo: Option<String>,
 
@@ -131,6 +468,11 @@ pub enum BugCommands {
         #[arg(long, value_delimiter = ',')]
         introduced_by: Vec<String>,
 
+        /// Only show bugs introduced by you, matching commit authors against
+        /// git's user.name and user.email
+        #[arg(long, conflicts_with = "introduced_by")]
+        mine: bool,
+
         /// Filter bugs to a specific scan by workflow request ID
         #[arg(long)]
         scan_id: Option<String>,
@@ -145,10 +487,30 @@ pub enum BugCommands {
         #[arg(long)]
         until: Option<String>,
 
+        /// Only show bugs matching this expression, e.g.
+        /// `security && path:src/** && age<30d`. A filter that tests
+        /// `status:` searches every status. See `detail help filters`.
+        #[arg(long, value_name = "EXPR")]
+        filter: Option<String>,
+
+        /// Only show bugs in files under the current directory, e.g.
+        /// `cd services/auth && detail bugs list --here`
+        #[arg(long)]
+        here: bool,
+
         /// Auto-paginate: fetch every matching bug instead of a single page.
         #[arg(long, conflicts_with_all = ["page", "limit"])]
         all: bool,
 
+        /// Show a uniform random sample of N bugs drawn from every matching
+        /// bug, not just the first page.
+        #[arg(long, value_name = "N", conflicts_with_all = ["all", "page", "limit"], value_parser = clap::value_parser!(u32).range(1..))]
+        sample: Option<u32>,
+
+        /// Seed for --sample, so the same sample can be drawn again
+        #[arg(long, requires = "sample")]
+        seed: Option<u64>,
+
         /// Maximum number of results per page
         #[arg(long, default_value = "50", value_parser = clap::value_parser!(u32).range(1..=100))]
         limit: u32,
@@ -157,9 +519,33 @@ pub enum BugCommands {
         #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..))]
         page: u32,
 
-        /// Output format
-        #[arg(long, value_enum, default_value = "table")]
-        format: crate::OutputFormat,
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<ListFormat>,
+
+        /// Print only bug IDs, one per line (e.g. to pipe into `bugs close -`)
+        #[arg(long, conflicts_with = "format")]
+        ids_only: bool,
+
+        /// Print each bug through a minijinja template instead, e.g.
+        /// `{{id}}\t{{title}}\t{{file_path}}`. Fields are as in `--format json`,
+        /// in snake_case, plus `state`
+        #[arg(long, value_name = "TEMPLATE", conflicts_with_all = ["format", "ids_only", "dedupe_titles", "group_by"])]
+        template: Option<String>,
+
+        /// Collapse bugs with the same title in a repo into one row with an
+        /// occurrence count, largest groups first
+        #[arg(long, conflicts_with_all = ["ids_only", "sample"])]
+        dedupe_titles: bool,
+
+        /// With --dedupe-titles, list every occurrence's bug ID and file
+        #[arg(long, requires = "dedupe_titles")]
+        expand: bool,
+
+        /// Show the page in sections with subtotals, one per file, status,
+        /// or security vs. other bugs
+        #[arg(long, value_enum, conflicts_with_all = ["ids_only", "dedupe_titles"])]
+        group_by: Option<GroupBy>,
     },
 
     /// Show the report for a bug
@@ -167,14 +553,24 @@ pub enum BugCommands {
         /// Bug ID
         bug_id: String,
 
-        /// Output format
-        #[arg(long, value_enum, default_value = "table")]
-        format: crate::OutputFormat,
+        /// Also show who last touched the affected code, from `git blame` on
+        /// the local checkout
+        #[arg(long, conflicts_with = "format")]
+        blame: bool,
+
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<crate::OutputFormat>,
+
+        /// Print the bug through a minijinja template instead, e.g.
+        /// `{{id}}: {{title}}`. See `bugs list --template`
+        #[arg(long, value_name = "TEMPLATE", conflicts_with_all = ["format", "blame"])]
+        template: Option<String>,
     },
 
     /// Close a bug as resolved or dismissed
     Close {
-        /// Bug ID
+        /// Bug ID, or `-` to read newline-separated bug IDs from stdin
         bug_id: String,
 
         /// Close state (prompted interactively if omitted in a TTY)
@@ -189,9 +585,29 @@ pub enum BugCommands {
         #[arg(long)]
         notes: Option<String>,
 
-        /// Output format
-        #[arg(long, value_enum, default_value = "table")]
-        format: crate::OutputFormat,
+        /// Validate flags and print the review that would be submitted
+        /// without closing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Write each bug's outcome as JSON to this file (bulk closes from
+        /// stdin only)
+        #[arg(long, value_name = "FILE", conflicts_with = "dry_run")]
+        report: Option<PathBuf>,
+
+        /// Only close the bugs read from stdin that match this expression.
+        /// Same syntax as `bugs list --filter`.
+        #[arg(long, value_name = "EXPR")]
+        filter: Option<String>,
+
+        /// Post a summary of what was closed to `slack_webhook_url` from
+        /// config
+        #[arg(long, conflicts_with = "dry_run")]
+        notify_slack: bool,
+
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<crate::OutputFormat>,
     },
 
     /// Reopen a previously resolved or dismissed bug — flips it back to
@@ -201,6 +617,439 @@ pub enum BugCommands {
         /// Bug ID
         bug_id: String,
     },
+
+    /// Walk through fixing a bug: show it, open its file in $EDITOR, run the
+    /// test command from `.detail.toml`, then close it as resolved with the
+    /// fixing commit
+    Fix {
+        /// Bug ID
+        bug_id: String,
+    },
+
+    /// Show the commit that introduced a bug, limited to the bug's file,
+    /// from the local checkout's history
+    Diff {
+        /// Bug ID
+        bug_id: String,
+    },
+
+    /// Print the patch suggested in a bug's report, or check or apply it to
+    /// the working tree with `git apply`
+    Patch {
+        /// Bug ID
+        bug_id: String,
+
+        /// Apply the patch to the working tree
+        #[arg(long, conflicts_with = "check")]
+        apply: bool,
+
+        /// Only check that the patch applies cleanly, changing nothing
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Export every bug matching the filters to a file (or stdout)
+    Export {
+        /// Repository by owner/repo (e.g., usedetail/cli), repo (e.g., cli),
+        /// or an @name shortcut from the [shortcuts] config table.
+        /// If omitted, inferred from the git remote (origin).
+        repo: Option<String>,
+
+        /// Status filter — repeat the flag or comma-separate values to
+        /// combine (e.g. `--status pending,resolved`). Default: pending.
+        #[arg(long, value_enum, value_delimiter = ',', default_value = "pending")]
+        status: Vec<BugReviewState>,
+
+        /// Only export security vulnerabilities
+        #[arg(long)]
+        vulns: bool,
+
+        /// Only export bugs introduced by these authors (comma-separated or repeat flag)
+        #[arg(long, value_delimiter = ',')]
+        introduced_by: Vec<String>,
+
+        /// Filter bugs to a specific scan by workflow request ID
+        #[arg(long)]
+        scan_id: Option<String>,
+
+        /// Only export bugs created at or after this point. Same forms as
+        /// `bugs list --since`.
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only export bugs created at or before this point. Same forms as --since.
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Only export bugs matching this expression. Same syntax as
+        /// `bugs list --filter`.
+        #[arg(long, value_name = "EXPR")]
+        filter: Option<String>,
+
+        /// Export format [default: from the --output extension (.json,
+        /// .csv, .sarif, .md), else json]
+        #[arg(long, value_enum)]
+        format: Option<ExportFormat>,
+
+        /// File to write to. Defaults to stdout. The file only appears once
+        /// the export is complete and checked.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+
+        /// Strip code blocks, file paths, URLs, commits, authors, linked
+        /// issues and review notes, for sharing outside your org. Add more
+        /// patterns with `redact_patterns` in config.
+        #[arg(long)]
+        redact: bool,
+    },
+
+    /// Write a markdown or HTML report (summary, bug table and a section per bug)
+    /// for a sprint review or a PR
+    Report {
+        /// Repository by owner/repo (e.g., usedetail/cli), repo (e.g., cli),
+        /// or an @name shortcut from the [shortcuts] config table.
+        /// If omitted, inferred from the git remote (origin).
+        repo: Option<String>,
+
+        /// Status filter — repeat the flag or comma-separate values to
+        /// combine (e.g. `--status pending,resolved`). Default: pending.
+        #[arg(long, value_enum, value_delimiter = ',', default_value = "pending")]
+        status: Vec<BugReviewState>,
+
+        /// Only report bugs matching this expression. Same syntax as
+        /// `bugs list --filter`.
+        #[arg(long, value_name = "EXPR")]
+        filter: Option<String>,
+
+        /// Report format [default: from the --output extension (.md,
+        /// .html), else markdown]
+        #[arg(long, value_enum)]
+        format: Option<ReportFormat>,
+
+        /// File to write to, e.g. report.md or report.html. Defaults to stdout.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Poll a repository and print each new pending bug as it appears
+    Watch {
+        /// Repository by owner/repo (e.g., usedetail/cli) or repo (e.g., cli).
+        /// If omitted, inferred from the git remote (origin).
+        repo: Option<String>,
+
+        /// Seconds between polls
+        #[arg(long, default_value = "60", value_parser = clap::value_parser!(u64).range(10..))]
+        interval: u64,
+
+        /// Also show a desktop notification for each new bug
+        #[arg(long)]
+        notify: bool,
+
+        /// Notification sink from [notifications] in config to send each
+        /// new bug to; repeat for several
+        #[arg(long = "sink", value_name = "NAME")]
+        sinks: Vec<String>,
+    },
+
+    /// Check pending and vulnerability counts against thresholds, for cron —
+    /// prints (and runs hooks) only when a threshold is newly crossed or cleared
+    Alert {
+        /// Repository by owner/repo (e.g., usedetail/cli) or repo (e.g., cli).
+        /// If omitted, inferred from the git remote (origin).
+        repo: Option<String>,
+
+        /// Alert when more than this many bugs are pending. Defaults to
+        /// `alert_max_pending` under the repo's [repos."owner/repo"] config.
+        #[arg(long)]
+        max_pending: Option<usize>,
+
+        /// Alert when more than this many pending bugs are security
+        /// vulnerabilities. Defaults to `alert_max_vulns` in config.
+        #[arg(long)]
+        max_vulns: Option<usize>,
+
+        /// How far below a threshold a count must drop before the alert
+        /// clears, so a count hovering at the threshold doesn't flap
+        #[arg(long, default_value = "0")]
+        hysteresis: usize,
+
+        /// Shell command to run for each crossed or cleared threshold, with
+        /// DETAIL_ALERT_REPO, _METRIC, _TRANSITION, _COUNT and _THRESHOLD set
+        #[arg(long)]
+        exec: Option<String>,
+
+        /// URL to POST the crossed and cleared thresholds to, as JSON
+        #[arg(long)]
+        webhook: Option<String>,
+
+        /// Notification sink from [notifications] in config to send the
+        /// crossed and cleared thresholds to; repeat for several
+        #[arg(long = "sink", value_name = "NAME")]
+        sinks: Vec<String>,
+
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    /// Summarize bugs opened, resolved and dismissed over a window, by file
+    /// and by introducing author — for cron jobs that post to chat
+    Digest {
+        /// Repository by owner/repo (e.g., usedetail/cli) or repo (e.g., cli).
+        /// If omitted, inferred from the git remote (origin).
+        repo: Option<String>,
+
+        /// Start of the window. Same forms as `bugs list --since`.
+        #[arg(long, default_value = "1w")]
+        since: String,
+
+        /// Also post the digest to `slack_webhook_url` from config
+        #[arg(long)]
+        notify_slack: bool,
+
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    /// Compare bugs with the GitHub, Jira or Linear issues tracking them:
+    /// close issues whose bugs are resolved and flag drift either way
+    Reconcile {
+        /// Repository by owner/repo (e.g., usedetail/cli) or repo (e.g., cli).
+        /// If omitted, inferred from the git remote (origin).
+        repo: Option<String>,
+
+        /// Close open GitHub issues whose bugs are resolved or dismissed,
+        /// through the `gh` CLI. Without it, only report
+        #[arg(long)]
+        apply: bool,
+
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    /// File a bug as an issue in Jira or GitLab and remember the link for
+    /// `bugs reconcile`
+    #[command(group(ArgGroup::new("tracker").required(true)))]
+    ExportIssue {
+        /// Bug ID
+        bug_id: String,
+
+        /// Jira project key to create the issue in (e.g., PAY), using
+        /// `[jira]` from config
+        #[arg(long, value_name = "PROJECT-KEY", group = "tracker")]
+        jira: Option<String>,
+
+        /// GitLab project to create the issue in (e.g., acme/api), using
+        /// `[gitlab]` from config or gitlab.com with GITLAB_TOKEN
+        #[arg(long, value_name = "GROUP/PROJECT", group = "tracker")]
+        gitlab: Option<String>,
+
+        /// Create the issue even if the bug is already tracked there
+        #[arg(long)]
+        force: bool,
+
+        /// Print the request instead of creating the issue
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    /// Try list, show and close on built-in sample data — no login needed
+    #[command(subcommand)]
+    Demo(DemoCommands),
+}
+
+#[derive(Subcommand)]
+pub enum DemoCommands {
+    /// List the sample bugs
+    List {
+        /// Sample repository by owner/repo (e.g., acme/storefront) or repo
+        /// (e.g., storefront). If omitted, bugs from every sample repo are listed.
+        repo: Option<String>,
+
+        /// Status filter — repeat the flag or comma-separate values to
+        /// combine (e.g. `--status pending,resolved`). Default: pending.
+        #[arg(long, value_enum, value_delimiter = ',', default_value = "pending")]
+        status: Vec<BugReviewState>,
+
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<crate::OutputFormat>,
+
+        /// Print only bug IDs, one per line
+        #[arg(long, conflicts_with = "format")]
+        ids_only: bool,
+    },
+
+    /// Show the report for a sample bug
+    Show {
+        /// Bug ID (e.g., bug_demo_01)
+        bug_id: String,
+
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<crate::OutputFormat>,
+    },
+
+    /// Close a sample bug. Nothing is sent to Detail.
+    Close {
+        /// Bug ID (e.g., bug_demo_01)
+        bug_id: String,
+
+        /// Close state (prompted interactively if omitted in a TTY)
+        #[arg(long, value_enum)]
+        state: Option<BugReviewState>,
+
+        /// Dismissal reason (required if state is dismissed)
+        #[arg(long, value_enum)]
+        dismissal_reason: Option<BugDismissalReason>,
+
+        /// Additional notes
+        #[arg(long)]
+        notes: Option<String>,
+
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<crate::OutputFormat>,
+    },
+}
+
+// ── Demo mode ───────────────────────────────────────────────────────
+
+/// Sample repos and bugs served by `bugs demo`.
+const DEMO_DATA: &str = include_str!("demo.json");
+
+#[derive(Deserialize)]
+struct DemoData {
+    repos: Vec<Repo>,
+    bugs: Vec<Bug>,
+}
+
+fn load_demo_data() -> Result<DemoData> {
+    serde_json::from_str(DEMO_DATA).context("Failed to load the demo data")
+}
+
+/// The sample bug with `bug_id`.
+fn find_demo_bug(data: &DemoData, bug_id: &str) -> Result<Bug> {
+    data.bugs
+        .iter()
+        .find(|bug| bug.id.as_str() == bug_id.trim())
+        .cloned()
+        .ok_or_else(|| {
+            Failure::not_found(format!(
+                "No sample bug '{bug_id}'. Run `detail bugs demo list --status pending,resolved,dismissed` to see them all."
+            ))
+            .into()
+        })
+}
+
+/// Sample bugs in any of `status`, optionally limited to one sample repo.
+fn demo_bugs(data: &DemoData, repo: Option<&str>, status: &[BugReviewState]) -> Result<Vec<Bug>> {
+    let repo_id = repo
+        .map(|repo| resolve_repo_id_from_repos(&data.repos, repo))
+        .transpose()?;
+    Ok(data
+        .bugs
+        .iter()
+        .filter(|bug| repo_id.as_ref().is_none_or(|id| bug.repo_id == *id))
+        .filter(|bug| {
+            status.contains(
+                &bug.review
+                    .as_ref()
+                    .map_or(BugReviewState::Pending, |r| r.state),
+            )
+        })
+        .cloned()
+        .collect())
+}
+
+/// Printed to stderr before every demo command, so it never mixes into
+/// `--format json` output.
+fn print_demo_banner() -> Result<()> {
+    Term::stderr().write_line(&format!(
+        "{} {}",
+        style(" DEMO ").black().on_yellow().bold(),
+        style("Sample data only — nothing is sent to Detail. Run `detail auth login` to use your own repos.").dim()
+    ))?;
+    Ok(())
+}
+
+fn handle_demo(command: &DemoCommands) -> Result<()> {
+    print_demo_banner()?;
+    let data = load_demo_data()?;
+
+    match command {
+        DemoCommands::List {
+            repo,
+            status,
+            format,
+            ids_only,
+        } => {
+            let format = &format.unwrap_or_default();
+            let bugs = demo_bugs(&data, repo.as_deref(), status)?;
+            let limit = u32::try_from(bugs.len()).unwrap_or(u32::MAX).max(1);
+            let lines = ids_only.then_some(Lines::Ids);
+            output_bugs(&bugs, bugs.len(), 1, limit, &format.into(), lines, None)
+        }
+
+        DemoCommands::Show { bug_id, format } => {
+            let bug = find_demo_bug(&data, bug_id)?;
+            if let Some(format @ (crate::OutputFormat::Json | crate::OutputFormat::Yaml)) = format {
+                return write_structured(&bug, format);
+            }
+            render_bug_show(&bug, None)
+        }
+
+        DemoCommands::Close {
+            bug_id,
+            state,
+            dismissal_reason,
+            notes,
+            format,
+        } => {
+            let bug = find_demo_bug(&data, bug_id)?;
+            let is_interactive = Term::stdout().is_term();
+            let (state, dismissal_reason, notes) =
+                validate_close_flags(*state, *dismissal_reason, notes.clone(), is_interactive)?;
+            let state = match state {
+                Some(s) => s,
+                None => prompt_close_state()?,
+            };
+            let dismissal_reason = match dismissal_reason {
+                Some(r) => Some(r),
+                None if matches!(state, BugReviewState::Dismissed) => {
+                    Some(prompt_dismissal_reason()?)
+                }
+                None => None,
+            };
+            let notes = match notes {
+                Some(n) => Some(n),
+                None if is_interactive => prompt_notes()?,
+                None => None,
+            };
+
+            if let Some(format @ (crate::OutputFormat::Json | crate::OutputFormat::Yaml)) = format {
+                let review: BugReview = serde_json::from_value(serde_json::json!({
+                    "state": state,
+                    "dismissalReason": dismissal_reason,
+                    "notes": notes,
+                    "createdAt": chrono::Utc::now().timestamp_millis(),
+                }))?;
+                return write_structured(&review, format);
+            }
+            stdout().write_line(&format!(
+                "{} {}",
+                style(format!("✓ Bug closed as: {}", review_state_label(&state))).green(),
+                style(format!("({} — demo, not saved)", bug.id)).dim()
+            ))?;
+            Ok(())
+        }
+    }
 }
 
 // ── Interactive prompt helpers ──────────────────────────────────────
@@ -275,16 +1124,18 @@ fn validate_close_flags(
 )> {
     // Reject --state pending
     if matches!(state, Some(BugReviewState::Pending)) {
-        bail!("'pending' is not a valid close state. Use 'resolved' or 'dismissed'.");
+        bail!(Failure::usage(
+            "'pending' is not a valid close state. Use 'resolved' or 'dismissed'."
+        ));
     }
 
     // Non-interactive: --state is required
     let state = match state {
         Some(s) => Some(s),
         None if is_interactive => None, // will prompt
-        None => bail!(
+        None => bail!(Failure::usage(
             "--state is required in non-interactive mode. Use --state resolved or --state dismissed."
-        ),
+        )),
     };
 
     // Non-interactive + Dismissed: --dismissal-reason is required
@@ -292,31 +1143,70 @@ fn validate_close_flags(
         match dismissal_reason {
             Some(r) => Some(r),
             None if is_interactive => None, // will prompt
-            None => bail!(
+            None => bail!(Failure::usage(
                 "--dismissal-reason is required when state is 'dismissed' in non-interactive mode."
-            ),
+            )),
         }
     } else {
         dismissal_reason
     };
 
-    Ok((state, dismissal_reason, notes))
+    Ok((state, dismissal_reason, notes))
+}
+
+/// Number of recent scans plotted in the `bugs show` trend sparkline.
+const TREND_SCANS: u32 = 20;
+
+/// Open-bug counts from `scans`, oldest first. Scans without bug counts are
+/// skipped; fewer than two data points isn't a trend, so that yields `None`.
+fn open_bug_trend(scans: Vec<Scan>) -> Option<Vec<i64>> {
+    let mut points: Vec<(i64, i64)> = scans
+        .into_iter()
+        .filter_map(|s| Some((s.created_at, s.bug_counts?.open)))
+        .collect();
+    if points.len() < 2 {
+        return None;
+    }
+    points.sort_by_key(|(created_at, _)| *created_at);
+    Some(points.into_iter().map(|(_, open)| open).collect())
+}
+
+/// Fetch the repo's open-bug trend from its most recent scans. Best effort:
+/// any failure yields `None` so `bugs show` still renders the report.
+pub async fn fetch_open_bug_trend(client: &ApiClient, repo_id: &RepoId) -> Option<Vec<i64>> {
+    let response = client.list_scans(repo_id, TREND_SCANS, 0).await.ok()?;
+    open_bug_trend(response.scans)
 }
 
-/// Render a single bug as the human-readable `bugs show` view.
-fn render_bug_show(bug: &Bug) -> Result<()> {
-    let mut pairs: Vec<(&str, String)> = vec![
-        ("ID", bug.id.to_string()),
-        ("Title", bug.title.clone()),
+/// Render a single bug as the human-readable `bugs show` view, with the
+/// repo's open-bug trend (oldest scan first) when one is available.
+pub fn render_bug_show(bug: &Bug, trend: Option<&[i64]>) -> Result<()> {
+    let mut pairs: Vec<(&str, String)> =
+        vec![("ID", bug.id.to_string()), ("Title", bug.title.clone())];
+    if let Some(trend) = trend {
+        let latest = trend.last().copied().unwrap_or(0);
+        pairs.push((
+            "Repo Trend",
+            format!(
+                "{} {latest} open over the last {} scans",
+                sparkline(trend, term_caps::get().unicode),
+                trend.len()
+            ),
+        ));
+    }
+    pairs.extend([
         ("File", bug.file_path.as_deref().unwrap_or("-").to_string()),
-        ("Created", format_datetime(bug.created_at)),
+        (
+            "Created",
+            format_relative_or(bug.created_at, format_datetime),
+        ),
         (
             "Security",
             bug.is_security_vulnerability
                 .map_or("-", |v| if v { "Yes" } else { "No" })
                 .to_string(),
         ),
-    ];
+    ]);
     if let Some(intro) = &bug.introduced_in {
         pairs.push(("Introduced", format_introduced_in(intro)));
     }
@@ -339,36 +1229,374 @@ fn render_bug_show(bug: &Bug) -> Result<()> {
         .print()
 }
 
-/// Page size used when scanning all bugs for client-side vulnerability filtering.
-const BUG_PAGE_SIZE: u32 = 100;
+/// Build the `--dry-run` JSON preview: each resolved bug alongside the review
+/// body that would be submitted for it. A single ID yields one object; bulk
+/// closes from stdin yield an array, mirroring the real close output.
+fn dry_run_json(bugs: &[Bug], body: &CreatePublicBugReviewBody, bulk: bool) -> serde_json::Value {
+    let previews: Vec<serde_json::Value> = bugs
+        .iter()
+        .map(|bug| {
+            serde_json::json!({
+                "bugId": bug.id,
+                "title": bug.title,
+                "currentState": bug.review.as_ref().map_or(BugReviewState::Pending, |r| r.state),
+                "review": body,
+            })
+        })
+        .collect();
+    if bulk {
+        return serde_json::Value::from(previews);
+    }
+    previews.into_iter().next().unwrap_or_default()
+}
 
-/// Fetch every bug for a repo/status by paginating through all pages.
-async fn fetch_all_bugs(
-    client: &ApiClient,
-    repo_id: &RepoId,
-    status: BugReviewState,
-    scan_id: Option<&ListPublicBugsWorkflowRequestId>,
-) -> Result<Vec<Bug>> {
-    let mut all_bugs = Vec::new();
-    let mut offset = 0;
+/// Render the `bugs close --dry-run` preview without submitting anything.
+fn render_close_dry_run(
+    bugs: &[Bug],
+    body: &CreatePublicBugReviewBody,
+    format: &crate::OutputFormat,
+    bulk: bool,
+) -> Result<()> {
+    let term = stdout();
+    if matches!(
+        format,
+        crate::OutputFormat::Json | crate::OutputFormat::Yaml
+    ) {
+        return write_structured(&dry_run_json(bugs, body, bulk), format);
+    }
+
+    term.write_line(&format!(
+        "{}",
+        style("Dry run — no changes were made.").yellow()
+    ))?;
+    let label = review_state_label(&body.state);
+    for bug in bugs {
+        let current = bug
+            .review
+            .as_ref()
+            .map_or("Pending", |r| review_state_label(&r.state));
+        term.write_line(&format!(
+            "Would close {} ({}) as: {label} (currently {current})",
+            bug.id, bug.title
+        ))?;
+    }
+    term.write_line("Review payload:")?;
+    term.write_line(&serde_json::to_string_pretty(body)?)?;
+    Ok(())
+}
+
+// ── Bulk close helpers ──────────────────────────────────────────────
+
+/// Attempts per bug before a transient failure is reported.
+const CLOSE_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry; each further retry waits one step longer.
+const CLOSE_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CloseOutcome {
+    Closed,
+    Failed,
+}
+
+/// How closing one bug of a bulk close went, as rendered in the results
+/// table and written by `--report`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CloseResult {
+    bug_id: String,
+    outcome: CloseOutcome,
+    attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    review: Option<BugReview>,
+}
 
+/// Close one bug, retrying transient failures (network errors, rate limits,
+/// 5xx) up to `CLOSE_ATTEMPTS` times.
+async fn close_with_retry(
+    client: &ApiClient,
+    id: &BugId,
+    state: BugReviewState,
+    dismissal_reason: Option<BugDismissalReason>,
+    notes: Option<&str>,
+) -> CloseResult {
+    let mut attempts = 0;
     loop {
-        let response = client
-            .list_bugs(repo_id, status, BUG_PAGE_SIZE, offset, scan_id)
+        attempts += 1;
+        match client
+            .update_bug_close(id, state, dismissal_reason, notes)
             .await
-            .context("Failed to fetch bugs from repository")?;
+        {
+            Ok(review) => {
+                return CloseResult {
+                    bug_id: id.to_string(),
+                    outcome: CloseOutcome::Closed,
+                    attempts,
+                    error: None,
+                    review: Some(review),
+                };
+            }
+            Err(err) if attempts < CLOSE_ATTEMPTS && is_transient_error(&err) => {
+                sleep(CLOSE_RETRY_DELAY * attempts).await;
+            }
+            Err(err) => {
+                return CloseResult {
+                    bug_id: id.to_string(),
+                    outcome: CloseOutcome::Failed,
+                    attempts,
+                    error: Some(format!("{err:#}")),
+                    review: None,
+                };
+            }
+        }
+    }
+}
 
-        let total = usize::try_from(response.total.max(0)).unwrap_or(0);
-        let page_len = response.bugs.len();
-        all_bugs.extend(response.bugs);
+/// Close every bug in `ids`, up to `--concurrency` at a time. Results come
+/// back in input order, and a failure doesn't stop the remaining closes.
+async fn close_all(
+    client: &ApiClient,
+    ids: &[BugId],
+    state: BugReviewState,
+    dismissal_reason: Option<BugDismissalReason>,
+    notes: Option<&str>,
+) -> Vec<CloseResult> {
+    stream::iter(ids)
+        .map(|id| close_with_retry(client, id, state, dismissal_reason, notes))
+        .buffered(concurrency::limit())
+        .collect()
+        .await
+}
 
-        if page_len == 0 || (usize::try_from(offset).unwrap_or(0) + page_len) >= total {
-            break;
+fn write_close_report(path: &Path, results: &[CloseResult]) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(results)?)
+        .with_context(|| format!("Failed to write report to {}", path.display()))
+}
+
+/// Lines of the bulk close results table: bug ID, outcome, error.
+fn close_results_table(results: &[CloseResult], state: BugReviewState) -> Vec<String> {
+    let id_width = results
+        .iter()
+        .map(|r| r.bug_id.len())
+        .chain(["BUG ID".len()])
+        .max()
+        .unwrap_or_default();
+    let closed = format!("closed as {}", review_state_label(&state));
+    let outcome_width = closed.len().max("failed".len());
+
+    let mut lines = vec![format!(
+        "{:<id_width$}  {:<outcome_width$}  ERROR",
+        "BUG ID", "OUTCOME"
+    )];
+    for result in results {
+        let line = match result.outcome {
+            CloseOutcome::Closed => {
+                format!("{:<id_width$}  {}", result.bug_id, style(&closed).green())
+            }
+            CloseOutcome::Failed => {
+                let retried = if result.attempts > 1 {
+                    format!(" (after {} attempts)", result.attempts)
+                } else {
+                    String::new()
+                };
+                format!(
+                    "{:<id_width$}  {}  {}{retried}",
+                    result.bug_id,
+                    style(format!("{:<outcome_width$}", "failed")).red(),
+                    result.error.as_deref().unwrap_or_default()
+                )
+            }
+        };
+        lines.push(line);
+    }
+    lines
+}
+
+fn render_close_results(
+    results: &[CloseResult],
+    state: BugReviewState,
+    format: &crate::OutputFormat,
+) -> Result<()> {
+    let term = stdout();
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Yaml => {
+            write_structured(results, format)?;
+        }
+        crate::OutputFormat::Table => {
+            for line in close_results_table(results, state) {
+                term.write_line(&line)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Closed bugs listed by ID in a `--notify-slack` summary; the rest are
+/// counted.
+const SLACK_CLOSE_IDS: usize = 20;
+
+/// A closed bug for Slack: its ID, then the dismissal reason and notes when
+/// given.
+fn close_line(bug_id: &str, reason: Option<BugDismissalReason>, notes: Option<&str>) -> String {
+    let mut line = format!("`{bug_id}`");
+    if let Some(reason) = &reason {
+        line.push_str(" — ");
+        line.push_str(dismissal_reason_label(reason));
+    }
+    if let Some(notes) = notes.filter(|notes| !notes.trim().is_empty()) {
+        for note in notes.lines() {
+            line.push_str("\n> ");
+            line.push_str(note);
+        }
+    }
+    line
+}
+
+/// The `--notify-slack` summary of a bulk close.
+fn close_message(
+    results: &[CloseResult],
+    state: BugReviewState,
+    reason: Option<BugDismissalReason>,
+) -> notify::Message {
+    let closed: Vec<&str> = results
+        .iter()
+        .filter(|r| r.outcome == CloseOutcome::Closed)
+        .map(|r| r.bug_id.as_str())
+        .collect();
+    let failed = results.len() - closed.len();
+    let label = review_state_label(&state);
+    let title = if failed == 0 {
+        format!("{} bugs closed as {label}", closed.len())
+    } else {
+        format!(
+            "{} of {} bugs closed as {label}",
+            closed.len(),
+            results.len()
+        )
+    };
+    let mut lines: Vec<String> = closed
+        .iter()
+        .take(SLACK_CLOSE_IDS)
+        .map(|id| format!("• `{id}`"))
+        .collect();
+    if closed.len() > SLACK_CLOSE_IDS {
+        lines.push(format!("…and {} more", closed.len() - SLACK_CLOSE_IDS));
+    }
+    if let Some(reason) = &reason {
+        lines.push(format!("Reason: {}", dismissal_reason_label(reason)));
+    }
+    if failed > 0 {
+        lines.push(format!("{failed} failed to close"));
+    }
+    notify::Message {
+        source: "bugs close".to_string(),
+        title,
+        body: lines.join("\n"),
+    }
+}
+
+/// Print a failed notification or hook as a warning; the action it reports
+/// on has already happened.
+fn warn_on_failure(result: Result<()>) {
+    if let Err(err) = result {
+        Term::stderr()
+            .write_line(&format!("{}", style(format!("Warning: {err:#}")).yellow()))
+            .ok();
+    }
+}
+
+/// Run the `[hooks]` entry for `event` on bug `id`, if there is one. The
+/// bug is fetched afresh so the hook sees the review just made.
+async fn run_bug_hook(client: &ApiClient, id: &BugId, event: hooks::Event, cli: &crate::Cli) {
+    let configured = load_config().is_ok_and(|config| config.hooks.get(event).is_some());
+    if !configured {
+        return;
+    }
+    let result = match client.get_bug(id).await {
+        Ok(bug) => hooks::run(event, &bug, cli).await,
+        Err(err) => Err(err.context(format!(
+            "Failed to fetch bug {id} for the {} hook",
+            event.name()
+        ))),
+    };
+    warn_on_failure(result);
+}
+
+// ── Watch helpers ───────────────────────────────────────────────────
+
+/// Return the bugs not yet in `seen`, recording them as seen.
+fn take_new_bugs(seen: &mut BTreeSet<String>, bugs: Vec<Bug>) -> Vec<Bug> {
+    bugs.into_iter()
+        .filter(|bug| seen.insert(bug.id.to_string()))
+        .collect()
+}
+
+fn watch_line(bug: &Bug) -> String {
+    let marker = if bug.is_security_vulnerability == Some(true) {
+        style("● vuln").red().bold()
+    } else {
+        style("● bug").yellow()
+    };
+    format!(
+        "{marker} {} {} {}",
+        style(format_datetime(bug.created_at)).dim(),
+        bug.title,
+        style(&bug.id).dim()
+    )
+}
+
+fn new_bug_title(repo: &str, bug: &Bug) -> String {
+    if bug.is_security_vulnerability == Some(true) {
+        format!("New security vulnerability in {repo}")
+    } else {
+        format!("New bug in {repo}")
+    }
+}
+
+fn notify_new_bug(repo: &str, bug: &Bug) -> Result<()> {
+    Notification::new()
+        .appname("Detail")
+        .summary(&new_bug_title(repo, bug))
+        .body(&bug.title)
+        .show()
+        .context("Failed to show desktop notification")?;
+    Ok(())
+}
+
+/// Live "Fetched N bugs…" counter on stderr for long-running fetches such as
+/// `bugs export`. Only drawn when stderr is a terminal so redirected logs
+/// stay clean.
+pub(crate) struct FetchProgress {
+    term: Term,
+    fetched: AtomicUsize,
+}
+
+impl FetchProgress {
+    fn new() -> Option<Self> {
+        let term = Term::stderr();
+        if !term.is_term() {
+            return None;
         }
-        offset += u32::try_from(page_len).unwrap_or(u32::MAX);
+        Some(Self {
+            term,
+            fetched: AtomicUsize::new(0),
+        })
+    }
+
+    fn advance(&self, count: usize) {
+        let fetched = self.fetched.fetch_add(count, Ordering::Relaxed) + count;
+        self.term.clear_line().ok();
+        self.term
+            .write_str(&format!("Fetched {fetched} bugs…"))
+            .ok();
     }
 
-    Ok(all_bugs)
+    fn finish(&self) {
+        self.term.clear_line().ok();
+    }
 }
 
 /// Dedupe a slice of `BugReviewState` while preserving first-seen order.
@@ -394,17 +1622,58 @@ async fn fetch_all_bugs_multi_status(
     repo_id: &RepoId,
     statuses: &[BugReviewState],
     scan_id: Option<&ListPublicBugsWorkflowRequestId>,
+    progress: Option<&FetchProgress>,
 ) -> Result<Vec<Bug>> {
     let mut combined = Vec::new();
     for status in dedupe_statuses(statuses) {
-        let bugs = fetch_all_bugs(client, repo_id, status, scan_id).await?;
+        let bugs = client
+            .list_all_bugs_with(repo_id, status, scan_id, |count| {
+                if let Some(progress) = progress {
+                    progress.advance(count);
+                }
+            })
+            .await?;
         combined.extend(bugs);
     }
     Ok(combined)
 }
 
+/// Fetch every bug for each of `repos`, continuing past per-repo failures
+/// so one broken repo doesn't hide results from the rest.
+///
+/// A single repo keeps the plain fail-fast behaviour. With several repos the
+/// call only fails outright when every repo fails; otherwise the failures
+/// are returned alongside the bugs that were fetched.
+async fn fetch_all_bugs_multi_repo(
+    client: &ApiClient,
+    repos: &[(String, RepoId)],
+    statuses: &[BugReviewState],
+    scan_id: Option<&ListPublicBugsWorkflowRequestId>,
+    progress: Option<&FetchProgress>,
+) -> Result<(Vec<Bug>, Vec<RepoFailure>)> {
+    let mut combined = Vec::new();
+    let mut failures = Vec::new();
+    for (name, repo_id) in repos {
+        match fetch_all_bugs_multi_status(client, repo_id, statuses, scan_id, progress).await {
+            Ok(bugs) => combined.extend(bugs),
+            Err(error) if repos.len() > 1 => failures.push(RepoFailure {
+                repo: name.clone(),
+                error,
+            }),
+            Err(error) => return Err(error),
+        }
+    }
+    if !failures.is_empty() && failures.len() == repos.len() {
+        bail!(
+            "Failed to fetch bugs from every repository:\n{}",
+            format_repo_failures(&failures)
+        );
+    }
+    Ok((combined, failures))
+}
+
 /// Fetch up to `max_items` bugs for a single `status`, paginating through
-/// the API in `BUG_PAGE_SIZE` chunks so we never exceed the server limit.
+/// the API in the client's page size so we never exceed the server limit.
 ///
 /// Returns the collected bugs and the total count reported by the API.
 async fn fetch_bugs_up_to(
@@ -424,7 +1693,7 @@ async fn fetch_bugs_up_to(
         if remaining == 0 {
             break;
         }
-        let page_size = BUG_PAGE_SIZE.min(remaining);
+        let page_size = client.pagination().page_size.min(remaining);
         let response = client
             .list_bugs(repo_id, status, page_size, offset, scan_id)
             .await
@@ -447,7 +1716,7 @@ async fn fetch_bugs_up_to(
 /// order and then paginated from the combined result.
 ///
 /// Each status is fetched from offset 0 up to `offset + limit` items
-/// (paginating internally in `BUG_PAGE_SIZE` chunks to respect the API
+/// (paginating internally in page-size chunks to respect the API
 /// limit). The merged list is then advanced past `offset` items and
 /// truncated to at most `limit` items, ensuring every bug is reachable
 /// across pages.
@@ -479,7 +1748,31 @@ async fn fetch_page_multi_status(
     Ok((combined, total))
 }
 
+/// `filter` narrowed to files under `prefix`, a directory relative to the
+/// repository root like `services/auth/`. At the root it is unchanged.
+fn with_path_prefix(filter: Option<&str>, prefix: &str) -> Result<Option<String>> {
+    let dir = prefix.trim_end_matches('/');
+    if dir.is_empty() {
+        return Ok(filter.map(str::to_owned));
+    }
+    let quote = if dir.contains('\'') { '"' } else { '\'' };
+    if dir.contains(quote) {
+        bail!(Failure::usage(format!(
+            "--here can't filter on the path {dir:?}; use --filter instead"
+        )));
+    }
+    let path = format!("path:{quote}{dir}/**{quote}");
+    Ok(Some(match filter {
+        Some(filter) => format!("({filter}) && {path}"),
+        None => path,
+    }))
+}
+
 pub async fn handle(command: &BugCommands, cli: &crate::Cli) -> Result<()> {
+    // The demo runs on built-in data, so it must not require a login.
+    if let BugCommands::Demo(demo) = command {
+        return handle_demo(demo);
+    }
     let client = cli.create_client()?;
 
     match command {
@@ -488,25 +1781,48 @@ pub async fn handle(command: &BugCommands, cli: &crate::Cli) -> Result<()> {
             status,
             vulns,
             introduced_by,
+            mine,
             scan_id,
             since,
             until,
+            filter,
+            here,
             all,
+            sample,
+            seed,
             limit,
             page,
             format,
+            ids_only,
+            template,
+            dedupe_titles,
+            expand,
+            group_by,
         } => {
-            // Resolve owner/repo or repo to internal repo ID
-            let repo = resolve_repo_arg(repo.as_deref())?;
-            let resolved_repo_id = resolve_repo_id(&client, &repo)
-                .await
-                .context("Failed to resolve repository identifier")?;
-
-            let scan_id: Option<ListPublicBugsWorkflowRequestId> = scan_id
-                .as_deref()
-                .map(TryInto::try_into)
-                .transpose()
-                .context("Invalid scan ID format (expected wr_...)")?;
+            let format = &format.unwrap_or_default();
+            let template = template::parse_flag(template.as_deref())?;
+            let lines = if *ids_only {
+                Some(Lines::Ids)
+            } else {
+                template.as_ref().map(Lines::Template)
+            };
+            let filter = if *here {
+                let Some(prefix) = repo_prefix() else {
+                    bail!(Failure::usage(
+                        "--here needs to run inside a checkout of the repository"
+                    ));
+                };
+                with_path_prefix(filter.as_deref(), &prefix)?
+            } else {
+                filter.clone()
+            };
+            let filter = filter::parse_flag(filter.as_deref())?;
+            let introduced_by = &if *mine {
+                local_author_aliases()?
+            } else {
+                introduced_by.clone()
+            };
+            let scan_id = parse_scan_id(scan_id.as_deref())?;
 
             // Resolve --since/--until against the same `now` so a relative
             // window like `--since 7d --until 1d` reads as a single half-open
@@ -514,119 +1830,222 @@ pub async fn handle(command: &BugCommands, cli: &crate::Cli) -> Result<()> {
             let now = chrono::Utc::now();
             let since_ms = resolve_time_flag("--since", since.as_deref(), now)?;
             let until_ms = resolve_time_flag("--until", until.as_deref(), now)?;
+            let statuses = filter_statuses(filter.as_ref(), status);
 
             // The bugs API takes a single status per request. When the
             // user asks for client-side filters (`--all`, `--vulns`,
-            // `--introduced-by`, `--since`, `--until`) we must fetch every
+            // `--introduced-by`, `--since`, `--until`, `--filter`) we must fetch every
             // bug to apply them. Multi-status alone does NOT require a full
             // fetch — we can issue one page-sized request per status.
             let needs_full_fetch = *all
+                || *dedupe_titles
+                || sample.is_some()
                 || *vulns
                 || !introduced_by.is_empty()
                 || since_ms.is_some()
-                || until_ms.is_some();
+                || until_ms.is_some()
+                || filter.is_some();
             let multi_status = status.len() > 1;
+            let single_status = status.first().copied().unwrap_or(BugReviewState::Pending);
+            let offset = page_to_offset(*page, *limit);
+
+            // Resolve owner/repo, repo, or an `@name` shortcut to internal
+            // repo IDs. Shortcuts may expand to several repos. When one page
+            // of bugs is all that's needed, its request can start as soon as
+            // the repo ID is known, alongside the rest of the resolution.
+            let repo = resolve_repo_arg(repo.as_deref())?;
+            let (repo_ids, prefetched) = if needs_full_fetch || multi_status {
+                (resolve_repo_ids(&client, &repo).await, None)
+            } else {
+                let client = &client;
+                let scan_id = scan_id.as_ref();
+                match resolve_repo_ids_prefetching(client, &repo, |repo_id| async move {
+                    client
+                        .list_bugs(&repo_id, single_status, *limit, offset, scan_id)
+                        .await
+                })
+                .await
+                {
+                    Ok((repo_ids, bugs)) => (Ok(repo_ids), bugs),
+                    Err(err) => (Err(err), None),
+                }
+            };
+            let repo_ids = repo_ids.context("Failed to resolve repository identifier")?;
+            let (_, resolved_repo_id) = repo_ids
+                .first()
+                .context("Repository identifier did not resolve to any repository")?;
+
+            // Multi-repo shortcuts take the full-fetch path: pages can't be
+            // merged server-side across repos.
+            let needs_full_fetch = needs_full_fetch || repo_ids.len() > 1;
 
             if needs_full_fetch {
-                let all_bugs = fetch_all_bugs_multi_status(
+                let (all_bugs, failures) = fetch_all_bugs_multi_repo(
                     &client,
-                    &resolved_repo_id,
-                    status,
+                    &repo_ids,
+                    &statuses,
                     scan_id.as_ref(),
+                    None,
                 )
                 .await?;
-                let mut filtered = all_bugs;
-                if since_ms.is_some() || until_ms.is_some() {
-                    filtered = filter_by_time_range(&filtered, since_ms, until_ms);
-                }
-                if *vulns {
-                    filtered = filter_vulns_only(&filtered);
-                }
-                if !introduced_by.is_empty() {
-                    let pre_filter = filtered;
-                    filtered = filter_by_introduced_by(&pre_filter, introduced_by);
-                    if filtered.is_empty() {
-                        if matches!(format, crate::OutputFormat::Table) {
-                            let hint = empty_filter_hint(&pre_filter, *vulns);
-                            Term::stdout().write_line(&hint)?;
+                // Render whatever was fetched, then report failed repos (if
+                // any) so partial results still reach the user.
+                let rendered = 'render: {
+                    let mut filtered = all_bugs;
+                    if let Some(filter) = &filter {
+                        filtered = filter.apply(filtered, now.timestamp_millis());
+                    }
+                    if since_ms.is_some() || until_ms.is_some() {
+                        filtered = filter_by_time_range(&filtered, since_ms, until_ms);
+                    }
+                    if *vulns {
+                        filtered = filter_vulns_only(&filtered);
+                    }
+                    if !introduced_by.is_empty() {
+                        let pre_filter = filtered;
+                        filtered = filter_by_introduced_by(&pre_filter, introduced_by);
+                        if filtered.is_empty() {
+                            if matches!(format, ListFormat::Output(crate::OutputFormat::Table))
+                                && lines.is_none()
+                            {
+                                let hint = empty_filter_hint(&pre_filter, *vulns);
+                                stdout().write_line(&hint)?;
+                            }
+                            break 'render output_bugs(
+                                &filtered, 0, *page, *limit, format, lines, *group_by,
+                            );
                         }
-                        return output_list(&filtered, 0, *page, *limit, format);
+                    } else if filtered.is_empty() {
+                        // Filters (or `--all` against an empty repo) removed
+                        // everything. Print the hint so the user gets context
+                        // beyond an empty table.
+                        if matches!(format, ListFormat::Output(crate::OutputFormat::Table))
+                            && lines.is_none()
+                        {
+                            let hint = empty_filter_hint(&filtered, *vulns);
+                            stdout().write_line(&hint)?;
+                        }
+                        break 'render output_bugs(
+                            &filtered, 0, *page, *limit, format, lines, *group_by,
+                        );
                     }
-                } else if filtered.is_empty() {
-                    // Filters (or `--all` against an empty repo) removed
-                    // everything. Print the hint so the user gets context
-                    // beyond an empty table.
-                    if matches!(format, crate::OutputFormat::Table) {
-                        let hint = empty_filter_hint(&filtered, *vulns);
-                        Term::stdout().write_line(&hint)?;
+                    if *dedupe_titles {
+                        let groups = group_by_title(&filtered, *expand);
+                        let total = groups.len();
+                        if *all {
+                            let effective_limit = u32::try_from(total.max(1)).unwrap_or(u32::MAX);
+                            break 'render output_list(&groups, total, 1, effective_limit, format);
+                        }
+                        let page_items = paginate_items(&groups, *page, *limit);
+                        break 'render output_list(&page_items, total, *page, *limit, format);
                     }
-                    return output_list(&filtered, 0, *page, *limit, format);
-                }
-                let total = filtered.len();
-                if *all {
-                    // No client-side paging: emit every matching bug as a
-                    // single page so JSON consumers and table users alike
-                    // see the full result set.
-                    let effective_limit = u32::try_from(total.max(1)).unwrap_or(u32::MAX);
-                    return output_list(&filtered, total, 1, effective_limit, format);
-                }
-                let page_items = paginate_items(&filtered, *page, *limit);
-                output_list(&page_items, total, *page, *limit, format)
+                    if let Some(n) = sample {
+                        let mut rng = seed.map_or_else(
+                            || SmallRng::seed_from_u64(rand::random()),
+                            SmallRng::seed_from_u64,
+                        );
+                        let n = usize::try_from(*n).unwrap_or(usize::MAX);
+                        filtered = sample_bugs(filtered, n, &mut rng);
+                    }
+                    let total = filtered.len();
+                    if *all || sample.is_some() {
+                        // No client-side paging: emit every matching bug as a
+                        // single page so JSON consumers and table users alike
+                        // see the full result set.
+                        let effective_limit = u32::try_from(total.max(1)).unwrap_or(u32::MAX);
+                        break 'render output_bugs(
+                            &filtered,
+                            total,
+                            1,
+                            effective_limit,
+                            format,
+                            lines,
+                            *group_by,
+                        );
+                    }
+                    let page_items = paginate_items(&filtered, *page, *limit);
+                    output_bugs(&page_items, total, *page, *limit, format, lines, *group_by)
+                };
+                rendered?;
+                report_repo_failures(&failures, repo_ids.len())
             } else if multi_status {
                 // Multiple statuses but no client-side filters: fetch one
                 // page per status and merge, avoiding a full exhaust.
                 let (bugs, total) = fetch_page_multi_status(
                     &client,
-                    &resolved_repo_id,
+                    resolved_repo_id,
                     status,
                     *limit,
                     *page,
                     scan_id.as_ref(),
                 )
                 .await?;
-                output_list(&bugs, total, *page, *limit, format)
+                output_bugs(&bugs, total, *page, *limit, format, lines, *group_by)
             } else {
                 // Single-status, no other filters: keep the original
                 // single-page server fetch — cheaper and lets the API drive
                 // pagination.
-                let single_status = status.first().copied().unwrap_or(BugReviewState::Pending);
-                let offset = page_to_offset(*page, *limit);
-                let bugs = client
-                    .list_bugs(
-                        &resolved_repo_id,
-                        single_status,
-                        *limit,
-                        offset,
-                        scan_id.as_ref(),
-                    )
-                    .await
-                    .context("Failed to fetch bugs from repository")?;
+                let bugs = match prefetched {
+                    Some(bugs) => bugs,
+                    None => {
+                        client
+                            .list_bugs(
+                                resolved_repo_id,
+                                single_status,
+                                *limit,
+                                offset,
+                                scan_id.as_ref(),
+                            )
+                            .await
+                    }
+                }
+                .context("Failed to fetch bugs from repository")?;
 
-                output_list(
+                output_bugs(
                     &bugs.bugs,
                     usize::try_from(bugs.total.max(0)).unwrap_or(0),
                     *page,
                     *limit,
                     format,
+                    lines,
+                    *group_by,
                 )
             }
         }
 
-        BugCommands::Show { bug_id, format } => {
+        BugCommands::Show {
+            bug_id,
+            blame,
+            format,
+            template,
+        } => {
+            let format = &format.unwrap_or_default();
+            let template = template::parse_flag(template.as_deref())?;
             let bug_id: BugId = bug_id
                 .as_str()
                 .try_into()
-                .context("Invalid bug ID format (expected bug_...)")?;
+                .context(Failure::usage("Invalid bug ID format (expected bug_...)"))?;
             let bug = client
                 .get_bug(&bug_id)
                 .await
                 .context("Failed to fetch bug details")?;
 
-            if matches!(format, crate::OutputFormat::Json) {
-                Term::stdout().write_line(&serde_json::to_string_pretty(&bug)?)?;
+            if let Some(template) = &template {
+                stdout().write_line(&template.render(&bug)?)?;
                 return Ok(());
             }
-            render_bug_show(&bug)
+            if matches!(
+                format,
+                crate::OutputFormat::Json | crate::OutputFormat::Yaml
+            ) {
+                return write_structured(&bug, format);
+            }
+            let trend = fetch_open_bug_trend(&client, &bug.repo_id).await;
+            render_bug_show(&bug, trend.as_deref())?;
+            if *blame {
+                blame::print_last_touched(&bug)?;
+            }
+            Ok(())
         }
 
         BugCommands::Close {
@@ -634,13 +2053,42 @@ pub async fn handle(command: &BugCommands, cli: &crate::Cli) -> Result<()> {
             state,
             dismissal_reason,
             notes,
+            dry_run,
+            report,
+            filter,
+            notify_slack,
             format,
         } => {
-            let bug_id: BugId = bug_id
-                .as_str()
-                .try_into()
-                .context("Invalid bug ID format (expected bug_...)")?;
-            let is_interactive = Term::stdout().is_term();
+            let format = &format.unwrap_or_default();
+            let slack_url = notify_slack.then(notify::slack_webhook_url).transpose()?;
+            // `-` reads the IDs from stdin. Stdin is then consumed by the ID
+            // list, so fall back to non-interactive flag validation.
+            let from_stdin = bug_id == "-";
+            if report.is_some() && !from_stdin {
+                bail!(Failure::usage(
+                    "--report is only supported when reading bug IDs from stdin (`-`)"
+                ));
+            }
+            if filter.is_some() && !from_stdin {
+                bail!(Failure::usage(
+                    "--filter is only supported when reading bug IDs from stdin (`-`)"
+                ));
+            }
+            let filter = filter::parse_flag(filter.as_deref())?;
+            let mut bug_ids: Vec<BugId> = if from_stdin {
+                let input =
+                    io::read_to_string(io::stdin()).context("Failed to read bug IDs from stdin")?;
+                parse_bug_ids(&input)?
+            } else {
+                vec![bug_id
+                    .as_str()
+                    .try_into()
+                    .context(Failure::usage("Invalid bug ID format (expected bug_...)"))?]
+            };
+            if let Some(filter) = &filter {
+                bug_ids = matching_bug_ids(&client, bug_ids, filter).await?;
+            }
+            let is_interactive = !from_stdin && Term::stdout().is_term();
 
             let (state, dismissal_reason, notes) =
                 validate_close_flags(*state, *dismissal_reason, notes.clone(), is_interactive)?;
@@ -666,48 +2114,460 @@ pub async fn handle(command: &BugCommands, cli: &crate::Cli) -> Result<()> {
                 None => None,
             };
 
-            let review = client
-                .update_bug_close(&bug_id, state, dismissal_reason, notes.as_deref())
+            if *dry_run {
+                // Resolve every bug first so typos and missing IDs surface
+                // here rather than halfway through a real bulk close.
+                let mut bugs = Vec::with_capacity(bug_ids.len());
+                for id in &bug_ids {
+                    let bug = client
+                        .get_bug(id)
+                        .await
+                        .with_context(|| format!("Failed to fetch bug {id}"))?;
+                    bugs.push(bug);
+                }
+                let body = bug_review_body(state, dismissal_reason, notes.as_deref());
+                return render_close_dry_run(&bugs, &body, format, from_stdin);
+            }
+
+            if from_stdin {
+                let results =
+                    close_all(&client, &bug_ids, state, dismissal_reason, notes.as_deref()).await;
+                if let Some(path) = report {
+                    write_close_report(path, &results)?;
+                }
+                render_close_results(&results, state, format)?;
+                for (id, result) in bug_ids.iter().zip(&results) {
+                    if result.outcome == CloseOutcome::Closed {
+                        run_bug_hook(&client, id, hooks::Event::Close, cli).await;
+                    }
+                }
+                if let Some(url) = &slack_url {
+                    let message = close_message(&results, state, dismissal_reason);
+                    warn_on_failure(notify::post_slack(url, &message, cli).await);
+                }
+                let failed = results
+                    .iter()
+                    .filter(|r| r.outcome == CloseOutcome::Failed)
+                    .count();
+                if failed > 0 {
+                    bail!(Failure::partial(format!(
+                        "{failed} of {} bugs failed to close",
+                        results.len()
+                    )));
+                }
+                return Ok(());
+            }
+
+            let Some(id) = bug_ids.first() else {
+                bail!(Failure::usage("No bug ID given"));
+            };
+            let review = match client
+                .update_bug_close(id, state, dismissal_reason, notes.as_deref())
+                .await
+            {
+                Ok(review) => review,
+                Err(err) if is_interactive && is_network_error(&err) => {
+                    let queued = QueuedReview::new(
+                        &id.to_string(),
+                        state,
+                        dismissal_reason,
+                        notes.as_deref(),
+                        chrono::Utc::now().timestamp_millis(),
+                    );
+                    return offer_to_queue(
+                        err.context(format!("Failed to close bug {id}")),
+                        queued,
+                    );
+                }
+                Err(err) => return Err(err.context(format!("Failed to close bug {id}"))),
+            };
+            if let Some(url) = &slack_url {
+                let message = notify::Message {
+                    source: "bugs close".to_string(),
+                    title: format!("Bug closed as {}", review_state_label(&state)),
+                    body: close_line(&id.to_string(), dismissal_reason, notes.as_deref()),
+                };
+                warn_on_failure(notify::post_slack(url, &message, cli).await);
+            }
+            run_bug_hook(&client, id, hooks::Event::Close, cli).await;
+
+            if matches!(
+                format,
+                crate::OutputFormat::Json | crate::OutputFormat::Yaml
+            ) {
+                // Emit only the BugReview — the human-friendly success
+                // banner would corrupt the structured output.
+                return write_structured(&review, format);
+            }
+
+            let label = review_state_label(&state);
+            stdout()
+                .write_line(&format!(
+                    "{}",
+                    style(format!("✓ Bug closed as: {label}")).green()
+                ))
+                .ok();
+            Ok(())
+        }
+
+        BugCommands::Reopen { bug_id } => {
+            let bug_id: BugId = bug_id
+                .as_str()
+                .try_into()
+                .context(Failure::usage("Invalid bug ID format (expected bug_...)"))?;
+
+            // Don't pass notes from the CLI — `create_public_bug_review`
+            // replaces the whole review row, so any value (including the
+            // implicit None) overwrites whatever notes the existing review
+            // already carried. Until the API gains PATCH semantics, the
+            // safe shape for `reopen` is a pure state flip.
+            match client
+                .update_bug_close(&bug_id, BugReviewState::Pending, None, None)
+                .await
+            {
+                Ok(_) => {}
+                Err(err) if Term::stdout().is_term() && is_network_error(&err) => {
+                    let queued = QueuedReview::new(
+                        &bug_id.to_string(),
+                        BugReviewState::Pending,
+                        None,
+                        None,
+                        chrono::Utc::now().timestamp_millis(),
+                    );
+                    return offer_to_queue(err.context("Failed to reopen bug"), queued);
+                }
+                Err(err) => return Err(err.context("Failed to reopen bug")),
+            }
+            run_bug_hook(&client, &bug_id, hooks::Event::Reopen, cli).await;
+
+            stdout()
+                .write_line(&format!("{}", style("✓ Bug reopened (pending)").green()))
+                .ok();
+            Ok(())
+        }
+
+        BugCommands::Fix { bug_id } => fix::handle(&client, bug_id).await,
+
+        BugCommands::Diff { bug_id } => diff::handle(&client, bug_id).await,
+
+        BugCommands::Patch {
+            bug_id,
+            apply,
+            check,
+        } => {
+            let mode = if *apply {
+                PatchMode::Apply
+            } else if *check {
+                PatchMode::Check
+            } else {
+                PatchMode::Print
+            };
+            patch::handle(&client, bug_id, mode).await
+        }
+
+        BugCommands::Export {
+            repo,
+            status,
+            vulns,
+            introduced_by,
+            scan_id,
+            since,
+            until,
+            filter,
+            format,
+            output,
+            redact,
+        } => {
+            // Check the configured patterns before fetching anything.
+            let redactor = redact
+                .then(|| load_config().and_then(|config| Redactor::new(&config.redact_patterns)))
+                .transpose()?;
+            let repo = resolve_repo_arg(repo.as_deref())?;
+            let repo_ids = resolve_repo_ids(&client, &repo)
+                .await
+                .context("Failed to resolve repository identifier")?;
+            let scan_id = parse_scan_id(scan_id.as_deref())?;
+
+            let filter = filter::parse_flag(filter.as_deref())?;
+            let now = chrono::Utc::now();
+            let since_ms = resolve_time_flag("--since", since.as_deref(), now)?;
+            let until_ms = resolve_time_flag("--until", until.as_deref(), now)?;
+
+            let progress = FetchProgress::new();
+            let fetched = fetch_all_bugs_multi_repo(
+                &client,
+                &repo_ids,
+                &filter_statuses(filter.as_ref(), status),
+                scan_id.as_ref(),
+                progress.as_ref(),
+            )
+            .await;
+            if let Some(progress) = &progress {
+                progress.finish();
+            }
+            let (all_bugs, failures) = fetched?;
+
+            let mut bugs =
+                apply_client_filters(all_bugs, since_ms, until_ms, *vulns, introduced_by);
+            if let Some(filter) = &filter {
+                bugs = filter.apply(bugs, now.timestamp_millis());
+            }
+            if let Some(redactor) = &redactor {
+                for bug in &mut bugs {
+                    redactor.redact(bug);
+                }
+            }
+            let format = format
+                .or_else(|| output.as_deref().and_then(ExportFormat::from_path))
+                .unwrap_or(ExportFormat::Json);
+            if let Some(path) = output {
+                let content = export::render(&bugs, format)?;
+                export::validate(&content, format)?;
+                write_atomic(path, &content)
+                    .with_context(|| format!("Failed to write export to {}", path.display()))?;
+                Term::stderr()
+                    .write_line(&format!(
+                        "{}",
+                        style(format!(
+                            "✓ Exported {} bugs to {}",
+                            bugs.len(),
+                            path.display()
+                        ))
+                        .green()
+                    ))
+                    .ok();
+            } else {
+                export::write(&mut stdout(), &bugs, format)?;
+            }
+            report_repo_failures(&failures, repo_ids.len())
+        }
+
+        BugCommands::Report {
+            repo,
+            status,
+            filter,
+            format,
+            output,
+        } => {
+            let repo = resolve_repo_arg(repo.as_deref())?;
+            let repo_ids = resolve_repo_ids(&client, &repo)
+                .await
+                .context("Failed to resolve repository identifier")?;
+            let filter = filter::parse_flag(filter.as_deref())?;
+            let progress = FetchProgress::new();
+            let fetched = fetch_all_bugs_multi_repo(
+                &client,
+                &repo_ids,
+                &filter_statuses(filter.as_ref(), status),
+                None,
+                progress.as_ref(),
+            )
+            .await;
+            if let Some(progress) = &progress {
+                progress.finish();
+            }
+            let (mut bugs, failures) = fetched?;
+            let now_ms = chrono::Utc::now().timestamp_millis();
+            if let Some(filter) = &filter {
+                bugs = filter.apply(bugs, now_ms);
+            }
+            let format = format
+                .or_else(|| output.as_deref().and_then(ReportFormat::from_path))
+                .unwrap_or_default();
+            let report = export::render_report(&repo, &bugs, now_ms, format)?;
+
+            if let Some(path) = output {
+                write_atomic(path, &report)
+                    .with_context(|| format!("Failed to write report to {}", path.display()))?;
+                Term::stderr()
+                    .write_line(&format!(
+                        "{}",
+                        style(format!(
+                            "✓ Wrote a report on {} bugs to {}",
+                            bugs.len(),
+                            path.display()
+                        ))
+                        .green()
+                    ))
+                    .ok();
+            } else {
+                write_stdout(&report)?;
+            }
+            report_repo_failures(&failures, repo_ids.len())
+        }
+
+        BugCommands::Watch {
+            repo,
+            interval,
+            notify,
+            sinks,
+        } => {
+            let repo = resolve_repo_arg(repo.as_deref())?;
+            let repo_id = resolve_repo_id(&client, &repo)
                 .await
-                .context("Failed to close bug")?;
+                .context("Failed to resolve repository identifier")?;
 
-            if matches!(format, crate::OutputFormat::Json) {
-                // Emit only the BugReview JSON — the human-friendly success
-                // banner would corrupt the structured output.
-                Term::stdout().write_line(&serde_json::to_string_pretty(&review)?)?;
-                return Ok(());
-            }
+            // The first poll is the baseline: only bugs that show up after
+            // it are reported.
+            let mut seen = BTreeSet::new();
+            let baseline = client
+                .list_all_bugs(&repo_id, BugReviewState::Pending)
+                .await?;
+            take_new_bugs(&mut seen, baseline);
 
-            Term::stdout()
+            let stderr = Term::stderr();
+            stderr
                 .write_line(&format!(
                     "{}",
-                    style(format!("✓ Bug closed as: {}", review_state_label(&state))).green()
+                    style(format!(
+                        "Watching {repo} ({} pending) every {interval}s — press Ctrl+C to stop",
+                        seen.len()
+                    ))
+                    .dim()
                 ))
                 .ok();
-            Ok(())
-        }
-
-        BugCommands::Reopen { bug_id } => {
-            let bug_id: BugId = bug_id
-                .as_str()
-                .try_into()
-                .context("Invalid bug ID format (expected bug_...)")?;
 
-            // Don't pass notes from the CLI — `create_public_bug_review`
-            // replaces the whole review row, so any value (including the
-            // implicit None) overwrites whatever notes the existing review
-            // already carried. Until the API gains PATCH semantics, the
-            // safe shape for `reopen` is a pure state flip.
-            client
-                .update_bug_close(&bug_id, BugReviewState::Pending, None, None)
-                .await
-                .context("Failed to reopen bug")?;
+            // Lines go out as bugs appear, even when piped.
+            let mut out = stdout();
+            let mut notify_failed = false;
+            let mut sinks_failed = false;
+            loop {
+                tokio::select! {
+                    _ = signal::ctrl_c() => break,
+                    () = sleep(Duration::from_secs(*interval)) => {}
+                }
 
-            Term::stdout()
-                .write_line(&format!("{}", style("✓ Bug reopened (pending)").green()))
-                .ok();
+                // A failed poll (network blip, expired token) shouldn't end
+                // the watch; report it and try again next interval.
+                let bugs = match client
+                    .list_all_bugs(&repo_id, BugReviewState::Pending)
+                    .await
+                {
+                    Ok(bugs) => bugs,
+                    Err(err) => {
+                        stderr
+                            .write_line(&format!(
+                                "{}",
+                                style(format!("Warning: poll failed: {err:#}")).yellow()
+                            ))
+                            .ok();
+                        continue;
+                    }
+                };
+
+                for bug in take_new_bugs(&mut seen, bugs) {
+                    out.write_line(&watch_line(&bug))?;
+                    out.flush()?;
+                    if *notify && !notify_failed {
+                        if let Err(err) = notify_new_bug(&repo, &bug) {
+                            // Warn once rather than on every new bug.
+                            notify_failed = true;
+                            stderr
+                                .write_line(&format!(
+                                    "{}",
+                                    style(format!("Warning: {err:#}")).yellow()
+                                ))
+                                .ok();
+                        }
+                    }
+                    if !sinks.is_empty() && !sinks_failed {
+                        let message = notify::Message {
+                            source: "bugs watch".to_string(),
+                            title: new_bug_title(&repo, &bug),
+                            body: format!("{} ({})", bug.title, bug.id),
+                        };
+                        if let Err(err) = notify::send(sinks, &message, cli).await {
+                            sinks_failed = true;
+                            stderr
+                                .write_line(&format!(
+                                    "{}",
+                                    style(format!("Warning: {err:#}")).yellow()
+                                ))
+                                .ok();
+                        }
+                    }
+                }
+            }
             Ok(())
         }
+
+        BugCommands::Alert {
+            repo,
+            max_pending,
+            max_vulns,
+            hysteresis,
+            exec,
+            webhook,
+            sinks,
+            format,
+        } => {
+            let repo = resolve_repo_arg(repo.as_deref())?;
+            let options = AlertOptions {
+                max_pending: *max_pending,
+                max_vulns: *max_vulns,
+                hysteresis: *hysteresis,
+                exec: exec.as_deref(),
+                webhook: webhook.as_deref(),
+                sinks,
+            };
+            alert::handle(&client, &repo, &options, &format.unwrap_or_default(), cli).await
+        }
+        BugCommands::Digest {
+            repo,
+            since,
+            notify_slack,
+            format,
+        } => {
+            let repo = resolve_repo_arg(repo.as_deref())?;
+            digest::handle(
+                &client,
+                &repo,
+                since,
+                *notify_slack,
+                &format.unwrap_or_default(),
+                cli,
+            )
+            .await
+        }
+        BugCommands::Reconcile {
+            repo,
+            apply,
+            format,
+        } => {
+            let repo = resolve_repo_arg(repo.as_deref())?;
+            reconcile::handle(&client, &repo, *apply, &format.unwrap_or_default()).await
+        }
+        BugCommands::ExportIssue {
+            bug_id,
+            jira,
+            gitlab,
+            force,
+            dry_run,
+            format,
+        } => {
+            let target = match (jira, gitlab) {
+                (Some(project), _) => Target::Jira(project),
+                (None, Some(project)) => Target::Gitlab(project),
+                (None, None) => {
+                    bail!(Failure::usage(
+                        "Pass --jira PROJECT-KEY or --gitlab GROUP/PROJECT"
+                    ))
+                }
+            };
+            let options = ExportOptions {
+                force: *force,
+                dry_run: *dry_run,
+            };
+            export_issue::handle(
+                &client,
+                bug_id,
+                target,
+                &options,
+                &format.unwrap_or_default(),
+                cli,
+            )
+            .await
+        }
     }
 }
 
@@ -715,6 +2575,140 @@ pub async fn handle(command: &BugCommands, cli: &crate::Cli) -> Result<()> {
 mod tests {
     use super::*;
 
+    // ── sample_bugs ──────────────────────────────────────────────────
+
+    fn numbered_bugs(count: usize) -> Vec<Bug> {
+        (0..count).map(|i| watch_bug(&format!("bug_{i}"))).collect()
+    }
+
+    fn ids(bugs: &[Bug]) -> Vec<String> {
+        bugs.iter().map(|b| b.id.to_string()).collect()
+    }
+
+    #[test]
+    fn sample_returns_n_distinct_bugs_in_original_order() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let sample = sample_bugs(numbered_bugs(50), 10, &mut rng);
+        assert_eq!(sample.len(), 10);
+        let positions: Vec<usize> = sample
+            .iter()
+            .map(|b| b.id.to_string()["bug_".len()..].parse().unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn sample_is_reproducible_with_same_seed() {
+        let a = sample_bugs(numbered_bugs(50), 5, &mut SmallRng::seed_from_u64(42));
+        let b = sample_bugs(numbered_bugs(50), 5, &mut SmallRng::seed_from_u64(42));
+        assert_eq!(ids(&a), ids(&b));
+    }
+
+    #[test]
+    fn sample_larger_than_population_returns_everything() {
+        let sample = sample_bugs(numbered_bugs(3), 10, &mut SmallRng::seed_from_u64(1));
+        assert_eq!(ids(&sample), ["bug_0", "bug_1", "bug_2"]);
+    }
+
+    // ── bulk close results ───────────────────────────────────────────
+
+    fn close_results() -> Vec<CloseResult> {
+        vec![
+            CloseResult {
+                bug_id: "bug_first".into(),
+                outcome: CloseOutcome::Closed,
+                attempts: 1,
+                error: None,
+                review: None,
+            },
+            CloseResult {
+                bug_id: "bug_2".into(),
+                outcome: CloseOutcome::Failed,
+                attempts: 3,
+                error: Some("API error: 503 Service Unavailable".into()),
+                review: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn close_results_table_keeps_input_order_and_shows_errors() {
+        let lines: Vec<String> = close_results_table(&close_results(), BugReviewState::Resolved)
+            .iter()
+            .map(|line| console::strip_ansi_codes(line).into_owned())
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "BUG ID     OUTCOME             ERROR",
+                "bug_first  closed as Resolved",
+                "bug_2      failed              API error: 503 Service Unavailable (after 3 attempts)",
+            ]
+        );
+    }
+
+    #[test]
+    fn slack_close_summary_counts_failures() {
+        let message = close_message(
+            &close_results(),
+            BugReviewState::Dismissed,
+            Some(BugDismissalReason::WontFix),
+        );
+        assert_eq!(message.title, "1 of 2 bugs closed as Dismissed");
+        assert_eq!(
+            message.body,
+            "• `bug_first`\nReason: Won't Fix\n1 failed to close"
+        );
+        assert_eq!(
+            close_line("bug_1", None, Some("fixed in #12\nsee thread")),
+            "`bug_1`\n> fixed in #12\n> see thread"
+        );
+    }
+
+    #[test]
+    fn close_report_json_is_camel_case_and_omits_empty_fields() {
+        let json = serde_json::to_value(close_results()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                { "bugId": "bug_first", "outcome": "closed", "attempts": 1 },
+                {
+                    "bugId": "bug_2", "outcome": "failed", "attempts": 3,
+                    "error": "API error: 503 Service Unavailable"
+                },
+            ])
+        );
+    }
+
+    // ── take_new_bugs ────────────────────────────────────────────────
+
+    fn watch_bug(id: &str) -> Bug {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "title": "t", "summary": "s", "createdAt": 0,
+            "repoId": "repo_1", "linkedIssues": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn take_new_bugs_reports_each_bug_once() {
+        let mut seen = BTreeSet::new();
+        let first = take_new_bugs(&mut seen, vec![watch_bug("bug_1"), watch_bug("bug_2")]);
+        assert_eq!(first.len(), 2);
+
+        let second = take_new_bugs(&mut seen, vec![watch_bug("bug_2"), watch_bug("bug_3")]);
+        let ids: Vec<String> = second.iter().map(|b| b.id.to_string()).collect();
+        assert_eq!(ids, ["bug_3"]);
+    }
+
+    #[test]
+    fn take_new_bugs_ignores_resolved_then_reappearing_ids() {
+        let mut seen = BTreeSet::new();
+        take_new_bugs(&mut seen, vec![watch_bug("bug_1")]);
+        assert!(take_new_bugs(&mut seen, vec![]).is_empty());
+        assert!(take_new_bugs(&mut seen, vec![watch_bug("bug_1")]).is_empty());
+    }
+
     // ── validate_close_flags ─────────────────────────────────────────
 
     #[test]
@@ -805,6 +2799,62 @@ mod tests {
         assert!(matches!(reason, Some(BugDismissalReason::Duplicate)));
     }
 
+    // ── parse_bug_ids ────────────────────────────────────────────────
+
+    #[test]
+    fn parse_bug_ids_reads_one_per_line() {
+        let ids = parse_bug_ids("bug_1\nbug_2\n").unwrap();
+        let ids: Vec<String> = ids.iter().map(ToString::to_string).collect();
+        assert_eq!(ids, vec!["bug_1", "bug_2"]);
+    }
+
+    #[test]
+    fn parse_bug_ids_skips_blank_lines_and_whitespace() {
+        let ids = parse_bug_ids("  bug_1  \n\n\r\nbug_2\r\n").unwrap();
+        let ids: Vec<String> = ids.iter().map(ToString::to_string).collect();
+        assert_eq!(ids, vec!["bug_1", "bug_2"]);
+    }
+
+    #[test]
+    fn parse_bug_ids_rejects_empty_input() {
+        let err = parse_bug_ids("\n  \n").unwrap_err();
+        assert!(err.to_string().contains("No bug IDs"));
+    }
+
+    #[test]
+    fn parse_bug_ids_rejects_malformed_id() {
+        let err = parse_bug_ids("bug_1\nnot-a-bug\n").unwrap_err();
+        assert!(err.to_string().contains("not-a-bug"));
+    }
+
+    // ── dry_run_json ─────────────────────────────────────────────────
+
+    #[test]
+    fn dry_run_json_single_bug_is_object() {
+        let bugs = sample_bugs();
+        let body = bug_review_body(
+            BugReviewState::Dismissed,
+            Some(BugDismissalReason::WontFix),
+            Some("flaky"),
+        );
+        let json = dry_run_json(&bugs[..1], &body, false);
+        assert_eq!(json["bugId"], "bug_1");
+        assert_eq!(json["currentState"], "pending");
+        assert_eq!(json["review"]["state"], "dismissed");
+        assert_eq!(json["review"]["dismissalReason"], "wont_fix");
+        assert_eq!(json["review"]["notes"], "flaky");
+    }
+
+    #[test]
+    fn dry_run_json_bulk_is_array() {
+        let bugs = sample_bugs();
+        let body = bug_review_body(BugReviewState::Resolved, None, None);
+        let json = dry_run_json(&bugs, &body, true);
+        let items = json.as_array().unwrap();
+        assert_eq!(items.len(), 3);
+        assert!(items[0]["review"].get("notes").is_none());
+    }
+
     // ── filter_vulns_only ────────────────────────────────────────
 
     fn sample_bugs() -> Vec<Bug> {
@@ -930,6 +2980,138 @@ mod tests {
         assert!(filtered.is_empty());
     }
 
+    // ── apply_client_filters ─────────────────────────────────────────
+
+    #[test]
+    fn client_filters_pass_everything_through_when_unset() {
+        let bugs = sample_bugs_with_authors();
+        assert_eq!(apply_client_filters(bugs, None, None, false, &[]).len(), 4);
+    }
+
+    #[test]
+    fn client_filters_combine_time_range_and_author() {
+        let bugs = sample_bugs_with_authors();
+        let filtered = apply_client_filters(
+            bugs,
+            Some(2),
+            None,
+            false,
+            &["alice".to_string(), "bob".to_string()],
+        );
+        let ids: Vec<_> = filtered.iter().map(|b| b.id.to_string()).collect();
+        assert_eq!(ids, vec!["bug_2"]);
+    }
+
+    #[test]
+    fn client_filters_apply_vulns() {
+        let filtered = apply_client_filters(sample_bugs(), None, None, true, &[]);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].id.to_string(), "bug_1");
+    }
+
+    // ── parse_scan_id ────────────────────────────────────────────────
+
+    #[test]
+    fn parse_scan_id_accepts_workflow_request_id() {
+        let id = parse_scan_id(Some("wr_abc")).unwrap();
+        assert_eq!(id.map(|i| i.to_string()).as_deref(), Some("wr_abc"));
+    }
+
+    #[test]
+    fn parse_scan_id_none_is_none() {
+        assert!(parse_scan_id(None).unwrap().is_none());
+    }
+
+    #[test]
+    fn here_filter_limits_to_the_directory() {
+        assert_eq!(
+            with_path_prefix(None, "services/auth/").unwrap().as_deref(),
+            Some("path:'services/auth/**'")
+        );
+        assert_eq!(
+            with_path_prefix(Some("security || age<7d"), "services/auth/")
+                .unwrap()
+                .as_deref(),
+            Some("(security || age<7d) && path:'services/auth/**'")
+        );
+        assert_eq!(
+            with_path_prefix(None, "it's/").unwrap().as_deref(),
+            Some("path:\"it's/**\"")
+        );
+    }
+
+    #[test]
+    fn here_filter_at_the_root_keeps_the_filter() {
+        assert_eq!(with_path_prefix(None, "").unwrap(), None);
+        assert_eq!(
+            with_path_prefix(Some("security"), "").unwrap().as_deref(),
+            Some("security")
+        );
+    }
+
+    #[test]
+    fn here_filter_parses_and_matches_files_below() {
+        let expr = with_path_prefix(None, "services/auth/").unwrap().unwrap();
+        let here = Filter::parse(&expr).unwrap();
+        let bug = |path: &str| -> Bug {
+            serde_json::from_value(serde_json::json!({
+                "id": "bug_1", "title": "t", "summary": "s",
+                "filePath": path, "createdAt": 0, "repoId": "repo_1",
+                "linkedIssues": []
+            }))
+            .unwrap()
+        };
+        assert!(here.matches(&bug("services/auth/src/login.rs"), 0));
+        assert!(!here.matches(&bug("services/billing/src/charge.rs"), 0));
+    }
+
+    #[test]
+    fn parse_scan_id_rejects_bad_prefix() {
+        let err = parse_scan_id(Some("scan_1")).unwrap_err();
+        assert!(err.to_string().contains("Invalid scan ID format"));
+    }
+
+    // ── open_bug_trend ───────────────────────────────────────────────
+
+    fn scan_with_open(created_at: i64, open: Option<i64>) -> Scan {
+        let mut scan = serde_json::json!({
+            "repoId": "repo_1", "repoName": "cli", "ownerName": "usedetail",
+            "createdAt": created_at, "completedAt": null, "initiator": "scheduler",
+            "workflowRequestId": null, "commitSha": null
+        });
+        if let Some(open) = open {
+            scan["bugCounts"] =
+                serde_json::json!({ "open": open, "dismissed": 0, "resolved": 0, "total": open });
+        }
+        serde_json::from_value(scan).unwrap()
+    }
+
+    #[test]
+    fn open_bug_trend_sorts_oldest_first() {
+        let scans = vec![
+            scan_with_open(300, Some(3)),
+            scan_with_open(100, Some(7)),
+            scan_with_open(200, Some(5)),
+        ];
+        assert_eq!(open_bug_trend(scans), Some(vec![7, 5, 3]));
+    }
+
+    #[test]
+    fn open_bug_trend_skips_scans_without_counts() {
+        let scans = vec![
+            scan_with_open(100, Some(1)),
+            scan_with_open(200, None),
+            scan_with_open(300, Some(2)),
+        ];
+        assert_eq!(open_bug_trend(scans), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn open_bug_trend_needs_two_points() {
+        assert_eq!(open_bug_trend(vec![scan_with_open(100, Some(4))]), None);
+        assert_eq!(open_bug_trend(vec![]), None);
+    }
+
     // ── dedupe_statuses ──────────────────────────────────────────────
 
     #[test]
@@ -1330,4 +3512,164 @@ mod tests {
 
     // `format_introduced_in` moved to `crate::api::types`; tests now live
     // alongside the function in `src/api/types.rs`.
+
+    // ── dedupe titles ────────────────────────────────────────────────
+
+    fn titled_bug(id: &str, repo: &str, title: &str, file: &str) -> Bug {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": title,
+            "summary": "...",
+            "createdAt": 0,
+            "repoId": repo,
+            "filePath": file,
+            "linkedIssues": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn group_by_title_collapses_within_a_repo() {
+        let bugs = vec![
+            titled_bug("bug_1", "repo_a", "Unchecked unwrap", "src/a.rs"),
+            titled_bug("bug_2", "repo_a", "Missing timeout", "src/b.rs"),
+            titled_bug("bug_3", "repo_a", "Unchecked unwrap", "src/c.rs"),
+            titled_bug("bug_4", "repo_b", "Unchecked unwrap", "src/a.rs"),
+            titled_bug("bug_5", "repo_a", "Unchecked unwrap", "src/d.rs"),
+        ];
+        let groups = group_by_title(&bugs, false);
+        let summary: Vec<(&str, &str, usize)> = groups
+            .iter()
+            .map(|g| (g.repo_id.as_str(), g.title.as_str(), g.count))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("repo_a", "Unchecked unwrap", 3),
+                ("repo_a", "Missing timeout", 1),
+                ("repo_b", "Unchecked unwrap", 1),
+            ]
+        );
+        let ids: Vec<String> = groups[0]
+            .occurrences
+            .iter()
+            .map(|o| o.bug_id.to_string())
+            .collect();
+        assert_eq!(ids, ["bug_1", "bug_3", "bug_5"]);
+    }
+
+    #[test]
+    fn collapsed_group_card_previews_files() {
+        let bugs: Vec<Bug> = (0..5)
+            .map(|i| {
+                titled_bug(
+                    &format!("bug_{i}"),
+                    "repo_a",
+                    "Same",
+                    &format!("src/{i}.rs"),
+                )
+            })
+            .collect();
+        let (header, pairs) = group_by_title(&bugs, false)[0].to_card();
+        assert_eq!(header, "Same");
+        assert_eq!(
+            pairs,
+            [
+                ("Occurrences", "5".to_string()),
+                ("Files", "src/0.rs, src/1.rs, src/2.rs, +2 more".to_string()),
+            ]
+        );
+
+        let (_, expanded) = group_by_title(&bugs, true)[0].to_card();
+        assert_eq!(expanded.len(), 6);
+        assert_eq!(expanded[1], ("Bug", "bug_0  src/0.rs".to_string()));
+    }
+
+    // ── group by ─────────────────────────────────────────────────────
+
+    fn group_summary(groups: &[ListGroup<'_, Bug>]) -> Vec<(String, usize)> {
+        groups.iter().map(|g| (g.name.clone(), g.total)).collect()
+    }
+
+    #[test]
+    fn group_by_file_puts_fileless_bugs_last() {
+        let mut no_file = titled_bug("bug_3", "repo_a", "Loose", "");
+        no_file.file_path = None;
+        let bugs = vec![
+            titled_bug("bug_1", "repo_a", "One", "src/b.rs"),
+            no_file,
+            titled_bug("bug_2", "repo_a", "Two", "src/a.rs"),
+            titled_bug("bug_4", "repo_a", "Four", "src/b.rs"),
+        ];
+        let groups = group_bugs(&bugs, GroupBy::File);
+        assert_eq!(
+            group_summary(&groups),
+            [
+                ("src/a.rs".to_string(), 1),
+                ("src/b.rs".to_string(), 2),
+                (NO_FILE_GROUP.to_string(), 1),
+            ]
+        );
+        let ids: Vec<String> = groups[1].items.iter().map(|b| b.id.to_string()).collect();
+        assert_eq!(ids, ["bug_1", "bug_4"]);
+    }
+
+    #[test]
+    fn group_by_status_and_security_use_a_fixed_order() {
+        let mut vuln = titled_bug("bug_1", "repo_a", "Vuln", "src/a.rs");
+        vuln.is_security_vulnerability = Some(true);
+        let mut resolved = titled_bug("bug_2", "repo_a", "Fixed", "src/a.rs");
+        resolved.review = Some(
+            serde_json::from_value(serde_json::json!({
+                "state": "resolved", "createdAt": 0
+            }))
+            .unwrap(),
+        );
+        let bugs = vec![resolved, vuln];
+        assert_eq!(
+            group_summary(&group_bugs(&bugs, GroupBy::Status)),
+            [("Pending".to_string(), 1), ("Resolved".to_string(), 1)]
+        );
+        assert_eq!(
+            group_summary(&group_bugs(&bugs, GroupBy::Security)),
+            [
+                ("Security vulnerabilities".to_string(), 1),
+                ("Other bugs".to_string(), 1),
+            ]
+        );
+    }
+
+    // ── demo ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn demo_bugs_belong_to_demo_repos() {
+        let data = load_demo_data().unwrap();
+        assert!(!data.bugs.is_empty());
+        assert!(data
+            .bugs
+            .iter()
+            .all(|bug| data.repos.iter().any(|repo| repo.id == bug.repo_id)));
+    }
+
+    #[test]
+    fn demo_bugs_filter_by_repo_and_status() {
+        let data = load_demo_data().unwrap();
+        let pending = demo_bugs(&data, None, &[BugReviewState::Pending]).unwrap();
+        assert!(pending.iter().all(|bug| bug.review.is_none()));
+
+        let storefront =
+            demo_bugs(&data, Some("acme/storefront"), &[BugReviewState::Pending]).unwrap();
+        assert!(!storefront.is_empty());
+        assert!(storefront.len() < pending.len());
+
+        assert!(demo_bugs(&data, Some("acme/unknown"), &[BugReviewState::Pending]).is_err());
+    }
+
+    #[test]
+    fn unknown_demo_bug_is_not_found() {
+        let data = load_demo_data().unwrap();
+        assert!(find_demo_bug(&data, "bug_demo_01").is_ok());
+        let err = find_demo_bug(&data, "bug_nope").unwrap_err();
+        assert_eq!(crate::exit::code(&err), crate::exit::NOT_FOUND);
+    }
 }
@@ -0,0 +1,174 @@
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use console::{style, Term};
+use serde_json::json;
+
+use crate::api::types::{Bug, BugReviewState};
+use crate::exit::Failure;
+use crate::filter;
+use crate::notify::{self, Message};
+use crate::output::{stdout, write_structured};
+use crate::utils::git::resolve_repo_arg;
+use crate::utils::repos::resolve_repo_id;
+
+/// Which pending bugs count towards the `detail check` threshold.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum FailOn {
+    /// Every pending bug
+    Pending,
+    /// Only pending security vulnerabilities
+    Vulns,
+}
+
+impl FailOn {
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Pending => "pending bugs",
+            Self::Vulns => "pending security vulnerabilities",
+        }
+    }
+}
+
+/// Counts gathered by `detail check`.
+struct CheckSummary {
+    pending: usize,
+    vulns: usize,
+}
+
+impl CheckSummary {
+    fn from_bugs(bugs: &[Bug]) -> Self {
+        Self {
+            pending: bugs.len(),
+            vulns: bugs
+                .iter()
+                .filter(|b| b.is_security_vulnerability == Some(true))
+                .count(),
+        }
+    }
+
+    const fn count(&self, fail_on: FailOn) -> usize {
+        match fail_on {
+            FailOn::Pending => self.pending,
+            FailOn::Vulns => self.vulns,
+        }
+    }
+}
+
+/// Threshold and notifications for one `detail check` run.
+pub struct CheckOptions<'a> {
+    pub fail_on: FailOn,
+    pub max: usize,
+    pub filter: Option<&'a str>,
+    /// Notification sinks to tell when the check fails, by name.
+    pub sinks: &'a [String],
+}
+
+pub async fn handle(
+    repo: Option<&str>,
+    options: &CheckOptions<'_>,
+    format: &crate::OutputFormat,
+    cli: &crate::Cli,
+) -> Result<()> {
+    let CheckOptions {
+        fail_on,
+        max,
+        filter,
+        sinks,
+    } = *options;
+    let filter = filter::parse_flag(filter)?;
+    let client = cli.create_client()?;
+    let repo = resolve_repo_arg(repo)?;
+    let repo_id = resolve_repo_id(&client, &repo)
+        .await
+        .context("Failed to resolve repository identifier")?;
+    let mut bugs = client
+        .list_all_bugs(&repo_id, BugReviewState::Pending)
+        .await?;
+    if let Some(filter) = &filter {
+        bugs = filter.apply(bugs, chrono::Utc::now().timestamp_millis());
+    }
+
+    let summary = CheckSummary::from_bugs(&bugs);
+    let count = summary.count(fail_on);
+    let passed = count <= max;
+
+    let term = stdout();
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Yaml => {
+            let fail_on_name = fail_on
+                .to_possible_value()
+                .map(|v| v.get_name().to_string());
+            write_structured(
+                &json!({
+                    "repo": repo,
+                    "pending": summary.pending,
+                    "vulns": summary.vulns,
+                    "failOn": fail_on_name,
+                    "max": max,
+                    "passed": passed,
+                }),
+                format,
+            )?;
+        }
+        crate::OutputFormat::Table => {
+            term.write_line(&format!(
+                "{}  {} pending, {} security vulnerabilities",
+                style(&repo).bold(),
+                summary.pending,
+                summary.vulns
+            ))?;
+            if passed {
+                term.write_line(&format!(
+                    "{}",
+                    style(format!("✓ {count} {} (max {max})", fail_on.label())).green()
+                ))?;
+            }
+        }
+    }
+
+    if !passed {
+        let reason = format!("{count} {} exceeds --max {max}", fail_on.label());
+        if !sinks.is_empty() {
+            let message = Message {
+                source: "check".to_string(),
+                title: format!("Detail check failed for {repo}"),
+                body: format!(
+                    "{reason}\n{} pending, {} security vulnerabilities",
+                    summary.pending, summary.vulns
+                ),
+            };
+            // The failed check is the result that matters; a failed
+            // notification is only worth a warning.
+            if let Err(err) = notify::send(sinks, &message, cli).await {
+                Term::stderr()
+                    .write_line(&format!("{}", style(format!("Warning: {err:#}")).yellow()))
+                    .ok();
+            }
+        }
+        bail!(Failure::threshold_exceeded(reason));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_bug;
+
+    fn bug(id: &str, vuln: bool) -> Bug {
+        test_bug(serde_json::json!({ "id": id, "isSecurityVulnerability": vuln }))
+    }
+
+    #[test]
+    fn summary_counts_pending_and_vulns() {
+        let summary = CheckSummary::from_bugs(&[bug("bug_1", true), bug("bug_2", false)]);
+        assert_eq!(summary.count(FailOn::Pending), 2);
+        assert_eq!(summary.count(FailOn::Vulns), 1);
+    }
+
+    #[test]
+    fn empty_repo_passes_zero_threshold() {
+        let summary = CheckSummary::from_bugs(&[]);
+        assert_eq!(summary.count(FailOn::Pending), 0);
+    }
+}
@@ -1,8 +1,10 @@
 use std::env;
-use std::io::{self, Write};
+use std::io::Write;
 
 use anyhow::{bail, Context, Result};
 
+use crate::output::stdout;
+
 fn detect_shell() -> Result<String> {
     let shell = env::var("SHELL").context("could not detect shell from $SHELL")?;
     let shell = shell.trim();
@@ -44,8 +46,7 @@ pub fn handle(shell: Option<&str>) -> Result<()> {
         detected = detect_shell()?;
         detected.as_str()
     };
-    let stdout = io::stdout();
-    print_snippet(shell, &mut stdout.lock())
+    print_snippet(shell, &mut stdout())
 }
 
 #[cfg(test)]
@@ -0,0 +1,97 @@
+//! `detail daemon`: run, inspect and stop the background daemon that other
+//! commands ask before calling the API. See `crate::daemon`.
+
+use anyhow::{bail, Result};
+use clap::Subcommand;
+use console::{style, Term};
+
+use crate::config::storage::load_config;
+use crate::daemon::{self, DaemonStatus};
+use crate::output::{stdout, write_structured, SectionRenderer};
+use crate::utils::datetime::format_datetime;
+
+#[derive(Subcommand)]
+pub enum DaemonCommands {
+    /// Run the daemon in the foreground until stopped. Start it from your
+    /// login session, launchd or systemd to keep it around
+    Run,
+
+    /// Show whether a daemon is running and what it holds
+    Status {
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<crate::OutputFormat>,
+    },
+
+    /// Stop the running daemon
+    Stop,
+}
+
+fn status_rows(status: &DaemonStatus) -> Vec<(&'static str, String)> {
+    let watched = if status.watched_repos.is_empty() {
+        "none (add repos under [repos] in config)".to_string()
+    } else {
+        status.watched_repos.join(", ")
+    };
+    vec![
+        ("PID", status.pid.to_string()),
+        ("Started", format_datetime(status.started_at)),
+        ("Cached responses", status.cached_responses.to_string()),
+        ("Watched repos", watched),
+    ]
+}
+
+pub async fn handle(command: &DaemonCommands, cli: &crate::Cli) -> Result<()> {
+    match command {
+        DaemonCommands::Run => {
+            let repos: Vec<String> = load_config()?.repos.keys().cloned().collect();
+            let client = cli.create_client()?.without_daemon();
+            Term::stderr().write_line(&format!(
+                "{} Daemon running; stop it with `detail daemon stop` or Ctrl-C.",
+                style("✓").green()
+            ))?;
+            daemon::run(client, repos).await
+        }
+        DaemonCommands::Status { format } => {
+            let format = &format.unwrap_or_default();
+            let status = daemon::status().await;
+            match format {
+                crate::OutputFormat::Json | crate::OutputFormat::Yaml => {
+                    write_structured(&status, format)?;
+                }
+                crate::OutputFormat::Table => match &status {
+                    Some(status) => SectionRenderer::new()
+                        .key_value("Daemon", &status_rows(status))
+                        .print()?,
+                    None => stdout().write_line("No daemon is running.")?,
+                },
+            }
+            Ok(())
+        }
+        DaemonCommands::Stop => {
+            if !daemon::stop().await {
+                bail!("No daemon is running");
+            }
+            stdout().write_line(&format!("{}", style("✓ Daemon stopped").green()))?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_rows_explain_an_empty_watch_list() {
+        let status = DaemonStatus {
+            pid: 42,
+            started_at: 0,
+            cached_responses: 3,
+            watched_repos: Vec::new(),
+        };
+        let rows = status_rows(&status);
+        assert_eq!(rows[0], ("PID", "42".to_string()));
+        assert!(rows[3].1.starts_with("none"));
+    }
+}
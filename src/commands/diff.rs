@@ -0,0 +1,183 @@
+//! `detail bugs diff`: the change that introduced a bug, from the local
+//! checkout's history.
+
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use console::style;
+
+use crate::api::client::ApiClient;
+use crate::api::types::BugId;
+use crate::exit::Failure;
+use crate::output::write_stdout;
+use crate::utils::git::{has_commit, infer_repo_from_git_remote, repo_root};
+
+/// How a line of `git show` output is colored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    /// `commit <sha>`.
+    Commit,
+    /// `diff --git`, `index`, `---` and `+++` lines.
+    FileHeader,
+    /// `@@ -1,3 +1,4 @@`.
+    Hunk,
+    Added,
+    Removed,
+    /// Context lines and the commit message.
+    Plain,
+}
+
+/// Classify `line`. `in_diff` is whether a `diff --git` header has been
+/// seen, so commit message lines starting with `-` or `+` stay plain.
+fn classify(line: &str, in_diff: bool) -> LineKind {
+    if line.starts_with("commit ") && !in_diff {
+        LineKind::Commit
+    } else if line.starts_with("diff --git ") {
+        LineKind::FileHeader
+    } else if !in_diff {
+        LineKind::Plain
+    } else if line.starts_with("index ") || line.starts_with("--- ") || line.starts_with("+++ ") {
+        LineKind::FileHeader
+    } else if line.starts_with("@@") {
+        LineKind::Hunk
+    } else if line.starts_with('+') {
+        LineKind::Added
+    } else if line.starts_with('-') {
+        LineKind::Removed
+    } else {
+        LineKind::Plain
+    }
+}
+
+/// `git show` output with diff-style coloring. Styles are dropped when
+/// colors are off (`--no-color`, `NO_COLOR`, or output to a pipe).
+fn colorize(show: &str) -> String {
+    let mut in_diff = false;
+    let mut out = String::with_capacity(show.len());
+    for line in show.lines() {
+        let kind = classify(line, in_diff);
+        in_diff |= kind == LineKind::FileHeader;
+        let styled = match kind {
+            LineKind::Commit => style(line).yellow().to_string(),
+            LineKind::FileHeader => style(line).bold().to_string(),
+            LineKind::Hunk => style(line).cyan().to_string(),
+            LineKind::Added => style(line).green().to_string(),
+            LineKind::Removed => style(line).red().to_string(),
+            LineKind::Plain => line.to_string(),
+        };
+        out.push_str(&styled);
+        out.push('\n');
+    }
+    out
+}
+
+/// Explain that `sha` isn't in this checkout, with how to get it and, for
+/// GitHub remotes, where to see it instead.
+fn missing_commit_message(sha: &str) -> String {
+    let mut message =
+        format!("Commit {sha} isn't in this checkout. Fetch it with `git fetch origin {sha}`");
+    if let Ok(repo) = infer_repo_from_git_remote() {
+        message = format!("{message}, or view it at https://github.com/{repo}/commit/{sha}");
+    }
+    message
+}
+
+pub async fn handle(client: &ApiClient, bug_id: &str) -> Result<()> {
+    let bug_id: BugId = bug_id
+        .try_into()
+        .context(Failure::usage("Invalid bug ID format (expected bug_...)"))?;
+    let bug = client
+        .get_bug(&bug_id)
+        .await
+        .context("Failed to fetch bug details")?;
+    let Some(sha) = bug.introduced_in.as_ref().map(|intro| intro.sha.as_str()) else {
+        bail!(Failure::not_found(format!(
+            "No introducing commit is recorded for {bug_id}"
+        )));
+    };
+    let Some(root) = repo_root() else {
+        bail!(Failure::usage(
+            "`bugs diff` reads the local history; run it inside a checkout of the repository"
+        ));
+    };
+    if !has_commit(sha) {
+        bail!(Failure::not_found(missing_commit_message(sha)));
+    }
+
+    let mut git = Command::new("git");
+    git.args(["show", "--no-color", "--no-ext-diff", sha])
+        .current_dir(&root);
+    if let Some(file) = bug.file_path.as_deref() {
+        git.args(["--", file]);
+    }
+    let output = git.output().context("Failed to run `git show`")?;
+    if !output.status.success() {
+        bail!(
+            "`git show {sha}` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    write_stdout(&colorize(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHOW: &str = "\
+commit abc1234def5678
+Author: Alice <alice@example.com>
+
+    Handle empty input
+    - now with fewer panics
+
+diff --git a/src/handler.rs b/src/handler.rs
+index 1111111..2222222 100644
+--- a/src/handler.rs
++++ b/src/handler.rs
+@@ -1,3 +1,3 @@ fn handle()
+ fn handle(input: Option<&str>) {
+-    let v = input?;
++    let v = input.unwrap();
+";
+
+    #[test]
+    fn lines_are_classified_like_a_diff() {
+        let mut in_diff = false;
+        let kinds: Vec<LineKind> = SHOW
+            .lines()
+            .map(|line| {
+                let kind = classify(line, in_diff);
+                in_diff |= kind == LineKind::FileHeader;
+                kind
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                LineKind::Commit,
+                LineKind::Plain,
+                LineKind::Plain,
+                LineKind::Plain,
+                LineKind::Plain,
+                LineKind::Plain,
+                LineKind::FileHeader,
+                LineKind::FileHeader,
+                LineKind::FileHeader,
+                LineKind::FileHeader,
+                LineKind::Hunk,
+                LineKind::Plain,
+                LineKind::Removed,
+                LineKind::Added,
+            ]
+        );
+    }
+
+    #[test]
+    fn colorize_keeps_every_line() {
+        assert_eq!(
+            console::strip_ansi_codes(&colorize(SHOW)).lines().count(),
+            SHOW.lines().count()
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! `detail bugs digest`: what happened to a repo's bugs over a window —
+//! opened, resolved and dismissed, grouped by file and by the author who
+//! introduced them — for posting to chat from cron.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use anyhow::{Context, Result};
+use console::{style, Term};
+use serde::Serialize;
+
+use crate::api::client::ApiClient;
+use crate::api::types::{Bug, BugReviewState};
+use crate::exit::Failure;
+use crate::notify::{self, Message};
+use crate::output::{write_stdout, write_structured};
+use crate::utils::datetime::{format_datetime, parse_time_spec};
+use crate::utils::repos::resolve_repo_id;
+
+/// Group name for bugs without a file.
+const NO_FILE: &str = "(no file)";
+
+/// Group name for bugs whose introducing author isn't known.
+const UNKNOWN_AUTHOR: &str = "(unknown)";
+
+/// Groups listed per section in the text digest; JSON lists them all.
+const TEXT_TOP_GROUPS: usize = 10;
+
+/// How many bugs moved each way within the window.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+struct Counts {
+    opened: usize,
+    resolved: usize,
+    dismissed: usize,
+}
+
+impl Counts {
+    const fn total(self) -> usize {
+        self.opened + self.resolved + self.dismissed
+    }
+
+    /// `3 opened, 1 resolved`, leaving out zeros.
+    fn describe(self) -> String {
+        let parts: Vec<String> = [
+            (self.opened, "opened"),
+            (self.resolved, "resolved"),
+            (self.dismissed, "dismissed"),
+        ]
+        .into_iter()
+        .filter(|&(count, _)| count > 0)
+        .map(|(count, label)| format!("{count} {label}"))
+        .collect();
+        parts.join(", ")
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct Group {
+    name: String,
+    #[serde(flatten)]
+    counts: Counts,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Digest {
+    repo: String,
+    /// Window start and end, in epoch millis.
+    since: i64,
+    until: i64,
+    #[serde(flatten)]
+    counts: Counts,
+    /// Security vulnerabilities among the opened bugs.
+    opened_vulns: usize,
+    /// Most active first.
+    by_file: Vec<Group>,
+    by_author: Vec<Group>,
+}
+
+/// Groups from `counts`, most movement first, then by name.
+fn ranked(counts: BTreeMap<&str, Counts>) -> Vec<Group> {
+    let mut groups: Vec<Group> = counts
+        .into_iter()
+        .map(|(name, counts)| Group {
+            name: name.to_string(),
+            counts,
+        })
+        .collect();
+    groups.sort_by_key(|group| Reverse(group.counts.total()));
+    groups
+}
+
+/// Tally `bugs` for the window `since..=until`. A bug counts as opened when
+/// it was created in the window, and as resolved or dismissed when its
+/// review happened in the window.
+fn digest(repo: &str, bugs: &[Bug], since: i64, until: i64) -> Digest {
+    let in_window = |at: i64| (since..=until).contains(&at);
+    let mut counts = Counts::default();
+    let mut opened_vulns = 0;
+    let mut by_file: BTreeMap<&str, Counts> = BTreeMap::new();
+    let mut by_author: BTreeMap<&str, Counts> = BTreeMap::new();
+    for bug in bugs {
+        let mut moved = Counts::default();
+        if in_window(bug.created_at) {
+            moved.opened = 1;
+            if bug.is_security_vulnerability == Some(true) {
+                opened_vulns += 1;
+            }
+        }
+        if let Some(review) = bug.review.as_ref().filter(|r| in_window(r.created_at)) {
+            match review.state {
+                BugReviewState::Resolved => moved.resolved = 1,
+                BugReviewState::Dismissed => moved.dismissed = 1,
+                BugReviewState::Pending => {}
+            }
+        }
+        if moved.total() == 0 {
+            continue;
+        }
+        let file = bug.file_path.as_deref().unwrap_or(NO_FILE);
+        let author = bug
+            .introduced_in
+            .as_ref()
+            .and_then(|intro| intro.author.as_deref())
+            .unwrap_or(UNKNOWN_AUTHOR);
+        for tally in [
+            &mut counts,
+            by_file.entry(file).or_default(),
+            by_author.entry(author).or_default(),
+        ] {
+            tally.opened += moved.opened;
+            tally.resolved += moved.resolved;
+            tally.dismissed += moved.dismissed;
+        }
+    }
+    Digest {
+        repo: repo.to_string(),
+        since,
+        until,
+        counts,
+        opened_vulns,
+        by_file: ranked(by_file),
+        by_author: ranked(by_author),
+    }
+}
+
+/// The digest as plain text, ready to paste into chat.
+fn render_text(digest: &Digest) -> Result<String> {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "Bug digest for {}, {} to {}",
+        digest.repo,
+        format_datetime(digest.since),
+        format_datetime(digest.until)
+    )?;
+    if digest.counts.total() == 0 {
+        writeln!(out, "No bugs opened, resolved or dismissed.")?;
+        return Ok(out);
+    }
+    let vulns = if digest.opened_vulns > 0 {
+        format!(" ({} security)", digest.opened_vulns)
+    } else {
+        String::new()
+    };
+    writeln!(
+        out,
+        "Opened {}{vulns}, resolved {}, dismissed {}",
+        digest.counts.opened, digest.counts.resolved, digest.counts.dismissed
+    )?;
+    for (title, groups) in [
+        ("By file", &digest.by_file),
+        ("By introducing author", &digest.by_author),
+    ] {
+        writeln!(out)?;
+        writeln!(out, "{title}:")?;
+        for group in groups.iter().take(TEXT_TOP_GROUPS) {
+            writeln!(out, "  {}: {}", group.name, group.counts.describe())?;
+        }
+        if groups.len() > TEXT_TOP_GROUPS {
+            writeln!(out, "  …and {} more", groups.len() - TEXT_TOP_GROUPS)?;
+        }
+    }
+    Ok(out)
+}
+
+pub async fn handle(
+    client: &ApiClient,
+    repo: &str,
+    since: &str,
+    notify_slack: bool,
+    format: &crate::OutputFormat,
+    cli: &crate::Cli,
+) -> Result<()> {
+    let slack_url = notify_slack.then(notify::slack_webhook_url).transpose()?;
+    let now = chrono::Utc::now();
+    let since_ms = parse_time_spec(since, now)
+        .map(|dt| dt.timestamp_millis())
+        .map_err(|e| Failure::usage(format!("invalid --since value: {e}")))?;
+    let until_ms = now.timestamp_millis();
+
+    let repo_id = resolve_repo_id(client, repo)
+        .await
+        .context("Failed to resolve repository identifier")?;
+    let mut bugs = Vec::new();
+    for status in [
+        BugReviewState::Pending,
+        BugReviewState::Resolved,
+        BugReviewState::Dismissed,
+    ] {
+        bugs.extend(client.list_all_bugs(&repo_id, status).await?);
+    }
+
+    let summary = digest(repo, &bugs, since_ms, until_ms);
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Yaml => {
+            write_structured(&summary, format)?;
+        }
+        crate::OutputFormat::Table => write_stdout(&render_text(&summary)?)?,
+    }
+    if let Some(url) = slack_url {
+        let text = render_text(&summary)?;
+        // The first line is the heading; Slack shows it as the header.
+        let (title, body) = text.split_once('\n').unwrap_or((&text, ""));
+        let message = Message {
+            source: "bugs digest".to_string(),
+            title: title.to_string(),
+            body: body.trim().to_string(),
+        };
+        // The digest is printed already; cron mails any warning.
+        if let Err(err) = notify::post_slack(&url, &message, cli).await {
+            Term::stderr()
+                .write_line(&format!("{}", style(format!("Warning: {err:#}")).yellow()))
+                .ok();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_bug;
+
+    fn sample_bugs() -> Vec<Bug> {
+        vec![
+            test_bug(serde_json::json!({
+                "id": "bug_1", "createdAt": 150, "filePath": "src/login.rs",
+                "isSecurityVulnerability": true,
+                "introducedIn": { "sha": "a", "date": "2024-01-01", "author": "alice" }
+            })),
+            test_bug(serde_json::json!({
+                "id": "bug_2", "createdAt": 10, "filePath": "src/login.rs",
+                "review": { "state": "resolved", "createdAt": 120 },
+                "introducedIn": { "sha": "b", "date": "2024-01-01", "author": "bob" }
+            })),
+            test_bug(serde_json::json!({
+                "id": "bug_3", "createdAt": 110,
+                "review": { "state": "dismissed", "createdAt": 130 }
+            })),
+            // Resolved before the window: not in the digest.
+            test_bug(serde_json::json!({
+                "id": "bug_4", "createdAt": 10, "filePath": "src/old.rs",
+                "review": { "state": "resolved", "createdAt": 20 }
+            })),
+        ]
+    }
+
+    #[test]
+    fn counts_moves_within_the_window() {
+        let digest = digest("acme/api", &sample_bugs(), 100, 200);
+        assert_eq!(
+            digest.counts,
+            Counts {
+                opened: 2,
+                resolved: 1,
+                dismissed: 1
+            }
+        );
+        assert_eq!(digest.opened_vulns, 1);
+    }
+
+    #[test]
+    fn groups_by_file_and_author_most_active_first() {
+        let digest = digest("acme/api", &sample_bugs(), 100, 200);
+        let files: Vec<(&str, usize)> = digest
+            .by_file
+            .iter()
+            .map(|g| (g.name.as_str(), g.counts.total()))
+            .collect();
+        assert_eq!(files, [(NO_FILE, 2), ("src/login.rs", 2)]);
+        let authors: Vec<&str> = digest.by_author.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(authors, [UNKNOWN_AUTHOR, "alice", "bob"]);
+    }
+
+    #[test]
+    fn text_digest_reads_as_a_chat_message() {
+        let text = render_text(&digest("acme/api", &sample_bugs(), 100, 200)).unwrap();
+        assert!(text.contains("Opened 2 (1 security), resolved 1, dismissed 1\n"));
+        assert!(text.contains("By file:\n  (no file): 1 opened, 1 dismissed\n"));
+        assert!(text.contains("  src/login.rs: 1 opened, 1 resolved\n"));
+        let empty = render_text(&digest("acme/api", &[], 100, 200)).unwrap();
+        assert!(empty.ends_with("No bugs opened, resolved or dismissed.\n"));
+    }
+}
@@ -0,0 +1,134 @@
+//! `detail bugs export-issue`: file a bug in an external tracker and
+//! remember the link for `detail bugs reconcile`.
+
+use std::slice;
+
+use anyhow::{bail, Context, Result};
+use console::{style, Term};
+
+use crate::api::client::ApiClient;
+use crate::api::types::BugId;
+use crate::commands::reconcile::all_links;
+use crate::config::issue_links::{load_links, record_link, IssueLink};
+use crate::config::storage::load_config;
+use crate::exit::Failure;
+use crate::output::{stdout, write_structured};
+use crate::trackers::{gitlab, jira, IssueDraft};
+
+/// Where to file the issue.
+#[derive(Debug, Clone, Copy)]
+pub enum Target<'a> {
+    /// A Jira project, by key.
+    Jira(&'a str),
+    /// A GitLab project, by path.
+    Gitlab(&'a str),
+}
+
+impl Target<'_> {
+    /// The tracker's name, as in the API's linked issues.
+    const fn tracker(self) -> &'static str {
+        match self {
+            Self::Jira(_) => "jira",
+            Self::Gitlab(_) => "gitlab",
+        }
+    }
+}
+
+pub struct ExportOptions {
+    /// Create an issue even if the bug already has one in this tracker.
+    pub force: bool,
+    /// Print the request instead of sending it.
+    pub dry_run: bool,
+}
+
+pub async fn handle(
+    client: &ApiClient,
+    bug_id: &str,
+    target: Target<'_>,
+    options: &ExportOptions,
+    format: &crate::OutputFormat,
+    cli: &crate::Cli,
+) -> Result<()> {
+    let id: BugId = bug_id
+        .try_into()
+        .context(Failure::usage("Invalid bug ID format (expected bug_...)"))?;
+    let bug = client
+        .get_bug(&id)
+        .await
+        .context("Failed to fetch bug details")?;
+
+    let tracker = target.tracker();
+    if !options.force {
+        let links = all_links(load_links()?, slice::from_ref(&bug));
+        if let Some(link) = links.iter().find(|link| link.tracker == tracker) {
+            let url = link
+                .url
+                .as_deref()
+                .map(|url| format!(" ({url})"))
+                .unwrap_or_default();
+            bail!(Failure::usage(format!(
+                "{bug_id} is already tracked in {}{url}. Pass --force to create another issue.",
+                link.issue_id
+            )));
+        }
+    }
+
+    let config = load_config()?;
+    let draft = IssueDraft::from_bug(&bug);
+    let created = match target {
+        Target::Jira(project) => {
+            let Some(settings) = &config.jira else {
+                bail!(Failure::usage(
+                    "Jira isn't configured. Add a [jira] table with `base_url` to config.toml."
+                ));
+            };
+            if options.dry_run {
+                return write_structured(&jira::request_body(settings, project, &draft)?, format);
+            }
+            jira::create_issue(settings, project, &draft, &cli.http_options(&config)).await?
+        }
+        Target::Gitlab(project) => {
+            // gitlab.com needs no [gitlab] table, only a token.
+            let settings = config.gitlab.clone().unwrap_or_default();
+            if options.dry_run {
+                gitlab::check_project(project)?;
+                return write_structured(&gitlab::request_body(&settings, &draft), format);
+            }
+            gitlab::create_issue(&settings, project, &draft, &cli.http_options(&config)).await?
+        }
+    };
+
+    let link = IssueLink {
+        bug_id: bug.id.to_string(),
+        tracker: tracker.to_string(),
+        issue_id: created.key,
+        url: Some(created.url),
+    };
+    // The issue exists either way; losing the local record only means
+    // reconcile won't see it.
+    if let Err(err) = record_link(link.clone()) {
+        Term::stderr()
+            .write_line(&format!(
+                "{}",
+                style(format!("Warning: Failed to record the issue link: {err:#}")).yellow()
+            ))
+            .ok();
+    }
+
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Yaml => write_structured(&link, format),
+        crate::OutputFormat::Table => {
+            let term = stdout();
+            term.write_line(&format!(
+                "{} Created {} for {}",
+                style("✓").green(),
+                style(&link.issue_id).bold(),
+                link.bug_id
+            ))?;
+            if let Some(url) = &link.url {
+                term.write_line(url)?;
+            }
+            Ok(())
+        }
+    }
+}
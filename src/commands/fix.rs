@@ -0,0 +1,192 @@
+//! `detail bugs fix`: one guided pass over a bug — read the report, edit the
+//! file in `$EDITOR`, run the project's tests, then close the bug as resolved
+//! with the fixing commit.
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use console::style;
+use dialoguer::{Confirm, Input};
+
+use crate::api::client::ApiClient;
+use crate::api::types::{BugId, BugReviewState};
+use crate::commands::bugs::{fetch_open_bug_trend, render_bug_show};
+use crate::config::project::{load_project_config, PROJECT_FILE};
+use crate::exit::Failure;
+use crate::output::{stdout, Stdout};
+use crate::utils::git::{head_commit, repo_root};
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set.
+const FALLBACK_EDITOR: &str = "vi";
+
+/// The editor to run, as a program and its arguments: `$VISUAL`, then
+/// `$EDITOR`, split on whitespace so values like `code --wait` work.
+fn editor_command(var: impl Fn(&str) -> Option<String>) -> (String, Vec<String>) {
+    let editor = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .find_map(|name| var(name).filter(|value| !value.trim().is_empty()))
+        .unwrap_or_else(|| FALLBACK_EDITOR.to_string());
+    let mut parts = editor.split_whitespace().map(str::to_owned);
+    let program = parts.next().unwrap_or_else(|| FALLBACK_EDITOR.to_string());
+    (program, parts.collect())
+}
+
+/// Open `path` in the user's editor and wait for it to exit.
+fn open_in_editor(path: &Path) -> Result<()> {
+    let (program, args) = editor_command(|name| env::var(name).ok());
+    let status = Command::new(&program)
+        .args(&args)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to start editor `{program}`"))?;
+    if !status.success() {
+        bail!("Editor `{program}` exited with {status}");
+    }
+    Ok(())
+}
+
+/// Run the project's test command through the shell, in `dir` when known.
+/// Returns whether it passed.
+fn run_tests(command: &str, dir: Option<&Path>) -> Result<bool> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    };
+    if let Some(dir) = dir {
+        shell.current_dir(dir);
+    }
+    let status = shell
+        .status()
+        .with_context(|| format!("Failed to run `{command}`"))?;
+    Ok(status.success())
+}
+
+/// Review notes recording the fixing commit, if one was given.
+fn fixed_in_notes(commit: &str) -> Option<String> {
+    let commit = commit.trim();
+    (!commit.is_empty()).then(|| format!("Fixed in {commit}"))
+}
+
+fn confirm(prompt: &str, default: bool) -> Result<bool> {
+    Confirm::new()
+        .with_prompt(prompt)
+        .default(default)
+        .interact()
+        .context("Failed to read confirmation")
+}
+
+fn note(term: Stdout, message: &str) -> Result<()> {
+    term.write_line(&format!("{}", style(message).dim()))?;
+    Ok(())
+}
+
+pub async fn handle(client: &ApiClient, bug_id: &str) -> Result<()> {
+    let term = stdout();
+    if !term.is_term() {
+        bail!(Failure::usage(
+            "`bugs fix` is interactive; run it in a terminal"
+        ));
+    }
+    let bug_id: BugId = bug_id
+        .try_into()
+        .context(Failure::usage("Invalid bug ID format (expected bug_...)"))?;
+    let bug = client
+        .get_bug(&bug_id)
+        .await
+        .context("Failed to fetch bug details")?;
+    let trend = fetch_open_bug_trend(client, &bug.repo_id).await;
+    render_bug_show(&bug, trend.as_deref())?;
+
+    let root = repo_root();
+    match bug.file_path.as_deref() {
+        Some(file) => {
+            let path = root
+                .as_deref()
+                .map_or_else(|| Path::new(file).to_path_buf(), |root| root.join(file));
+            if path.is_file() {
+                if confirm(&format!("Open {file} in your editor?"), true)? {
+                    open_in_editor(&path)?;
+                }
+            } else {
+                note(
+                    term,
+                    &format!("{file} isn't in this checkout; skipping the editor."),
+                )?;
+            }
+        }
+        None => note(term, "The bug isn't tied to a file; skipping the editor.")?,
+    }
+
+    match load_project_config()?.test_command {
+        Some(command) => {
+            if confirm(&format!("Run `{command}`?"), true)? {
+                if run_tests(&command, root.as_deref())? {
+                    term.write_line(&format!("{}", style("✓ Tests passed").green()))?;
+                } else if !confirm("Tests failed. Close the bug anyway?", false)? {
+                    return Ok(());
+                }
+            }
+        }
+        None => note(
+            term,
+            &format!("No test_command in {PROJECT_FILE}; skipping tests."),
+        )?,
+    }
+
+    let commit: String = Input::new()
+        .with_prompt("Fixing commit (empty for none)")
+        .default(head_commit().unwrap_or_default())
+        .allow_empty(true)
+        .interact_text()
+        .context("Failed to read the fixing commit")?;
+    let notes = fixed_in_notes(&commit);
+    if !confirm(&format!("Close {bug_id} as resolved?"), true)? {
+        return Ok(());
+    }
+    client
+        .update_bug_close(&bug_id, BugReviewState::Resolved, None, notes.as_deref())
+        .await
+        .with_context(|| format!("Failed to close bug {bug_id}"))?;
+    term.write_line(&format!("{}", style("✓ Bug closed as: Resolved").green()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor_from(vars: &[(&str, &str)]) -> (String, Vec<String>) {
+        editor_command(|name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (*value).to_string())
+        })
+    }
+
+    #[test]
+    fn editor_prefers_visual_then_editor_then_vi() {
+        let both = [("VISUAL", "code --wait"), ("EDITOR", "nano")];
+        assert_eq!(
+            editor_from(&both),
+            ("code".to_string(), vec!["--wait".to_string()])
+        );
+        assert_eq!(editor_from(&[("EDITOR", "nano")]).0, "nano");
+        assert_eq!(editor_from(&[("VISUAL", " ")]).0, FALLBACK_EDITOR);
+    }
+
+    #[test]
+    fn notes_record_the_fixing_commit() {
+        assert_eq!(
+            fixed_in_notes(" abc1234\n").as_deref(),
+            Some("Fixed in abc1234")
+        );
+        assert_eq!(fixed_in_notes(""), None);
+    }
+}
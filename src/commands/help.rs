@@ -0,0 +1,117 @@
+//! `detail help`: clap's help for a command, plus task-oriented guides
+//! (`detail help ci-gating`, …) embedded at build time so they work offline.
+
+use anyhow::{bail, Result};
+use clap::CommandFactory;
+use console::style;
+
+use crate::exit::Failure;
+use crate::output::{stdout, SectionRenderer, Stdout};
+
+/// A long-form guide, written in markdown.
+struct Guide {
+    name: &'static str,
+    summary: &'static str,
+    body: &'static str,
+}
+
+const GUIDES: &[Guide] = &[
+    Guide {
+        name: "auth",
+        summary: "Logging in, profiles, and tokens for scripts and CI",
+        body: include_str!("guides/auth.md"),
+    },
+    Guide {
+        name: "ci-gating",
+        summary: "Failing builds on pending bugs with `detail check`",
+        body: include_str!("guides/ci-gating.md"),
+    },
+    Guide {
+        name: "exports",
+        summary: "Exporting bugs to CSV, SARIF or markdown, and redacting them",
+        body: include_str!("guides/exports.md"),
+    },
+    Guide {
+        name: "filters",
+        summary: "The --filter expression language for list, export, close and check",
+        body: include_str!("guides/filters.md"),
+    },
+    Guide {
+        name: "agents",
+        summary: "Letting coding agents read and close bugs",
+        body: include_str!("guides/agents.md"),
+    },
+];
+
+fn find_guide(name: &str) -> Option<&'static Guide> {
+    GUIDES.iter().find(|guide| guide.name == name)
+}
+
+fn print_topics(term: Stdout) -> Result<()> {
+    term.write_line(&format!("{}", style("Guides").bold()))?;
+    let width = GUIDES
+        .iter()
+        .map(|guide| guide.name.len())
+        .max()
+        .unwrap_or(0);
+    for guide in GUIDES {
+        term.write_line(&format!(
+            "  {:<width$}  {}",
+            style(guide.name).cyan(),
+            guide.summary
+        ))?;
+    }
+    term.write_line("")?;
+    term.write_line("Run `detail help <guide>` to read one, or `detail help <command>` for a command's options.")?;
+    Ok(())
+}
+
+/// Print clap's long help for the command at `path` (e.g. `["bugs", "list"]`).
+fn print_command_help(term: Stdout, path: &[String]) -> Result<()> {
+    let mut command = crate::Cli::command();
+    command.build();
+    for name in path {
+        let Some(subcommand) = command.find_subcommand(name).cloned() else {
+            bail!(Failure::usage(format!(
+                "No command or guide named '{}'. Run `detail help topics` for the guides",
+                path.join(" ")
+            )));
+        };
+        command = subcommand;
+    }
+    term.write_str(&command.render_long_help().ansi().to_string())?;
+    Ok(())
+}
+
+pub fn handle(topic: &[String]) -> Result<()> {
+    let term = stdout();
+    match topic {
+        [] => {
+            print_command_help(term, topic)?;
+            term.write_line("")?;
+            print_topics(term)
+        }
+        [name] if name == "topics" => print_topics(term),
+        // Guides win over commands of the same name (`auth`); the command's
+        // own help is still `detail auth --help`.
+        [name] => find_guide(name).map_or_else(
+            || print_command_help(term, topic),
+            |guide| SectionRenderer::new().markdown("", guide.body).print(),
+        ),
+        _ => print_command_help(term, topic),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guides_are_titled_and_uniquely_named() {
+        for (i, guide) in GUIDES.iter().enumerate() {
+            assert!(guide.body.starts_with("# "), "{} has no title", guide.name);
+            assert!(GUIDES[..i].iter().all(|other| other.name != guide.name));
+        }
+        assert!(find_guide("topics").is_none());
+    }
+}
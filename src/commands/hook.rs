@@ -0,0 +1,184 @@
+//! `detail hook`: install a git pre-push hook that runs `detail check`, so
+//! open security vulnerabilities are caught before they're pushed.
+
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use console::style;
+
+use crate::exit::{Failure, THRESHOLD_EXCEEDED};
+use crate::output::stdout;
+use crate::utils::git::{hooks_dir, resolve_repo_arg};
+
+/// First comment line of hooks this command writes; hooks without it are
+/// someone else's and left alone.
+const MARKER: &str = "# Installed by `detail hook install`";
+
+const HOOK_NAME: &str = "pre-push";
+
+#[derive(Subcommand)]
+pub enum HookCommands {
+    /// Write a git pre-push hook that runs `detail check --fail-on vulns`
+    /// and stops the push while security vulnerabilities are open
+    Install {
+        /// Repository by owner/repo (e.g., usedetail/cli) or repo (e.g., cli).
+        /// If omitted, inferred from the git remote (origin).
+        repo: Option<String>,
+
+        /// Only print a warning; never stop the push
+        #[arg(long)]
+        warn_only: bool,
+
+        /// Replace a pre-push hook that `detail` didn't install
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Remove the pre-push hook installed by `detail hook install`
+    Uninstall,
+}
+
+/// `s` quoted for `sh`.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// The hook script. Only the check's threshold exit code counts, so being
+/// offline or logged out never blocks a push.
+fn script(detail: &str, repo: &str, warn_only: bool) -> String {
+    let (exit, hint) = if warn_only {
+        (0, "")
+    } else {
+        (1, "; push with --no-verify to skip this check")
+    };
+    let lines = [
+        "#!/bin/sh".to_string(),
+        format!("{MARKER}; `detail hook uninstall` removes it."),
+        format!(
+            "{} check {} --fail-on vulns",
+            shell_quote(detail),
+            shell_quote(repo)
+        ),
+        format!("if [ $? -eq {THRESHOLD_EXCEEDED} ]; then"),
+        format!("    echo \"detail: security vulnerabilities are open in {repo}{hint}\" >&2"),
+        format!("    exit {exit}"),
+        "fi".to_string(),
+        "exit 0".to_string(),
+    ];
+    let mut script = lines.join("\n");
+    script.push('\n');
+    script
+}
+
+/// Whether the hook at `path` was written by `detail hook install`.
+fn is_ours(path: &Path) -> Result<bool> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(contents.lines().any(|line| line.starts_with(MARKER))),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+        // Binary or unreadable: someone else's.
+        Err(e) if e.kind() == ErrorKind::InvalidData => Ok(false),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+/// Git skips hooks it can't execute.
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt as _;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("Failed to make {} executable", path.display()))
+}
+
+pub fn handle(command: &HookCommands) -> Result<()> {
+    let Some(dir) = hooks_dir() else {
+        bail!(Failure::usage("Not in a git repository"));
+    };
+    let path = dir.join(HOOK_NAME);
+    let term = stdout();
+    match command {
+        HookCommands::Install {
+            repo,
+            warn_only,
+            force,
+        } => {
+            let repo = resolve_repo_arg(repo.as_deref())?;
+            if path.exists() && !*force && !is_ours(&path)? {
+                bail!(Failure::usage(format!(
+                    "{} already exists. Pass --force to replace it.",
+                    path.display()
+                )));
+            }
+            let detail = env::current_exe().context("Failed to locate the detail binary")?;
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+            fs::write(&path, script(&detail.to_string_lossy(), &repo, *warn_only))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            #[cfg(unix)]
+            make_executable(&path)?;
+            term.write_line(&format!(
+                "{} Installed {} for {repo}",
+                style("✓").green(),
+                path.display()
+            ))?;
+        }
+        HookCommands::Uninstall => {
+            if !is_ours(&path)? {
+                bail!(Failure::not_found(format!(
+                    "No pre-push hook from `detail hook install` at {}",
+                    path.display()
+                )));
+            }
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+            term.write_line(&format!(
+                "{} Removed {}",
+                style("✓").green(),
+                path.display()
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use super::*;
+
+    #[test]
+    fn script_blocks_only_on_the_threshold_exit_code() {
+        let script = script("/usr/local/bin/detail", "acme/api", false);
+        assert!(script.starts_with("#!/bin/sh\n# Installed by `detail hook install`"));
+        assert!(script.contains("'/usr/local/bin/detail' check 'acme/api' --fail-on vulns\n"));
+        assert!(script.contains("if [ $? -eq 4 ]; then\n"));
+        assert!(script.contains("    exit 1\n"));
+        assert!(script.ends_with("fi\nexit 0\n"));
+
+        let warn = super::script("detail", "acme/api", true);
+        assert!(warn.contains("    exit 0\n"));
+        assert!(!warn.contains("--no-verify"));
+    }
+
+    #[test]
+    fn quotes_survive_the_shell() {
+        assert_eq!(shell_quote("/opt/it's/detail"), r"'/opt/it'\''s/detail'");
+    }
+
+    #[test]
+    fn only_marked_hooks_count_as_ours() {
+        let dir = env::temp_dir().join(format!("detail-cli-hook-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(HOOK_NAME);
+        assert!(!is_ours(&path).unwrap());
+        fs::write(&path, "#!/bin/sh\nnpm test\n").unwrap();
+        assert!(!is_ours(&path).unwrap());
+        fs::write(&path, script("detail", "acme/api", false)).unwrap();
+        assert!(is_ours(&path).unwrap());
+        fs::remove_dir_all(&dir).ok();
+    }
+}
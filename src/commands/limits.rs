@@ -0,0 +1,64 @@
+use anyhow::{Context, Result};
+
+use crate::api::client::RateLimits;
+use crate::output::{stdout, write_structured, SectionRenderer};
+
+/// Key-value rows for the rate-limit headers the API sent.
+fn limit_rows(limits: &RateLimits) -> Vec<(&'static str, String)> {
+    let mut rows = Vec::new();
+    if let Some(limit) = limits.limit {
+        rows.push(("Limit", limit.to_string()));
+    }
+    if let Some(remaining) = limits.remaining {
+        rows.push(("Remaining", remaining.to_string()));
+    }
+    if let Some(reset) = &limits.reset {
+        rows.push(("Resets in", format!("{reset} s")));
+    }
+    rows
+}
+
+pub async fn handle(format: &crate::OutputFormat, cli: &crate::Cli) -> Result<()> {
+    let client = cli.create_client()?;
+    let limits = client
+        .rate_limits()
+        .await
+        .context("Failed to query rate limits")?;
+
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Yaml => {
+            write_structured(&limits, format)?;
+        }
+        crate::OutputFormat::Table => {
+            if limits.is_empty() {
+                stdout().write_line("The API did not report any rate limits.")?;
+                return Ok(());
+            }
+            SectionRenderer::new()
+                .key_value("API rate limits", &limit_rows(&limits))
+                .print()?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_skip_missing_headers() {
+        let limits = RateLimits {
+            limit: Some(1000),
+            remaining: None,
+            reset: Some("30".into()),
+        };
+        assert_eq!(
+            limit_rows(&limits),
+            [
+                ("Limit", "1000".to_string()),
+                ("Resets in", "30 s".to_string())
+            ]
+        );
+    }
+}
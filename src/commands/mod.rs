@@ -1,9 +1,29 @@
+pub mod alert;
 pub mod auth;
+pub mod blame;
 pub mod bugs;
+pub mod check;
 pub mod completions;
+pub mod daemon;
+pub mod diff;
+pub mod digest;
+pub mod export_issue;
+pub mod fix;
+pub mod help;
+pub mod hook;
+pub mod limits;
+pub mod notify;
+pub mod palette;
+pub mod patch;
+pub mod prompt_segment;
+pub mod queue;
+pub mod reconcile;
 pub mod repos;
 pub mod rules;
 pub mod satisfying_sort;
 pub mod scans;
+pub mod schema;
+pub mod selftest;
 pub mod skill;
 pub mod update;
+pub mod usage;
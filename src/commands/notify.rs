@@ -0,0 +1,49 @@
+//! `detail notify`: send a message to the configured notification sinks.
+
+use std::io;
+
+use anyhow::{bail, Context, Result};
+use console::style;
+
+use crate::config::storage::load_config;
+use crate::exit::Failure;
+use crate::notify::{self, Message, Payload};
+use crate::output::{stdout, write_stdout};
+
+pub async fn handle(
+    message: Option<&str>,
+    title: &str,
+    sinks: &[String],
+    dry_run: bool,
+    cli: &crate::Cli,
+) -> Result<()> {
+    let body = match message {
+        Some(message) if message != "-" => message.to_string(),
+        _ => io::read_to_string(io::stdin()).context("Failed to read the message from stdin")?,
+    };
+    let body = body.trim_end().to_string();
+    if body.is_empty() {
+        bail!(Failure::usage("The message is empty"));
+    }
+    let message = Message {
+        source: "notify".to_string(),
+        title: title.to_string(),
+        body,
+    };
+
+    if dry_run {
+        let config = load_config()?;
+        for (name, sink) in notify::select(&config.notifications, sinks)? {
+            let rendered = match notify::payload(sink, &message)? {
+                Payload::Json(body) => serde_json::to_string_pretty(&body)?,
+                Payload::Mail(mail) => mail,
+            };
+            write_stdout(&format!("# {name}\n{}\n", rendered.trim_end()))?;
+        }
+        return Ok(());
+    }
+
+    notify::send(sinks, &message, cli).await?;
+    stdout().write_line(&format!("{}", style("✓ Notification sent").green()))?;
+    Ok(())
+}
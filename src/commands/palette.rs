@@ -0,0 +1,193 @@
+//! Command palette shown when `detail` runs with no arguments in a terminal
+
+use std::cmp::Reverse;
+use std::env;
+use std::iter;
+
+use anyhow::{Context, Result};
+use console::Term;
+use dialoguer::{FuzzySelect, Input};
+
+use crate::api::types::{Bug, BugReviewState};
+use crate::create_client;
+use crate::utils::git::infer_repo_from_git_remote;
+use crate::utils::repos::resolve_repo_id;
+use crate::utils::text::truncate_display;
+
+/// How many pending bugs to offer when an action needs a bug ID.
+const RECENT_BUGS: u32 = 20;
+
+/// Columns of a bug picker row besides the title and ID: the selection
+/// marker, ` (` and `)`, and a spare column so rows never touch the edge.
+const PICKER_MARGIN: usize = 6;
+
+struct Action {
+    label: &'static str,
+    args: &'static [&'static str],
+    /// The bug ID is picked after the action and appended to `args`.
+    needs_bug: bool,
+}
+
+const ACTIONS: [Action; 9] = [
+    Action {
+        label: "List pending bugs in this repo",
+        args: &["bugs", "list"],
+        needs_bug: false,
+    },
+    Action {
+        label: "Show a recent bug",
+        args: &["bugs", "show"],
+        needs_bug: true,
+    },
+    Action {
+        label: "Close a bug",
+        args: &["bugs", "close"],
+        needs_bug: true,
+    },
+    Action {
+        label: "List scans in this repo",
+        args: &["scans", "list"],
+        needs_bug: false,
+    },
+    Action {
+        label: "List repositories",
+        args: &["repos", "list"],
+        needs_bug: false,
+    },
+    Action {
+        label: "Show auth status",
+        args: &["auth", "status"],
+        needs_bug: false,
+    },
+    Action {
+        label: "Log in",
+        args: &["auth", "login"],
+        needs_bug: false,
+    },
+    Action {
+        label: "Update the CLI",
+        args: &["update"],
+        needs_bug: false,
+    },
+    Action {
+        label: "Show help",
+        args: &["--help"],
+        needs_bug: false,
+    },
+];
+
+/// The palette opens only for a bare `detail` with both stdout and stderr
+/// attached to a terminal; scripts and pipes keep getting the help text.
+pub fn should_open() -> bool {
+    env::args_os().len() == 1 && Term::stdout().is_term() && Term::stderr().is_term()
+}
+
+fn action_args(action: &Action) -> Vec<String> {
+    iter::once("detail")
+        .chain(action.args.iter().copied())
+        .map(String::from)
+        .collect()
+}
+
+/// Let the user pick an action and return the argv to run it with, or `None`
+/// if the palette was dismissed.
+pub async fn choose() -> Result<Option<Vec<String>>> {
+    let labels: Vec<&str> = ACTIONS.iter().map(|action| action.label).collect();
+    let Some(action) = FuzzySelect::new()
+        .with_prompt("What would you like to do?")
+        .items(&labels)
+        .default(0)
+        .interact_opt()
+        .context("Failed to read palette selection")?
+        .and_then(|index| ACTIONS.get(index))
+    else {
+        return Ok(None);
+    };
+
+    let mut args = action_args(action);
+    if action.needs_bug {
+        let Some(bug_id) = pick_bug().await? else {
+            return Ok(None);
+        };
+        args.push(bug_id);
+    }
+    Ok(Some(args))
+}
+
+/// Offer the current repo's most recent pending bugs, falling back to typing
+/// a bug ID when they can't be listed (no git remote, not logged in, …).
+async fn pick_bug() -> Result<Option<String>> {
+    if let Some(bugs) = recent_bugs().await.filter(|bugs| !bugs.is_empty()) {
+        // Titles that wrap would break the picker's redraw; leave room for
+        // the selection marker and the bug ID.
+        let title_width = usize::from(Term::stderr().size().1).saturating_sub(PICKER_MARGIN);
+        let items: Vec<String> = bugs
+            .iter()
+            .map(|bug| {
+                let title = truncate_display(&bug.title, title_width.saturating_sub(bug.id.len()));
+                format!("{title} ({})", bug.id)
+            })
+            .collect();
+        let selection = FuzzySelect::new()
+            .with_prompt("Bug")
+            .items(&items)
+            .default(0)
+            .interact_opt()
+            .context("Failed to read bug selection")?;
+        return Ok(selection
+            .and_then(|index| bugs.get(index))
+            .map(|bug| bug.id.to_string()));
+    }
+
+    let bug_id: String = Input::new()
+        .with_prompt("Bug ID")
+        .interact_text()
+        .context("Failed to read bug ID")?;
+    Ok(Some(bug_id))
+}
+
+async fn recent_bugs() -> Option<Vec<Bug>> {
+    let repo = infer_repo_from_git_remote().ok()?;
+    let client = create_client().ok()?;
+    let repo_id = resolve_repo_id(&client, &repo).await.ok()?;
+    let mut bugs = client
+        .list_bugs(&repo_id, BugReviewState::Pending, RECENT_BUGS, 0, None)
+        .await
+        .ok()?
+        .bugs;
+    bugs.sort_by_key(|bug| Reverse(bug.created_at));
+    Some(bugs)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::Cli;
+
+    use super::*;
+
+    #[test]
+    fn every_action_parses() {
+        for action in &ACTIONS {
+            let mut args = action_args(action);
+            if action.needs_bug {
+                args.push("bug_123".to_string());
+            }
+            match Cli::try_parse_from(&args) {
+                Ok(_) => {}
+                Err(err) => assert_eq!(
+                    err.kind(),
+                    clap::error::ErrorKind::DisplayHelp,
+                    "{}: {err}",
+                    action.label
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn action_args_start_with_binary_name() {
+        assert_eq!(action_args(&ACTIONS[0]), ["detail", "bugs", "list"]);
+    }
+}
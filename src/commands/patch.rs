@@ -0,0 +1,209 @@
+//! `detail bugs patch`: pull the suggested fix out of a bug report and hand
+//! it to `git apply`.
+
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use console::style;
+
+use crate::api::client::ApiClient;
+use crate::api::types::BugId;
+use crate::exit::Failure;
+use crate::output::{stdout, write_stdout};
+use crate::utils::git::repo_root;
+
+/// What to do with the extracted patch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchMode {
+    /// Print it.
+    Print,
+    /// Check that it applies, without touching the working tree.
+    Check,
+    /// Apply it to the working tree.
+    Apply,
+}
+
+/// A fenced code block: its info string and its contents.
+struct Fence<'a> {
+    info: &'a str,
+    body: String,
+}
+
+/// The opening fence of a code block, as its marker (e.g. ```` ``` ````) and
+/// info string, if `line` is one.
+fn opening_fence(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_start();
+    let marker_char = line.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = line.chars().take_while(|&c| c == marker_char).count();
+    (len >= 3).then(|| line.split_at(len))
+}
+
+/// Whether `line` closes a block opened with `marker`: the same
+/// character, at least as many times, and nothing else.
+fn closes_fence(line: &str, marker: &str) -> bool {
+    let line = line.trim();
+    let marker_char = marker.chars().next();
+    line.len() >= marker.len() && line.chars().all(|c| Some(c) == marker_char)
+}
+
+/// The fenced code blocks in a markdown document, in order. An unclosed
+/// block runs to the end of the document.
+fn fences(markdown: &str) -> Vec<Fence<'_>> {
+    let mut fences = Vec::new();
+    let mut open: Option<(&str, Fence<'_>)> = None;
+    for line in markdown.lines() {
+        let closes = open
+            .as_ref()
+            .is_some_and(|(marker, _)| closes_fence(line, marker));
+        if closes {
+            fences.extend(open.take().map(|(_, fence)| fence));
+        } else if let Some((_, fence)) = &mut open {
+            fence.body.push_str(line);
+            fence.body.push('\n');
+        } else if let Some((marker, info)) = opening_fence(line) {
+            let info = info.split_whitespace().next().unwrap_or_default();
+            let body = String::new();
+            open = Some((marker, Fence { info, body }));
+        }
+    }
+    fences.extend(open.map(|(_, fence)| fence));
+    fences
+}
+
+/// Whether `text` reads as a unified diff.
+fn looks_like_diff(text: &str) -> bool {
+    let lines: Vec<&str> = text.lines().collect();
+    lines.iter().any(|line| line.starts_with("diff --git "))
+        || lines
+            .windows(2)
+            .any(|pair| pair[0].starts_with("--- ") && pair[1].starts_with("+++ "))
+}
+
+/// The suggested patch in a bug report: the first fenced block tagged
+/// `diff` or `patch` that holds a diff, else the first untagged one that
+/// does.
+pub fn extract_patch(report: &str) -> Option<String> {
+    let fences = fences(report);
+    let tagged =
+        |fence: &&Fence<'_>| matches!(fence.info.to_ascii_lowercase().as_str(), "diff" | "patch");
+    fences
+        .iter()
+        .filter(tagged)
+        .chain(fences.iter().filter(|fence| !tagged(fence)))
+        .find(|fence| looks_like_diff(&fence.body))
+        .map(|fence| fence.body.clone())
+}
+
+/// Feed `patch` to `git apply` (with `--check` when only checking) at the
+/// top of the work tree. Git explains any failure on stderr.
+fn git_apply(patch: &str, check: bool) -> Result<bool> {
+    let mut git = Command::new("git");
+    git.arg("apply");
+    if check {
+        git.arg("--check");
+    }
+    git.arg("-").stdin(Stdio::piped());
+    if let Some(root) = repo_root() {
+        git.current_dir(root);
+    }
+    let mut child = git.spawn().context("Failed to run `git apply`")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(patch.as_bytes())
+            .context("Failed to pass the patch to `git apply`")?;
+    }
+    let status = child.wait().context("Failed to run `git apply`")?;
+    Ok(status.success())
+}
+
+pub async fn handle(client: &ApiClient, bug_id: &str, mode: PatchMode) -> Result<()> {
+    let bug_id: BugId = bug_id
+        .try_into()
+        .context(Failure::usage("Invalid bug ID format (expected bug_...)"))?;
+    let bug = client
+        .get_bug(&bug_id)
+        .await
+        .context("Failed to fetch bug details")?;
+    let Some(patch) = extract_patch(&bug.summary) else {
+        bail!(Failure::not_found(format!(
+            "The report for {bug_id} doesn't include a suggested patch"
+        )));
+    };
+
+    let term = stdout();
+    match mode {
+        PatchMode::Print => write_stdout(&patch),
+        PatchMode::Check => {
+            if !git_apply(&patch, true)? {
+                bail!("The suggested patch for {bug_id} doesn't apply to this checkout");
+            }
+            term.write_line(&format!(
+                "{}",
+                style("✓ The suggested patch applies cleanly").green()
+            ))?;
+            Ok(())
+        }
+        PatchMode::Apply => {
+            if !git_apply(&patch, false)? {
+                bail!("Failed to apply the suggested patch for {bug_id}; the working tree is unchanged");
+            }
+            term.write_line(&format!(
+                "{}",
+                style("✓ Applied the suggested patch; review it with `git diff`").green()
+            ))?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+--- a/src/handler.rs
++++ b/src/handler.rs
+@@ -1,3 +1,3 @@
+-    let v = input.unwrap();
++    let v = input?;
+";
+
+    #[test]
+    fn extracts_the_diff_block() {
+        let report = format!(
+            "The handler panics on empty input.\n\n```rust\nlet v = input.unwrap();\n```\n\n\
+             Suggested fix:\n\n```diff\n{DIFF}```\n"
+        );
+        assert_eq!(extract_patch(&report).as_deref(), Some(DIFF));
+    }
+
+    #[test]
+    fn falls_back_to_an_untagged_block_that_reads_as_a_diff() {
+        let report = format!("Fix:\n\n~~~~\n{DIFF}~~~~\n");
+        assert_eq!(extract_patch(&report).as_deref(), Some(DIFF));
+    }
+
+    #[test]
+    fn prefers_tagged_blocks_and_skips_non_diffs() {
+        let git_diff = "diff --git a/x b/x\n--- a/x\n+++ b/x\n";
+        let report =
+            format!("```\n{DIFF}```\n\n```diff\nnot a diff\n```\n\n```patch\n{git_diff}```\n");
+        assert_eq!(extract_patch(&report).as_deref(), Some(git_diff));
+    }
+
+    #[test]
+    fn longer_fences_can_contain_shorter_ones() {
+        let report = format!("````diff\n{DIFF}```\n````\n");
+        assert_eq!(extract_patch(&report), Some(format!("{DIFF}```\n")));
+    }
+
+    #[test]
+    fn reports_without_a_diff_have_no_patch() {
+        assert_eq!(
+            extract_patch("Just prose.\n\n```rust\nfn main() {}\n```\n"),
+            None
+        );
+        assert_eq!(extract_patch(""), None);
+    }
+}
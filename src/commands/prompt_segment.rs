@@ -0,0 +1,182 @@
+//! `detail prompt-segment`: a compact bug summary for shell prompts
+//! (starship, powerlevel10k, …). It only reads a per-repo cache; when that
+//! is stale, a detached `detail prompt-segment --refresh` updates it for
+//! the next prompt, so drawing the prompt never waits on the network.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::api::cache::request_scope;
+use crate::api::client::ApiClient;
+use crate::api::types::BugReviewState;
+use crate::config::storage::{api_token, cache_dir, load_config};
+use crate::output::stdout;
+use crate::utils::git::infer_repo_from_git_remote;
+use crate::utils::repos::resolve_repo_id;
+
+/// How long cached counts are shown before a background refresh starts.
+const SEGMENT_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// How long a started refresh holds off starting another, so a burst of
+/// prompts doesn't spawn one refresh each.
+const REFRESH_LOCK_TTL: Duration = Duration::from_secs(60);
+
+/// Pending-bug counts for one repo, as last fetched.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SegmentCache {
+    /// Token and org the counts were fetched with; see `request_scope`.
+    scope: String,
+    /// Epoch millis.
+    fetched_at: i64,
+    pending: usize,
+    vulns: usize,
+}
+
+/// The segment text, e.g. `detail: 4⚠ 1🔒`. Empty when nothing is pending,
+/// so the prompt stays clean.
+fn render_segment(cache: &SegmentCache) -> String {
+    if cache.pending == 0 {
+        return String::new();
+    }
+    let vulns = if cache.vulns > 0 {
+        format!(" {}🔒", cache.vulns)
+    } else {
+        String::new()
+    };
+    format!("detail: {}⚠{vulns}", cache.pending)
+}
+
+/// Whether counts fetched at `fetched_at` are still fresh at `now`.
+fn is_fresh(cache: &SegmentCache, now: i64) -> bool {
+    let ttl = i64::try_from(SEGMENT_TTL.as_millis()).unwrap_or(i64::MAX);
+    now.checked_sub(cache.fetched_at)
+        .is_some_and(|age| (0..ttl).contains(&age))
+}
+
+/// Cache file for `repo` (`owner/repo`).
+fn cache_path(repo: &str) -> Result<PathBuf> {
+    let dir = cache_dir()?.join("prompt");
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    Ok(dir.join(format!("{}.json", repo.replace('/', "__"))))
+}
+
+fn read_cache(path: &Path, scope: &str) -> Option<SegmentCache> {
+    let contents = fs::read_to_string(path).ok()?;
+    let cache: SegmentCache = serde_json::from_str(&contents).ok()?;
+    (cache.scope == scope).then_some(cache)
+}
+
+/// Fetch the pending and pending-vulnerability counts for `repo` and cache
+/// them.
+async fn refresh(client: &ApiClient, repo: &str, path: &Path) -> Result<SegmentCache> {
+    let repo_id = resolve_repo_id(client, repo).await?;
+    let bugs = client
+        .list_all_bugs(&repo_id, BugReviewState::Pending)
+        .await?;
+    let cache = SegmentCache {
+        scope: client.cache_scope().to_string(),
+        fetched_at: chrono::Utc::now().timestamp_millis(),
+        pending: bugs.len(),
+        vulns: bugs
+            .iter()
+            .filter(|bug| bug.is_security_vulnerability == Some(true))
+            .count(),
+    };
+    fs::write(path, serde_json::to_string(&cache)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(cache)
+}
+
+/// Start `detail prompt-segment --refresh` in the background, unless one
+/// was started recently.
+fn spawn_refresh(path: &Path) -> Result<()> {
+    let lock = path.with_extension("lock");
+    let recently_started = fs::metadata(&lock)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < REFRESH_LOCK_TTL);
+    if recently_started {
+        return Ok(());
+    }
+    fs::write(&lock, "")?;
+    Command::new(env::current_exe()?)
+        .args(["prompt-segment", "--refresh"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
+/// The segment for the current directory's repo. `None` outside a repo,
+/// before login, or before the first refresh has finished.
+async fn segment(cli: &crate::Cli) -> Result<Option<String>> {
+    let repo = infer_repo_from_git_remote()?;
+    let path = cache_path(&repo)?;
+
+    if cli.refresh() {
+        let client = cli.create_client()?;
+        let cache = refresh(&client, &repo, &path).await;
+        let _ = fs::remove_file(path.with_extension("lock"));
+        return cache.map(|cache| Some(render_segment(&cache)));
+    }
+
+    let config = load_config()?;
+    let Some(token) = api_token(&config)? else {
+        return Ok(None);
+    };
+    let cached = read_cache(&path, &request_scope(Some(&token), config.org.as_deref()));
+    let now = chrono::Utc::now().timestamp_millis();
+    if !cached.as_ref().is_some_and(|cache| is_fresh(cache, now)) {
+        spawn_refresh(&path)?;
+    }
+    Ok(cached.map(|cache| render_segment(&cache)))
+}
+
+pub async fn handle(cli: &crate::Cli) -> Result<()> {
+    // A prompt segment must never print errors into the prompt: anything
+    // that goes wrong just means an empty segment.
+    if let Ok(Some(segment)) = segment(cli).await {
+        if !segment.is_empty() {
+            stdout().write_line(&segment)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(fetched_at: i64, pending: usize, vulns: usize) -> SegmentCache {
+        SegmentCache {
+            scope: "tok".into(),
+            fetched_at,
+            pending,
+            vulns,
+        }
+    }
+
+    #[test]
+    fn segment_shows_pending_and_vulns() {
+        assert_eq!(render_segment(&cache(0, 4, 1)), "detail: 4⚠ 1🔒");
+        assert_eq!(render_segment(&cache(0, 4, 0)), "detail: 4⚠");
+        assert_eq!(render_segment(&cache(0, 0, 0)), "");
+    }
+
+    #[test]
+    fn counts_expire_after_the_ttl() {
+        let ttl = i64::try_from(SEGMENT_TTL.as_millis()).unwrap();
+        assert!(is_fresh(&cache(1_000, 1, 0), 1_000 + ttl - 1));
+        assert!(!is_fresh(&cache(1_000, 1, 0), 1_000 + ttl));
+        assert!(!is_fresh(&cache(1_000, 1, 0), 0));
+    }
+}
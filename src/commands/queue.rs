@@ -0,0 +1,236 @@
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use console::{style, Term};
+use dialoguer::Confirm;
+
+use crate::api::client::{is_network_error, ApiClient};
+use crate::api::types::{Bug, BugId, BugReviewState};
+use crate::config::queue::{self, QueuedReview};
+use crate::exit::Failure;
+use crate::output::{output_list, stdout};
+
+#[derive(Subcommand)]
+pub enum QueueCommands {
+    /// List bug reviews queued while offline
+    List {
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<crate::OutputFormat>,
+    },
+
+    /// Submit every queued bug review, oldest first
+    Flush,
+
+    /// Remove a queued bug review without submitting it
+    Drop {
+        /// Queue key (shown by `detail queue list`)
+        #[arg(required_unless_present = "all")]
+        key: Option<String>,
+
+        /// Remove every queued bug review
+        #[arg(long, conflicts_with = "key")]
+        all: bool,
+    },
+}
+
+/// Offer to queue a bug review that failed because the network is down.
+/// Declining returns the original error.
+pub fn offer_to_queue(err: anyhow::Error, review: QueuedReview) -> Result<()> {
+    let term = Term::stderr();
+    term.write_line(&format!(
+        "{} {err:#}",
+        style("Network unavailable:").yellow()
+    ))
+    .ok();
+    let confirmed = Confirm::new()
+        .with_prompt("Queue this change and submit it later with `detail queue flush`?")
+        .default(true)
+        .interact()
+        .context("Failed to read queue confirmation")?;
+    if !confirmed {
+        return Err(err);
+    }
+
+    let key = review.key.clone();
+    if queue::enqueue(review)? {
+        term.write_line(&format!("{}", style(format!("✓ Queued as {key}")).green()))
+            .ok();
+    } else {
+        term.write_line(&format!(
+            "{}",
+            style(format!("Already queued as {key}")).dim()
+        ))
+        .ok();
+    }
+    Ok(())
+}
+
+/// Whether the bug already carries the queued review, e.g. because it was
+/// closed from the web app or another machine in the meantime. Flushing skips
+/// these so a replay never overwrites a newer review with the same change.
+fn already_applied(bug: &Bug, entry: &QueuedReview) -> bool {
+    bug.review
+        .as_ref()
+        .map_or(entry.state == BugReviewState::Pending, |review| {
+            review.state == entry.state
+                && review.dismissal_reason == entry.dismissal_reason
+                && review.notes == entry.notes
+        })
+}
+
+enum Replay {
+    Applied,
+    AlreadyApplied,
+}
+
+async fn replay(client: &ApiClient, entry: &QueuedReview) -> Result<Replay> {
+    let bug_id: BugId = entry
+        .bug_id
+        .as_str()
+        .try_into()
+        .context(Failure::usage("Invalid bug ID format (expected bug_...)"))?;
+    let bug = client.get_bug(&bug_id).await?;
+    if already_applied(&bug, entry) {
+        return Ok(Replay::AlreadyApplied);
+    }
+    client
+        .update_bug_close(
+            &bug_id,
+            entry.state,
+            entry.dismissal_reason,
+            entry.notes.as_deref(),
+        )
+        .await?;
+    Ok(Replay::Applied)
+}
+
+async fn flush(client: &ApiClient) -> Result<()> {
+    let entries = queue::load_queue()?;
+    let term = stdout();
+    if entries.is_empty() {
+        term.write_line("No queued changes.")?;
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for entry in &entries {
+        match replay(client, entry).await {
+            Ok(Replay::Applied) => {
+                queue::remove(&entry.key)?;
+                term.write_line(&format!(
+                    "{}",
+                    style(format!("✓ {} → {}", entry.bug_id, entry.state)).green()
+                ))?;
+            }
+            Ok(Replay::AlreadyApplied) => {
+                queue::remove(&entry.key)?;
+                term.write_line(&format!(
+                    "{}",
+                    style(format!(
+                        "✓ {} already {} — skipped",
+                        entry.bug_id, entry.state
+                    ))
+                    .dim()
+                ))?;
+            }
+            Err(err) if is_network_error(&err) => {
+                let remaining = queue::load_queue()?.len();
+                bail!("Still offline ({err:#}); {remaining} changes remain queued");
+            }
+            Err(err) => {
+                failed += 1;
+                term.write_line(&format!(
+                    "{}",
+                    style(format!("✗ {}: {err:#}", entry.bug_id)).red()
+                ))?;
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!(
+            "{failed} of {} queued changes failed and remain queued",
+            entries.len()
+        );
+    }
+    Ok(())
+}
+
+pub async fn handle(command: &QueueCommands, cli: &crate::Cli) -> Result<()> {
+    match command {
+        QueueCommands::List { format } => {
+            let format = &format.unwrap_or_default();
+            let entries = queue::load_queue()?;
+            if entries.is_empty() && matches!(format, crate::OutputFormat::Table) {
+                stdout().write_line("No queued changes.")?;
+                return Ok(());
+            }
+            let limit = u32::try_from(entries.len()).unwrap_or(u32::MAX).max(1);
+            output_list(&entries, entries.len(), 1, limit, format)
+        }
+
+        QueueCommands::Flush => flush(&cli.create_client()?).await,
+
+        QueueCommands::Drop { key, all } => {
+            let term = stdout();
+            if *all {
+                let dropped = queue::update_queue(|entries| {
+                    let count = entries.len();
+                    entries.clear();
+                    count
+                })?;
+                term.write_line(&format!(
+                    "{}",
+                    style(format!("✓ Dropped {dropped} queued changes")).green()
+                ))?;
+                return Ok(());
+            }
+
+            let Some(key) = key else {
+                bail!("Pass a queue key or --all");
+            };
+            if !queue::remove(key)? {
+                bail!("No queued change with key {key}");
+            }
+            term.write_line(&format!("{}", style(format!("✓ Dropped {key}")).green()))?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::BugDismissalReason;
+    use crate::test_support::test_bug;
+
+    fn bug_with_review(review: Option<serde_json::Value>) -> Bug {
+        test_bug(serde_json::json!({ "review": review }))
+    }
+
+    #[test]
+    fn already_applied_matches_state_reason_and_notes() {
+        let entry = QueuedReview::new(
+            "bug_1",
+            BugReviewState::Dismissed,
+            Some(BugDismissalReason::WontFix),
+            None,
+            0,
+        );
+        let same = bug_with_review(Some(serde_json::json!({
+            "state": "dismissed", "dismissalReason": "wont_fix", "createdAt": 1
+        })));
+        let other = bug_with_review(Some(serde_json::json!({
+            "state": "dismissed", "dismissalReason": "duplicate", "createdAt": 1
+        })));
+        assert!(already_applied(&same, &entry));
+        assert!(!already_applied(&other, &entry));
+        assert!(!already_applied(&bug_with_review(None), &entry));
+    }
+
+    #[test]
+    fn reopen_is_applied_when_bug_has_no_review() {
+        let entry = QueuedReview::new("bug_1", BugReviewState::Pending, None, None, 0);
+        assert!(already_applied(&bug_with_review(None), &entry));
+    }
+}
@@ -0,0 +1,374 @@
+//! `detail bugs reconcile`: compare a repo's bugs with the external issues
+//! tracking them, close issues whose bugs are resolved, and flag drift the
+//! other way.
+
+use std::collections::HashMap;
+use std::process::Command;
+
+use anyhow::{anyhow, bail, Context, Result};
+use console::style;
+use serde::Serialize;
+
+use crate::api::client::ApiClient;
+use crate::api::types::{Bug, BugReviewState};
+use crate::config::issue_links::{load_links, IssueLink};
+use crate::export::bug_state;
+use crate::output::{stdout, write_structured};
+use crate::utils::repos::resolve_repo_id;
+
+/// The one tracker the CLI can read and close issues in, through `gh`.
+const GITHUB: &str = "github";
+
+/// Slack links point at threads, not issues, so there's nothing to sync.
+const SLACK: &str = "slack";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ExternalState {
+    Open,
+    Closed,
+}
+
+/// How a bug and its issue compare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum Drift {
+    InSync,
+    /// Resolved or dismissed in Detail, still open in the tracker.
+    CloseExternal,
+    /// Closed in the tracker, still pending in Detail.
+    ClosedExternally,
+    /// The issue's state couldn't be read.
+    Unknown,
+}
+
+const fn compare(bug: BugReviewState, external: ExternalState) -> Drift {
+    match (bug, external) {
+        (BugReviewState::Pending, ExternalState::Open)
+        | (BugReviewState::Resolved | BugReviewState::Dismissed, ExternalState::Closed) => {
+            Drift::InSync
+        }
+        (BugReviewState::Pending, ExternalState::Closed) => Drift::ClosedExternally,
+        (BugReviewState::Resolved | BugReviewState::Dismissed, ExternalState::Open) => {
+            Drift::CloseExternal
+        }
+    }
+}
+
+/// One link, compared.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Reconciled {
+    bug_id: String,
+    title: String,
+    tracker: String,
+    issue_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    bug_state: BugReviewState,
+    external_state: Option<ExternalState>,
+    drift: Drift,
+    /// Whether `--apply` closed the issue.
+    closed: bool,
+    /// Why the state is unknown, or why closing failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+}
+
+/// `owner/repo` and number of a GitHub issue, from its URL or its ID
+/// (`owner/repo#42`, or `42` / `#42` in `default_repo`).
+fn github_issue(link: &IssueLink, default_repo: &str) -> Option<(String, u64)> {
+    let from_url = link.url.as_deref().and_then(|url| {
+        let path = url
+            .strip_prefix("https://github.com/")?
+            .trim_end_matches('/');
+        let parts: Vec<&str> = path.split('/').collect();
+        match parts.as_slice() {
+            [owner, repo, "issues" | "pull", number] => {
+                Some((format!("{owner}/{repo}"), number.parse().ok()?))
+            }
+            _ => None,
+        }
+    });
+    from_url.or_else(|| {
+        let (repo, number) = link
+            .issue_id
+            .rsplit_once('#')
+            .unwrap_or((default_repo, &link.issue_id));
+        let repo = if repo.is_empty() { default_repo } else { repo };
+        let number = number.parse().ok()?;
+        repo.contains('/').then(|| (repo.to_string(), number))
+    })
+}
+
+/// Run `gh` with `args`, returning its stdout.
+fn gh(args: &[&str]) -> Result<String> {
+    let output = Command::new("gh")
+        .args(args)
+        .output()
+        .context("Failed to run `gh`; install the GitHub CLI to reconcile GitHub issues")?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn github_state(repo: &str, number: u64) -> Result<ExternalState> {
+    let number = number.to_string();
+    let state = gh(&[
+        "issue", "view", &number, "--repo", repo, "--json", "state", "--jq", ".state",
+    ])?;
+    match state.as_str() {
+        "OPEN" => Ok(ExternalState::Open),
+        "CLOSED" | "MERGED" => Ok(ExternalState::Closed),
+        other => bail!("Unexpected issue state `{other}`"),
+    }
+}
+
+fn close_github_issue(repo: &str, number: u64, bug: &Bug) -> Result<()> {
+    let number = number.to_string();
+    let comment = format!("The Detail bug {} was {}.", bug.id, bug_state(bug));
+    gh(&[
+        "issue",
+        "close",
+        &number,
+        "--repo",
+        repo,
+        "--comment",
+        &comment,
+    ])?;
+    Ok(())
+}
+
+/// Local links plus those the API reports on `bugs`, each issue once.
+pub fn all_links(mut links: Vec<IssueLink>, bugs: &[Bug]) -> Vec<IssueLink> {
+    for bug in bugs {
+        for issue in &bug.linked_issues {
+            let link = IssueLink {
+                bug_id: bug.id.to_string(),
+                tracker: issue.tracker.to_string(),
+                issue_id: issue.issue_id.clone(),
+                url: issue.url.clone(),
+            };
+            if !links.iter().any(|existing| existing.same_issue(&link)) {
+                links.push(link);
+            }
+        }
+    }
+    links.retain(|link| link.tracker != SLACK);
+    links
+}
+
+/// Compare `link`'s issue with `bug`, closing it when `apply` is set and
+/// the bug is no longer pending.
+fn reconcile(link: IssueLink, bug: &Bug, repo: &str, apply: bool) -> Reconciled {
+    let state = bug_state(bug);
+    let issue = (link.tracker == GITHUB)
+        .then(|| github_issue(&link, repo))
+        .flatten();
+    let external = match (&issue, link.tracker.as_str()) {
+        (Some((issue_repo, number)), _) => github_state(issue_repo, *number),
+        (None, GITHUB) => Err(anyhow!("Not a GitHub issue reference")),
+        (None, tracker) => Err(anyhow!(
+            "{tracker} issues can't be read from the CLI yet; check it by hand"
+        )),
+    };
+    let (external_state, mut note) = match external {
+        Ok(external) => (Some(external), None),
+        Err(e) => (None, Some(format!("{e:#}"))),
+    };
+    let drift = external_state.map_or(Drift::Unknown, |external| compare(state, external));
+
+    let mut closed = false;
+    if apply && drift == Drift::CloseExternal {
+        if let Some((issue_repo, number)) = &issue {
+            match close_github_issue(issue_repo, *number, bug) {
+                Ok(()) => closed = true,
+                Err(e) => note = Some(format!("Failed to close: {e:#}")),
+            }
+        }
+    }
+    Reconciled {
+        bug_id: link.bug_id,
+        title: bug.title.clone(),
+        tracker: link.tracker,
+        issue_id: link.issue_id,
+        url: link.url,
+        bug_state: state,
+        external_state,
+        drift,
+        closed,
+        note,
+    }
+}
+
+fn line(entry: &Reconciled) -> Option<String> {
+    let issue = format!("{} {}", entry.tracker, entry.issue_id);
+    let bug = style(&entry.bug_id).dim();
+    let note = entry
+        .note
+        .as_deref()
+        .map(|note| format!(" ({note})"))
+        .unwrap_or_default();
+    match entry.drift {
+        Drift::InSync => None,
+        Drift::CloseExternal if entry.closed => Some(format!(
+            "{} {issue}: closed, the bug is {} {bug}",
+            style("✓").green(),
+            entry.bug_state
+        )),
+        Drift::CloseExternal => Some(format!(
+            "{} {issue}: still open, but the bug is {} {bug}{note}",
+            style("⚠").yellow(),
+            entry.bug_state
+        )),
+        Drift::ClosedExternally => Some(format!(
+            "{} {issue}: closed, but the bug is still pending {bug} \
+             — close it with `detail bugs close {}`",
+            style("⚠").yellow(),
+            entry.bug_id
+        )),
+        Drift::Unknown => Some(format!(
+            "{} {issue}: {} {bug}",
+            style("?").dim(),
+            entry.note.as_deref().unwrap_or("state unknown")
+        )),
+    }
+}
+
+pub async fn handle(
+    client: &ApiClient,
+    repo: &str,
+    apply: bool,
+    format: &crate::OutputFormat,
+) -> Result<()> {
+    let repo_id = resolve_repo_id(client, repo)
+        .await
+        .context("Failed to resolve repository identifier")?;
+    let mut bugs = Vec::new();
+    for status in [
+        BugReviewState::Pending,
+        BugReviewState::Resolved,
+        BugReviewState::Dismissed,
+    ] {
+        bugs.extend(client.list_all_bugs(&repo_id, status).await?);
+    }
+    let links = all_links(load_links()?, &bugs);
+    let by_id: HashMap<String, &Bug> = bugs.iter().map(|bug| (bug.id.to_string(), bug)).collect();
+
+    // Links to bugs in other repos are left for their own reconcile.
+    let entries: Vec<Reconciled> = links
+        .into_iter()
+        .filter_map(|link| {
+            let bug = by_id.get(&link.bug_id)?;
+            Some(reconcile(link, bug, repo, apply))
+        })
+        .collect();
+
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Yaml => {
+            write_structured(&entries, format)?;
+        }
+        crate::OutputFormat::Table => {
+            let term = stdout();
+            if entries.is_empty() {
+                term.write_line(&format!("No bugs in {repo} are linked to external issues."))?;
+                return Ok(());
+            }
+            for text in entries.iter().filter_map(line) {
+                term.write_line(&text)?;
+            }
+            let count = |drift| entries.iter().filter(|e| e.drift == drift).count();
+            let to_close = entries
+                .iter()
+                .filter(|e| e.drift == Drift::CloseExternal && !e.closed)
+                .count();
+            term.write_line(&format!(
+                "{}",
+                style(format!(
+                    "{} linked issues: {} in sync, {to_close} to close, {} closed externally, {} unknown",
+                    entries.len(),
+                    count(Drift::InSync),
+                    count(Drift::ClosedExternally),
+                    count(Drift::Unknown)
+                ))
+                .dim()
+            ))?;
+            if to_close > 0 && !apply {
+                term.write_line(&format!("{}", style("Pass --apply to close them").dim()))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExternalState::{Closed, Open};
+    use super::*;
+    use crate::api::types::BugReviewState::{Dismissed, Pending, Resolved};
+    use crate::test_support::test_bug;
+
+    fn link(issue_id: &str, url: Option<&str>) -> IssueLink {
+        IssueLink {
+            bug_id: "bug_1".into(),
+            tracker: GITHUB.into(),
+            issue_id: issue_id.into(),
+            url: url.map(str::to_owned),
+        }
+    }
+
+    fn bug(id: &str, linked: serde_json::Value) -> Bug {
+        test_bug(serde_json::json!({ "id": id, "linkedIssues": linked }))
+    }
+
+    #[test]
+    fn states_compare_both_ways() {
+        assert_eq!(compare(Pending, Open), Drift::InSync);
+        assert_eq!(compare(Resolved, Closed), Drift::InSync);
+        assert_eq!(compare(Dismissed, Open), Drift::CloseExternal);
+        assert_eq!(compare(Pending, Closed), Drift::ClosedExternally);
+    }
+
+    #[test]
+    fn github_issues_come_from_urls_or_ids() {
+        let url = link("42", Some("https://github.com/acme/web/issues/7"));
+        assert_eq!(github_issue(&url, "acme/api"), Some(("acme/web".into(), 7)));
+        assert_eq!(
+            github_issue(&link("acme/web#9", None), "acme/api"),
+            Some(("acme/web".into(), 9))
+        );
+        assert_eq!(
+            github_issue(&link("#12", None), "acme/api"),
+            Some(("acme/api".into(), 12))
+        );
+        assert_eq!(
+            github_issue(&link("12", None), "acme/api"),
+            Some(("acme/api".into(), 12))
+        );
+        // A bare repo name doesn't say which owner a bare number is under.
+        assert_eq!(github_issue(&link("12", None), "api"), None);
+        assert_eq!(github_issue(&link("ENG-4", None), "acme/api"), None);
+    }
+
+    #[test]
+    fn api_links_join_local_ones_once_and_slack_is_skipped() {
+        let local = vec![IssueLink {
+            bug_id: "bug_1".into(),
+            tracker: "jira".into(),
+            issue_id: "PAY-1".into(),
+            url: None,
+        }];
+        let bugs = [bug(
+            "bug_1",
+            serde_json::json!([
+                { "tracker": "jira", "issueId": "PAY-1", "url": "https://acme.atlassian.net/browse/PAY-1" },
+                { "tracker": "github", "issueId": "acme/api#3", "url": null },
+                { "tracker": "slack", "issueId": "C1", "url": "https://acme.slack.com/archives/C1" }
+            ]),
+        )];
+        let links = all_links(local, &bugs);
+        let ids: Vec<&str> = links.iter().map(|l| l.issue_id.as_str()).collect();
+        assert_eq!(ids, ["PAY-1", "acme/api#3"]);
+    }
+}
@@ -2,10 +2,12 @@ use std::collections::BTreeMap;
 
 use anyhow::{Context, Result};
 use clap::Subcommand;
-use console::{style, Term};
+use console::style;
 
-use crate::output::output_list;
+use crate::api::types::Repo;
+use crate::output::{output_list, stdout};
 use crate::utils::pagination::page_to_offset;
+use crate::utils::term_caps;
 
 #[derive(Subcommand)]
 pub enum RepoCommands {
@@ -19,12 +21,125 @@ pub enum RepoCommands {
         #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..))]
         page: u32,
 
-        /// Output format
-        #[arg(long, value_enum, default_value = "table")]
-        format: crate::OutputFormat,
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<crate::OutputFormat>,
+
+        /// Show repos as an organization → owner → repo tree with counts
+        #[arg(long, conflicts_with = "format")]
+        tree: bool,
     },
 }
 
+/// A level of the `repos list --tree` view: an organization, an owner or a
+/// repo.
+#[derive(Debug, PartialEq, Eq)]
+struct TreeNode {
+    label: String,
+    /// Repos at or under this node.
+    repos: usize,
+    children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    fn leaf(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            repos: 1,
+            children: Vec::new(),
+        }
+    }
+
+    fn branch(label: &str, children: Vec<Self>) -> Self {
+        Self {
+            label: label.to_string(),
+            repos: children.iter().map(|child| child.repos).sum(),
+            children,
+        }
+    }
+
+    /// Fold each node with a single child into it, e.g. an owner with one
+    /// repo becomes the leaf `owner/repo`. A child named like its parent
+    /// (an owner named after its organization) keeps just the one name.
+    fn collapse(self) -> Self {
+        let mut children: Vec<Self> = self.children.into_iter().map(Self::collapse).collect();
+        if children.len() != 1 {
+            return Self { children, ..self };
+        }
+        let child = children.remove(0);
+        let label = if child.label == self.label {
+            child.label
+        } else {
+            format!("{}/{}", self.label, child.label)
+        };
+        Self { label, ..child }
+    }
+}
+
+/// Group `repos` into one tree per organization, each split by owner,
+/// sorted alphabetically at every level.
+fn build_tree(repos: &[Repo]) -> Vec<TreeNode> {
+    let mut by_org: BTreeMap<&str, BTreeMap<&str, Vec<&str>>> = BTreeMap::new();
+    for repo in repos {
+        by_org
+            .entry(&repo.org_name)
+            .or_default()
+            .entry(&repo.owner_name)
+            .or_default()
+            .push(&repo.name);
+    }
+    by_org
+        .into_iter()
+        .map(|(org, owners)| {
+            let owners = owners
+                .into_iter()
+                .map(|(owner, mut names)| {
+                    names.sort_unstable();
+                    TreeNode::branch(owner, names.into_iter().map(TreeNode::leaf).collect())
+                })
+                .collect();
+            TreeNode::branch(org, owners).collapse()
+        })
+        .collect()
+}
+
+/// Lines drawing `roots` with branch characters, Unicode or ASCII. Nodes
+/// with children show how many repos they hold.
+fn render_tree(roots: &[TreeNode], unicode: bool) -> Vec<String> {
+    fn label(node: &TreeNode) -> String {
+        if node.children.is_empty() {
+            node.label.clone()
+        } else {
+            format!("{} ({})", node.label, node.repos)
+        }
+    }
+
+    fn walk(nodes: &[TreeNode], prefix: &str, unicode: bool, lines: &mut Vec<String>) {
+        let (tee, elbow, pipe) = if unicode {
+            ("├── ", "└── ", "│   ")
+        } else {
+            ("|-- ", "`-- ", "|   ")
+        };
+        for (i, node) in nodes.iter().enumerate() {
+            let last = i + 1 == nodes.len();
+            lines.push(format!(
+                "{prefix}{}{}",
+                if last { elbow } else { tee },
+                label(node)
+            ));
+            let prefix = format!("{prefix}{}", if last { "    " } else { pipe });
+            walk(&node.children, &prefix, unicode, lines);
+        }
+    }
+
+    let mut lines = Vec::new();
+    for root in roots {
+        lines.push(label(root));
+        walk(&root.children, "", unicode, &mut lines);
+    }
+    lines
+}
+
 pub async fn handle(command: &RepoCommands, cli: &crate::Cli) -> Result<()> {
     let client = cli.create_client()?;
 
@@ -33,19 +148,34 @@ pub async fn handle(command: &RepoCommands, cli: &crate::Cli) -> Result<()> {
             limit,
             page,
             format,
+            tree,
         } => {
+            let format = &format.unwrap_or_default();
             let offset = page_to_offset(*page, *limit);
 
             let repos = client
                 .list_repos(*limit, offset)
                 .await
                 .context("Failed to fetch repositories")?;
+            let total_pages = u32::try_from(repos.total.max(0))
+                .unwrap_or(u32::MAX)
+                .div_ceil(*limit)
+                .max(1);
+
+            if *tree {
+                let term = stdout();
+                for line in render_tree(&build_tree(&repos.repos), term_caps::get().unicode) {
+                    term.write_line(&line)?;
+                }
+                term.write_line("")?;
+                term.write_line(&format!("Page: {page} of {total_pages}"))?;
+                return Ok(());
+            }
 
             match format {
                 crate::OutputFormat::Table => {
-                    let term = Term::stdout();
-                    let width = term.size().1.into();
-                    let separator = "─".repeat(width);
+                    let term = stdout();
+                    let separator = term_caps::get().rule();
 
                     // Group repos by organization, sorted alphabetically
                     let mut by_org: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
@@ -62,14 +192,10 @@ pub async fn handle(command: &RepoCommands, cli: &crate::Cli) -> Result<()> {
                         term.write_line("")?;
                     }
 
-                    let total_pages = u32::try_from(repos.total.max(0))
-                        .unwrap_or(u32::MAX)
-                        .div_ceil(*limit)
-                        .max(1);
                     term.write_line(&format!("Page: {page} of {total_pages}"))?;
                     Ok(())
                 }
-                crate::OutputFormat::Json => output_list(
+                crate::OutputFormat::Json | crate::OutputFormat::Yaml => output_list(
                     &repos.repos,
                     usize::try_from(repos.total.max(0)).unwrap_or(0),
                     *page,
@@ -80,3 +206,75 @@ pub async fn handle(command: &RepoCommands, cli: &crate::Cli) -> Result<()> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(org: &str, owner: &str, name: &str) -> Repo {
+        serde_json::from_value(serde_json::json!({
+            "id": format!("repo_{name}"),
+            "name": name,
+            "ownerName": owner,
+            "fullName": format!("{owner}/{name}"),
+            "visibility": "private",
+            "primaryBranch": "main",
+            "orgId": "org_001",
+            "orgName": org
+        }))
+        .expect("valid Repo JSON")
+    }
+
+    #[test]
+    fn tree_groups_by_org_then_owner_with_counts() {
+        let repos = [
+            repo("Acme", "acme", "web"),
+            repo("Acme", "acme", "api"),
+            repo("Acme", "acme-labs", "proto"),
+            repo("Acme", "acme-labs", "sandbox"),
+        ];
+        assert_eq!(
+            render_tree(&build_tree(&repos), true),
+            [
+                "Acme (4)",
+                "├── acme (2)",
+                "│   ├── api",
+                "│   └── web",
+                "└── acme-labs (2)",
+                "    ├── proto",
+                "    └── sandbox",
+            ]
+        );
+    }
+
+    #[test]
+    fn tree_collapses_single_child_chains() {
+        let repos = [
+            repo("Acme", "acme", "api"),
+            repo("Acme", "acme", "web"),
+            repo("Acme", "infra", "terraform"),
+            repo("Solo", "solo", "site"),
+        ];
+        assert_eq!(
+            render_tree(&build_tree(&repos), false),
+            [
+                "Acme (3)",
+                "|-- acme (2)",
+                "|   |-- api",
+                "|   `-- web",
+                "`-- infra/terraform",
+                "Solo/solo/site",
+            ]
+        );
+    }
+
+    #[test]
+    fn tree_drops_owner_named_after_its_org() {
+        let repos = [repo("acme", "acme", "api"), repo("acme", "acme", "web")];
+        let tree = build_tree(&repos);
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].label, "acme");
+        assert_eq!(tree[0].repos, 2);
+        assert_eq!(render_tree(&tree, true), ["acme (2)", "├── api", "└── web"]);
+    }
+}
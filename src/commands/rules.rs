@@ -4,14 +4,15 @@ use std::path::{Component, Path, PathBuf};
 
 use anyhow::{bail, Context, Result};
 use clap::Subcommand;
-use console::{style, Term};
+use console::style;
 
 use crate::api::types::{
     rule_status_label, CreateRuleInput, RuleCreationRequestId, RuleId, RuleListItem,
     RuleRequestStatus,
 };
-use crate::output::{output_list, Formattable, SectionRenderer};
-use crate::utils::datetime::{format_date, format_datetime};
+use crate::exit::Failure;
+use crate::output::{output_list, stdout, Formattable, SectionRenderer};
+use crate::utils::datetime::{format_date, format_datetime, format_relative_or};
 use crate::utils::git::resolve_repo_arg;
 use crate::utils::repos::resolve_repo_id;
 
@@ -53,9 +54,9 @@ pub enum RuleCommands {
         /// If omitted, inferred from the git remote (origin).
         repo: Option<String>,
 
-        /// Output format
-        #[arg(long, value_enum, default_value = "table")]
-        format: crate::OutputFormat,
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<crate::OutputFormat>,
     },
 
     /// Show a rule's details and content
@@ -83,9 +84,9 @@ pub enum RuleRequestCommands {
         /// If omitted, inferred from the git remote (origin).
         repo: Option<String>,
 
-        /// Output format
-        #[arg(long, value_enum, default_value = "table")]
-        format: crate::OutputFormat,
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<crate::OutputFormat>,
     },
 
     /// Show details and status of a rule creation request
@@ -134,17 +135,17 @@ pub async fn handle(command: &RuleCommands, cli: &crate::Cli) -> Result<()> {
                 .await
                 .context("Failed to start rule creation")?;
 
-            Term::stdout().write_line(&format!("{} Rule creation started.", style("✓").green()))?;
-            Term::stdout().write_line(&format!(
+            stdout().write_line(&format!("{} Rule creation started.", style("✓").green()))?;
+            stdout().write_line(&format!(
                 "  Request ID: {}",
                 style(response.rule_creation_request_id.to_string()).bold(),
             ))?;
-            Term::stdout()
-                .write_line("  Use 'detail rules requests show <id>' to check progress.")?;
+            stdout().write_line("  Use 'detail rules requests show <id>' to check progress.")?;
             Ok(())
         }
 
         RuleCommands::List { repo, format } => {
+            let format = &format.unwrap_or_default();
             let repo = resolve_repo_arg(repo.as_deref())?;
             let repo_id = resolve_repo_id(&client, &repo)
                 .await
@@ -164,7 +165,7 @@ pub async fn handle(command: &RuleCommands, cli: &crate::Cli) -> Result<()> {
             let rule_id: RuleId = rule_id
                 .as_str()
                 .try_into()
-                .context("Invalid rule ID format (expected rule_...)")?;
+                .context(Failure::usage("Invalid rule ID format (expected rule_...)"))?;
 
             let rule = client
                 .get_rule(&rule_id)
@@ -174,7 +175,10 @@ pub async fn handle(command: &RuleCommands, cli: &crate::Cli) -> Result<()> {
             let pairs: Vec<(&str, String)> = vec![
                 ("ID", rule.id.to_string()),
                 ("Name", rule.rule_name.clone()),
-                ("Created", format_datetime(rule.created_at)),
+                (
+                    "Created",
+                    format_relative_or(rule.created_at, format_datetime),
+                ),
             ];
 
             let mut renderer = SectionRenderer::new().key_value("", &pairs);
@@ -195,7 +199,7 @@ pub async fn handle(command: &RuleCommands, cli: &crate::Cli) -> Result<()> {
             let rule_id: RuleId = rule_id
                 .as_str()
                 .try_into()
-                .context("Invalid rule ID format (expected rule_...)")?;
+                .context(Failure::usage("Invalid rule ID format (expected rule_...)"))?;
 
             let rule = client
                 .get_rule(&rule_id)
@@ -254,14 +258,14 @@ pub async fn handle(command: &RuleCommands, cli: &crate::Cli) -> Result<()> {
                         .map(Path::to_path_buf)
                 })
                 .unwrap_or_else(|| out_dir.clone());
-            Term::stdout().write_line(&format!(
+            stdout().write_line(&format!(
                 "{} Wrote {} file(s) to {}",
                 style("✓").green(),
                 written.len(),
                 style(display_dir.display()).bold(),
             ))?;
             for path in &written {
-                Term::stdout().write_line(&format!("  {path}"))?;
+                stdout().write_line(&format!("  {path}"))?;
             }
             Ok(())
         }
@@ -277,18 +281,18 @@ pub async fn handle(command: &RuleCommands, cli: &crate::Cli) -> Result<()> {
                 .await
                 .context("Failed to start rule proposal")?;
 
-            Term::stdout().write_line(&format!("{} Rule proposal started.", style("✓").green()))?;
-            Term::stdout().write_line(&format!(
+            stdout().write_line(&format!("{} Rule proposal started.", style("✓").green()))?;
+            stdout().write_line(&format!(
                 "  Request ID: {}",
                 style(response.rule_creation_request_id.to_string()).bold(),
             ))?;
-            Term::stdout()
-                .write_line("  Use 'detail rules requests show <id>' to check progress.")?;
+            stdout().write_line("  Use 'detail rules requests show <id>' to check progress.")?;
             Ok(())
         }
 
         RuleCommands::Requests(sub) => match sub {
             RuleRequestCommands::List { repo, format } => {
+                let format = &format.unwrap_or_default();
                 let repo = resolve_repo_arg(repo.as_deref())?;
                 let repo_id = resolve_repo_id(&client, &repo)
                     .await
@@ -305,10 +309,9 @@ pub async fn handle(command: &RuleCommands, cli: &crate::Cli) -> Result<()> {
             }
 
             RuleRequestCommands::Show { request_id } => {
-                let rcr_id: RuleCreationRequestId = request_id
-                    .as_str()
-                    .try_into()
-                    .context("Invalid request ID format (expected rcr_...)")?;
+                let rcr_id: RuleCreationRequestId = request_id.as_str().try_into().context(
+                    Failure::usage("Invalid request ID format (expected rcr_...)"),
+                )?;
 
                 let req = client
                     .get_rule_request(&rcr_id)
@@ -318,7 +321,10 @@ pub async fn handle(command: &RuleCommands, cli: &crate::Cli) -> Result<()> {
                 let mut pairs: Vec<(&str, String)> = vec![
                     ("ID", req.id.to_string()),
                     ("Status", rule_status_label(&req.status).to_string()),
-                    ("Created", format_datetime(req.created_at)),
+                    (
+                        "Created",
+                        format_relative_or(req.created_at, format_datetime),
+                    ),
                 ];
 
                 if let Some(completed_at) = req.completed_at {
@@ -355,7 +361,7 @@ impl Formattable for RuleRequestStatus {
     fn to_card(&self) -> (String, Vec<(&'static str, String)>) {
         let mut pairs = vec![
             ("Status", rule_status_label(&self.status).to_string()),
-            ("Created", format_date(self.created_at)),
+            ("Created", format_relative_or(self.created_at, format_date)),
         ];
 
         if let Some(desc) = &self.input.description {
@@ -377,7 +383,7 @@ impl Formattable for RuleListItem {
     fn to_card(&self) -> (String, Vec<(&'static str, String)>) {
         let pairs = vec![
             ("ID", self.id.to_string()),
-            ("Created", format_date(self.created_at)),
+            ("Created", format_relative_or(self.created_at, format_date)),
         ];
         (self.rule_name.clone(), pairs)
     }
@@ -43,9 +43,9 @@ pub enum ScanCommands {
         #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..))]
         page: u32,
 
-        /// Output format
-        #[arg(long, value_enum, default_value = "table")]
-        format: crate::OutputFormat,
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<crate::OutputFormat>,
     },
 }
 
@@ -118,6 +118,7 @@ pub async fn handle(command: &ScanCommands, cli: &crate::Cli) -> Result<()> {
             page,
             format,
         } => {
+            let format = &format.unwrap_or_default();
             let repo = resolve_repo_arg(repo.as_deref())?;
             let repo_id = resolve_repo_id(&client, &repo)
                 .await
@@ -0,0 +1,82 @@
+//! `detail schema`: JSON Schemas of what `--format json` prints, generated
+//! from the same types the output is serialized from.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+use serde_json::{Map, Value};
+
+use crate::api::types::{Bug, Repo};
+use crate::output::{write_structured, ListResponse};
+
+/// Output a schema describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SchemaKind {
+    /// `bugs show`: one bug
+    Bug,
+    /// `bugs list`: a page of bugs
+    Bugs,
+    /// `repos list`: a page of repositories
+    Repos,
+    /// The page envelope every list command uses, with items of any shape
+    ListResponse,
+}
+
+impl SchemaKind {
+    fn schema(self) -> RootSchema {
+        match self {
+            Self::Bug => schema_for!(Bug),
+            Self::Bugs => schema_for!(ListResponse<'static, Bug>),
+            Self::Repos => schema_for!(ListResponse<'static, Repo>),
+            Self::ListResponse => schema_for!(ListResponse<'static, Value>),
+        }
+    }
+
+    fn name(self) -> String {
+        self.to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Print the schema for `kind`, or every schema keyed by name.
+pub fn handle(kind: Option<SchemaKind>) -> Result<()> {
+    let value = match kind {
+        Some(kind) => serde_json::to_value(kind.schema())?,
+        None => {
+            let mut all = Map::new();
+            for kind in SchemaKind::value_variants() {
+                all.insert(kind.name(), serde_json::to_value(kind.schema())?);
+            }
+            Value::Object(all)
+        }
+    };
+    write_structured(&value, &crate::OutputFormat::Json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bugs_schema_describes_the_list_envelope() {
+        let schema = serde_json::to_value(SchemaKind::Bugs.schema()).unwrap();
+        let properties = &schema["properties"];
+        for field in ["items", "page", "total", "total_pages"] {
+            assert!(properties.get(field).is_some(), "missing {field}");
+        }
+        assert_eq!(properties["items"]["items"]["$ref"], "#/definitions/Bug");
+        let bug = &schema["definitions"]["Bug"];
+        assert!(bug["properties"].get("isSecurityVulnerability").is_some());
+    }
+
+    #[test]
+    fn every_kind_has_a_named_schema() {
+        for kind in SchemaKind::value_variants() {
+            assert!(!kind.name().is_empty());
+            assert!(serde_json::to_value(kind.schema()).unwrap().is_object());
+        }
+        assert_eq!(SchemaKind::ListResponse.name(), "list-response");
+    }
+}
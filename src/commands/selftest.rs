@@ -0,0 +1,239 @@
+//! `detail selftest`: exercise the whole stack against the configured API,
+//! e.g. right after an install or upgrade.
+
+use std::fmt::Write as _;
+
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use console::style;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::api::client::ApiClient;
+use crate::api::types::{Bug, BugReviewState, Repo};
+use crate::config::storage::{config_path, load_config};
+use crate::exit::Failure;
+use crate::export::{self, ExportFormat};
+use crate::output::{stdout, write_structured, Formattable};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    Fail,
+    Skip,
+}
+
+/// One row of the pass/fail matrix.
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+}
+
+impl Check {
+    fn from_result(name: &'static str, result: Result<String>) -> Self {
+        match result {
+            Ok(detail) => Self {
+                name,
+                status: Status::Pass,
+                detail,
+            },
+            Err(err) => Self {
+                name,
+                status: Status::Fail,
+                detail: format!("{err:#}"),
+            },
+        }
+    }
+
+    fn skip(name: &'static str, reason: &str) -> Self {
+        Self {
+            name,
+            status: Status::Skip,
+            detail: reason.to_string(),
+        }
+    }
+}
+
+/// Render `items` as list cards into a string, the way `output_list` prints
+/// them to the terminal.
+fn render_cards<T: Formattable>(items: &[T]) -> Result<String> {
+    let mut out = String::new();
+    for item in items {
+        let (header, pairs) = item.to_card();
+        writeln!(out, "{header}")?;
+        for (key, value) in pairs {
+            writeln!(out, "    {key}  {value}")?;
+        }
+    }
+    Ok(out)
+}
+
+/// Render the fetched repos and bugs in every output and export format,
+/// describing which formats rendered.
+fn render_all_formats(repos: &[Repo], bugs: &[Bug]) -> Result<String> {
+    render_cards(repos)?;
+    render_cards(bugs)?;
+    serde_json::to_string(repos)?;
+    serde_json::to_string(bugs)?;
+    let mut exported = Vec::new();
+    for format in ExportFormat::value_variants() {
+        export::render(bugs, *format)?;
+        if let Some(value) = format.to_possible_value() {
+            exported.push(value.get_name().to_string());
+        }
+    }
+    Ok(format!("table, json; export: {}", exported.join(", ")))
+}
+
+/// The checks that can't run without a working login.
+fn skip_after_auth() -> impl Iterator<Item = Check> {
+    ["repos", "bugs", "render"]
+        .into_iter()
+        .map(|name| Check::skip(name, "needs a working login"))
+}
+
+async fn run_checks(client: Result<ApiClient>) -> Vec<Check> {
+    let mut checks = vec![Check::from_result(
+        "config",
+        load_config().and_then(|_| config_path().map(|path| path.display().to_string())),
+    )];
+
+    let client = match client {
+        Ok(client) => client,
+        Err(err) => {
+            checks.push(Check::from_result("auth", Err(err)));
+            checks.extend(skip_after_auth());
+            return checks;
+        }
+    };
+
+    let auth = client.get_current_user().await;
+    let authenticated = auth.is_ok();
+    checks.push(Check::from_result(
+        "auth",
+        auth.map(|user| format!("signed in as {}", user.email)),
+    ));
+    if !authenticated {
+        checks.extend(skip_after_auth());
+        return checks;
+    }
+
+    let repos = match client.list_repos(1, 0).await {
+        Ok(response) => {
+            checks.push(Check::from_result(
+                "repos",
+                Ok(format!("{} accessible", response.total)),
+            ));
+            response.repos
+        }
+        Err(err) => {
+            checks.push(Check::from_result("repos", Err(err)));
+            Vec::new()
+        }
+    };
+
+    let bugs = if let Some(repo) = repos.first() {
+        match client
+            .list_bugs(&repo.id, BugReviewState::Pending, 1, 0, None)
+            .await
+        {
+            Ok(response) => {
+                checks.push(Check::from_result(
+                    "bugs",
+                    Ok(format!("{} pending in {}", response.total, repo.full_name)),
+                ));
+                response.bugs
+            }
+            Err(err) => {
+                checks.push(Check::from_result("bugs", Err(err)));
+                Vec::new()
+            }
+        }
+    } else {
+        checks.push(Check::skip("bugs", "no repository to list bugs from"));
+        Vec::new()
+    };
+
+    checks.push(Check::from_result(
+        "render",
+        render_all_formats(&repos, &bugs),
+    ));
+    checks
+}
+
+fn status_label(status: Status) -> String {
+    match status {
+        Status::Pass => style("✓ pass").green().to_string(),
+        Status::Fail => style("✗ fail").red().to_string(),
+        Status::Skip => style("- skip").dim().to_string(),
+    }
+}
+
+pub async fn handle(format: &crate::OutputFormat, cli: &crate::Cli) -> Result<()> {
+    let checks = run_checks(cli.create_client()).await;
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+
+    let term = stdout();
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Yaml => {
+            write_structured(
+                &json!({
+                    "passed": failed == 0,
+                    "checks": checks,
+                }),
+                format,
+            )?;
+        }
+        crate::OutputFormat::Table => {
+            let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+            for check in &checks {
+                term.write_line(&format!(
+                    "{}  {:<width$}  {}",
+                    status_label(check.status),
+                    check.name,
+                    check.detail
+                ))?;
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!(Failure::partial(format!(
+            "{failed} of {} self-test checks failed",
+            checks.len()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn missing_login_fails_auth_and_skips_the_rest() {
+        let checks = run_checks(Err(anyhow::anyhow!("No token found"))).await;
+        let statuses: Vec<(&str, Status)> =
+            checks.iter().skip(1).map(|c| (c.name, c.status)).collect();
+        assert_eq!(
+            statuses,
+            [
+                ("auth", Status::Fail),
+                ("repos", Status::Skip),
+                ("bugs", Status::Skip),
+                ("render", Status::Skip),
+            ]
+        );
+    }
+
+    #[test]
+    fn render_all_formats_handles_empty_collections() {
+        assert_eq!(
+            render_all_formats(&[], &[]).unwrap(),
+            "table, json; export: json, csv, sarif, markdown"
+        );
+    }
+}
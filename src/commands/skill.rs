@@ -3,8 +3,11 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::str;
 
-use anyhow::{Context, Result};
-use clap::Subcommand;
+use anyhow::{bail, Context, Result};
+use clap::{Subcommand, ValueEnum};
+
+use crate::exit::Failure;
+use crate::output::stdout;
 
 const BUGS_SKILL_CONTENT: &str = include_str!("../../.claude/skills/detail-bugs/SKILL.md");
 const RULES_SKILL_CONTENT: &str = include_str!("../../.claude/skills/detail-create-rules/SKILL.md");
@@ -14,6 +17,193 @@ pub enum SkillCommands {
     /// Install the detail-create-rules skill
     #[command(name = "rules")]
     Rules,
+
+    /// Remove an installed skill
+    Uninstall {
+        /// Which skill to remove
+        #[arg(value_enum, default_value = "bugs")]
+        skill: SkillName,
+    },
+
+    /// Show whether each skill is installed and matches this version of the
+    /// CLI
+    Status,
+}
+
+/// A skill bundled with the CLI.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SkillName {
+    /// detail-bugs
+    Bugs,
+    /// detail-create-rules
+    Rules,
+}
+
+impl SkillName {
+    const ALL: [Self; 2] = [Self::Bugs, Self::Rules];
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Bugs => "detail-bugs",
+            Self::Rules => "detail-create-rules",
+        }
+    }
+
+    const fn content(self) -> &'static str {
+        match self {
+            Self::Bugs => BUGS_SKILL_CONTENT,
+            Self::Rules => RULES_SKILL_CONTENT,
+        }
+    }
+}
+
+/// How an installed skill compares with the one embedded in this binary.
+#[derive(Debug, PartialEq, Eq)]
+enum SkillState {
+    Missing,
+    Current,
+    /// Installed by another version of the CLI, or edited since.
+    Outdated,
+}
+
+/// The coding agent to install instructions for.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum Agent {
+    /// A skill under .claude/skills/
+    #[default]
+    Claude,
+    /// A rule under .cursor/rules/
+    Cursor,
+    /// A section of .github/copilot-instructions.md
+    Copilot,
+    /// A section of AGENTS.md
+    Codex,
+}
+
+/// Split a skill file into its frontmatter `description` and the markdown
+/// body after the frontmatter.
+fn split_frontmatter(content: &str) -> (Option<&str>, &str) {
+    let Some((frontmatter, body)) = content
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---\n"))
+    else {
+        return (None, content);
+    };
+    let description = frontmatter
+        .lines()
+        .find_map(|line| line.strip_prefix("description:"))
+        .map(str::trim);
+    (description, body.trim_start())
+}
+
+/// Markers around the section `detail skill` owns in a shared instructions
+/// file, so reinstalling replaces it and leaves the rest of the file alone.
+fn section_markers(skill_name: &str) -> (String, String) {
+    (
+        format!("<!-- {skill_name}: installed by `detail skill`; edits inside are overwritten -->"),
+        format!("<!-- /{skill_name} -->"),
+    )
+}
+
+/// `existing` with the section for `skill_name` replaced by `body`, or
+/// with the section appended when there is none yet.
+fn merge_section(existing: &str, skill_name: &str, body: &str) -> String {
+    let (start, end) = section_markers(skill_name);
+    let section = format!("{start}\n{}\n{end}\n", body.trim_end());
+    if let Some((before, rest)) = existing.split_once(&start) {
+        if let Some((_, after)) = rest.split_once(&end) {
+            return format!("{before}{section}{}", after.trim_start_matches('\n'));
+        }
+    }
+    if existing.trim().is_empty() {
+        section
+    } else {
+        format!("{}\n\n{section}", existing.trim_end())
+    }
+}
+
+/// The section for `skill_name` in `existing`, markers included.
+fn find_section<'a>(existing: &'a str, skill_name: &str) -> Option<&'a str> {
+    let (start, end) = section_markers(skill_name);
+    let from = existing.find(&start)?;
+    let to = from + existing.get(from..)?.find(&end)? + end.len();
+    existing.get(from..to)
+}
+
+/// `existing` without the section for `skill_name`.
+fn remove_section(existing: &str, skill_name: &str) -> String {
+    find_section(existing, skill_name).map_or_else(
+        || existing.to_string(),
+        |section| {
+            let (before, after) = existing.split_once(section).unwrap_or((existing, ""));
+            let before = before.trim_end();
+            let after = after.trim_start_matches('\n');
+            if before.is_empty() {
+                after.to_string()
+            } else if after.is_empty() {
+                format!("{before}\n")
+            } else {
+                format!("{before}\n\n{after}")
+            }
+        },
+    )
+}
+
+/// Compare the installed file's `contents` (if any) with what installing
+/// would write.
+fn skill_state(
+    agent: Agent,
+    skill_name: &str,
+    content: &str,
+    installed: Option<&str>,
+) -> SkillState {
+    let Some(installed) = installed else {
+        return SkillState::Missing;
+    };
+    let expected = render_for_agent(agent, skill_name, content, "");
+    let current = match agent {
+        Agent::Claude | Agent::Cursor => installed == expected,
+        Agent::Copilot | Agent::Codex => match find_section(installed, skill_name) {
+            Some(section) => section == expected.trim_end(),
+            None => return SkillState::Missing,
+        },
+    };
+    if current {
+        SkillState::Current
+    } else {
+        SkillState::Outdated
+    }
+}
+
+/// The file `agent` reads instructions from, under the repo root or, with
+/// `global`, the home directory.
+fn agent_install_path(root: &Path, agent: Agent, skill_name: &str, global: bool) -> PathBuf {
+    match agent {
+        Agent::Claude => skill_install_path(root, skill_name),
+        Agent::Cursor => root
+            .join(".cursor")
+            .join("rules")
+            .join(format!("{skill_name}.mdc")),
+        Agent::Copilot => root.join(".github").join("copilot-instructions.md"),
+        Agent::Codex if global => root.join(".codex").join("AGENTS.md"),
+        Agent::Codex => root.join("AGENTS.md"),
+    }
+}
+
+/// The contents of `path` after installing the skill for `agent`. The
+/// skill files double as the shared template: Claude gets them verbatim,
+/// Cursor gets Cursor's rule frontmatter, and the shared files get a
+/// managed section.
+fn render_for_agent(agent: Agent, skill_name: &str, content: &str, existing: &str) -> String {
+    let (description, body) = split_frontmatter(content);
+    match agent {
+        Agent::Claude => content.to_string(),
+        Agent::Cursor => format!(
+            "---\ndescription: {}\nalwaysApply: false\n---\n\n{body}",
+            description.unwrap_or(skill_name)
+        ),
+        Agent::Copilot | Agent::Codex => merge_section(existing, skill_name, body),
+    }
 }
 
 fn parse_git_root_output(success: bool, stdout: &[u8]) -> Result<PathBuf> {
@@ -22,14 +212,26 @@ fn parse_git_root_output(success: bool, stdout: &[u8]) -> Result<PathBuf> {
     Ok(PathBuf::from(root.trim()))
 }
 
-fn skill_install_path(repo_root: &Path, skill_name: &str) -> PathBuf {
-    repo_root
-        .join(".claude")
+fn skill_install_path(root: &Path, skill_name: &str) -> PathBuf {
+    root.join(".claude")
         .join("skills")
         .join(skill_name)
         .join("SKILL.md")
 }
 
+/// The home directory, for `--global`. Only Claude and Codex read
+/// user-level instructions from a file.
+fn user_root(agent: Agent) -> Result<PathBuf> {
+    match agent {
+        Agent::Claude | Agent::Codex => homedir::my_home()
+            .context("Failed to determine home directory")?
+            .context("Home directory not found"),
+        Agent::Cursor | Agent::Copilot => bail!(Failure::usage(
+            "--global works with --agent claude or codex; Cursor and Copilot only read instructions from the repository"
+        )),
+    }
+}
+
 fn git_root() -> Result<PathBuf> {
     let output = Command::new("git")
         .args(["rev-parse", "--show-toplevel"])
@@ -38,27 +240,112 @@ fn git_root() -> Result<PathBuf> {
     parse_git_root_output(output.status.success(), &output.stdout)
 }
 
-fn install_skill(repo_root: &Path, skill_name: &str, content: &str) -> Result<()> {
-    let path = skill_install_path(repo_root, skill_name);
+fn install_skill(root: &Path, global: bool, agent: Agent, skill: SkillName) -> Result<()> {
+    let skill_name = skill.name();
+    let path = agent_install_path(root, agent, skill_name, global);
+    let existing = fs::read_to_string(&path).ok();
+    let state = skill_state(agent, skill_name, skill.content(), existing.as_deref());
+    let verb = match state {
+        SkillState::Current => {
+            console::Term::stderr().write_line(&format!(
+                "{skill_name} skill at {} is already up to date",
+                path.display()
+            ))?;
+            return Ok(());
+        }
+        SkillState::Outdated => "Updated",
+        SkillState::Missing => "Installed",
+    };
     let dir = path
         .parent()
         .context("failed to compute skill install directory")?;
     fs::create_dir_all(dir)?;
-    fs::write(&path, content)?;
-    console::Term::stderr().write_line(&format!(
-        "Installed {skill_name} skill to {}",
+    fs::write(
+        &path,
+        render_for_agent(
+            agent,
+            skill_name,
+            skill.content(),
+            existing.as_deref().unwrap_or_default(),
+        ),
+    )?;
+    console::Term::stderr()
+        .write_line(&format!("{verb} {skill_name} skill to {}", path.display()))?;
+    Ok(())
+}
+
+fn uninstall_skill(root: &Path, global: bool, agent: Agent, skill: SkillName) -> Result<()> {
+    let skill_name = skill.name();
+    let path = agent_install_path(root, agent, skill_name, global);
+    let existing = fs::read_to_string(&path).ok();
+    let term = console::Term::stderr();
+    if skill_state(agent, skill_name, skill.content(), existing.as_deref()) == SkillState::Missing {
+        term.write_line(&format!(
+            "{skill_name} skill is not installed at {}",
+            path.display()
+        ))?;
+        return Ok(());
+    }
+    match agent {
+        Agent::Claude => {
+            fs::remove_file(&path)?;
+            // The skill's directory holds nothing else; leave it if it does.
+            if let Some(dir) = path.parent() {
+                let _ = fs::remove_dir(dir);
+            }
+        }
+        Agent::Cursor => fs::remove_file(&path)?,
+        Agent::Copilot | Agent::Codex => {
+            let rest = remove_section(existing.as_deref().unwrap_or_default(), skill_name);
+            if rest.trim().is_empty() {
+                fs::remove_file(&path)?;
+            } else {
+                fs::write(&path, rest)?;
+            }
+        }
+    }
+    term.write_line(&format!(
+        "Removed {skill_name} skill from {}",
         path.display()
     ))?;
     Ok(())
 }
 
-pub fn handle(command: Option<&SkillCommands>) -> Result<()> {
-    let root = git_root()?;
+fn print_status(root: &Path, global: bool, agent: Agent) -> Result<()> {
+    let term = stdout();
+    for skill in SkillName::ALL {
+        let path = agent_install_path(root, agent, skill.name(), global);
+        let installed = fs::read_to_string(&path).ok();
+        let state = match skill_state(agent, skill.name(), skill.content(), installed.as_deref()) {
+            SkillState::Missing => console::style("not installed").dim(),
+            SkillState::Current => console::style("up to date").green(),
+            SkillState::Outdated => {
+                console::style("out of date (run `detail skill` to update)").yellow()
+            }
+        };
+        let shown = path.strip_prefix(root).map_or_else(
+            |_| path.display().to_string(),
+            |relative| {
+                let prefix = if global { "~/" } else { "" };
+                format!("{prefix}{}", relative.display())
+            },
+        );
+        term.write_line(&format!("{:<20} {state}  {shown}", skill.name()))?;
+    }
+    Ok(())
+}
+
+pub fn handle(command: Option<&SkillCommands>, agent: Agent, global: bool) -> Result<()> {
+    let root = if global {
+        user_root(agent)?
+    } else {
+        git_root()?
+    };
     match command {
-        None => install_skill(&root, "detail-bugs", BUGS_SKILL_CONTENT),
-        Some(SkillCommands::Rules) => {
-            install_skill(&root, "detail-create-rules", RULES_SKILL_CONTENT)
-        }
+        None => install_skill(&root, global, agent, SkillName::Bugs),
+        Some(SkillCommands::Rules) => install_skill(&root, global, agent, SkillName::Rules),
+        Some(SkillCommands::Uninstall { skill }) => uninstall_skill(&root, global, agent, *skill),
+        Some(SkillCommands::Status) => print_status(&root, global, agent),
     }
 }
 
@@ -93,6 +380,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn agent_install_paths() {
+        let root = Path::new("/work/repo");
+        assert_eq!(
+            agent_install_path(root, Agent::Cursor, "detail-bugs", false),
+            PathBuf::from("/work/repo/.cursor/rules/detail-bugs.mdc")
+        );
+        assert_eq!(
+            agent_install_path(root, Agent::Copilot, "detail-bugs", false),
+            PathBuf::from("/work/repo/.github/copilot-instructions.md")
+        );
+        assert_eq!(
+            agent_install_path(root, Agent::Codex, "detail-bugs", false),
+            PathBuf::from("/work/repo/AGENTS.md")
+        );
+        assert_eq!(
+            agent_install_path(Path::new("/home/me"), Agent::Codex, "detail-bugs", true),
+            PathBuf::from("/home/me/.codex/AGENTS.md")
+        );
+        assert_eq!(
+            agent_install_path(Path::new("/home/me"), Agent::Claude, "detail-bugs", true),
+            PathBuf::from("/home/me/.claude/skills/detail-bugs/SKILL.md")
+        );
+    }
+
+    #[test]
+    fn cursor_rule_keeps_the_description() {
+        let rule = render_for_agent(Agent::Cursor, "detail-bugs", BUGS_SKILL_CONTENT, "");
+        assert!(rule.starts_with("---\ndescription: Interact with Detail bugs"));
+        assert!(rule.contains("alwaysApply: false\n---\n\n# Detail Bugs"));
+    }
+
+    #[test]
+    fn shared_files_keep_other_content_and_replace_the_section() {
+        let first = merge_section("# Project notes\n", "detail-bugs", "# Detail Bugs\nv1");
+        assert!(first.starts_with("# Project notes\n\n<!-- detail-bugs:"));
+        let mut second = merge_section(&first, "detail-bugs", "# Detail Bugs\nv2");
+        second.push_str("\nMore notes\n");
+        let third = merge_section(&second, "detail-bugs", "# Detail Bugs\nv3");
+        assert!(third.contains("v3") && !third.contains("v1") && !third.contains("v2"));
+        assert!(third.starts_with("# Project notes") && third.ends_with("More notes\n"));
+        assert_eq!(third.matches("<!-- /detail-bugs -->").count(), 1);
+    }
+
+    #[test]
+    fn skill_state_compares_with_the_embedded_skill() {
+        let skill = SkillName::Bugs;
+        let rendered = render_for_agent(Agent::Claude, skill.name(), skill.content(), "");
+        let state = |agent, installed| skill_state(agent, skill.name(), skill.content(), installed);
+        assert_eq!(state(Agent::Claude, None), SkillState::Missing);
+        assert_eq!(state(Agent::Claude, Some(&rendered)), SkillState::Current);
+        assert_eq!(state(Agent::Claude, Some("old")), SkillState::Outdated);
+
+        let shared = render_for_agent(Agent::Codex, skill.name(), skill.content(), "# Notes\n");
+        assert_eq!(state(Agent::Codex, Some(&shared)), SkillState::Current);
+        assert_eq!(state(Agent::Codex, Some("# Notes\n")), SkillState::Missing);
+    }
+
+    #[test]
+    fn removing_a_section_keeps_the_rest() {
+        let installed = merge_section("# Notes\n", "detail-bugs", "body");
+        assert_eq!(remove_section(&installed, "detail-bugs"), "# Notes\n");
+        let only = merge_section("", "detail-bugs", "body");
+        assert_eq!(remove_section(&only, "detail-bugs"), "");
+    }
+
     #[test]
     fn rules_install_path_is_repo_relative() {
         let path = skill_install_path(Path::new("/work/repo"), "detail-create-rules");
@@ -1,33 +1,76 @@
-use anyhow::Result;
-use console::{style, Term};
+use anyhow::{bail, Result};
+use console::style;
 
+use crate::exit::Failure;
+use crate::output::{stdout, Stdout};
 use crate::upgrade::{self, ManualUpdateOutcome};
 
-pub async fn handle() -> Result<()> {
-    let term = Term::stdout();
-    term.write_line("Checking for Detail CLI updates...")?;
+fn print_unavailable(term: Stdout) -> Result<()> {
+    term.write_line(&format!(
+        "{}",
+        style("Self-update is not available for this installation.").yellow()
+    ))?;
+    term.write_line("Reinstall using the official installer to enable `detail update`:")?;
+    term.write_line("  curl --proto '=https' --tlsv1.2 -LsSf https://cli.detail.dev | sh")?;
+    Ok(())
+}
+
+/// `detail update --check`: report the current and latest versions without
+/// installing anything, failing with its own exit code when they differ.
+pub async fn check() -> Result<()> {
+    let term = stdout();
+    let Some(check) = upgrade::check_latest().await? else {
+        return print_unavailable(term);
+    };
+
+    term.write_line(&format!("{}  v{}", style("Current").bold(), check.current))?;
+    term.write_line(&format!("{}   v{}", style("Latest").bold(), check.latest))?;
+    if !check.update_available {
+        term.write_line(&format!("{}", style("✓ Detail CLI is up to date.").green()))?;
+        return Ok(());
+    }
+    term.write_line(&format!(
+        "{}  {}",
+        style("Changes").bold(),
+        check.release_notes_url()
+    ))?;
+    bail!(Failure::update_available(format!(
+        "Detail CLI v{} is available; run `detail update` to install it",
+        check.latest
+    )));
+}
 
-    match upgrade::update_now().await? {
+pub async fn handle(version: Option<&str>, allow_unverified: bool) -> Result<()> {
+    let mut term = stdout();
+    let intro = version.map_or_else(
+        || "Checking for Detail CLI updates...".to_string(),
+        |version| {
+            format!(
+                "Installing Detail CLI v{} (currently v{})...",
+                version.trim_start_matches('v'),
+                crate::VERSION
+            )
+        },
+    );
+    term.write_line(&intro)?;
+
+    match upgrade::update_now(version, allow_unverified).await? {
         ManualUpdateOutcome::Updated {
             old_version,
             new_version,
         } => {
-            upgrade::print_update_success_message(&term, &old_version, &new_version);
+            upgrade::print_update_success_message(&mut term, &old_version, &new_version);
+            upgrade::print_release_notes(&mut term, &new_version).await;
         }
         ManualUpdateOutcome::AlreadyUpToDate => {
-            term.write_line(&format!(
-                "{}",
-                style("✓ Detail CLI is already up to date.").green()
-            ))?;
-        }
-        ManualUpdateOutcome::Unavailable => {
-            term.write_line(&format!(
-                "{}",
-                style("Self-update is not available for this installation.").yellow()
-            ))?;
-            term.write_line("Reinstall using the official installer to enable `detail update`:")?;
-            term.write_line("  curl --proto '=https' --tlsv1.2 -LsSf https://cli.detail.dev | sh")?;
+            let message = if version.is_some() {
+                format!("✓ Detail CLI v{} is already installed.", crate::VERSION)
+            } else {
+                format!("✓ Detail CLI is already up to date (v{}).", crate::VERSION)
+            };
+            term.write_line(&format!("{}", style(message).green()))?;
         }
+        ManualUpdateOutcome::Unavailable => print_unavailable(term)?,
     }
 
     Ok(())
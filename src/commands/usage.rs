@@ -0,0 +1,211 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use console::style;
+use serde::Serialize;
+
+use crate::config::usage::{self, UsageRecord};
+use crate::output::{stdout, write_structured, SectionRenderer};
+use crate::utils::datetime::format_date;
+
+/// How often one command ran, and how it went.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CommandUsage {
+    command: String,
+    runs: usize,
+    failures: usize,
+    average_ms: u64,
+}
+
+/// Totals across the whole usage log.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageSummary {
+    /// When the oldest record was written, in epoch millis.
+    since: Option<i64>,
+    runs: usize,
+    requests: usize,
+    from_cache: usize,
+    /// Most-run first.
+    commands: Vec<CommandUsage>,
+}
+
+fn summarize(records: &[UsageRecord]) -> UsageSummary {
+    let mut by_command: BTreeMap<&str, Vec<&UsageRecord>> = BTreeMap::new();
+    for record in records {
+        by_command.entry(&record.command).or_default().push(record);
+    }
+    let mut commands: Vec<CommandUsage> = by_command
+        .into_iter()
+        .map(|(command, runs)| {
+            let total_ms: u64 = runs.iter().map(|r| r.duration_ms).sum();
+            CommandUsage {
+                command: command.to_string(),
+                runs: runs.len(),
+                failures: runs.iter().filter(|r| !r.succeeded).count(),
+                average_ms: total_ms / u64::try_from(runs.len()).unwrap_or(1).max(1),
+            }
+        })
+        .collect();
+    // Stable sort: ties stay alphabetical.
+    commands.sort_by_key(|usage| Reverse(usage.runs));
+    UsageSummary {
+        since: records.iter().map(|r| r.finished_at).min(),
+        runs: records.len(),
+        requests: records.iter().map(|r| r.requests).sum(),
+        from_cache: records.iter().map(|r| r.from_cache).sum(),
+        commands,
+    }
+}
+
+/// `n of total (p%)`, or just `0` when there's nothing to divide.
+fn share(n: usize, total: usize) -> String {
+    if total == 0 {
+        return "0".to_string();
+    }
+    let percent = n.saturating_mul(100) / total;
+    format!("{n} of {total} ({percent}%)")
+}
+
+fn summary_rows(summary: &UsageSummary) -> Vec<(&'static str, String)> {
+    vec![
+        ("Runs", summary.runs.to_string()),
+        ("API requests", summary.requests.to_string()),
+        ("From cache", share(summary.from_cache, summary.requests)),
+    ]
+}
+
+fn command_rows(summary: &UsageSummary) -> Vec<(&str, String)> {
+    summary
+        .commands
+        .iter()
+        .map(|usage| {
+            let runs = if usage.runs == 1 { "run" } else { "runs" };
+            let failed = if usage.failures > 0 {
+                format!(", {} failed", usage.failures)
+            } else {
+                String::new()
+            };
+            let row = format!(
+                "{} {runs}, {} ms on average{failed}",
+                usage.runs, usage.average_ms
+            );
+            (usage.command.as_str(), row)
+        })
+        .collect()
+}
+
+pub fn handle(reset: bool, format: &crate::OutputFormat) -> Result<()> {
+    if reset {
+        let message = if usage::clear()? {
+            "✓ Usage log cleared"
+        } else {
+            "No usage recorded yet"
+        };
+        stdout().write_line(&format!("{}", style(message).green()))?;
+        return Ok(());
+    }
+
+    let summary = summarize(&usage::load_usage()?);
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Yaml => {
+            write_structured(&summary, format)?;
+        }
+        crate::OutputFormat::Table => {
+            let Some(since) = summary.since else {
+                stdout().write_line(
+                    "No usage recorded yet. Runs are logged locally as you use the CLI.",
+                )?;
+                return Ok(());
+            };
+            SectionRenderer::new()
+                .key_value(
+                    &format!("Usage since {}", format_date(since)),
+                    &summary_rows(&summary),
+                )
+                .key_value("Commands", &command_rows(&summary))
+                .print()?;
+            stdout().write_line(&format!(
+                "{}",
+                style("Recorded on this machine only; never uploaded.").dim()
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(command: &str, duration_ms: u64, succeeded: bool) -> UsageRecord {
+        UsageRecord {
+            command: command.to_string(),
+            finished_at: 1_736_899_200_000 + i64::try_from(duration_ms).unwrap(),
+            duration_ms,
+            succeeded,
+            requests: 4,
+            from_cache: 1,
+        }
+    }
+
+    #[test]
+    fn summary_ranks_commands_by_runs() {
+        let records = [
+            record("repos list", 300, true),
+            record("bugs list", 100, true),
+            record("bugs list", 300, false),
+            record("bugs show", 50, true),
+        ];
+        let summary = summarize(&records);
+        assert_eq!(summary.since, Some(1_736_899_200_050));
+        assert_eq!(summary.runs, 4);
+        assert_eq!(summary.requests, 16);
+        assert_eq!(summary.from_cache, 4);
+        assert_eq!(
+            summary.commands,
+            [
+                CommandUsage {
+                    command: "bugs list".into(),
+                    runs: 2,
+                    failures: 1,
+                    average_ms: 200,
+                },
+                CommandUsage {
+                    command: "bugs show".into(),
+                    runs: 1,
+                    failures: 0,
+                    average_ms: 50,
+                },
+                CommandUsage {
+                    command: "repos list".into(),
+                    runs: 1,
+                    failures: 0,
+                    average_ms: 300,
+                },
+            ]
+        );
+        assert_eq!(
+            command_rows(&summary)[0],
+            (
+                "bugs list",
+                "2 runs, 200 ms on average, 1 failed".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn empty_log_summarizes_to_nothing() {
+        let summary = summarize(&[]);
+        assert_eq!(summary.since, None);
+        assert!(summary.commands.is_empty());
+        assert_eq!(share(summary.from_cache, summary.requests), "0");
+    }
+
+    #[test]
+    fn cache_share_is_a_percentage() {
+        assert_eq!(share(1, 4), "1 of 4 (25%)");
+    }
+}
@@ -0,0 +1,106 @@
+//! Local record of the external issues bugs were exported to, so
+//! `detail bugs reconcile` can compare the two sides later.
+//!
+//! Stored as JSON next to `config.toml`. Exporters add entries with
+//! `record_link`; links the API reports on a bug are used alongside them.
+
+use std::fs;
+use std::fs::File;
+use std::io::{ErrorKind, Read as _, Seek as _, SeekFrom, Write as _};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+
+use super::storage::config_path;
+
+/// A bug and the external issue tracking it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueLink {
+    pub bug_id: String,
+    /// `github`, `gitlab`, `jira`, `linear` or `asana`, as in the API's
+    /// linked issues.
+    pub tracker: String,
+    /// The tracker's own key, e.g. `PAY-231` or `acme/api#42`.
+    pub issue_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl IssueLink {
+    /// Whether `self` and `other` name the same issue for the same bug.
+    pub fn same_issue(&self, other: &Self) -> bool {
+        self.bug_id == other.bug_id
+            && self.tracker == other.tracker
+            && self.issue_id == other.issue_id
+    }
+}
+
+pub fn links_path() -> Result<PathBuf> {
+    config_path().map(|p| p.with_file_name("issue-links.json"))
+}
+
+pub fn load_links() -> Result<Vec<IssueLink>> {
+    let path = links_path()?;
+    match fs::read_to_string(&path) {
+        Ok(contents) => parse_links(&contents),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+fn parse_links(contents: &str) -> Result<Vec<IssueLink>> {
+    if contents.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(contents).context("Failed to parse issue links")
+}
+
+/// Add `link`, replacing any earlier record of the same issue (e.g. one
+/// without a URL). Locked like the offline queue, so concurrent exports
+/// don't drop each other's entries.
+pub fn record_link(link: IssueLink) -> Result<()> {
+    let path = links_path()?;
+    let file = File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)?;
+    file.lock_exclusive()?;
+
+    let mut contents = String::new();
+    (&file).read_to_string(&mut contents)?;
+    let mut links = parse_links(&contents)?;
+    links.retain(|existing| !existing.same_issue(&link));
+    links.push(link);
+
+    let new_contents = serde_json::to_string_pretty(&links)?;
+    (&file).seek(SeekFrom::Start(0))?;
+    file.set_len(0)?;
+    (&file).write_all(new_contents.as_bytes())?;
+    file.unlock()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_round_trip_through_json() {
+        let links = vec![IssueLink {
+            bug_id: "bug_1".into(),
+            tracker: "github".into(),
+            issue_id: "acme/api#42".into(),
+            url: None,
+        }];
+        let json = serde_json::to_string(&links).unwrap();
+        assert!(json.contains("\"issueId\":\"acme/api#42\""));
+        assert!(!json.contains("url"));
+        assert_eq!(parse_links(&json).unwrap(), links);
+        assert!(parse_links("\n").unwrap().is_empty());
+    }
+}
@@ -1 +1,5 @@
+pub mod issue_links;
+pub mod project;
+pub mod queue;
 pub mod storage;
+pub mod usage;
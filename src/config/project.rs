@@ -0,0 +1,79 @@
+//! Per-project settings checked into the repository as `.detail.toml`, for
+//! things that depend on the codebase rather than the user, such as how to
+//! run its tests.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::utils::git::repo_root;
+
+/// File name of the project config, at the root of the repository.
+pub const PROJECT_FILE: &str = ".detail.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ProjectConfig {
+    /// Shell command that runs the project's tests, e.g. `cargo test`.
+    pub test_command: Option<String>,
+}
+
+/// The project config in `root`; defaults when there's no `.detail.toml`.
+pub fn load_project_config_in(root: &Path) -> Result<ProjectConfig> {
+    let path = root.join(PROJECT_FILE);
+    match fs::read_to_string(&path) {
+        Ok(contents) => {
+            toml::from_str(&contents).with_context(|| format!("Failed to parse {}", path.display()))
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(ProjectConfig::default()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+/// The project config of the git checkout we're in, if any.
+pub fn load_project_config() -> Result<ProjectConfig> {
+    repo_root().map_or_else(
+        || Ok(ProjectConfig::default()),
+        |root| load_project_config_in(&root),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    /// A fresh, empty directory unique to `name` and this test run.
+    fn temp_root(name: &str) -> std::path::PathBuf {
+        let dir = env::temp_dir().join(format!("detail-cli-project-{name}-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn reads_test_command_and_defaults_without_a_file() {
+        let dir = temp_root("read");
+        assert_eq!(
+            load_project_config_in(&dir).unwrap(),
+            ProjectConfig::default()
+        );
+
+        fs::write(dir.join(PROJECT_FILE), "test_command = \"cargo test\"\n").unwrap();
+        let config = load_project_config_in(&dir).unwrap();
+        assert_eq!(config.test_command.as_deref(), Some("cargo test"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_malformed_file() {
+        let dir = temp_root("malformed");
+        fs::write(dir.join(PROJECT_FILE), "test_command = [").unwrap();
+        assert!(load_project_config_in(&dir).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
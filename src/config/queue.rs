@@ -0,0 +1,182 @@
+//! Local queue of bug reviews that couldn't be submitted while offline.
+//!
+//! Stored as JSON next to `config.toml` and replayed by `detail queue flush`.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::storage::{config_path, lock_file};
+use crate::api::types::{BugDismissalReason, BugReviewState};
+use crate::output::Formattable;
+use crate::utils::datetime::format_datetime;
+use crate::utils::files::write_atomic;
+
+/// A bug review (close or reopen) waiting to be submitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedReview {
+    /// Idempotency key derived from the review's contents, so queueing the
+    /// same change twice keeps a single entry.
+    pub key: String,
+    pub bug_id: String,
+    pub state: BugReviewState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dismissal_reason: Option<BugDismissalReason>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// When the review was queued, in epoch millis.
+    pub queued_at: i64,
+}
+
+impl QueuedReview {
+    pub fn new(
+        bug_id: &str,
+        state: BugReviewState,
+        dismissal_reason: Option<BugDismissalReason>,
+        notes: Option<&str>,
+        queued_at: i64,
+    ) -> Self {
+        Self {
+            key: idempotency_key(bug_id, state, dismissal_reason, notes),
+            bug_id: bug_id.to_string(),
+            state,
+            dismissal_reason,
+            notes: notes.map(str::to_owned),
+            queued_at,
+        }
+    }
+}
+
+impl Formattable for QueuedReview {
+    fn to_card(&self) -> (String, Vec<(&'static str, String)>) {
+        let mut pairs = vec![("Key", self.key.clone()), ("State", self.state.to_string())];
+        if let Some(reason) = &self.dismissal_reason {
+            pairs.push(("Reason", reason.to_string()));
+        }
+        if let Some(notes) = &self.notes {
+            pairs.push(("Notes", notes.clone()));
+        }
+        pairs.push(("Queued", format_datetime(self.queued_at)));
+        (self.bug_id.clone(), pairs)
+    }
+}
+
+fn idempotency_key(
+    bug_id: &str,
+    state: BugReviewState,
+    dismissal_reason: Option<BugDismissalReason>,
+    notes: Option<&str>,
+) -> String {
+    let reason = dismissal_reason
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    let material = format!("{bug_id}\0{state}\0{reason}\0{}", notes.unwrap_or_default());
+    let digest = format!("{:x}", Sha256::digest(material.as_bytes()));
+    format!("q_{}", digest.get(..16).unwrap_or(&digest))
+}
+
+pub fn queue_path() -> Result<PathBuf> {
+    config_path().map(|p| p.with_file_name("queue.json"))
+}
+
+pub fn load_queue() -> Result<Vec<QueuedReview>> {
+    let path = queue_path()?;
+    match fs::read_to_string(&path) {
+        Ok(contents) => parse_queue(&contents),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+fn parse_queue(contents: &str) -> Result<Vec<QueuedReview>> {
+    if contents.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(contents).context("Failed to parse offline queue")
+}
+
+/// Held while the queue is read, changed and replaced. The queue file
+/// itself can't carry the lock: replacing it by a rename would leave the
+/// lock on the old file.
+fn queue_lock_path() -> Result<PathBuf> {
+    config_path().map(|p| p.with_file_name("queue.lock"))
+}
+
+/// Read-modify-write the queue file under an exclusive lock, so a flush and
+/// a concurrent `bugs close` never drop each other's entries. The new queue
+/// is written to a temporary file and renamed over the old one, so a crash
+/// mid-write can't lose the entries already queued.
+pub fn update_queue<R>(f: impl FnOnce(&mut Vec<QueuedReview>) -> R) -> Result<R> {
+    let _lock = lock_file(&queue_lock_path()?)?;
+    let mut queue = load_queue()?;
+
+    let result = f(&mut queue);
+
+    let contents =
+        serde_json::to_string_pretty(&queue).context("Failed to serialize offline queue")?;
+    write_atomic(&queue_path()?, &contents)?;
+    Ok(result)
+}
+
+/// Add `review` to the queue. Returns `false` if an identical review (same
+/// idempotency key) is already queued.
+pub fn enqueue(review: QueuedReview) -> Result<bool> {
+    update_queue(|queue| {
+        if queue.iter().any(|q| q.key == review.key) {
+            return false;
+        }
+        queue.push(review);
+        true
+    })
+}
+
+/// Remove the entry with `key`. Returns `false` if it wasn't queued.
+pub fn remove(key: &str) -> Result<bool> {
+    update_queue(|queue| {
+        let before = queue.len();
+        queue.retain(|q| q.key != key);
+        queue.len() != before
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idempotency_key_is_stable_and_content_addressed() {
+        let a = QueuedReview::new("bug_1", BugReviewState::Resolved, None, None, 1);
+        let b = QueuedReview::new("bug_1", BugReviewState::Resolved, None, None, 2);
+        let c = QueuedReview::new("bug_1", BugReviewState::Resolved, None, Some("fixed"), 1);
+        assert_eq!(a.key, b.key);
+        assert_ne!(a.key, c.key);
+        assert!(a.key.starts_with("q_"));
+        assert_eq!(a.key.len(), 18);
+    }
+
+    #[test]
+    fn queue_round_trips_through_json() {
+        let queue = vec![QueuedReview::new(
+            "bug_1",
+            BugReviewState::Dismissed,
+            Some(BugDismissalReason::WontFix),
+            Some("later"),
+            1_000,
+        )];
+        let json = serde_json::to_string(&queue).unwrap();
+        assert!(json.contains("\"dismissalReason\":\"wont_fix\""));
+        assert_eq!(parse_queue(&json).unwrap(), queue);
+    }
+
+    #[test]
+    fn empty_queue_file_parses_as_empty() {
+        assert!(parse_queue("").unwrap().is_empty());
+        assert!(parse_queue("  \n").unwrap().is_empty());
+    }
+}
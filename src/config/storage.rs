@@ -1,14 +1,24 @@
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{ErrorKind, Read as _, Seek as _, SeekFrom, Write as _};
-use std::path::PathBuf;
-use std::{env, fs};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::{env, fs, slice};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use fs2::FileExt;
+use keyring::{Entry, Error as KeyringError};
 use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
 use toml_edit::ser::to_document;
 use toml_edit::DocumentMut;
 
+use crate::hooks::Hooks;
+use crate::notify::Sink;
+use crate::trackers::gitlab::GitlabSettings;
+use crate::trackers::jira::JiraSettings;
+use crate::OutputFormat;
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Config {
@@ -16,7 +26,80 @@ pub struct Config {
     pub app_url: Option<String>,
     pub check_for_updates: bool,
     pub last_update_check: Option<u64>,
+    /// The API token, when `token_storage` is `file`. Read it through
+    /// `load_token`/`stored_token`, which also handle the keyring.
     pub api_token: Option<String>,
+    /// Where `detail auth login` keeps the API token.
+    pub token_storage: TokenStorage,
+    /// Named logins selected with `--profile`, e.g. `[profiles.work]`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+    /// `@name` repo shortcuts, e.g. `payments = ["acme/api", "acme/web"]`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub shortcuts: BTreeMap<String, RepoShortcut>,
+    /// Output format used when `--format` isn't passed.
+    pub default_format: Option<OutputFormat>,
+    /// Per-repo overrides, keyed by `owner/repo`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub repos: BTreeMap<String, RepoSettings>,
+    /// Append JSON logs of API calls and command outcomes to this file, as
+    /// if `--log-file` were always passed.
+    pub log_file: Option<PathBuf>,
+    /// Shared config files merged underneath this one, e.g. team defaults
+    /// distributed via dotfiles. Paths may start with `~/` or be relative to
+    /// the including file.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// Proxy URL for API requests, e.g. `http://proxy.corp:3128`. Without
+    /// it, `HTTPS_PROXY`, `HTTP_PROXY` and `NO_PROXY` are honored.
+    pub proxy: Option<String>,
+    /// PEM bundle of extra root certificates to trust for API requests, as
+    /// if `--cacert` were always passed.
+    pub ca_cert: Option<PathBuf>,
+    /// Seconds before an API request times out (default 30), as if
+    /// `--timeout` were always passed.
+    pub timeout_secs: Option<u64>,
+    /// Seconds allowed for connecting to the API, within `timeout_secs`.
+    pub connect_timeout_secs: Option<u64>,
+    /// Extra regular expressions `bugs export --redact` blanks out, e.g.
+    /// internal ticket IDs or hostnames.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub redact_patterns: Vec<String>,
+    /// API requests in flight at once (default 4), as if `--concurrency`
+    /// were always passed.
+    pub concurrency: Option<u32>,
+    /// Organization ID this login is pinned to, sent as `X-Detail-Org` for
+    /// enterprise deployments that route by org. Set with
+    /// `detail auth login --org`.
+    pub org: Option<String>,
+    /// Key ID for gateways that require HMAC-signed requests; the secret is
+    /// kept in the OS keyring. Set with `detail auth signing-key`.
+    pub signing_key_id: Option<String>,
+    /// Show creation times as "3 days ago", as if `--relative-dates` were
+    /// always passed.
+    pub relative_dates: bool,
+    /// Zone timestamps are shown in (`local`, `UTC` or an IANA name like
+    /// `Europe/Berlin`), as if `--tz` were always passed.
+    pub timezone: Option<String>,
+    /// Log each command and its duration locally for `detail usage`. Never
+    /// uploaded.
+    pub record_usage: bool,
+    /// Named notification sinks (Slack, webhook, email), e.g.
+    /// `[notifications.team-chat]`. See `crate::notify`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub notifications: BTreeMap<String, Sink>,
+    /// Slack incoming webhook that `bugs close --notify-slack` and
+    /// `bugs digest --notify-slack` post to.
+    pub slack_webhook_url: Option<String>,
+    /// URLs or shell commands run after bug actions. See `crate::hooks`.
+    #[serde(skip_serializing_if = "Hooks::is_empty")]
+    pub hooks: Hooks,
+    /// Where `bugs export-issue --jira` files issues. See `crate::trackers::jira`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jira: Option<JiraSettings>,
+    /// Where `bugs export-issue --gitlab` files issues. See `crate::trackers::gitlab`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gitlab: Option<GitlabSettings>,
 }
 
 impl Default for Config {
@@ -27,6 +110,186 @@ impl Default for Config {
             check_for_updates: true,
             last_update_check: None,
             api_token: None,
+            token_storage: TokenStorage::File,
+            profiles: BTreeMap::new(),
+            shortcuts: BTreeMap::new(),
+            default_format: None,
+            repos: BTreeMap::new(),
+            log_file: None,
+            include: Vec::new(),
+            proxy: None,
+            ca_cert: None,
+            timeout_secs: None,
+            connect_timeout_secs: None,
+            redact_patterns: Vec::new(),
+            concurrency: None,
+            org: None,
+            signing_key_id: None,
+            relative_dates: false,
+            timezone: None,
+            record_usage: true,
+            notifications: BTreeMap::new(),
+            slack_webhook_url: None,
+            hooks: Hooks::default(),
+            jira: None,
+            gitlab: None,
+        }
+    }
+}
+
+/// Backend holding the API token.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenStorage {
+    /// `api_token` in config.toml, in plain text.
+    #[default]
+    File,
+    /// The OS credential store: macOS Keychain, Windows Credential Manager,
+    /// or the Secret Service on Linux.
+    Keyring,
+}
+
+impl Config {
+    /// Use profile `name`'s token, pinned org and signing key, and its API
+    /// and app URLs where it sets them. A profile that doesn't exist yet has
+    /// no token.
+    fn apply_profile(&mut self, name: &str) {
+        let profile = self.profiles.get(name).cloned().unwrap_or_default();
+        self.api_token = profile.api_token;
+        self.org = profile.org;
+        self.signing_key_id = profile.signing_key_id;
+        if profile.api_url.is_some() {
+            self.api_url = profile.api_url;
+        }
+        if profile.app_url.is_some() {
+            self.app_url = profile.app_url;
+        }
+    }
+
+    /// Settings for `repo`, given as `owner/repo` or a bare repo name.
+    pub fn repo_settings(&self, repo: &str) -> Option<&RepoSettings> {
+        let repo = repo.trim();
+        self.repos.get(repo).or_else(|| {
+            self.repos
+                .iter()
+                .find(|(key, _)| key.rsplit_once('/').is_some_and(|(_, name)| name == repo))
+                .map(|(_, settings)| settings)
+        })
+    }
+
+    /// The configured output format for `repo`, falling back to the global
+    /// `default_format`.
+    pub fn default_format_for(&self, repo: Option<&str>) -> Option<OutputFormat> {
+        repo.and_then(|repo| self.repo_settings(repo))
+            .and_then(|settings| settings.default_format)
+            .or(self.default_format)
+    }
+}
+
+/// A `[profiles.<name>]` table: a separate login, e.g. for another org.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub api_url: Option<String>,
+    pub app_url: Option<String>,
+    /// The profile's token, when `token_storage` is `file`.
+    pub api_token: Option<String>,
+    /// Organization ID the profile is pinned to; see `Config::org`.
+    pub org: Option<String>,
+    /// Gateway signing key ID; see `Config::signing_key_id`.
+    pub signing_key_id: Option<String>,
+}
+
+/// Environment variable naming the profile to use, like `--profile`.
+pub const PROFILE_ENV: &str = "DETAIL_PROFILE";
+
+/// The profile named by `DETAIL_PROFILE`, if it's set and not blank.
+pub fn env_profile() -> Option<String> {
+    env::var(PROFILE_ENV)
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// Profile chosen with `--profile` for this process; `load_config` and the
+/// token helpers use it in place of the top-level login.
+static ACTIVE_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Make `name` the active profile. Only the first call has an effect.
+pub fn select_profile(name: &str) {
+    let _ = ACTIVE_PROFILE.set(name.to_string());
+}
+
+pub fn active_profile() -> Option<&'static str> {
+    ACTIVE_PROFILE.get().map(String::as_str)
+}
+
+/// The error shown when no token is stored for the active profile.
+pub fn no_token_message() -> String {
+    active_profile().map_or_else(
+        || format!("No token found. Run `detail auth login` or set {TOKEN_ENV}"),
+        |name| {
+            format!("No token found for profile '{name}'. Run `detail auth login --profile {name}`")
+        },
+    )
+}
+
+/// Error context for a command the API answered with `401 Unauthorized`.
+pub fn rejected_token_message() -> String {
+    if env_token().is_some() {
+        return format!("The API token in {TOKEN_ENV} was rejected (expired or revoked)");
+    }
+    active_profile().map_or_else(
+        || "The API token was rejected (expired or revoked). Run `detail auth login`".to_string(),
+        |name| {
+            format!(
+                "The API token for profile '{name}' was rejected (expired or revoked). \
+                 Run `detail auth login --profile {name}`"
+            )
+        },
+    )
+}
+
+/// Error context for a command the API answered with `403 Forbidden`
+/// while requests were pinned to `org`.
+pub fn pinned_org_message(org: &str) -> String {
+    let login = active_profile().map_or_else(
+        || "detail auth login".to_string(),
+        |name| format!("detail auth login --profile {name}"),
+    );
+    format!(
+        "Access denied for pinned organization {org}. If you're no longer a member, \
+         run `{login} --org <ORG>` to pin another"
+    )
+}
+
+/// A `[repos."owner/repo"]` table.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RepoSettings {
+    pub default_format: Option<OutputFormat>,
+    /// `bugs alert` threshold for pending bugs, unless `--max-pending` is passed.
+    pub alert_max_pending: Option<usize>,
+    /// `bugs alert` threshold for pending security vulnerabilities, unless
+    /// `--max-vulns` is passed.
+    pub alert_max_vulns: Option<usize>,
+}
+
+/// Target of an `@name` repo shortcut: a single `owner/repo` string or a
+/// list of them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RepoShortcut {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl RepoShortcut {
+    /// The repo identifiers this shortcut expands to.
+    pub fn repos(&self) -> &[String] {
+        match self {
+            Self::One(repo) => slice::from_ref(repo),
+            Self::Many(repos) => repos,
         }
     }
 }
@@ -54,14 +317,150 @@ pub fn config_path() -> Result<PathBuf> {
     Ok(config_dir.join("config.toml"))
 }
 
+/// Directory for disposable cached data: `$XDG_CACHE_HOME/detail-cli`,
+/// `%LOCALAPPDATA%\detail-cli\cache` on Windows, `~/.cache/detail-cli`
+/// elsewhere. Safe to delete at any time.
+pub fn cache_dir() -> Result<PathBuf> {
+    let cache_dir = if let Ok(xdg_cache) = env::var("XDG_CACHE_HOME") {
+        PathBuf::from(xdg_cache).join("detail-cli")
+    } else if cfg!(windows) {
+        let local_app_data =
+            env::var("LOCALAPPDATA").context("LOCALAPPDATA environment variable not set")?;
+        PathBuf::from(local_app_data)
+            .join("detail-cli")
+            .join("cache")
+    } else {
+        let home = homedir::my_home()
+            .context("Failed to determine home directory")?
+            .context("Home directory not found")?;
+        home.join(".cache").join("detail-cli")
+    };
+
+    fs::create_dir_all(&cache_dir)?;
+    Ok(cache_dir)
+}
+
 pub fn load_config() -> Result<Config> {
     let path = config_path()?;
     if !path.exists() {
         return Ok(Config::default());
     }
 
+    let table = load_table_with_includes(&path, &mut Vec::new())?;
+    let mut config: Config = Table::try_into(table).context("Failed to parse config")?;
+    if let Some(name) = active_profile() {
+        config.apply_profile(name);
+    }
+    Ok(config)
+}
+
+/// Keys an included file may not set: credentials stay in the local file.
+const LOCAL_ONLY_KEYS: [&str; 2] = ["api_token", "last_update_check"];
+
+/// Read `path` as a TOML table with the files it `include`s merged
+/// underneath it — its own keys win. `stack` holds the files currently being
+/// loaded, to reject include cycles.
+fn load_table_with_includes(path: &Path, stack: &mut Vec<PathBuf>) -> Result<Table> {
+    let canonical = fs::canonicalize(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    if stack.contains(&canonical) {
+        let cycle: Vec<String> = stack
+            .iter()
+            .chain([&canonical])
+            .map(|p| p.display().to_string())
+            .collect();
+        bail!("Config include cycle: {}", cycle.join(" -> "));
+    }
+
     let contents = fs::read_to_string(path)?;
-    toml::from_str(&contents).context("Failed to parse config")
+    let table: Table = if stack.is_empty() {
+        toml::from_str(&contents).context("Failed to parse config")?
+    } else {
+        toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse config include {}", path.display()))?
+    };
+    let includes: Vec<String> = match table.get("include") {
+        Some(value) => value
+            .clone()
+            .try_into()
+            .context("`include` must be a list of file paths")?,
+        None => return Ok(table),
+    };
+
+    stack.push(canonical);
+    let mut merged = Table::new();
+    for include in &includes {
+        let include_path = resolve_include_path(include, path)?;
+        let mut included = load_table_with_includes(&include_path, stack)?;
+        included.remove("include");
+        for key in LOCAL_ONLY_KEYS {
+            included.remove(key);
+        }
+        if let Some(Value::Table(profiles)) = included.get_mut("profiles") {
+            for profile in profiles.values_mut() {
+                if let Value::Table(profile) = profile {
+                    profile.remove("api_token");
+                }
+            }
+        }
+        merge_tables(&mut merged, included);
+    }
+    stack.pop();
+
+    merge_tables(&mut merged, table);
+    Ok(merged)
+}
+
+/// Expand `~/` and resolve relative include paths against the including file.
+fn resolve_include_path(include: &str, including_file: &Path) -> Result<PathBuf> {
+    if let Some(rest) = include.strip_prefix("~/") {
+        let home = homedir::my_home()
+            .context("Failed to determine home directory")?
+            .context("Home directory not found")?;
+        return Ok(home.join(rest));
+    }
+    let include = Path::new(include);
+    if include.is_absolute() {
+        return Ok(include.to_path_buf());
+    }
+    Ok(including_file
+        .parent()
+        .map_or_else(|| include.to_path_buf(), |dir| dir.join(include)))
+}
+
+/// Merge `overlay` into `base`; nested tables merge key by key, any other
+/// value in `overlay` replaces the one in `base`.
+fn merge_tables(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        if let Value::Table(overlay_table) = value {
+            if let Some(Value::Table(base_table)) = base.get_mut(&key) {
+                merge_tables(base_table, overlay_table);
+            } else {
+                base.insert(key, Value::Table(overlay_table));
+            }
+        } else {
+            base.insert(key, value);
+        }
+    }
+}
+
+/// Open `path` for reading and writing, creating it and its directory if
+/// missing, and take an exclusive lock on it. The lock is held until the
+/// file is dropped or unlocked.
+pub(crate) fn lock_file(path: &Path) -> Result<File> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let file = File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.lock_exclusive()
+        .with_context(|| format!("Failed to lock {}", path.display()))?;
+    Ok(file)
 }
 
 /// Atomically read-modify-write the config file under an exclusive lock.
@@ -74,13 +473,7 @@ pub fn load_config() -> Result<Config> {
 /// the update didn't care about — and leaves unknown user-added keys alone.
 pub fn update_config(f: impl FnOnce(&mut Config)) -> Result<()> {
     let path = config_path()?;
-    let file = File::options()
-        .read(true)
-        .write(true)
-        .create(true)
-        .truncate(false)
-        .open(&path)?;
-    file.lock_exclusive()?;
+    let file = lock_file(&path)?;
 
     let mut contents = String::new();
     (&file).read_to_string(&mut contents)?;
@@ -116,23 +509,163 @@ pub fn update_config(f: impl FnOnce(&mut Config)) -> Result<()> {
     Ok(())
 }
 
-// Token storage in config file
+// Token storage, in the config file or the OS keyring
+
+const KEYRING_SERVICE: &str = "detail-cli";
+const KEYRING_USER: &str = "api_token";
+
+/// The keyring entry for the active profile's token.
+fn keyring_entry() -> Result<Entry> {
+    let user = active_profile().map_or_else(
+        || KEYRING_USER.to_string(),
+        |name| format!("{KEYRING_USER}:{name}"),
+    );
+    Entry::new(KEYRING_SERVICE, &user).context("Failed to open the OS keyring")
+}
+
+/// Where the active profile's token lives in config.toml.
+fn token_slot(config: &mut Config) -> &mut Option<String> {
+    match active_profile() {
+        Some(name) => {
+            &mut config
+                .profiles
+                .entry(name.to_string())
+                .or_default()
+                .api_token
+        }
+        None => &mut config.api_token,
+    }
+}
+
+/// The token from `config`'s storage backend, if one is stored. With
+/// `token_storage = "keyring"`, a token still in config.toml is moved into
+/// the keyring first, so switching backends needs no new login.
+pub fn stored_token(config: &Config) -> Result<Option<String>> {
+    match config.token_storage {
+        TokenStorage::File => Ok(config.api_token.clone()),
+        TokenStorage::Keyring => {
+            let entry = keyring_entry()?;
+            if let Some(token) = &config.api_token {
+                entry
+                    .set_password(token)
+                    .context("Failed to move the API token into the OS keyring")?;
+                update_config(|config| *token_slot(config) = None)?;
+                return Ok(Some(token.clone()));
+            }
+            let token = entry.get_password();
+            if let Err(KeyringError::NoEntry) = token {
+                return Ok(None);
+            }
+            token
+                .map(Some)
+                .context("Failed to read the API token from the OS keyring")
+        }
+    }
+}
+
+/// Environment variable holding an API token that takes precedence over the
+/// stored one, so CI jobs can authenticate without a config file.
+pub const TOKEN_ENV: &str = "DETAIL_API_TOKEN";
+
+/// The token from `DETAIL_API_TOKEN`, if it's set and not blank.
+pub fn env_token() -> Option<String> {
+    env::var(TOKEN_ENV)
+        .ok()
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// The token API calls use: `DETAIL_API_TOKEN` if set, otherwise the one
+/// stored for the active profile.
+pub fn api_token(config: &Config) -> Result<Option<String>> {
+    env_token().map_or_else(|| stored_token(config), |token| Ok(Some(token)))
+}
+
 pub fn store_token(token: &str) -> Result<()> {
-    update_config(|config| {
-        config.api_token = Some(token.to_string());
-    })
+    match load_config()?.token_storage {
+        TokenStorage::File => update_config(|config| {
+            *token_slot(config) = Some(token.to_string());
+        }),
+        TokenStorage::Keyring => keyring_entry()?
+            .set_password(token)
+            .context("Failed to store the API token in the OS keyring"),
+    }
+}
+
+/// Where the active profile's pinned org lives in config.toml.
+fn org_slot(config: &mut Config) -> &mut Option<String> {
+    match active_profile() {
+        Some(name) => &mut config.profiles.entry(name.to_string()).or_default().org,
+        None => &mut config.org,
+    }
+}
+
+/// Pin the active profile to organization `org`, by ID.
+pub fn store_org(org: &str) -> Result<()> {
+    update_config(|config| *org_slot(config) = Some(org.to_string()))
+}
+
+// Request signing secrets, always in the OS keyring
+
+/// The keyring entry for signing key `key_id`'s secret. Keyed by the ID
+/// rather than the profile, so profiles behind the same gateway share it.
+fn signing_entry(key_id: &str) -> Result<Entry> {
+    Entry::new(KEYRING_SERVICE, &format!("signing_key:{key_id}"))
+        .context("Failed to open the OS keyring")
+}
+
+/// The secret of signing key `key_id`.
+pub fn signing_secret(key_id: &str) -> Result<String> {
+    match signing_entry(key_id)?.get_password() {
+        Err(KeyringError::NoEntry) => bail!(
+            "No secret stored for signing key '{key_id}'. Run `detail auth signing-key {key_id}`"
+        ),
+        secret => secret.context("Failed to read the signing secret from the OS keyring"),
+    }
+}
+
+/// Sign the active profile's requests with `key_id`, storing `secret` in
+/// the keyring.
+pub fn store_signing_key(key_id: &str, secret: &str) -> Result<()> {
+    signing_entry(key_id)?
+        .set_password(secret)
+        .context("Failed to store the signing secret in the OS keyring")?;
+    update_config(|config| *signing_key_slot(config) = Some(key_id.to_string()))
+}
+
+/// Stop signing the active profile's requests. The secret stays in the
+/// keyring, since other profiles may use the same key.
+pub fn clear_signing_key() -> Result<()> {
+    update_config(|config| *signing_key_slot(config) = None)
+}
+
+/// Where the active profile's signing key ID lives in config.toml.
+fn signing_key_slot(config: &mut Config) -> &mut Option<String> {
+    match active_profile() {
+        Some(name) => {
+            &mut config
+                .profiles
+                .entry(name.to_string())
+                .or_default()
+                .signing_key_id
+        }
+        None => &mut config.signing_key_id,
+    }
 }
 
 pub fn load_token() -> Result<String> {
-    let config = load_config()?;
-    config
-        .api_token
-        .context("No token found. Run `detail auth login`")
+    stored_token(&load_config()?)?.with_context(no_token_message)
 }
 
 pub fn clear_credentials() -> Result<()> {
+    if load_config()?.token_storage == TokenStorage::Keyring {
+        let deleted = keyring_entry()?.delete_credential();
+        if !matches!(deleted, Ok(()) | Err(KeyringError::NoEntry)) {
+            deleted.context("Failed to remove the API token from the OS keyring")?;
+        }
+    }
     update_config(|config| {
-        config.api_token = None;
+        *token_slot(config) = None;
     })
 }
 
@@ -158,14 +691,7 @@ pub fn try_acquire_update_lock() -> Result<Option<File>> {
 
 /// Acquire the update lock, blocking until it is available.
 pub fn acquire_update_lock() -> Result<File> {
-    let lock_path = update_lock_path()?;
-    let file = File::options()
-        .write(true)
-        .create(true)
-        .truncate(false)
-        .open(lock_path)?;
-    file.lock_exclusive()?;
-    Ok(file)
+    lock_file(&update_lock_path()?)
 }
 
 #[cfg(test)]
@@ -216,6 +742,29 @@ mod tests {
             check_for_updates: true,
             last_update_check: Some(12345),
             api_token: Some("dtl_test_token".into()),
+            token_storage: TokenStorage::File,
+            profiles: BTreeMap::new(),
+            shortcuts: BTreeMap::new(),
+            default_format: None,
+            repos: BTreeMap::new(),
+            log_file: None,
+            include: Vec::new(),
+            proxy: None,
+            ca_cert: None,
+            timeout_secs: None,
+            connect_timeout_secs: None,
+            redact_patterns: Vec::new(),
+            concurrency: None,
+            org: None,
+            signing_key_id: None,
+            relative_dates: false,
+            timezone: None,
+            record_usage: true,
+            notifications: BTreeMap::new(),
+            slack_webhook_url: None,
+            hooks: Hooks::default(),
+            jira: None,
+            gitlab: None,
         };
         let toml_str = toml::to_string_pretty(&config).unwrap();
         let restored: Config = toml::from_str(&toml_str).unwrap();
@@ -234,6 +783,45 @@ mod tests {
         assert!(config.last_update_check.is_none());
     }
 
+    #[test]
+    fn profile_replaces_token_and_overrides_urls() {
+        let mut config: Config = toml::from_str(
+            r#"
+api_url = "https://api.detail.dev"
+app_url = "https://app.detail.dev"
+api_token = "dtl_default"
+org = "org_default"
+signing_key_id = "default-gateway"
+
+[profiles.work]
+api_url = "https://api.work.example"
+api_token = "dtl_work"
+org = "org_work"
+signing_key_id = "work-gateway"
+"#,
+        )
+        .unwrap();
+        config.apply_profile("work");
+        assert_eq!(config.api_token.as_deref(), Some("dtl_work"));
+        assert_eq!(config.org.as_deref(), Some("org_work"));
+        assert_eq!(config.signing_key_id.as_deref(), Some("work-gateway"));
+        assert_eq!(config.api_url.as_deref(), Some("https://api.work.example"));
+        assert_eq!(config.app_url.as_deref(), Some("https://app.detail.dev"));
+
+        config.apply_profile("unknown");
+        assert!(config.api_token.is_none());
+        assert!(config.org.is_none());
+        assert!(config.signing_key_id.is_none());
+    }
+
+    #[test]
+    fn token_storage_defaults_to_file() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.token_storage, TokenStorage::File);
+        let config: Config = toml::from_str(r#"token_storage = "keyring""#).unwrap();
+        assert_eq!(config.token_storage, TokenStorage::Keyring);
+    }
+
     #[test]
     fn config_missing_check_for_updates_defaults_to_true() {
         let toml_str = r#"api_url = "https://api.example.com""#;
@@ -242,6 +830,61 @@ mod tests {
         assert_eq!(config.api_url.as_deref(), Some("https://api.example.com"));
     }
 
+    #[test]
+    fn config_shortcuts_accept_single_repo_or_list() {
+        let toml_str = r#"
+[shortcuts]
+core = "acme/core"
+payments = ["acme/payments-api", "acme/payments-web"]
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.shortcuts["core"].repos(), ["acme/core"]);
+        assert_eq!(
+            config.shortcuts["payments"].repos(),
+            ["acme/payments-api", "acme/payments-web"]
+        );
+    }
+
+    #[test]
+    fn config_default_omits_empty_shortcuts() {
+        let toml_str = toml::to_string_pretty(&Config::default()).unwrap();
+        assert!(!toml_str.contains("shortcuts"));
+    }
+
+    #[test]
+    fn default_format_prefers_repo_over_global() {
+        let toml_str = r#"
+default_format = "json"
+
+[repos."acme/web"]
+default_format = "table"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(matches!(
+            config.default_format_for(Some("acme/web")),
+            Some(OutputFormat::Table)
+        ));
+        assert!(matches!(
+            config.default_format_for(Some("web")),
+            Some(OutputFormat::Table)
+        ));
+        assert!(matches!(
+            config.default_format_for(Some("acme/api")),
+            Some(OutputFormat::Json)
+        ));
+        assert!(matches!(
+            config.default_format_for(None),
+            Some(OutputFormat::Json)
+        ));
+    }
+
+    #[test]
+    fn default_format_unset_is_none() {
+        assert!(Config::default()
+            .default_format_for(Some("acme/web"))
+            .is_none());
+    }
+
     // ── config_path ──────────────────────────────────────────────────
 
     #[test]
@@ -289,6 +932,70 @@ mod tests {
         });
     }
 
+    // ── include ──────────────────────────────────────────────────────
+
+    #[test]
+    fn include_merges_shared_settings_under_local_ones() {
+        with_temp_config(|| {
+            let path = config_path().unwrap();
+            let shared = path.with_file_name("team.toml");
+            fs::write(
+                &shared,
+                r#"
+api_url = "https://api.team.dev"
+api_token = "dtl_shared_should_be_ignored"
+default_format = "json"
+
+[shortcuts]
+core = "acme/core"
+web = "acme/web"
+"#,
+            )
+            .unwrap();
+            fs::write(
+                &path,
+                r#"
+include = ["team.toml"]
+default_format = "table"
+
+[shortcuts]
+web = "acme/web-next"
+"#,
+            )
+            .unwrap();
+
+            let config = load_config().unwrap();
+            assert_eq!(config.api_url.as_deref(), Some("https://api.team.dev"));
+            assert!(config.api_token.is_none());
+            assert!(matches!(config.default_format, Some(OutputFormat::Table)));
+            assert_eq!(config.shortcuts["core"].repos(), ["acme/core"]);
+            assert_eq!(config.shortcuts["web"].repos(), ["acme/web-next"]);
+        });
+    }
+
+    #[test]
+    fn include_cycle_is_rejected() {
+        with_temp_config(|| {
+            let path = config_path().unwrap();
+            let other = path.with_file_name("other.toml");
+            fs::write(&path, r#"include = ["other.toml"]"#).unwrap();
+            fs::write(&other, r#"include = ["config.toml"]"#).unwrap();
+
+            let err = load_config().unwrap_err();
+            assert!(err.to_string().contains("Config include cycle"));
+        });
+    }
+
+    #[test]
+    fn missing_include_is_an_error() {
+        with_temp_config(|| {
+            let path = config_path().unwrap();
+            fs::write(&path, r#"include = ["nope.toml"]"#).unwrap();
+            let err = load_config().unwrap_err();
+            assert!(err.to_string().contains("nope.toml"));
+        });
+    }
+
     // ── token helpers ────────────────────────────────────────────────
 
     #[test]
@@ -306,6 +1013,32 @@ mod tests {
         });
     }
 
+    #[test]
+    fn env_token_takes_precedence_over_stored_token() {
+        with_temp_config(|| {
+            store_token("dtl_live_stored").unwrap();
+            env::set_var(TOKEN_ENV, " dtl_live_env\n");
+            let from_env = api_token(&load_config().unwrap()).unwrap();
+            env::set_var(TOKEN_ENV, "");
+            let blank_env = api_token(&load_config().unwrap()).unwrap();
+            env::remove_var(TOKEN_ENV);
+            assert_eq!(from_env.as_deref(), Some("dtl_live_env"));
+            assert_eq!(blank_env.as_deref(), Some("dtl_live_stored"));
+        });
+    }
+
+    #[test]
+    fn env_profile_ignores_blank_values() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        env::set_var(PROFILE_ENV, " work\n");
+        let named = env_profile();
+        env::set_var(PROFILE_ENV, "");
+        let blank = env_profile();
+        env::remove_var(PROFILE_ENV);
+        assert_eq!(named.as_deref(), Some("work"));
+        assert_eq!(blank, None);
+    }
+
     #[test]
     fn clear_credentials_removes_token() {
         with_temp_config(|| {
@@ -340,6 +1073,17 @@ mod tests {
         });
     }
 
+    #[test]
+    fn lock_file_creates_missing_directories() {
+        let dir = env::temp_dir().join(format!("detail-cli-lock-test-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("nested").join("queue.lock");
+        let lock = lock_file(&path).unwrap();
+        assert!(path.is_file());
+        drop(lock);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     // ── comment preservation ─────────────────────────────────────────
 
     #[test]
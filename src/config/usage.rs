@@ -0,0 +1,112 @@
+//! Local log of the commands run and how long they took, for `detail usage`.
+//!
+//! Appended as JSON lines to `usage.jsonl` next to `config.toml` and never
+//! sent anywhere. `record_usage = false` in config turns it off.
+
+use std::fs;
+use std::fs::File;
+use std::io::{ErrorKind, Write as _};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::storage::config_path;
+
+/// One finished command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageRecord {
+    /// Subcommand path, e.g. `bugs list`. Arguments aren't kept.
+    pub command: String,
+    /// When the command finished, in epoch millis.
+    pub finished_at: i64,
+    pub duration_ms: u64,
+    pub succeeded: bool,
+    /// API requests sent.
+    pub requests: usize,
+    /// Of those, the ones answered from the response cache.
+    pub from_cache: usize,
+}
+
+pub fn usage_path() -> Result<PathBuf> {
+    config_path().map(|p| p.with_file_name("usage.jsonl"))
+}
+
+/// Append `record`. Best effort: losing a record must never fail the
+/// command it describes.
+pub fn append(record: &UsageRecord) {
+    let Ok(mut line) = serde_json::to_string(record) else {
+        return;
+    };
+    line.push('\n');
+    let Ok(path) = usage_path() else {
+        return;
+    };
+    // One write per line, so concurrent runs don't interleave.
+    if let Ok(mut file) = File::options().create(true).append(true).open(path) {
+        let _ = file.write_all(line.as_bytes());
+    }
+}
+
+pub fn load_usage() -> Result<Vec<UsageRecord>> {
+    let path = usage_path()?;
+    match fs::read_to_string(&path) {
+        Ok(contents) => Ok(parse_usage(&contents)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+/// Records in `contents`, skipping lines that don't parse (e.g. one cut
+/// short by a full disk).
+fn parse_usage(contents: &str) -> Vec<UsageRecord> {
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Delete the log. Returns `false` if there was none.
+pub fn clear() -> Result<bool> {
+    let path = usage_path()?;
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e).with_context(|| format!("Failed to delete {}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(command: &str) -> UsageRecord {
+        UsageRecord {
+            command: command.to_string(),
+            finished_at: 1_736_899_200_000,
+            duration_ms: 420,
+            succeeded: true,
+            requests: 3,
+            from_cache: 1,
+        }
+    }
+
+    #[test]
+    fn records_round_trip_one_per_line() {
+        let lines: Vec<String> = [record("bugs list"), record("repos list")]
+            .iter()
+            .map(|r| serde_json::to_string(r).unwrap())
+            .collect();
+        assert!(lines[0].contains("\"durationMs\":420"));
+        let parsed = parse_usage(&lines.join("\n"));
+        assert_eq!(parsed, [record("bugs list"), record("repos list")]);
+    }
+
+    #[test]
+    fn malformed_lines_are_skipped() {
+        let good = serde_json::to_string(&record("bugs show")).unwrap();
+        let contents = format!("{good}\n{{\"command\":\"bugs li\n\n{good}\n");
+        assert_eq!(parse_usage(&contents).len(), 2);
+    }
+}
@@ -0,0 +1,524 @@
+//! `detail daemon`: a background process that keeps recently used API
+//! responses warm in memory and answers other `detail` processes (and
+//! editors) over a local socket, a Unix socket or a Windows named pipe.
+//!
+//! The protocol is one JSON request line and one JSON response line per
+//! connection. Clients give up quickly and call the API themselves when no
+//! daemon answers, so a missing or wedged daemon only costs a round trip.
+
+use std::collections::BTreeMap;
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::signal;
+use tokio::sync::Mutex;
+use tokio::time::{interval, timeout, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use crate::api::client::ApiClient;
+use crate::api::types::BugReviewState;
+use crate::utils::repos::resolve_repo_id;
+
+/// How long a response is served without refetching it.
+const FRESH_FOR: Duration = Duration::from_secs(30);
+
+/// Responses nobody asked for in this long stop being refreshed.
+const IDLE_EVICT: Duration = Duration::from_secs(10 * 60);
+
+/// How often warm responses and watched repos are refreshed.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(20);
+
+/// How long a client waits for the daemon before calling the API itself.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Page sizes warmed for each watched repo: `bugs list`'s default and the
+/// page size used when fetching every bug.
+const WARM_PAGE_SIZES: [u32; 2] = [50, 100];
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+enum Request {
+    /// The body of a GET of `url`, made with the token `scope` identifies.
+    Get {
+        scope: String,
+        url: String,
+    },
+    /// Data changed through the API; drop every held response.
+    Invalidate,
+    Status,
+    Stop,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "camelCase")]
+enum Response {
+    Body {
+        body: String,
+    },
+    /// The daemon can't answer this request; call the API directly.
+    Miss,
+    Invalidated,
+    Status(DaemonStatus),
+    Stopping,
+    Error {
+        message: String,
+    },
+}
+
+/// What `detail daemon status` reports.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaemonStatus {
+    pub pid: u32,
+    /// Epoch millis.
+    pub started_at: i64,
+    pub cached_responses: usize,
+    pub watched_repos: Vec<String>,
+}
+
+#[cfg(unix)]
+mod transport {
+    use std::fs::{self, DirBuilder, Permissions};
+    use std::io;
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    use std::path::PathBuf;
+
+    use tokio::net::{UnixListener, UnixStream};
+
+    use crate::config::storage::cache_dir;
+
+    /// Anyone who can connect can read responses fetched with the token,
+    /// so the socket lives in a directory only the owner can enter.
+    fn socket_dir() -> io::Result<PathBuf> {
+        cache_dir()
+            .map(|dir| dir.join("daemon"))
+            .map_err(io::Error::other)
+    }
+
+    fn socket_path() -> io::Result<PathBuf> {
+        socket_dir().map(|dir| dir.join("daemon.sock"))
+    }
+
+    pub async fn connect() -> io::Result<UnixStream> {
+        UnixStream::connect(socket_path()?).await
+    }
+
+    pub struct Listener(UnixListener);
+
+    impl Listener {
+        /// Callers check that no daemon answers first, so a socket file
+        /// still here was left by one that didn't shut down cleanly.
+        pub fn bind() -> io::Result<Self> {
+            let dir = socket_dir()?;
+            DirBuilder::new().recursive(true).mode(0o700).create(&dir)?;
+            // A directory left from before keeps its mode, so set it too,
+            // before the socket exists.
+            fs::set_permissions(&dir, Permissions::from_mode(0o700))?;
+            let path = socket_path()?;
+            let _ = fs::remove_file(&path);
+            let listener = UnixListener::bind(&path)?;
+            fs::set_permissions(&path, Permissions::from_mode(0o600))?;
+            Ok(Self(listener))
+        }
+
+        pub async fn accept(&mut self) -> io::Result<UnixStream> {
+            self.0.accept().await.map(|(stream, _)| stream)
+        }
+
+        pub fn close(self) {
+            if let Ok(path) = socket_path() {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod transport {
+    use std::env;
+    use std::io;
+    use std::mem;
+
+    use tokio::net::windows::named_pipe::{
+        ClientOptions, NamedPipeClient, NamedPipeServer, ServerOptions,
+    };
+
+    /// Named pipes are machine-wide, so the name includes the user.
+    fn pipe_name() -> String {
+        format!(
+            r"\\.\pipe\detail-cli-daemon-{}",
+            env::var("USERNAME").unwrap_or_default()
+        )
+    }
+
+    #[allow(
+        clippy::unused_async,
+        reason = "same signature as the Unix transport, whose connect awaits"
+    )]
+    pub async fn connect() -> io::Result<NamedPipeClient> {
+        ClientOptions::new().open(pipe_name())
+    }
+
+    pub struct Listener(NamedPipeServer);
+
+    impl Listener {
+        pub fn bind() -> io::Result<Self> {
+            ServerOptions::new()
+                .first_pipe_instance(true)
+                .reject_remote_clients(true)
+                .create(pipe_name())
+                .map(Self)
+        }
+
+        /// Wait for a client on the current pipe instance, and create the
+        /// next instance before handing this one over.
+        pub async fn accept(&mut self) -> io::Result<NamedPipeServer> {
+            self.0.connect().await?;
+            let next = ServerOptions::new()
+                .reject_remote_clients(true)
+                .create(pipe_name())?;
+            Ok(mem::replace(&mut self.0, next))
+        }
+
+        pub fn close(self) {}
+    }
+}
+
+/// Write `message` as one JSON line.
+async fn write_line<S: AsyncWrite + Unpin, T: Serialize>(
+    stream: &mut S,
+    message: &T,
+) -> Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes()).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+async fn exchange<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: S,
+    request: &Request,
+) -> Result<Response> {
+    let mut stream = BufReader::new(stream);
+    write_line(stream.get_mut(), request).await?;
+    let mut reply = String::new();
+    stream.read_line(&mut reply).await?;
+    Ok(serde_json::from_str(&reply)?)
+}
+
+/// Send `request` to the daemon. `None` when none is running or it doesn't
+/// answer in time.
+async fn request(request: &Request) -> Option<Response> {
+    timeout(CLIENT_TIMEOUT, async {
+        let stream = transport::connect().await.ok()?;
+        exchange(stream, request).await.ok()
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// The daemon's copy of the response to a GET of `url`, if a daemon running
+/// with the same token and org (see `request_scope`) has or can fetch one.
+pub async fn get(scope: &str, url: &str) -> Option<String> {
+    let response = request(&Request::Get {
+        scope: scope.to_string(),
+        url: url.to_string(),
+    })
+    .await?;
+    match response {
+        Response::Body { body } => Some(body),
+        Response::Error { message } => {
+            debug!("daemon could not fetch {url}: {message}");
+            None
+        }
+        Response::Miss | Response::Invalidated | Response::Status(_) | Response::Stopping => None,
+    }
+}
+
+/// Tell a running daemon that data changed, so it stops serving what it
+/// fetched before. Does nothing when none is running.
+pub async fn invalidate() {
+    if request(&Request::Invalidate).await.is_none() {
+        debug!("no daemon to invalidate");
+    }
+}
+
+/// The running daemon's status, or `None` when none is running.
+pub async fn status() -> Option<DaemonStatus> {
+    match request(&Request::Status).await? {
+        Response::Status(status) => Some(status),
+        Response::Body { .. }
+        | Response::Miss
+        | Response::Invalidated
+        | Response::Stopping
+        | Response::Error { .. } => None,
+    }
+}
+
+/// Ask the running daemon to exit. `false` when none is running.
+pub async fn stop() -> bool {
+    matches!(request(&Request::Stop).await, Some(Response::Stopping))
+}
+
+/// A response the daemon holds.
+struct Entry {
+    body: String,
+    fetched_at: Instant,
+    /// When a client last asked for it.
+    used_at: Instant,
+}
+
+struct Daemon {
+    client: ApiClient,
+    started_at: i64,
+    repos: Vec<String>,
+    /// First pages of pending bugs for `repos`, refreshed whether or not
+    /// anyone asked for them yet.
+    watched_urls: Vec<String>,
+    cache: Mutex<BTreeMap<String, Entry>>,
+    /// Bumped on every invalidation, so a fetch that was already in flight
+    /// doesn't put a response from before the change back in the cache.
+    generation: AtomicU64,
+    refreshing: AtomicBool,
+    shutdown: CancellationToken,
+}
+
+impl Daemon {
+    async fn get(&self, url: &str) -> Result<String> {
+        let now = Instant::now();
+        {
+            let mut cache = self.cache.lock().await;
+            if let Some(entry) = cache.get_mut(url) {
+                entry.used_at = now;
+                if now.duration_since(entry.fetched_at) < FRESH_FOR {
+                    return Ok(entry.body.clone());
+                }
+            }
+        }
+        self.fetch(url).await
+    }
+
+    async fn fetch(&self, url: &str) -> Result<String> {
+        let generation = self.generation.load(Ordering::Acquire);
+        let body = self.client.get_text(url).await?;
+        let now = Instant::now();
+        let mut cache = self.cache.lock().await;
+        if self.generation.load(Ordering::Acquire) != generation {
+            return Ok(body);
+        }
+        let used_at = cache.get(url).map_or(now, |entry| entry.used_at);
+        cache.insert(
+            url.to_string(),
+            Entry {
+                body: body.clone(),
+                fetched_at: now,
+                used_at,
+            },
+        );
+        Ok(body)
+    }
+
+    async fn respond(&self, request: Request) -> Response {
+        match request {
+            Request::Get { scope, url } => {
+                if scope != self.client.cache_scope() || !self.client.serves(&url) {
+                    return Response::Miss;
+                }
+                self.get(&url).await.map_or_else(
+                    |e| Response::Error {
+                        message: format!("{e:#}"),
+                    },
+                    |body| Response::Body { body },
+                )
+            }
+            Request::Invalidate => {
+                let mut cache = self.cache.lock().await;
+                self.generation.fetch_add(1, Ordering::AcqRel);
+                cache.clear();
+                Response::Invalidated
+            }
+            Request::Status => Response::Status(DaemonStatus {
+                pid: process::id(),
+                started_at: self.started_at,
+                cached_responses: self.cache.lock().await.len(),
+                watched_repos: self.repos.clone(),
+            }),
+            Request::Stop => {
+                self.shutdown.cancel();
+                Response::Stopping
+            }
+        }
+    }
+
+    async fn serve<S: AsyncRead + AsyncWrite + Unpin>(&self, stream: S) -> Result<()> {
+        let mut stream = BufReader::new(stream);
+        let mut line = String::new();
+        stream.read_line(&mut line).await?;
+        let response = match serde_json::from_str(&line) {
+            Ok(request) => self.respond(request).await,
+            Err(e) => Response::Error {
+                message: format!("Invalid request: {e}"),
+            },
+        };
+        write_line(stream.get_mut(), &response).await
+    }
+
+    /// Drop responses nobody used lately and refetch the rest, along with
+    /// the watched repos. Skipped while the previous refresh still runs.
+    async fn refresh(&self) {
+        if self.refreshing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let urls: Vec<String> = {
+            let now = Instant::now();
+            let mut cache = self.cache.lock().await;
+            cache.retain(|_, entry| now.duration_since(entry.used_at) < IDLE_EVICT);
+            cache.keys().cloned().collect()
+        };
+        for url in urls.iter().chain(&self.watched_urls) {
+            if let Err(e) = self.fetch(url).await {
+                debug!("refresh of {url} failed: {e:#}");
+            }
+        }
+        self.refreshing.store(false, Ordering::Release);
+    }
+}
+
+/// URLs of the first pages of pending bugs for `repos`. Repos that can't be
+/// resolved are skipped with a warning rather than stopping the daemon.
+async fn watched_urls(client: &ApiClient, repos: &[String]) -> Vec<String> {
+    let mut urls = Vec::new();
+    for repo in repos {
+        let repo_id = match resolve_repo_id(client, repo).await {
+            Ok(repo_id) => repo_id,
+            Err(e) => {
+                warn!("Not watching {repo}: {e:#}");
+                continue;
+            }
+        };
+        for limit in WARM_PAGE_SIZES {
+            if let Ok(url) = client.bugs_url(&repo_id, BugReviewState::Pending, limit, 0) {
+                urls.push(url.to_string());
+            }
+        }
+    }
+    urls
+}
+
+/// Serve requests until `detail daemon stop` or Ctrl-C. `client` must be
+/// built `without_daemon`.
+pub async fn run(client: ApiClient, repos: Vec<String>) -> Result<()> {
+    if status().await.is_some() {
+        bail!("A daemon is already running; stop it with `detail daemon stop`");
+    }
+    let mut listener = transport::Listener::bind().context("Failed to open the daemon socket")?;
+    let daemon = Arc::new(Daemon {
+        watched_urls: watched_urls(&client, &repos).await,
+        client,
+        started_at: chrono::Utc::now().timestamp_millis(),
+        repos,
+        cache: Mutex::new(BTreeMap::new()),
+        generation: AtomicU64::new(0),
+        refreshing: AtomicBool::new(false),
+        shutdown: CancellationToken::new(),
+    });
+
+    let mut ticker = interval(REFRESH_INTERVAL);
+    loop {
+        tokio::select! {
+            () = daemon.shutdown.cancelled() => break,
+            _ = signal::ctrl_c() => break,
+            _ = ticker.tick() => {
+                let daemon = Arc::clone(&daemon);
+                tokio::spawn(async move { daemon.refresh().await });
+            }
+            accepted = listener.accept() => match accepted {
+                Ok(stream) => {
+                    let daemon = Arc::clone(&daemon);
+                    tokio::spawn(async move {
+                        if let Err(e) = daemon.serve(stream).await {
+                            debug!("daemon connection failed: {e:#}");
+                        }
+                    });
+                }
+                Err(e) => warn!("Failed to accept a daemon connection: {e}"),
+            },
+        }
+    }
+    listener.close();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::HttpOptions;
+
+    /// A daemon whose API nothing listens on, so every fetch fails and
+    /// only held responses can be served.
+    fn offline_daemon() -> Daemon {
+        let client = ApiClient::new(
+            Some("http://127.0.0.1:9".into()),
+            None,
+            &HttpOptions::default(),
+        )
+        .unwrap()
+        .without_daemon();
+        Daemon {
+            client,
+            started_at: 0,
+            repos: Vec::new(),
+            watched_urls: Vec::new(),
+            cache: Mutex::new(BTreeMap::new()),
+            generation: AtomicU64::new(0),
+            refreshing: AtomicBool::new(false),
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn list_after_a_close_is_not_served_from_the_cache() {
+        let daemon = offline_daemon();
+        let url = "http://127.0.0.1:9/public/v1/bugs?status=pending";
+        let now = Instant::now();
+        daemon.cache.lock().await.insert(
+            url.to_string(),
+            Entry {
+                body: r#"{"bugs":[{"id":"bug_closed"}],"total":1}"#.into(),
+                fetched_at: now,
+                used_at: now,
+            },
+        );
+        assert!(daemon.get(url).await.unwrap().contains("bug_closed"));
+
+        // What `bugs close` sends after its write.
+        let response = daemon.respond(Request::Invalidate).await;
+        assert!(matches!(response, Response::Invalidated));
+        assert!(daemon.cache.lock().await.is_empty());
+        // The list now goes to the API instead of the stale copy.
+        assert!(daemon.get(url).await.is_err());
+    }
+
+    #[test]
+    fn requests_and_responses_are_tagged_json() {
+        let request = serde_json::to_string(&Request::Get {
+            scope: "tok".into(),
+            url: "https://api.detail.dev/public/v1/bugs".into(),
+        })
+        .unwrap();
+        assert_eq!(
+            request,
+            r#"{"op":"get","scope":"tok","url":"https://api.detail.dev/public/v1/bugs"}"#
+        );
+        let response: Response = serde_json::from_str(r#"{"result":"miss"}"#).unwrap();
+        assert!(matches!(response, Response::Miss));
+    }
+}
@@ -0,0 +1,282 @@
+//! `--events-file`: a JSONL record of what one invocation did — the command,
+//! its API calls and how it ended — for agents driving the CLI.
+//!
+//! Unlike `--log-file`, whose lines are whatever the tracing formatter
+//! emits, every line here has a fixed shape tagged with `SCHEMA_VERSION`;
+//! the schema is documented in `detail help agents`. Writing an event never
+//! fails the command: a full disk loses events, not work.
+
+use std::fs::File;
+use std::io::Write as _;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use chrono::SecondsFormat;
+use clap::Command;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::exit;
+
+/// Bumped when a field changes meaning or goes away. New fields and event
+/// types don't bump it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Hex digits of SHA-256 kept for a hashed argument.
+const ARG_HASH_LEN: usize = 12;
+
+/// One thing that happened during the invocation.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    CommandStarted {
+        /// Subcommand path, e.g. `bugs list`.
+        command: &'a str,
+        /// Arguments after the binary name; see `describe_args`.
+        args: &'a [String],
+    },
+    ApiCall {
+        method: &'a str,
+        url: &'a str,
+        status: u16,
+        elapsed_ms: Option<u64>,
+    },
+    ApiError {
+        method: Option<&'a str>,
+        url: Option<&'a str>,
+        elapsed_ms: Option<u64>,
+        error: String,
+    },
+    CommandFinished {
+        exit_code: u8,
+        duration_ms: u64,
+        error: Option<String>,
+    },
+}
+
+/// An event as written: the event's own fields plus the envelope.
+#[derive(Serialize)]
+struct Record<'a> {
+    v: u32,
+    /// RFC 3339, UTC, millisecond precision.
+    ts: String,
+    /// Random ID shared by every event of one invocation, so concurrent
+    /// runs appending to the same file can be told apart.
+    invocation: &'a str,
+    #[serde(flatten)]
+    event: &'a Event<'a>,
+}
+
+struct Sink {
+    file: Mutex<File>,
+    invocation: String,
+}
+
+static SINK: OnceLock<Sink> = OnceLock::new();
+
+/// Start appending events to `path`. Only the first call counts.
+pub fn init(path: &Path) -> Result<()> {
+    let file = File::options()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open events file {}", path.display()))?;
+    let _ = SINK.set(Sink {
+        file: Mutex::new(file),
+        invocation: format!("{:016x}", rand::random::<u64>()),
+    });
+    Ok(())
+}
+
+/// Whether `--events-file` is in effect.
+pub fn enabled() -> bool {
+    SINK.get().is_some()
+}
+
+/// The JSON line for `event`, without the trailing newline.
+fn render(event: &Event<'_>, invocation: &str, ts: String) -> serde_json::Result<String> {
+    serde_json::to_string(&Record {
+        v: SCHEMA_VERSION,
+        ts,
+        invocation,
+        event,
+    })
+}
+
+/// Append `event`, if `--events-file` is in effect.
+pub fn emit(event: &Event<'_>) {
+    let Some(sink) = SINK.get() else {
+        return;
+    };
+    let ts = chrono::Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+    let Ok(mut line) = render(event, &sink.invocation, ts) else {
+        return;
+    };
+    line.push('\n');
+    // One write per line, so lines from concurrent runs don't interleave.
+    let _ = sink
+        .file
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .write_all(line.as_bytes());
+}
+
+/// Milliseconds since `started`, saturating.
+pub fn elapsed_ms(started: Instant) -> u64 {
+    u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+/// A value as it appears in the log: a short SHA-256, so the same value
+/// can be matched across runs without the log holding tokens, paths or
+/// repository names.
+fn hash_value(value: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(value.as_bytes()));
+    format!("sha256:{}", digest.get(..ARG_HASH_LEN).unwrap_or(&digest))
+}
+
+/// Whether the long flag `--name` of `command` or one of its ancestors
+/// takes a value, so the next argument is that value.
+fn flag_takes_value(commands: &[&Command], name: &str) -> bool {
+    commands.iter().any(|command| {
+        command
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(name))
+            .is_some_and(|arg| arg.get_action().takes_values())
+    })
+}
+
+/// The subcommand path of `args` (without the binary name) and the
+/// arguments in hash-safe form: subcommand and flag names as given, every
+/// value — positionals, flag values and `--flag=value` — hashed.
+pub fn describe_args(root: &Command, args: &[String]) -> (String, Vec<String>) {
+    let mut commands = vec![root];
+    let mut path = Vec::new();
+    let mut described = Vec::with_capacity(args.len());
+    let mut positional_seen = false;
+    let mut value_next = false;
+    for arg in args {
+        if value_next {
+            value_next = false;
+            described.push(hash_value(arg));
+        } else if let Some((flag, value)) = arg.split_once('=').filter(|_| arg.starts_with("--")) {
+            described.push(format!("{flag}={}", hash_value(value)));
+        } else if let Some(name) = arg.strip_prefix("--").filter(|name| !name.is_empty()) {
+            value_next = flag_takes_value(&commands, name);
+            described.push(arg.clone());
+        } else if arg.starts_with('-') {
+            described.push(arg.clone());
+        } else if let Some(sub) = commands
+            .last()
+            .filter(|_| !positional_seen)
+            .and_then(|command| command.find_subcommand(arg))
+        {
+            path.push(sub.get_name());
+            commands.push(sub);
+            described.push(arg.clone());
+        } else {
+            positional_seen = true;
+            described.push(hash_value(arg));
+        }
+    }
+    (path.join(" "), described)
+}
+
+/// Record the start of the command described by `args`.
+pub fn command_started(root: &Command, args: &[String]) {
+    if !enabled() {
+        return;
+    }
+    let (command, args) = describe_args(root, args);
+    emit(&Event::CommandStarted {
+        command: &command,
+        args: &args,
+    });
+}
+
+/// Record how the command ended.
+pub fn command_finished(started: Instant, result: &Result<()>) {
+    emit(&Event::CommandFinished {
+        exit_code: result.as_ref().map_or_else(exit::code, |()| exit::SUCCESS),
+        duration_ms: elapsed_ms(started),
+        error: result.as_ref().err().map(|err| format!("{err:#}")),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory as _;
+
+    use super::*;
+
+    fn describe(args: &[&str]) -> (String, Vec<String>) {
+        let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+        describe_args(&crate::Cli::command(), &args)
+    }
+
+    #[test]
+    fn args_keep_names_and_hash_values() {
+        let (command, args) = describe(&[
+            "--refresh",
+            "bugs",
+            "list",
+            "acme/api",
+            "--format",
+            "json",
+            "--since=7d",
+            "--vulns",
+        ]);
+        assert_eq!(command, "bugs list");
+        assert_eq!(
+            args,
+            [
+                "--refresh".to_string(),
+                "bugs".to_string(),
+                "list".to_string(),
+                hash_value("acme/api"),
+                "--format".to_string(),
+                hash_value("json"),
+                format!("--since={}", hash_value("7d")),
+                "--vulns".to_string(),
+            ]
+        );
+        assert!(!args.iter().any(|arg| arg.contains("acme")));
+    }
+
+    #[test]
+    fn positional_named_like_a_subcommand_is_still_hashed() {
+        let (command, args) = describe(&["bugs", "show", "list"]);
+        assert_eq!(command, "bugs show");
+        assert_eq!(args[2], hash_value("list"));
+    }
+
+    #[test]
+    fn records_have_a_stable_envelope() {
+        let line = render(
+            &Event::ApiCall {
+                method: "GET",
+                url: "https://api.test/public/v1/repos",
+                status: 200,
+                elapsed_ms: Some(12),
+            },
+            "0123456789abcdef",
+            "2026-01-01T00:00:00.000Z".to_string(),
+        )
+        .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "v": SCHEMA_VERSION,
+                "ts": "2026-01-01T00:00:00.000Z",
+                "invocation": "0123456789abcdef",
+                "event": "api_call",
+                "method": "GET",
+                "url": "https://api.test/public/v1/repos",
+                "status": 200,
+                "elapsed_ms": 12,
+            })
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Process exit codes.
+//!
+//! Scripts can rely on these staying stable:
+//!
+//! | Code | Meaning                                              |
+//! |------|------------------------------------------------------|
+//! | 0    | Success                                              |
+//! | 1    | API or transport error (and anything unclassified),  |
+//! |      | or a partial failure after results were printed      |
+//! | 2    | Usage error: bad flags, arguments, or IDs            |
+//! | 3    | Not found: unknown repo, bug, rule, …                |
+//! | 4    | Threshold exceeded (`detail check`)                  |
+//! | 5    | Update available (`detail update --check`)           |
+//!
+//! Errors opt into a specific code by carrying a [`Failure`] somewhere in
+//! their anyhow chain, either as the root error or as a `.context(...)` layer.
+//!
+//! With `--format json`, a failed command prints [`error_document`] on
+//! stdout as well, so automation can tell failures apart without parsing
+//! stderr.
+
+use std::error;
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::api::client::{is_network_error, is_unauthorized, status};
+
+pub const SUCCESS: u8 = 0;
+pub const API_ERROR: u8 = 1;
+pub const USAGE: u8 = 2;
+pub const NOT_FOUND: u8 = 3;
+pub const THRESHOLD_EXCEEDED: u8 = 4;
+pub const UPDATE_AVAILABLE: u8 = 5;
+
+/// Which non-default exit code an error maps to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    Usage,
+    NotFound,
+    ThresholdExceeded,
+    UpdateAvailable,
+    /// Some of a batch (repos of a shortcut, bugs closed from stdin) failed
+    /// after the command printed results for the rest.
+    PartialFailure,
+}
+
+impl FailureKind {
+    pub const fn code(self) -> u8 {
+        match self {
+            Self::Usage => USAGE,
+            Self::NotFound => NOT_FOUND,
+            Self::ThresholdExceeded => THRESHOLD_EXCEEDED,
+            Self::UpdateAvailable => UPDATE_AVAILABLE,
+            Self::PartialFailure => API_ERROR,
+        }
+    }
+
+    /// The `type` of a JSON error document.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Usage => "usage",
+            Self::NotFound => "not_found",
+            Self::ThresholdExceeded => "threshold_exceeded",
+            Self::UpdateAvailable => "update_available",
+            Self::PartialFailure => "partial_failure",
+        }
+    }
+}
+
+/// An error message tagged with the exit code it should produce. Displays as
+/// the bare message, so swapping `bail!("…")` for a `Failure` doesn't change
+/// what users see.
+#[derive(Debug)]
+pub struct Failure {
+    kind: FailureKind,
+    message: String,
+}
+
+impl Failure {
+    pub fn usage(message: impl Into<String>) -> Self {
+        Self {
+            kind: FailureKind::Usage,
+            message: message.into(),
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self {
+            kind: FailureKind::NotFound,
+            message: message.into(),
+        }
+    }
+
+    pub fn threshold_exceeded(message: impl Into<String>) -> Self {
+        Self {
+            kind: FailureKind::ThresholdExceeded,
+            message: message.into(),
+        }
+    }
+
+    pub fn update_available(message: impl Into<String>) -> Self {
+        Self {
+            kind: FailureKind::UpdateAvailable,
+            message: message.into(),
+        }
+    }
+
+    pub fn partial(message: impl Into<String>) -> Self {
+        Self {
+            kind: FailureKind::PartialFailure,
+            message: message.into(),
+        }
+    }
+
+    pub const fn kind(&self) -> FailureKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl error::Error for Failure {}
+
+/// The `Failure` that classifies `err`, if any.
+fn failure(err: &anyhow::Error) -> Option<&Failure> {
+    // `downcast_ref` sees `.context(Failure)` layers; walking the chain
+    // catches a `Failure` used as a root error or source.
+    err.downcast_ref::<Failure>().or_else(|| {
+        err.chain()
+            .find_map(|cause| cause.downcast_ref::<Failure>())
+    })
+}
+
+/// Exit code for an error returned from `Cli::run`.
+pub fn code(err: &anyhow::Error) -> u8 {
+    failure(err).map_or(API_ERROR, |failure| failure.kind().code())
+}
+
+/// What went wrong, for scripts: a [`FailureKind`] name, or for errors
+/// exiting with [`API_ERROR`], `unauthorized`, `network` or `api`.
+pub fn error_type(err: &anyhow::Error) -> &'static str {
+    if let Some(failure) = failure(err) {
+        failure.kind().name()
+    } else if is_unauthorized(err) {
+        "unauthorized"
+    } else if is_network_error(err) {
+        "network"
+    } else {
+        "api"
+    }
+}
+
+/// Whether `err` only signals a result the command already printed, like
+/// `detail check` exceeding its threshold or some repos of a shortcut
+/// failing, rather than a failure to run. These never get an
+/// [`error_document`], which would follow the result on stdout.
+pub fn is_outcome(err: &anyhow::Error) -> bool {
+    failure(err).is_some_and(|failure| {
+        matches!(
+            failure.kind(),
+            FailureKind::ThresholdExceeded
+                | FailureKind::UpdateAvailable
+                | FailureKind::PartialFailure
+        )
+    })
+}
+
+/// `{"error": {"type", "message", "status"}}` for `err`: its
+/// [`error_type`], the message printed on stderr otherwise, and the API's
+/// HTTP status when the API answered with an error.
+pub fn error_document(err: &anyhow::Error) -> Value {
+    json!({
+        "error": {
+            "type": error_type(err),
+            "message": format!("{err:#}"),
+            "status": status(err),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Context;
+
+    use super::*;
+
+    #[test]
+    fn unclassified_errors_are_api_errors() {
+        assert_eq!(code(&anyhow::anyhow!("API error: 500")), API_ERROR);
+    }
+
+    #[test]
+    fn root_failure_survives_context() {
+        let err = anyhow::Error::new(Failure::not_found("Repository 'x' not found"))
+            .context("Failed to resolve repository identifier");
+        assert_eq!(code(&err), NOT_FOUND);
+    }
+
+    #[test]
+    fn failure_context_layer_is_found() {
+        let err = "bug_x"
+            .parse::<u8>()
+            .context(Failure::usage("Invalid bug ID format"))
+            .context("outer")
+            .unwrap_err();
+        assert_eq!(code(&err), USAGE);
+        assert_eq!(
+            format!("{err:#}").split(": ").nth(1),
+            Some("Invalid bug ID format")
+        );
+    }
+
+    #[test]
+    fn error_documents_classify_the_failure() {
+        let err = anyhow::Error::new(Failure::usage("Invalid bug ID format"))
+            .context("Failed to close bug");
+        assert_eq!(
+            error_document(&err),
+            json!({
+                "error": {
+                    "type": "usage",
+                    "message": "Failed to close bug: Invalid bug ID format",
+                    "status": null,
+                }
+            })
+        );
+        assert_eq!(error_type(&anyhow::anyhow!("API error: 500")), "api");
+        assert!(!is_outcome(&err));
+        assert!(is_outcome(&anyhow::Error::new(
+            Failure::threshold_exceeded("3 pending bugs exceeds --max 0")
+        )));
+    }
+
+    #[test]
+    fn partial_failures_are_outcomes_exiting_like_api_errors() {
+        let err = anyhow::Error::new(Failure::partial("1 of 3 bugs failed to close"));
+        assert!(is_outcome(&err));
+        assert_eq!(code(&err), API_ERROR);
+        assert_eq!(error_type(&err), "partial_failure");
+        // Other errors exiting 1 still get an error document.
+        assert!(!is_outcome(&anyhow::anyhow!("API error: 500")));
+    }
+
+    #[test]
+    fn failure_displays_bare_message() {
+        let err = Failure::threshold_exceeded("3 pending bugs exceeds --max 0");
+        assert_eq!(err.to_string(), "3 pending bugs exceeds --max 0");
+        assert_eq!(err.kind().code(), THRESHOLD_EXCEEDED);
+    }
+}
@@ -0,0 +1,884 @@
+//! Bug export rendering for `detail bugs export`
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, Write as _};
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::DateTime;
+use regress::Regex;
+use serde_json::{json, Value};
+
+use crate::api::types::{
+    dismissal_reason_label, format_introduced_in, format_linked_issue, review_state_label, Bug,
+    BugReviewState,
+};
+use crate::output::lsp_diagnostics;
+use crate::utils::datetime::format_datetime;
+use crate::utils::git::repo_root;
+
+/// File formats supported by `bugs export`.
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Sarif,
+    Markdown,
+    // LSP diagnostics per file, for editor plugins; see `FileDiagnostics`.
+    LspDiagnostics,
+}
+
+impl ExportFormat {
+    /// The format a file name's extension asks for (`.json`, `.csv`,
+    /// `.sarif`, `.md`), if any.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            "sarif" => Some(Self::Sarif),
+            "md" | "markdown" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+}
+
+const CSV_HEADER: [&str; 12] = [
+    "id",
+    "title",
+    "repo_id",
+    "state",
+    "dismissal_reason",
+    "security",
+    "file_path",
+    "commit_sha",
+    "introduced_sha",
+    "introduced_author",
+    "introduced_pr",
+    "created_at",
+];
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Write `bugs` to `out` in the requested export format, each record as
+/// soon as it's rendered, so a long export to stdout starts flowing at
+/// once. The output always ends with a trailing newline.
+pub fn write(out: &mut impl io::Write, bugs: &[Bug], format: ExportFormat) -> Result<()> {
+    match format {
+        ExportFormat::Json => serde_json::to_writer_pretty(&mut *out, bugs)?,
+        ExportFormat::Csv => return write_csv(out, bugs),
+        ExportFormat::Sarif => serde_json::to_writer_pretty(&mut *out, &to_sarif(bugs))?,
+        ExportFormat::Markdown => return write_markdown(out, bugs),
+        ExportFormat::LspDiagnostics => {
+            serde_json::to_writer_pretty(&mut *out, &lsp_diagnostics(bugs, repo_root().as_deref()))?
+        }
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// `write` into a string, for exports that are validated before they're
+/// saved to a file.
+pub fn render(bugs: &[Bug], format: ExportFormat) -> Result<String> {
+    let mut out = Vec::new();
+    write(&mut out, bugs, format)?;
+    Ok(String::from_utf8(out)?)
+}
+
+/// Field count of each record in `csv`, honouring quoted commas and
+/// newlines.
+fn csv_record_widths(csv: &str) -> Vec<usize> {
+    let mut widths = Vec::new();
+    let mut fields = 1;
+    let mut quoted = false;
+    for c in csv.trim_end_matches('\n').chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields += 1,
+            '\n' if !quoted => {
+                widths.push(fields);
+                fields = 1;
+            }
+            _ => {}
+        }
+    }
+    widths.push(fields);
+    widths
+}
+
+/// Check that rendered export `content` reads back as `format`, so a
+/// serialization bug fails the export instead of shipping a broken file.
+pub fn validate(content: &str, format: ExportFormat) -> Result<()> {
+    match format {
+        ExportFormat::Json | ExportFormat::Sarif | ExportFormat::LspDiagnostics => {
+            serde_json::from_str::<Value>(content).context("Export is not valid JSON")?;
+        }
+        ExportFormat::Csv => {
+            if let Some(line) = csv_record_widths(content)
+                .iter()
+                .position(|&width| width != CSV_HEADER.len())
+            {
+                bail!(
+                    "Export CSV record {} does not have {} fields",
+                    line + 1,
+                    CSV_HEADER.len()
+                );
+            }
+        }
+        ExportFormat::Markdown => {}
+    }
+    Ok(())
+}
+
+/// Fenced code blocks, up to the closing fence or the end of the text.
+const CODE_BLOCK_PATTERN: &str = r"```[\s\S]*?(?:```|$)";
+
+/// Anything with a URL scheme, e.g. `https://wiki.corp/...`.
+const URL_PATTERN: &str = r"\b[A-Za-z][A-Za-z0-9+.-]*://[^\s)>\]`]+";
+
+/// Slash-separated paths ending in a file name with an extension, e.g.
+/// `src/routes/refunds.ts`.
+const PATH_PATTERN: &str = r"(?:[\w.@-]*/)+[\w@-]+\.[A-Za-z0-9]+";
+
+/// The redaction pass behind `bugs export --redact`, for sharing reports
+/// outside the org. Titles and summaries keep their prose but lose code
+/// blocks, URLs and file paths; locations, commits, authors, linked issues
+/// and review notes are dropped.
+pub struct Redactor {
+    /// Patterns and their placeholders, applied in order.
+    rules: Vec<(Regex, &'static str)>,
+}
+
+fn compile(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern).map_err(|e| anyhow!("Invalid redaction pattern '{pattern}': {e}"))
+}
+
+impl Redactor {
+    /// The built-in rules, then `extra_patterns` (`redact_patterns` in
+    /// config, ECMAScript regex syntax), whose matches become `[redacted]`.
+    pub fn new(extra_patterns: &[String]) -> Result<Self> {
+        let mut rules = vec![
+            (compile(CODE_BLOCK_PATTERN)?, "[code redacted]"),
+            (compile(URL_PATTERN)?, "[url redacted]"),
+            (compile(PATH_PATTERN)?, "[path redacted]"),
+        ];
+        for pattern in extra_patterns {
+            rules.push((compile(pattern)?, "[redacted]"));
+        }
+        Ok(Self { rules })
+    }
+
+    /// `text` with every rule's matches replaced by its placeholder.
+    fn redact_text(&self, text: &str) -> String {
+        self.rules
+            .iter()
+            .fold(text.to_string(), |text, (regex, placeholder)| {
+                let mut out = String::with_capacity(text.len());
+                let mut last = 0;
+                for found in regex.find_iter(&text) {
+                    // A pattern that can match nothing mustn't sprinkle
+                    // placeholders between every character.
+                    if found.start() == found.end() {
+                        continue;
+                    }
+                    out.push_str(text.get(last..found.start()).unwrap_or_default());
+                    out.push_str(placeholder);
+                    last = found.end();
+                }
+                out.push_str(text.get(last..).unwrap_or_default());
+                out
+            })
+    }
+
+    pub fn redact(&self, bug: &mut Bug) {
+        let mut title = bug.title.clone();
+        let mut summary = bug.summary.clone();
+        // The bug's own file may lack an extension the path rule needs.
+        if let Some(path) = bug.file_path.take() {
+            title = title.replace(&path, "[path redacted]");
+            summary = summary.replace(&path, "[path redacted]");
+        }
+        bug.title = self.redact_text(&title);
+        bug.summary = self.redact_text(&summary);
+        bug.commit_sha = None;
+        bug.introduced_in = None;
+        bug.linked_issues.clear();
+        if let Some(review) = &mut bug.review {
+            review.notes = None;
+        }
+    }
+}
+
+/// A bug without a review is pending.
+pub fn bug_state(bug: &Bug) -> BugReviewState {
+    bug.review
+        .as_ref()
+        .map_or(BugReviewState::Pending, |r| r.state)
+}
+
+/// Format epoch millis as RFC3339 (UTC), or an empty string if out of range.
+fn rfc3339(timestamp_ms: i64) -> String {
+    DateTime::from_timestamp_millis(timestamp_ms)
+        .as_ref()
+        .map_or_else(String::new, DateTime::to_rfc3339)
+}
+
+/// Quote a CSV field per RFC 4180 when it contains a delimiter, quote, or
+/// line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_csv(out: &mut impl io::Write, bugs: &[Bug]) -> Result<()> {
+    writeln!(out, "{}", CSV_HEADER.join(","))?;
+    for bug in bugs {
+        let intro = bug.introduced_in.as_ref();
+        let row = [
+            bug.id.to_string(),
+            bug.title.clone(),
+            bug.repo_id.to_string(),
+            bug_state(bug).to_string(),
+            bug.review
+                .as_ref()
+                .and_then(|r| r.dismissal_reason.as_ref())
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            bug.is_security_vulnerability
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            bug.file_path.clone().unwrap_or_default(),
+            bug.commit_sha.clone().unwrap_or_default(),
+            intro.map(|i| i.sha.clone()).unwrap_or_default(),
+            intro.and_then(|i| i.author.clone()).unwrap_or_default(),
+            intro
+                .and_then(|i| i.pr_number.as_ref())
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            rfc3339(bug.created_at),
+        ];
+        let fields: Vec<String> = row.iter().map(String::as_str).map(csv_field).collect();
+        writeln!(out, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+/// Build a SARIF 2.1.0 log so exports can be uploaded to code-scanning
+/// dashboards. Security vulnerabilities are reported at `error` level under
+/// their own rule; everything else is a `warning`.
+fn to_sarif(bugs: &[Bug]) -> Value {
+    let results: Vec<Value> = bugs
+        .iter()
+        .map(|bug| {
+            let (rule_id, level) = if bug.is_security_vulnerability == Some(true) {
+                ("security-vulnerability", "error")
+            } else {
+                ("bug", "warning")
+            };
+            let mut result = json!({
+                "ruleId": rule_id,
+                "level": level,
+                "message": { "text": bug.title },
+                "partialFingerprints": { "detailBugId": bug.id },
+                "properties": {
+                    "bugId": bug.id,
+                    "repoId": bug.repo_id,
+                    "state": bug_state(bug),
+                    "createdAt": rfc3339(bug.created_at),
+                    "summary": bug.summary,
+                },
+            });
+            if let Some(path) = &bug.file_path {
+                result["locations"] = json!([{
+                    "physicalLocation": { "artifactLocation": { "uri": path } }
+                }]);
+            }
+            result
+        })
+        .collect();
+
+    json!({
+        "version": "2.1.0",
+        "$schema": SARIF_SCHEMA,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "detail",
+                    "informationUri": "https://detail.dev",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": [
+                        {
+                            "id": "bug",
+                            "shortDescription": { "text": "Bug found by Detail" },
+                        },
+                        {
+                            "id": "security-vulnerability",
+                            "shortDescription": { "text": "Security vulnerability found by Detail" },
+                        },
+                    ],
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn write_markdown(out: &mut impl io::Write, bugs: &[Bug]) -> Result<()> {
+    writeln!(out, "# Bugs ({})", bugs.len())?;
+    for bug in bugs {
+        let mut section = format!("\n## {}\n", bug.title);
+        write_bug_details(&mut section, bug)?;
+        out.write_all(section.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// A bug's fields as label/value pairs, in the order reports list them.
+fn bug_fields(bug: &Bug) -> Vec<(&'static str, String)> {
+    let mut fields = vec![
+        ("ID", bug.id.to_string()),
+        ("State", review_state_label(&bug_state(bug)).to_string()),
+    ];
+    if let Some(reason) = bug
+        .review
+        .as_ref()
+        .and_then(|r| r.dismissal_reason.as_ref())
+    {
+        fields.push(("Dismissal", dismissal_reason_label(reason).to_string()));
+    }
+    if let Some(path) = &bug.file_path {
+        fields.push(("File", path.clone()));
+    }
+    fields.push(("Created", format_datetime(bug.created_at)));
+    if bug.is_security_vulnerability == Some(true) {
+        fields.push(("Security", "Yes".to_string()));
+    }
+    if let Some(intro) = &bug.introduced_in {
+        fields.push(("Introduced", format_introduced_in(intro)));
+    }
+    for issue in &bug.linked_issues {
+        fields.push(("Issue", format_linked_issue(issue)));
+    }
+    fields
+}
+
+/// Fields shown as code: identifiers and paths.
+fn is_code_field(label: &str) -> bool {
+    matches!(label, "ID" | "File")
+}
+
+/// A bug's fields as a markdown list, then its summary.
+fn write_bug_details(out: &mut String, bug: &Bug) -> Result<()> {
+    writeln!(out)?;
+    for (label, value) in bug_fields(bug) {
+        if is_code_field(label) {
+            writeln!(out, "- **{label}:** `{value}`")?;
+        } else {
+            writeln!(out, "- **{label}:** {value}")?;
+        }
+    }
+    writeln!(out)?;
+    writeln!(out, "{}", bug.summary.trim_end())?;
+    Ok(())
+}
+
+/// Formats for `bugs report`.
+#[derive(Clone, Copy, Default, clap::ValueEnum)]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// The format a file name's extension asks for (`.md`, `.html`), if any.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "html" | "htm" => Some(Self::Html),
+            _ => None,
+        }
+    }
+}
+
+/// Files listed under "Most affected files" in a report.
+const REPORT_TOP_FILES: usize = 10;
+
+/// `text` safe to put in a markdown table cell.
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// `text` safe to put in HTML text or a quoted attribute.
+fn html_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// What a report covers, worked out once for every format.
+struct ReportData<'a> {
+    /// Security vulnerabilities first, newest first within each group.
+    bugs: Vec<&'a Bug>,
+    /// Summary rows: total, vulnerabilities, then each state present.
+    summary: Vec<(&'static str, usize)>,
+    /// The most affected files, most bugs first.
+    files: Vec<(&'a str, usize)>,
+}
+
+impl<'a> ReportData<'a> {
+    fn new(bugs: &'a [Bug]) -> Self {
+        let mut bugs: Vec<&Bug> = bugs.iter().collect();
+        bugs.sort_by_key(|bug| {
+            (
+                bug.is_security_vulnerability != Some(true),
+                Reverse(bug.created_at),
+            )
+        });
+
+        let vulns = bugs
+            .iter()
+            .filter(|bug| bug.is_security_vulnerability == Some(true))
+            .count();
+        let mut summary = vec![("Total", bugs.len()), ("Security vulnerabilities", vulns)];
+        for state in [
+            BugReviewState::Pending,
+            BugReviewState::Resolved,
+            BugReviewState::Dismissed,
+        ] {
+            let count = bugs.iter().filter(|bug| bug_state(bug) == state).count();
+            if count > 0 {
+                summary.push((review_state_label(&state), count));
+            }
+        }
+
+        let mut per_file: BTreeMap<&str, usize> = BTreeMap::new();
+        for path in bugs.iter().filter_map(|bug| bug.file_path.as_deref()) {
+            *per_file.entry(path).or_default() += 1;
+        }
+        let mut files: Vec<(&str, usize)> = per_file.into_iter().collect();
+        files.sort_by_key(|&(path, count)| (Reverse(count), path));
+        files.truncate(REPORT_TOP_FILES);
+
+        Self {
+            bugs,
+            summary,
+            files,
+        }
+    }
+}
+
+/// A report on `repo` as of `now_ms`, for sprint reviews, PRs and people
+/// outside the terminal: a summary, a table of every bug, then a section
+/// per bug. Security vulnerabilities come first, newest first within each
+/// group.
+pub fn render_report(
+    repo: &str,
+    bugs: &[Bug],
+    now_ms: i64,
+    format: ReportFormat,
+) -> Result<String> {
+    let data = ReportData::new(bugs);
+    match format {
+        ReportFormat::Markdown => markdown_report(repo, &data, now_ms),
+        ReportFormat::Html => html_report(repo, &data, now_ms),
+    }
+}
+
+fn markdown_report(repo: &str, data: &ReportData, now_ms: i64) -> Result<String> {
+    let mut out = String::new();
+    writeln!(out, "# Detail bug report: {repo}")?;
+    writeln!(out)?;
+    writeln!(out, "Generated {}.", format_datetime(now_ms))?;
+    writeln!(out)?;
+    writeln!(out, "## Summary")?;
+    writeln!(out)?;
+    writeln!(out, "| | Bugs |")?;
+    writeln!(out, "|---|---:|")?;
+    for (label, count) in &data.summary {
+        writeln!(out, "| {label} | {count} |")?;
+    }
+
+    if !data.files.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "### Most affected files")?;
+        writeln!(out)?;
+        writeln!(out, "| File | Bugs |")?;
+        writeln!(out, "|---|---:|")?;
+        for (path, count) in &data.files {
+            writeln!(out, "| `{}` | {count} |", table_cell(path))?;
+        }
+    }
+
+    if data.bugs.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "No bugs match.")?;
+        return Ok(out);
+    }
+
+    writeln!(out)?;
+    writeln!(out, "## Bugs")?;
+    writeln!(out)?;
+    writeln!(out, "| # | Title | State | File | Introduced |")?;
+    writeln!(out, "|---:|---|---|---|---|")?;
+    for (i, bug) in data.bugs.iter().enumerate() {
+        let security = if bug.is_security_vulnerability == Some(true) {
+            "🔒 "
+        } else {
+            ""
+        };
+        writeln!(
+            out,
+            "| {} | {security}{} | {} | {} | {} |",
+            i + 1,
+            table_cell(&bug.title),
+            review_state_label(&bug_state(bug)),
+            bug.file_path
+                .as_deref()
+                .map_or_else(String::new, |path| format!("`{}`", table_cell(path))),
+            bug.introduced_in
+                .as_ref()
+                .map_or_else(String::new, |intro| table_cell(&format_introduced_in(
+                    intro
+                ))),
+        )?;
+    }
+    for (i, bug) in data.bugs.iter().enumerate() {
+        writeln!(out)?;
+        writeln!(out, "### {}. {}", i + 1, bug.title)?;
+        write_bug_details(&mut out, bug)?;
+    }
+    Ok(out)
+}
+
+/// Styles for HTML reports, embedded so the page stands alone.
+const REPORT_CSS: &str = "\
+body { font: 15px/1.5 system-ui, sans-serif; color: #1f2328; max-width: 960px; margin: 2rem auto; padding: 0 1rem; }
+h1 { font-size: 1.6rem; margin-bottom: 0.25rem; }
+.generated { color: #59636e; margin-top: 0; }
+table { border-collapse: collapse; margin: 1rem 0; width: 100%; }
+th, td { border-bottom: 1px solid #d1d9e0; padding: 0.4rem 0.6rem; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; }
+td.count { text-align: right; font-variant-numeric: tabular-nums; }
+code { font: 13px ui-monospace, monospace; background: #f6f8fa; padding: 0.1rem 0.3rem; border-radius: 4px; }
+.security { color: #cf222e; font-weight: 600; }
+details { border: 1px solid #d1d9e0; border-radius: 6px; margin: 0.75rem 0; padding: 0.5rem 0.75rem; }
+summary { cursor: pointer; font-weight: 600; }
+dl { display: grid; grid-template-columns: max-content 1fr; gap: 0.2rem 1rem; }
+dt { font-weight: 600; }
+dd { margin: 0; }
+.bug-summary { white-space: pre-wrap; }
+";
+
+/// `bug`'s title, flagged when it's a security vulnerability.
+fn html_title(bug: &Bug) -> String {
+    let title = html_escape(&bug.title);
+    if bug.is_security_vulnerability == Some(true) {
+        format!("<span class=\"security\">Security:</span> {title}")
+    } else {
+        title
+    }
+}
+
+fn html_report(repo: &str, data: &ReportData, now_ms: i64) -> Result<String> {
+    let repo = html_escape(repo);
+    let mut out = String::new();
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html lang=\"en\">")?;
+    writeln!(out, "<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\">")?;
+    writeln!(
+        out,
+        "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">"
+    )?;
+    writeln!(out, "<title>Detail bug report: {repo}</title>")?;
+    writeln!(out, "<style>\n{REPORT_CSS}</style>")?;
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+    writeln!(out, "<h1>Detail bug report: {repo}</h1>")?;
+    writeln!(
+        out,
+        "<p class=\"generated\">Generated {}.</p>",
+        html_escape(&format_datetime(now_ms))
+    )?;
+
+    writeln!(out, "<h2>Summary</h2>")?;
+    writeln!(out, "<table>")?;
+    for (label, count) in &data.summary {
+        writeln!(
+            out,
+            "<tr><th>{label}</th><td class=\"count\">{count}</td></tr>"
+        )?;
+    }
+    writeln!(out, "</table>")?;
+
+    if !data.files.is_empty() {
+        writeln!(out, "<h3>Most affected files</h3>")?;
+        writeln!(out, "<table>")?;
+        writeln!(out, "<tr><th>File</th><th>Bugs</th></tr>")?;
+        for (path, count) in &data.files {
+            writeln!(
+                out,
+                "<tr><td><code>{}</code></td><td class=\"count\">{count}</td></tr>",
+                html_escape(path)
+            )?;
+        }
+        writeln!(out, "</table>")?;
+    }
+
+    if data.bugs.is_empty() {
+        writeln!(out, "<p>No bugs match.</p>")?;
+    } else {
+        writeln!(out, "<h2>Bugs</h2>")?;
+        writeln!(out, "<table>")?;
+        writeln!(
+            out,
+            "<tr><th>#</th><th>Title</th><th>State</th><th>File</th><th>Introduced</th></tr>"
+        )?;
+        for (i, bug) in data.bugs.iter().enumerate() {
+            writeln!(
+                out,
+                "<tr><td class=\"count\"><a href=\"#bug-{n}\">{n}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                html_title(bug),
+                review_state_label(&bug_state(bug)),
+                bug.file_path.as_deref().map_or_else(String::new, |path| format!(
+                    "<code>{}</code>",
+                    html_escape(path)
+                )),
+                bug.introduced_in
+                    .as_ref()
+                    .map_or_else(String::new, |intro| html_escape(&format_introduced_in(
+                        intro
+                    ))),
+                n = i + 1,
+            )?;
+        }
+        writeln!(out, "</table>")?;
+
+        for (i, bug) in data.bugs.iter().enumerate() {
+            writeln!(out, "<details id=\"bug-{}\">", i + 1)?;
+            writeln!(out, "<summary>{}. {}</summary>", i + 1, html_title(bug))?;
+            writeln!(out, "<dl>")?;
+            for (label, value) in bug_fields(bug) {
+                let value = html_escape(&value);
+                if is_code_field(label) {
+                    writeln!(out, "<dt>{label}</dt><dd><code>{value}</code></dd>")?;
+                } else {
+                    writeln!(out, "<dt>{label}</dt><dd>{value}</dd>")?;
+                }
+            }
+            writeln!(out, "</dl>")?;
+            writeln!(
+                out,
+                "<div class=\"bug-summary\">{}</div>",
+                html_escape(bug.summary.trim_end())
+            )?;
+            writeln!(out, "</details>")?;
+        }
+    }
+
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_bug;
+
+    fn sample_bugs() -> Vec<Bug> {
+        vec![
+            test_bug(serde_json::json!({
+                "title": "SQL injection, in login", "summary": "Use \"params\".",
+                "filePath": "src/login.rs", "isSecurityVulnerability": true,
+                "introducedIn": { "sha": "abc1234", "date": "2024-01-01", "author": "alice", "prNumber": 42 }
+            })),
+            test_bug(serde_json::json!({
+                "id": "bug_2", "title": "Off-by-one", "summary": "Loop bound.", "createdAt": 1_000,
+                "review": { "state": "dismissed", "dismissalReason": "wont_fix", "createdAt": 2_000 }
+            })),
+        ]
+    }
+
+    #[test]
+    fn format_follows_the_extension() {
+        assert!(matches!(
+            ExportFormat::from_path(Path::new("out/report.SARIF")),
+            Some(ExportFormat::Sarif)
+        ));
+        assert!(matches!(
+            ExportFormat::from_path(Path::new("report.md")),
+            Some(ExportFormat::Markdown)
+        ));
+        assert!(ExportFormat::from_path(Path::new("report.txt")).is_none());
+        assert!(ExportFormat::from_path(Path::new("report")).is_none());
+    }
+
+    #[test]
+    fn rendered_exports_validate() {
+        for format in [
+            ExportFormat::Json,
+            ExportFormat::Csv,
+            ExportFormat::Sarif,
+            ExportFormat::Markdown,
+            ExportFormat::LspDiagnostics,
+        ] {
+            let content = render(&sample_bugs(), format).unwrap();
+            validate(&content, format).unwrap();
+        }
+        assert!(validate("{\"runs\": [", ExportFormat::Sarif).is_err());
+        assert!(validate("id,title\nbug_1", ExportFormat::Csv).is_err());
+    }
+
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn csv_has_header_and_one_row_per_bug() {
+        let csv = render(&sample_bugs(), ExportFormat::Csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER.join(","));
+        assert_eq!(
+            lines[1],
+            "bug_1,\"SQL injection, in login\",repo_1,pending,,true,src/login.rs,,abc1234,alice,42,1970-01-01T00:00:00+00:00"
+        );
+        assert!(lines[2].starts_with("bug_2,Off-by-one,repo_1,dismissed,wont_fix,,"));
+    }
+
+    #[test]
+    fn json_export_round_trips() {
+        let json = render(&sample_bugs(), ExportFormat::Json).unwrap();
+        let parsed: Vec<Bug> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert!(json.ends_with('\n'));
+    }
+
+    #[test]
+    fn sarif_maps_security_and_locations() {
+        let sarif: Value =
+            serde_json::from_str(&render(&sample_bugs(), ExportFormat::Sarif).unwrap()).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "security-vulnerability");
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "src/login.rs"
+        );
+        assert_eq!(results[1]["ruleId"], "bug");
+        assert_eq!(results[1]["properties"]["state"], "dismissed");
+        assert!(results[1].get("locations").is_none());
+    }
+
+    #[test]
+    fn report_summarizes_then_details_each_bug() {
+        let report = render_report("acme/api", &sample_bugs(), 0, ReportFormat::Markdown).unwrap();
+        assert!(report.starts_with("# Detail bug report: acme/api\n"));
+        assert!(report.contains("| Total | 2 |\n| Security vulnerabilities | 1 |"));
+        assert!(report.contains("| Pending | 1 |\n| Dismissed | 1 |"));
+        assert!(report.contains("| `src/login.rs` | 1 |"));
+        assert!(report.contains("| 1 | 🔒 SQL injection, in login | Pending | `src/login.rs` |"));
+        assert!(report.contains("### 2. Off-by-one\n\n- **ID:** `bug_2`"));
+    }
+
+    #[test]
+    fn html_report_is_escaped_with_a_section_per_bug() {
+        let mut bugs = sample_bugs();
+        bugs[1].title = "<script>alert(1)</script>".to_string();
+        let report = render_report("acme/api", &bugs, 0, ReportFormat::Html).unwrap();
+        assert!(report.starts_with("<!DOCTYPE html>\n"));
+        assert!(report.contains("<title>Detail bug report: acme/api</title>"));
+        assert!(report.contains("<tr><th>Total</th><td class=\"count\">2</td></tr>"));
+        assert!(report.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!report.contains("<script>"));
+        assert!(report.contains("<details id=\"bug-1\">"));
+        assert!(report.contains("<dt>ID</dt><dd><code>bug_2</code></dd>"));
+        assert!(report.contains("Use &quot;params&quot;."));
+        assert_eq!(report.matches("<details").count(), 2);
+        assert!(report.trim_end().ends_with("</html>"));
+    }
+
+    #[test]
+    fn report_format_follows_the_extension() {
+        assert!(matches!(
+            ReportFormat::from_path(Path::new("report.HTML")),
+            Some(ReportFormat::Html)
+        ));
+        assert!(ReportFormat::from_path(Path::new("report.txt")).is_none());
+    }
+
+    #[test]
+    fn markdown_has_section_per_bug() {
+        let md = render(&sample_bugs(), ExportFormat::Markdown).unwrap();
+        assert!(md.starts_with("# Bugs (2)\n"));
+        assert!(md.contains("## SQL injection, in login"));
+        assert!(md.contains("- **File:** `src/login.rs`"));
+        assert!(md.contains("- **State:** Dismissed"));
+        assert!(md.contains("- **Dismissal:** Won't Fix"));
+    }
+
+    #[test]
+    fn redact_strips_code_urls_and_paths() {
+        let redactor = Redactor::new(&["ACME-\\d+".to_string()]).unwrap();
+        let mut bug: Bug = serde_json::from_value(serde_json::json!({
+            "id": "bug_1",
+            "title": "Unchecked refund in src/routes/refunds.ts",
+            "summary": "See https://wiki.acme.internal/pay and ACME-42.\n\n```ts\nrefund(amount)\n```\nAlso lib/money/units.ts.",
+            "createdAt": 0, "repoId": "repo_1", "filePath": "src/routes/refunds.ts",
+            "commitSha": "abc1234", "isSecurityVulnerability": true,
+            "introducedIn": { "sha": "abc1234", "date": "2024-01-01", "author": "alice" },
+            "linkedIssues": [{ "tracker": "linear", "issueId": "PAY-1", "url": "https://linear.app/acme/issue/PAY-1" }],
+            "review": { "state": "dismissed", "createdAt": 0, "notes": "ask alice" }
+        }))
+        .unwrap();
+        redactor.redact(&mut bug);
+
+        assert_eq!(bug.title, "Unchecked refund in [path redacted]");
+        assert_eq!(
+            bug.summary,
+            "See [url redacted] and [redacted].\n\n[code redacted]\nAlso [path redacted]."
+        );
+        assert!(bug.file_path.is_none() && bug.commit_sha.is_none());
+        assert!(bug.introduced_in.is_none() && bug.linked_issues.is_empty());
+        assert!(bug.review.unwrap().notes.is_none());
+        assert_eq!(bug.is_security_vulnerability, Some(true));
+    }
+
+    #[test]
+    fn invalid_redaction_pattern_is_an_error() {
+        assert!(Redactor::new(&["(".to_string()]).is_err());
+    }
+
+    #[test]
+    fn empty_export_is_still_valid() {
+        assert_eq!(render(&[], ExportFormat::Json).unwrap(), "[]\n");
+        assert_eq!(
+            render(&[], ExportFormat::Csv).unwrap(),
+            format!("{}\n", CSV_HEADER.join(","))
+        );
+    }
+}
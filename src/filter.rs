@@ -0,0 +1,405 @@
+//! The `--filter` expression language shared by `bugs list`, `bugs export`,
+//! `bugs close -` and `check`, e.g. `security && path:src/** && age<30d`.
+//!
+//! ```text
+//! expr      := and ("||" and)*
+//! and       := unary ("&&" unary)*
+//! unary     := "!" unary | "(" expr ")" | predicate
+//! predicate := security | status:STATE | path:GLOB | author:NAME
+//!            | title:TEXT | title~REGEX | age<DURATION | age>DURATION
+//! ```
+//!
+//! Values with spaces or operator characters can be quoted: `title~'a|b'`.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use anyhow::{anyhow, bail, Result};
+use clap::ValueEnum;
+use regress::Regex;
+
+use crate::api::types::{Bug, BugReviewState};
+use crate::exit::Failure;
+use crate::export::bug_state;
+use crate::utils::datetime::parse_relative_duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+    Word(String),
+}
+
+/// Read one predicate word, unquoting any `'…'` or `"…"` parts.
+fn read_word(chars: &mut Peekable<Chars<'_>>) -> Result<String> {
+    let mut word = String::new();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() || matches!(c, '(' | ')' | '&' | '|') {
+            break;
+        }
+        chars.next();
+        if matches!(c, '\'' | '"') {
+            loop {
+                match chars.next() {
+                    Some(q) if q == c => break,
+                    Some(inner) => word.push(inner),
+                    None => bail!("unterminated {c} quote"),
+                }
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    Ok(word)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            '(' | ')' | '!' => {
+                chars.next();
+                tokens.push(match c {
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    _ => Token::Not,
+                });
+            }
+            '&' | '|' => {
+                chars.next();
+                if chars.next() != Some(c) {
+                    bail!("expected '{c}{c}'");
+                }
+                tokens.push(if c == '&' { Token::And } else { Token::Or });
+            }
+            _ if c.is_whitespace() => {
+                chars.next();
+            }
+            _ => tokens.push(Token::Word(read_word(&mut chars)?)),
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug)]
+enum Predicate {
+    Security,
+    Status(BugReviewState),
+    /// A glob over the bug's file path, as chars.
+    Path(Vec<char>),
+    Author(String),
+    /// Lowercased; matched case-insensitively.
+    TitleContains(String),
+    TitleMatches(Regex),
+    /// Created less than this many millis ago.
+    YoungerThan(i64),
+    /// Created more than this many millis ago.
+    OlderThan(i64),
+}
+
+fn duration_ms(spec: &str) -> Result<i64> {
+    parse_relative_duration(spec)
+        .map(|d| d.num_milliseconds())
+        .ok_or_else(|| anyhow!("'{spec}' is not a duration (e.g. 7d, 24h, 2w)"))
+}
+
+impl Predicate {
+    fn parse(word: &str) -> Result<Self> {
+        if ["security", "vuln", "vulns"]
+            .iter()
+            .any(|name| word.eq_ignore_ascii_case(name))
+        {
+            return Ok(Self::Security);
+        }
+        if let Some(spec) = word.strip_prefix("age<") {
+            return Ok(Self::YoungerThan(duration_ms(spec)?));
+        }
+        if let Some(spec) = word.strip_prefix("age>") {
+            return Ok(Self::OlderThan(duration_ms(spec)?));
+        }
+        if let Some(pattern) = word.strip_prefix("title~") {
+            return Regex::with_flags(pattern, "i")
+                .map(Self::TitleMatches)
+                .map_err(|e| anyhow!("invalid regex '{pattern}': {e}"));
+        }
+        let Some((field, value)) = word.split_once(':') else {
+            bail!("unknown predicate '{word}'");
+        };
+        if value.is_empty() {
+            bail!("'{field}:' needs a value");
+        }
+        match field {
+            "status" => BugReviewState::value_variants()
+                .iter()
+                .find(|state| {
+                    state
+                        .to_possible_value()
+                        .is_some_and(|v| v.matches(value, true))
+                })
+                .copied()
+                .map(Self::Status)
+                .ok_or_else(|| anyhow!("unknown status '{value}' (pending, resolved, dismissed)")),
+            "path" => Ok(Self::Path(value.chars().collect())),
+            "author" => Ok(Self::Author(value.to_string())),
+            "title" => Ok(Self::TitleContains(value.to_lowercase())),
+            _ => bail!("unknown field '{field}' (status, path, author, title, age)"),
+        }
+    }
+
+    fn matches(&self, bug: &Bug, now_ms: i64) -> bool {
+        let age = now_ms.saturating_sub(bug.created_at);
+        match self {
+            Self::Security => bug.is_security_vulnerability == Some(true),
+            Self::Status(state) => bug_state(bug) == *state,
+            Self::Path(glob) => bug
+                .file_path
+                .as_deref()
+                .is_some_and(|path| glob_match(glob, &path.chars().collect::<Vec<_>>())),
+            Self::Author(name) => bug
+                .introduced_in
+                .as_ref()
+                .and_then(|i| i.author.as_deref())
+                .is_some_and(|author| author.eq_ignore_ascii_case(name)),
+            Self::TitleContains(text) => bug.title.to_lowercase().contains(text.as_str()),
+            Self::TitleMatches(regex) => regex.find(&bug.title).is_some(),
+            Self::YoungerThan(ms) => age < *ms,
+            Self::OlderThan(ms) => age > *ms,
+        }
+    }
+}
+
+/// Match `path` against a glob: `*` and `?` stay within one path segment,
+/// `**` spans segments, and `**/` also matches no directory at all.
+fn glob_match(glob: &[char], path: &[char]) -> bool {
+    match glob {
+        [] => path.is_empty(),
+        ['*', '*', '/', rest @ ..] => {
+            glob_match(rest, path)
+                || (0..path.len()).any(|i| {
+                    path.get(i) == Some(&'/')
+                        && glob_match(rest, path.get(i + 1..).unwrap_or_default())
+                })
+        }
+        ['*', '*', rest @ ..] => {
+            (0..=path.len()).any(|i| glob_match(rest, path.get(i..).unwrap_or_default()))
+        }
+        ['*', rest @ ..] => (0..=path.len())
+            .take_while(|&i| i == 0 || path.get(i - 1) != Some(&'/'))
+            .any(|i| glob_match(rest, path.get(i..).unwrap_or_default())),
+        ['?', rest @ ..] => match path {
+            [c, tail @ ..] if *c != '/' => glob_match(rest, tail),
+            _ => false,
+        },
+        [g, rest @ ..] => match path {
+            [c, tail @ ..] if c == g => glob_match(rest, tail),
+            _ => false,
+        },
+    }
+}
+
+#[derive(Debug)]
+enum Expr {
+    Predicate(Predicate),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn matches(&self, bug: &Bug, now_ms: i64) -> bool {
+        match self {
+            Self::Predicate(predicate) => predicate.matches(bug, now_ms),
+            Self::Not(inner) => !inner.matches(bug, now_ms),
+            Self::And(a, b) => a.matches(bug, now_ms) && b.matches(bug, now_ms),
+            Self::Or(a, b) => a.matches(bug, now_ms) || b.matches(bug, now_ms),
+        }
+    }
+
+    fn mentions_status(&self) -> bool {
+        match self {
+            Self::Predicate(predicate) => matches!(predicate, Predicate::Status(_)),
+            Self::Not(inner) => inner.mentions_status(),
+            Self::And(a, b) | Self::Or(a, b) => a.mentions_status() || b.mentions_status(),
+        }
+    }
+}
+
+/// Recursive-descent parser over the token list.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.unary()?))),
+            Some(Token::Open) => {
+                let expr = self.or()?;
+                if self.next() != Some(Token::Close) {
+                    bail!("missing ')'");
+                }
+                Ok(expr)
+            }
+            Some(Token::Word(word)) => Ok(Expr::Predicate(Predicate::parse(&word)?)),
+            Some(Token::And | Token::Or) => bail!("'&&' and '||' need a predicate on each side"),
+            Some(Token::Close) => bail!("unexpected ')'"),
+            None => bail!("expression ends early"),
+        }
+    }
+}
+
+/// A parsed `--filter` expression.
+#[derive(Debug)]
+pub struct Filter {
+    expr: Expr,
+}
+
+impl Filter {
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            pos: 0,
+        };
+        let expr = parser.or()?;
+        if let Some(token) = parser.peek() {
+            bail!("unexpected {token:?} after a complete expression");
+        }
+        Ok(Self { expr })
+    }
+
+    pub fn matches(&self, bug: &Bug, now_ms: i64) -> bool {
+        self.expr.matches(bug, now_ms)
+    }
+
+    /// Whether the expression tests `status:`, so callers should fetch bugs
+    /// in every state rather than only the `--status` ones.
+    pub fn mentions_status(&self) -> bool {
+        self.expr.mentions_status()
+    }
+
+    /// The bugs in `bugs` that match, in order.
+    pub fn apply(&self, bugs: Vec<Bug>, now_ms: i64) -> Vec<Bug> {
+        bugs.into_iter()
+            .filter(|bug| self.matches(bug, now_ms))
+            .collect()
+    }
+}
+
+/// Parse a `--filter` flag value, reporting a bad expression as a usage
+/// error.
+pub fn parse_flag(value: Option<&str>) -> Result<Option<Filter>> {
+    value
+        .map(|expr| {
+            Filter::parse(expr).map_err(|e| Failure::usage(format!("Invalid --filter: {e}")).into())
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_bug;
+
+    const NOW: i64 = 100 * 86_400_000;
+
+    fn bug(path: &str, security: bool, days_old: i64, state: Option<&str>) -> Bug {
+        let review = state.map(|s| serde_json::json!({ "state": s, "createdAt": 0 }));
+        test_bug(serde_json::json!({
+            "title": "SQL injection in search",
+            "createdAt": NOW - days_old * 86_400_000,
+            "filePath": path,
+            "isSecurityVulnerability": security,
+            "introducedIn": { "sha": "abc", "date": "2024-01-01", "author": "apatel" },
+            "review": review,
+        }))
+    }
+
+    fn matches(expr: &str, bug: &Bug) -> bool {
+        Filter::parse(expr).unwrap().matches(bug, NOW)
+    }
+
+    #[test]
+    fn combines_predicates_with_precedence() {
+        let b = bug("src/api/search.rs", true, 3, None);
+        assert!(matches("security && path:src/**", &b));
+        assert!(matches("!security || path:src/**/*.rs && age<7d", &b));
+        assert!(!matches("(!security || path:src/**) && age>7d", &b));
+        assert!(matches("author:APATEL && title~'inject|overflow'", &b));
+        assert!(matches("title:sql", &b));
+    }
+
+    #[test]
+    fn status_defaults_to_pending() {
+        assert!(matches("status:pending", &bug("a.rs", false, 1, None)));
+        assert!(matches(
+            "status:resolved",
+            &bug("a.rs", false, 1, Some("resolved"))
+        ));
+        assert!(Filter::parse("status:resolved").unwrap().mentions_status());
+        assert!(!Filter::parse("security").unwrap().mentions_status());
+    }
+
+    #[test]
+    fn globs_respect_path_segments() {
+        let glob = |g: &str, p: &str| {
+            glob_match(
+                &g.chars().collect::<Vec<_>>(),
+                &p.chars().collect::<Vec<_>>(),
+            )
+        };
+        assert!(glob("src/*.rs", "src/lib.rs"));
+        assert!(!glob("src/*.rs", "src/api/client.rs"));
+        assert!(glob("src/**/*.rs", "src/lib.rs"));
+        assert!(glob("src/**/*.rs", "src/api/client.rs"));
+        assert!(glob("**/client.?s", "src/api/client.rs"));
+        assert!(!glob("app/**", "src/app/x.ts"));
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for expr in [
+            "",
+            "security &&",
+            "(security",
+            "security & vulns",
+            "owner:x",
+            "age<soon",
+            "title~'(",
+        ] {
+            assert!(Filter::parse(expr).is_err(), "{expr} should not parse");
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Bug action hooks: after `bugs close` or `bugs reopen` succeeds, call the
+//! URL or run the shell command named under `[hooks]` in config.toml with
+//! the bug as JSON.
+//!
+//! ```toml
+//! [hooks]
+//! on_close = "https://automation.example.com/detail/closed"
+//! on_reopen = "./scripts/reopened.sh"
+//! ```
+//!
+//! URLs get the payload as a JSON POST; commands get it on stdin, with
+//! `DETAIL_HOOK_EVENT` and `DETAIL_BUG_ID` set, and their output goes to
+//! stderr.
+
+use std::io::{self, Write as _};
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::api::client::post_json;
+use crate::api::types::Bug;
+use crate::config::storage::load_config;
+
+/// The `[hooks]` table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    /// URL or shell command run after a bug is closed.
+    pub on_close: Option<String>,
+    /// URL or shell command run after a bug is reopened.
+    pub on_reopen: Option<String>,
+}
+
+impl Hooks {
+    pub const fn is_empty(&self) -> bool {
+        self.on_close.is_none() && self.on_reopen.is_none()
+    }
+
+    /// The hook configured for `event`, if any.
+    pub fn get(&self, event: Event) -> Option<&str> {
+        match event {
+            Event::Close => self.on_close.as_deref(),
+            Event::Reopen => self.on_reopen.as_deref(),
+        }
+    }
+}
+
+/// A bug action hooks run after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Close,
+    Reopen,
+}
+
+impl Event {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Close => "close",
+            Self::Reopen => "reopen",
+        }
+    }
+}
+
+/// What a hook does: POST to a URL, or run a shell command.
+#[derive(Debug, PartialEq, Eq)]
+enum Action<'a> {
+    Url(&'a str),
+    Command(&'a str),
+}
+
+fn action(hook: &str) -> Action<'_> {
+    let hook = hook.trim();
+    if hook.starts_with("https://") || hook.starts_with("http://") {
+        Action::Url(hook)
+    } else {
+        Action::Command(hook)
+    }
+}
+
+/// The document a hook receives.
+pub fn payload(event: Event, bug: &Bug) -> Value {
+    json!({ "event": event.name(), "bug": bug })
+}
+
+/// `command` run through the platform shell: `sh -c`, or `cmd /C` on
+/// Windows.
+pub fn shell(command: &str) -> Command {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell.arg(command);
+    shell
+}
+
+fn run_command(command: &str, event: Event, bug: &Bug, payload: &Value) -> Result<()> {
+    let mut child = shell(command)
+        .env("DETAIL_HOOK_EVENT", event.name())
+        .env("DETAIL_BUG_ID", bug.id.to_string())
+        .stdin(Stdio::piped())
+        // Hook output must not mix into our own stdout, e.g. `--format json`.
+        .stdout(io::stderr())
+        .spawn()
+        .with_context(|| format!("Failed to run {} hook `{command}`", event.name()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores its input may exit before reading it all.
+        let _ = stdin.write_all(serde_json::to_string(payload)?.as_bytes());
+    }
+    let status = child
+        .wait()
+        .with_context(|| format!("Failed to run {} hook `{command}`", event.name()))?;
+    if !status.success() {
+        bail!("{} hook `{command}` exited with {status}", event.name());
+    }
+    Ok(())
+}
+
+/// Run the hook for `event` on `bug`, if one is configured.
+pub async fn run(event: Event, bug: &Bug, cli: &crate::Cli) -> Result<()> {
+    let config = load_config()?;
+    let Some(hook) = config.hooks.get(event) else {
+        return Ok(());
+    };
+    let payload = payload(event, bug);
+    match action(hook) {
+        Action::Url(url) => post_json(url, &payload, &cli.http_options(&config))
+            .await
+            .with_context(|| format!("Failed to call the {} hook", event.name())),
+        Action::Command(command) => run_command(command, event, bug, &payload),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hooks_parse_and_pick_by_event() {
+        let hooks: Hooks = toml::from_str(r#"on_close = "https://example.com/closed""#).unwrap();
+        assert_eq!(hooks.get(Event::Close), Some("https://example.com/closed"));
+        assert_eq!(hooks.get(Event::Reopen), None);
+        assert!(!hooks.is_empty());
+        assert!(Hooks::default().is_empty());
+    }
+
+    #[test]
+    fn urls_are_posted_and_anything_else_is_run() {
+        assert_eq!(
+            action(" https://example.com/hook"),
+            Action::Url("https://example.com/hook")
+        );
+        assert_eq!(
+            action("./notify.sh --closed"),
+            Action::Command("./notify.sh --closed")
+        );
+    }
+
+    #[test]
+    fn payload_wraps_the_bug() {
+        let bug: Bug = serde_json::from_value(json!({
+            "id": "bug_1", "title": "t", "summary": "s", "createdAt": 0,
+            "repoId": "repo_1", "linkedIssues": []
+        }))
+        .unwrap();
+        let payload = payload(Event::Reopen, &bug);
+        assert_eq!(payload["event"], "reopen");
+        assert_eq!(payload["bug"]["id"], "bug_1");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn commands_get_the_payload_on_stdin() {
+        let bug: Bug = serde_json::from_value(json!({
+            "id": "bug_1", "title": "t", "summary": "s", "createdAt": 0,
+            "repoId": "repo_1", "linkedIssues": []
+        }))
+        .unwrap();
+        let payload = payload(Event::Close, &bug);
+        let check = r#"grep -q '"id":"bug_1"' && test "$DETAIL_HOOK_EVENT" = close"#;
+        assert!(run_command(check, Event::Close, &bug, &payload).is_ok());
+        assert!(run_command("exit 3", Event::Close, &bug, &payload).is_err());
+    }
+}
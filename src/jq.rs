@@ -0,0 +1,161 @@
+//! `--jq`: run a jq expression over a command's JSON output, e.g.
+//! `detail bugs list --format json --jq '.items[].id'`, without needing a
+//! `jq` binary. Expressions are evaluated by jaq.
+//!
+//! Like `gh --jq`, string results print raw, one per line, so they pipe
+//! straight into other commands; anything else prints as pretty JSON.
+
+use std::iter;
+
+use anyhow::{anyhow, Result};
+use jaq_core::load::{self, Arena, File, Loader};
+use jaq_core::{Compiler, Ctx, Native, RcIter};
+use jaq_json::Val;
+use serde_json::Value;
+
+use crate::exit::Failure;
+
+/// A compiled `--jq` expression.
+pub struct Query {
+    filter: jaq_core::Filter<Native<Val>>,
+}
+
+impl Query {
+    /// Compile `expr` with jq's standard library.
+    pub fn parse(expr: &str) -> Result<Self> {
+        let arena = Arena::default();
+        let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+        let modules = loader
+            .load(
+                &arena,
+                File {
+                    code: expr,
+                    path: (),
+                },
+            )
+            .map_err(|errors| anyhow!("{}", load_error(&errors)))?;
+        let filter = Compiler::default()
+            .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+            .compile(modules)
+            .map_err(|errors| {
+                let names: Vec<&str> = errors
+                    .into_iter()
+                    .flat_map(|(_, undefined)| undefined)
+                    .map(|(name, _)| name)
+                    .collect();
+                anyhow!("undefined {}", names.join(", "))
+            })?;
+        Ok(Self { filter })
+    }
+
+    /// Every result of the expression on `input`, in order.
+    pub fn run(&self, input: Value) -> Result<Vec<Value>> {
+        let inputs = RcIter::new(iter::empty());
+        self.filter
+            .run((Ctx::new([], &inputs), Val::from(input)))
+            .map(|result| {
+                result
+                    .map(Value::from)
+                    .map_err(|e| anyhow!("--jq failed: {e}"))
+            })
+            .collect()
+    }
+
+    /// The results on `input` as printed: raw strings and pretty JSON, one
+    /// result per line, with no trailing newline.
+    pub fn render(&self, input: Value) -> Result<String> {
+        let lines = self
+            .run(input)?
+            .into_iter()
+            .map(|value| match value {
+                Value::String(text) => Ok(text),
+                other => serde_json::to_string_pretty(&other),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(lines.join("\n"))
+    }
+}
+
+/// The first problem in `errors` from loading an expression, e.g.
+/// "expected term at `]`".
+fn load_error(errors: &[(File<&str, ()>, load::Error<&str>)]) -> String {
+    let found = errors.iter().find_map(|(_, error)| match error {
+        load::Error::Lex(errors) => errors
+            .first()
+            .map(|(expected, near)| (expected.as_str(), *near)),
+        load::Error::Parse(errors) => errors
+            .first()
+            .map(|(expected, near)| (expected.as_str(), *near)),
+        load::Error::Io(_) => None,
+    });
+    match found {
+        Some((expected, "")) => format!("expected {expected} at the end"),
+        Some((expected, near)) => {
+            let near: String = near.chars().take(20).collect();
+            format!("expected {expected} at `{near}`")
+        }
+        None => "syntax error".to_string(),
+    }
+}
+
+/// Parse a `--jq` flag value, reporting a bad expression as a usage error.
+pub fn parse_flag(value: Option<&str>) -> Result<Option<Query>> {
+    value
+        .map(|expr| {
+            Query::parse(expr).map_err(|e| {
+                Failure::usage(format!("Invalid --jq expression `{expr}`: {e}")).into()
+            })
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn page() -> Value {
+        json!({
+            "items": [
+                { "id": "bug_1", "title": "Null deref", "isSecurityVulnerability": false },
+                { "id": "bug_2", "title": "SQL injection", "isSecurityVulnerability": true }
+            ],
+            "page": 1,
+            "total": 2,
+            "total_pages": 1
+        })
+    }
+
+    #[test]
+    fn strings_print_raw_one_per_line() {
+        let query = Query::parse(".items[].id").unwrap();
+        assert_eq!(query.render(page()).unwrap(), "bug_1\nbug_2");
+    }
+
+    #[test]
+    fn other_results_print_as_json() {
+        let query = Query::parse("[.items[] | select(.isSecurityVulnerability) | .id]").unwrap();
+        assert_eq!(query.run(page()).unwrap(), vec![json!(["bug_2"])]);
+        let count = Query::parse(".total").unwrap();
+        assert_eq!(count.render(page()).unwrap(), "2");
+    }
+
+    #[test]
+    fn bad_expressions_are_usage_errors() {
+        assert!(parse_flag(None).unwrap().is_none());
+        for expr in [".items[", "no_such_function"] {
+            let err = parse_flag(Some(expr)).err().unwrap();
+            assert!(
+                err.to_string().starts_with("Invalid --jq expression"),
+                "{err}"
+            );
+        }
+    }
+
+    #[test]
+    fn runtime_errors_are_reported() {
+        let query = Query::parse(".total | keys").unwrap();
+        assert!(query.run(page()).is_err());
+    }
+}
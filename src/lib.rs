@@ -17,13 +17,32 @@
     )
 )]
 
-use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use std::env;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use clap::{CommandFactory as _, Parser, Subcommand};
+use serde::{Deserialize, Serialize};
 
 pub mod api;
+pub mod client;
 pub mod commands;
 pub mod config;
+pub mod daemon;
+pub mod events;
+pub mod exit;
+pub mod export;
+pub mod filter;
+pub mod hooks;
+pub mod jq;
+pub mod notify;
 pub mod output;
+pub mod template;
+#[cfg(test)]
+pub mod test_support;
+pub mod text_format;
+pub mod trackers;
 pub mod upgrade;
 pub mod utils;
 
@@ -58,12 +77,104 @@ Add the appropriate line to your shell's startup file:
 SHELL defaults to whatever is detected from $SHELL. Supported shells:
 bash, zsh, fish, elvish, powershell.";
 
+/// Create an authenticated API client from the stored config, for callers
+/// that run before a command line has been parsed (the command palette,
+/// library users). `DETAIL_PROFILE` picks the profile, as for commands.
+pub fn create_client() -> Result<api::client::ApiClient> {
+    if let Some(profile) = config::storage::env_profile() {
+        config::storage::select_profile(&profile);
+    }
+    create_client_with(api::client::HttpOptions::from_config)
+}
+
+/// Create an authenticated API client, with network options derived from
+/// the loaded config by `options`.
+fn create_client_with(
+    options: impl FnOnce(&config::storage::Config) -> api::client::HttpOptions,
+) -> Result<api::client::ApiClient> {
+    let config = config::storage::load_config()?;
+    let options = options(&config);
+    let token =
+        config::storage::api_token(&config)?.with_context(config::storage::no_token_message)?;
+    api::client::ApiClient::new(config.api_url, Some(token), &options)
+}
+
 #[derive(Parser)]
 #[command(name = "detail")]
 #[command(version = VERSION)]
+#[command(disable_help_subcommand = true)]
 #[command(about = "Detail CLI - Manage bugs from your terminal")]
 #[command(long_about = LONG_ABOUT)]
 pub struct Cli {
+    /// Log HTTP requests and response-cache use to stderr (-vv adds request headers, -vvv response headers)
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Append JSON logs of API calls and command outcomes to this file [or `log_file` from config]
+    #[arg(long, value_name = "PATH", global = true)]
+    log_file: Option<PathBuf>,
+
+    /// Append a JSONL record of this run (command, API calls, outcome) to this file, in the stable schema from `detail help agents`
+    #[arg(long, value_name = "PATH", global = true)]
+    events_file: Option<PathBuf>,
+
+    /// Use the login saved under this name in `[profiles.<NAME>]` (log in to it with `detail auth login --profile <NAME>`)
+    #[arg(long, value_name = "NAME", global = true, env = config::storage::PROFILE_ENV)]
+    profile: Option<String>,
+
+    /// Ignore cached repository lists and API responses and fetch fresh data
+    #[arg(long, global = true)]
+    refresh: bool,
+
+    /// Trust the root certificates in this PEM file for API requests [or `ca_cert` from config]
+    #[arg(long, value_name = "PATH", global = true)]
+    cacert: Option<PathBuf>,
+
+    /// DANGEROUS: skip TLS certificate verification for API requests. Anyone
+    /// between you and the API can then read and alter traffic, including your token
+    #[arg(long, global = true)]
+    insecure: bool,
+
+    /// Seconds before an API request times out [default: 30, or `timeout_secs` from config]
+    #[arg(long, value_name = "SECS", global = true, value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: Option<u64>,
+
+    /// API requests in flight at once, for slow networks or strict rate limits [default: 4, or `concurrency` from config]
+    #[arg(long, value_name = "N", global = true, value_parser = clap::value_parser!(u32).range(1..))]
+    concurrency: Option<u32>,
+
+    /// Skip the automatic update check for this run [or set DETAIL_NO_UPDATE=1, or `check_for_updates = false` in config]
+    #[arg(long, global = true)]
+    no_update_check: bool,
+
+    /// Draw tables, rules and charts with plain ASCII characters
+    #[arg(long, global = true)]
+    ascii: bool,
+
+    /// Turn off colors and text styles [or set NO_COLOR=1]
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Lay output out for this many columns instead of the terminal width
+    #[arg(long, value_name = "COLS", global = true, value_parser = clap::value_parser!(u16).range(20..))]
+    width: Option<u16>,
+
+    /// Show creation times as "3 days ago" instead of a date [or `relative_dates` from config]
+    #[arg(long, global = true)]
+    relative_dates: bool,
+
+    /// Show timestamps in this timezone: `local`, `UTC` or an IANA name like `Europe/Berlin` [default: local, or `timezone` from config]
+    #[arg(long, value_name = "ZONE", global = true)]
+    tz: Option<utils::datetime::Timezone>,
+
+    /// Make output snapshot-safe for tests: sort records by id, show dates in UTC rather than relative, and leave out timings and request ids
+    #[arg(long, global = true)]
+    stable_output: bool,
+
+    /// Filter JSON output through a jq expression, e.g. `.items[].id`; strings print raw. Implies `--format json`
+    #[arg(long, value_name = "EXPR", global = true)]
+    jq: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -71,15 +182,31 @@ pub struct Cli {
 impl Cli {
     /// Create an authenticated API client
     pub fn create_client(&self) -> Result<api::client::ApiClient> {
-        let config = config::storage::load_config()?;
-        let token = config
-            .api_token
-            .context("No token found. Run `detail auth login`")?;
-        api::client::ApiClient::new(config.api_url, Some(token))
+        create_client_with(|config| self.http_options(config))
+            .map(|client| client.with_refresh(self.refresh))
     }
 
-    const fn is_json(format: &OutputFormat) -> bool {
-        matches!(format, OutputFormat::Json)
+    /// Whether `--refresh` was passed.
+    pub const fn refresh(&self) -> bool {
+        self.refresh
+    }
+
+    /// Network options from `config`, overridden by `--cacert`, `--insecure`
+    /// and `--timeout`.
+    pub fn http_options(&self, config: &config::storage::Config) -> api::client::HttpOptions {
+        let mut options = api::client::HttpOptions::from_config(config);
+        if let Some(path) = &self.cacert {
+            options.ca_cert = Some(path.clone());
+        }
+        options.insecure = self.insecure;
+        if let Some(secs) = self.timeout {
+            options.timeout = Some(Duration::from_secs(secs));
+        }
+        options
+    }
+
+    const fn is_structured(format: Option<&OutputFormat>) -> bool {
+        matches!(format, Some(OutputFormat::Json | OutputFormat::Yaml))
     }
 
     /// Returns true when machine-readable output is requested (e.g. `--format json`),
@@ -88,22 +215,69 @@ impl Cli {
     const fn is_silent(&self) -> bool {
         match &self.command {
             Commands::Bugs { command } => match command {
-                commands::bugs::BugCommands::List { format, .. }
-                | commands::bugs::BugCommands::Show { format, .. }
-                | commands::bugs::BugCommands::Close { format, .. } => Self::is_json(format),
-                commands::bugs::BugCommands::Reopen { .. } => false,
+                // Text formats like org-mode are meant to be pasted or
+                // piped, so they stay as clean as JSON.
+                commands::bugs::BugCommands::List {
+                    format, ids_only, ..
+                } => {
+                    matches!(
+                        format,
+                        Some(
+                            output::ListFormat::Output(OutputFormat::Json | OutputFormat::Yaml)
+                                | output::ListFormat::Text(_)
+                        )
+                    ) || *ids_only
+                }
+                commands::bugs::BugCommands::Show { format, .. }
+                | commands::bugs::BugCommands::Close { format, .. }
+                | commands::bugs::BugCommands::Reconcile { format, .. }
+                | commands::bugs::BugCommands::ExportIssue { format, .. } => {
+                    Self::is_structured(format.as_ref())
+                }
+                commands::bugs::BugCommands::Export { output, .. }
+                | commands::bugs::BugCommands::Report { output, .. } => output.is_none(),
+                // Printing the patch is meant for piping, e.g. into `git apply`.
+                commands::bugs::BugCommands::Patch { apply, check, .. } => !*apply && !*check,
+                commands::bugs::BugCommands::Reopen { .. }
+                | commands::bugs::BugCommands::Fix { .. }
+                | commands::bugs::BugCommands::Diff { .. }
+                | commands::bugs::BugCommands::Watch { .. } => false,
+                // Meant for cron, which mails any output: only alerts and
+                // digests should be printed.
+                commands::bugs::BugCommands::Alert { .. }
+                | commands::bugs::BugCommands::Digest { .. } => true,
+                commands::bugs::BugCommands::Demo(demo) => match demo {
+                    commands::bugs::DemoCommands::List {
+                        format, ids_only, ..
+                    } => Self::is_structured(format.as_ref()) || *ids_only,
+                    commands::bugs::DemoCommands::Show { format, .. }
+                    | commands::bugs::DemoCommands::Close { format, .. } => {
+                        Self::is_structured(format.as_ref())
+                    }
+                },
+            },
+            Commands::Queue { command } => match command {
+                commands::queue::QueueCommands::List { format } => {
+                    Self::is_structured(format.as_ref())
+                }
+                commands::queue::QueueCommands::Flush
+                | commands::queue::QueueCommands::Drop { .. } => false,
             },
             Commands::Repos { command } => match command {
-                commands::repos::RepoCommands::List { format, .. } => Self::is_json(format),
+                commands::repos::RepoCommands::List { format, .. } => {
+                    Self::is_structured(format.as_ref())
+                }
             },
             Commands::Scans { command } => match command {
-                commands::scans::ScanCommands::List { format, .. } => Self::is_json(format),
+                commands::scans::ScanCommands::List { format, .. } => {
+                    Self::is_structured(format.as_ref())
+                }
             },
             Commands::Rules { command } => match command {
                 commands::rules::RuleCommands::List { format, .. }
                 | commands::rules::RuleCommands::Requests(
                     commands::rules::RuleRequestCommands::List { format, .. },
-                ) => Self::is_json(format),
+                ) => Self::is_structured(format.as_ref()),
                 commands::rules::RuleCommands::Create { .. }
                 | commands::rules::RuleCommands::Propose { .. }
                 | commands::rules::RuleCommands::Requests(_)
@@ -115,23 +289,331 @@ impl Cli {
             // any auto-update notice on stderr would surface on every shell
             // startup — keep this silent.
             Commands::Completions { .. } => true,
+            Commands::Daemon { command } => match command {
+                commands::daemon::DaemonCommands::Status { format } => {
+                    Self::is_structured(format.as_ref())
+                }
+                commands::daemon::DaemonCommands::Run | commands::daemon::DaemonCommands::Stop => {
+                    false
+                }
+            },
+            // Runs on every prompt: the output is the prompt itself.
+            Commands::PromptSegment => true,
+            // The output is a JSON document, typically redirected to a file.
+            Commands::Schema { .. } => true,
+            Commands::Check { format, .. }
+            | Commands::Limits { format }
+            | Commands::Selftest { format }
+            | Commands::Usage { format, .. } => Self::is_structured(format.as_ref()),
             Commands::Auth { .. }
+            | Commands::Help { .. }
+            | Commands::Hook { .. }
+            | Commands::Notify { .. }
             | Commands::SatisfyingSort
             | Commands::Skill { .. }
-            | Commands::Update
+            | Commands::Update { .. }
             | Commands::Version => false,
         }
     }
 
-    const fn should_run_auto_update(&self) -> bool {
-        if self.is_silent() {
+    fn should_run_auto_update(&self) -> bool {
+        if self.is_silent() || self.no_update_check || upgrade::disabled_by_env() {
             return false;
         }
-        !matches!(&self.command, Commands::Update)
+        !matches!(&self.command, Commands::Update { .. })
+    }
+
+    /// The `--format` flag of the command being run, if it has one.
+    fn format_flag_mut(&mut self) -> Option<&mut Option<OutputFormat>> {
+        match &mut self.command {
+            Commands::Bugs { command } => match command {
+                commands::bugs::BugCommands::Show { format, .. }
+                | commands::bugs::BugCommands::Close { format, .. }
+                | commands::bugs::BugCommands::Alert { format, .. }
+                | commands::bugs::BugCommands::Digest { format, .. }
+                | commands::bugs::BugCommands::Reconcile { format, .. }
+                | commands::bugs::BugCommands::ExportIssue { format, .. }
+                | commands::bugs::BugCommands::Demo(
+                    commands::bugs::DemoCommands::List { format, .. }
+                    | commands::bugs::DemoCommands::Show { format, .. }
+                    | commands::bugs::DemoCommands::Close { format, .. },
+                ) => Some(format),
+                commands::bugs::BugCommands::List { .. }
+                | commands::bugs::BugCommands::Reopen { .. }
+                | commands::bugs::BugCommands::Fix { .. }
+                | commands::bugs::BugCommands::Diff { .. }
+                | commands::bugs::BugCommands::Patch { .. }
+                | commands::bugs::BugCommands::Export { .. }
+                | commands::bugs::BugCommands::Report { .. }
+                | commands::bugs::BugCommands::Watch { .. } => None,
+            },
+            Commands::Check { format, .. }
+            | Commands::Limits { format }
+            | Commands::Selftest { format }
+            | Commands::Usage { format, .. } => Some(format),
+            Commands::Daemon { command } => match command {
+                commands::daemon::DaemonCommands::Status { format } => Some(format),
+                commands::daemon::DaemonCommands::Run | commands::daemon::DaemonCommands::Stop => {
+                    None
+                }
+            },
+            Commands::Queue { command } => match command {
+                commands::queue::QueueCommands::List { format } => Some(format),
+                commands::queue::QueueCommands::Flush
+                | commands::queue::QueueCommands::Drop { .. } => None,
+            },
+            Commands::Repos { command } => match command {
+                commands::repos::RepoCommands::List { format, .. } => Some(format),
+            },
+            Commands::Scans { command } => match command {
+                commands::scans::ScanCommands::List { format, .. } => Some(format),
+            },
+            Commands::Rules { command } => match command {
+                commands::rules::RuleCommands::List { format, .. }
+                | commands::rules::RuleCommands::Requests(
+                    commands::rules::RuleRequestCommands::List { format, .. },
+                ) => Some(format),
+                commands::rules::RuleCommands::Create { .. }
+                | commands::rules::RuleCommands::Propose { .. }
+                | commands::rules::RuleCommands::Requests(_)
+                | commands::rules::RuleCommands::Show { .. }
+                | commands::rules::RuleCommands::Pull { .. } => None,
+            },
+            Commands::Auth { .. }
+            | Commands::Completions { .. }
+            | Commands::Help { .. }
+            | Commands::Hook { .. }
+            | Commands::Notify { .. }
+            | Commands::PromptSegment
+            | Commands::SatisfyingSort
+            | Commands::Schema { .. }
+            | Commands::Skill { .. }
+            | Commands::Update { .. }
+            | Commands::Version => None,
+        }
+    }
+
+    /// The explicit repository positional of the command being run, if any.
+    fn repo_arg(&self) -> Option<&str> {
+        match &self.command {
+            Commands::Bugs {
+                command:
+                    commands::bugs::BugCommands::List { repo, .. }
+                    | commands::bugs::BugCommands::Alert { repo, .. }
+                    | commands::bugs::BugCommands::Digest { repo, .. }
+                    | commands::bugs::BugCommands::Reconcile { repo, .. },
+            }
+            | Commands::Check { repo, .. }
+            | Commands::Scans {
+                command: commands::scans::ScanCommands::List { repo, .. },
+            }
+            | Commands::Rules {
+                command:
+                    commands::rules::RuleCommands::List { repo, .. }
+                    | commands::rules::RuleCommands::Requests(
+                        commands::rules::RuleRequestCommands::List { repo, .. },
+                    ),
+            } => repo.as_deref(),
+            Commands::Auth { .. }
+            | Commands::Bugs { .. }
+            | Commands::Completions { .. }
+            | Commands::Daemon { .. }
+            | Commands::Help { .. }
+            | Commands::Hook { .. }
+            | Commands::Limits { .. }
+            | Commands::Notify { .. }
+            | Commands::Queue { .. }
+            | Commands::Repos { .. }
+            | Commands::Rules { .. }
+            | Commands::PromptSegment
+            | Commands::SatisfyingSort
+            | Commands::Scans { .. }
+            | Commands::Schema { .. }
+            | Commands::Selftest { .. }
+            | Commands::Skill { .. }
+            | Commands::Update { .. }
+            | Commands::Usage { .. }
+            | Commands::Version => None,
+        }
+    }
+
+    /// The `--format` flag of a list command that also takes text formats.
+    const fn list_format_flag_mut(&mut self) -> Option<&mut Option<output::ListFormat>> {
+        if let Commands::Bugs {
+            command: commands::bugs::BugCommands::List { format, .. },
+        } = &mut self.command
+        {
+            Some(format)
+        } else {
+            None
+        }
+    }
+
+    /// Fill in `--format` from the config when the flag wasn't passed, so
+    /// `is_silent` and the handlers both see the effective format. A
+    /// per-repo `default_format` wins over the global one; the repo is the
+    /// command's positional or, failing that, the git remote.
+    fn apply_default_format(&mut self) {
+        let repo = self.repo_arg().map(str::to_owned);
+        let unset = self
+            .format_flag_mut()
+            .is_some_and(|format| format.is_none())
+            || self
+                .list_format_flag_mut()
+                .is_some_and(|format| format.is_none());
+        if !unset {
+            return;
+        }
+        let Ok(config) = config::storage::load_config() else {
+            return;
+        };
+        let repo = if repo.is_none() && !config.repos.is_empty() {
+            utils::git::infer_repo_from_git_remote().ok()
+        } else {
+            repo
+        };
+        let default = config.default_format_for(repo.as_deref());
+        if let Some(format) = self.format_flag_mut() {
+            *format = default;
+        } else if let Some(format) = self.list_format_flag_mut() {
+            *format = default.map(output::ListFormat::Output);
+        }
+    }
+
+    /// Whether the command prints JSON, once `--format` has been filled in.
+    fn json_output(&mut self) -> bool {
+        if let Some(format) = self.format_flag_mut() {
+            matches!(format, Some(OutputFormat::Json))
+        } else if let Some(format) = self.list_format_flag_mut() {
+            matches!(format, Some(output::ListFormat::Output(OutputFormat::Json)))
+        } else {
+            false
+        }
+    }
+
+    /// Check `--jq` and switch the command to JSON output for it. Commands
+    /// without JSON output, or run with another explicit `--format`, are a
+    /// usage error.
+    fn apply_jq(&mut self) -> Result<()> {
+        let Some(expr) = self.jq.clone() else {
+            return Ok(());
+        };
+        jq::parse_flag(Some(&expr))?;
+        let json = if let Some(format) = self.format_flag_mut() {
+            matches!(format.get_or_insert(OutputFormat::Json), OutputFormat::Json)
+        } else if let Some(format) = self.list_format_flag_mut() {
+            matches!(
+                format.get_or_insert(output::ListFormat::Output(OutputFormat::Json)),
+                output::ListFormat::Output(OutputFormat::Json)
+            )
+        } else {
+            matches!(self.command, Commands::Schema { .. })
+        };
+        if !json {
+            bail!(exit::Failure::usage(format!(
+                "--jq needs JSON output, which `detail {}` doesn't print with these options",
+                self.command_name()
+            )));
+        }
+        output::set_jq(&expr);
+        Ok(())
+    }
+
+    /// Top-level command name, as recorded in the `--log-file` log.
+    const fn command_name(&self) -> &'static str {
+        match &self.command {
+            Commands::Auth { .. } => "auth",
+            Commands::Bugs { .. } => "bugs",
+            Commands::Check { .. } => "check",
+            Commands::Completions { .. } => "completions",
+            Commands::Daemon { .. } => "daemon",
+            Commands::Help { .. } => "help",
+            Commands::Hook { .. } => "hook",
+            Commands::Limits { .. } => "limits",
+            Commands::Notify { .. } => "notify",
+            Commands::PromptSegment => "prompt-segment",
+            Commands::Rules { .. } => "rules",
+            Commands::SatisfyingSort => "satisfying-sort",
+            Commands::Queue { .. } => "queue",
+            Commands::Repos { .. } => "repos",
+            Commands::Scans { .. } => "scans",
+            Commands::Schema { .. } => "schema",
+            Commands::Selftest { .. } => "selftest",
+            Commands::Skill { .. } => "skill",
+            Commands::Update { .. } => "update",
+            Commands::Usage { .. } => "usage",
+            Commands::Version => "version",
+        }
     }
 
     /// Run the CLI command
-    pub async fn run(self) -> Result<()> {
+    pub async fn run(mut self) -> Result<()> {
+        utils::term_caps::init(utils::term_caps::Overrides {
+            ascii: self.ascii,
+            no_color: self.no_color,
+            width: self.width.map(usize::from),
+        });
+        if let Some(profile) = &self.profile {
+            config::storage::select_profile(profile);
+        }
+        let config = config::storage::load_config().ok();
+        let log_file = self
+            .log_file
+            .clone()
+            .or_else(|| config.as_ref().and_then(|config| config.log_file.clone()));
+        if let Some(limit) = self
+            .concurrency
+            .or_else(|| config.as_ref().and_then(|config| config.concurrency))
+        {
+            utils::concurrency::set_limit(usize::try_from(limit).unwrap_or(usize::MAX));
+        }
+        output::set_stable_output(self.stable_output);
+        // Relative dates and the local zone depend on when and where the
+        // command runs, so stable output pins both.
+        utils::datetime::set_relative_dates(
+            !self.stable_output
+                && (self.relative_dates
+                    || config.as_ref().is_some_and(|config| config.relative_dates)),
+        );
+        if self.stable_output && self.tz.is_none() {
+            utils::datetime::set_timezone(utils::datetime::Timezone::Named(chrono_tz::UTC));
+        }
+        let zone = self.tz.or_else(|| {
+            let zone = config.as_ref()?.timezone.as_deref()?;
+            zone.parse::<utils::datetime::Timezone>()
+                .inspect_err(|err| {
+                    let _ = console::Term::stderr().write_line(&format!(
+                        "{} Ignoring `timezone` in config: {err}",
+                        console::style("Warning:").yellow().bold()
+                    ));
+                })
+                .ok()
+        });
+        if let Some(zone) = zone {
+            utils::datetime::set_timezone(zone);
+        }
+        api::trace::init(self.verbose, log_file.as_deref())?;
+        let args: Vec<String> = env::args_os()
+            .skip(1)
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        if let Some(path) = &self.events_file {
+            events::init(path)?;
+            events::command_started(&Self::command(), &args);
+        }
+        // A bad --jq still fails as JSON when it made the output JSON.
+        let jq = self.apply_jq();
+        self.apply_default_format();
+        output::set_json_errors(self.json_output());
+        jq?;
+
+        if self.insecure {
+            let _ = console::Term::stderr().write_line(&format!(
+                "{} TLS certificate verification is disabled (--insecure).",
+                console::style("Warning:").yellow().bold()
+            ));
+        }
+
         // Skip auto-update when outputting JSON to avoid corrupting structured output
         if self.should_run_auto_update() {
             if let Err(e) = upgrade::auto_update().await {
@@ -140,28 +622,149 @@ impl Cli {
             }
         }
 
+        let started = Instant::now();
+        let mut result = self.dispatch().await;
+        if matches!(&result, Err(err) if api::client::is_unauthorized(err)) {
+            result = self.retry_after_relogin(result).await;
+        }
+        if let Some(org) = config.as_ref().and_then(|config| config.org.as_deref()) {
+            if matches!(&result, Err(err) if api::client::is_forbidden(err)) {
+                result = result.with_context(|| config::storage::pinned_org_message(org));
+            }
+        }
+        api::trace::command_finished(self.command_name(), started, &result);
+        events::command_finished(started, &result);
+        if config.as_ref().is_none_or(|config| config.record_usage) && self.records_usage() {
+            let (requests, from_cache) = api::trace::request_counts();
+            config::usage::append(&config::usage::UsageRecord {
+                command: events::describe_args(&Self::command(), &args).0,
+                finished_at: chrono::Utc::now().timestamp_millis(),
+                duration_ms: events::elapsed_ms(started),
+                succeeded: result.is_ok(),
+                requests,
+                from_cache,
+            });
+        }
+        result
+    }
+
+    /// Whether this run belongs in the `detail usage` log. Prompt segments
+    /// run on every shell prompt and the daemon runs until stopped, so
+    /// either would drown out everything else; `usage` itself would
+    /// recreate the log right after `--reset`.
+    const fn records_usage(&self) -> bool {
+        !matches!(
+            self.command,
+            Commands::PromptSegment
+                | Commands::Usage { .. }
+                | Commands::Daemon {
+                    command: commands::daemon::DaemonCommands::Run
+                }
+        )
+    }
+
+    /// Handle a command the API answered with `401 Unauthorized`: in an
+    /// interactive session, offer to log in again and retry the command
+    /// once; otherwise explain how to re-authenticate.
+    async fn retry_after_relogin(&self, result: Result<()>) -> Result<()> {
+        if matches!(
+            self.command,
+            Commands::Auth { .. } | Commands::PromptSegment
+        ) {
+            return result;
+        }
+        // A new login can't replace a token supplied through the environment.
+        if config::storage::env_token().is_some() {
+            return result.with_context(config::storage::rejected_token_message);
+        }
+        let interactive = console::Term::stdout().is_term() && console::Term::stderr().is_term();
+        if interactive && commands::auth::offer_relogin(self).await? {
+            return self.dispatch().await;
+        }
+        result.with_context(config::storage::rejected_token_message)
+    }
+
+    async fn dispatch(&self) -> Result<()> {
         match &self.command {
-            Commands::Auth { command } => commands::auth::handle(command, &self).await,
-            Commands::Bugs { command } => commands::bugs::handle(command, &self).await,
+            Commands::Auth { command } => commands::auth::handle(command, self).await,
+            Commands::Bugs { command } => commands::bugs::handle(command, self).await,
+            Commands::Check {
+                repo,
+                fail_on,
+                max,
+                filter,
+                sinks,
+                format,
+            } => {
+                let format = &format.unwrap_or_default();
+                let options = commands::check::CheckOptions {
+                    fail_on: *fail_on,
+                    max: *max,
+                    filter: filter.as_deref(),
+                    sinks,
+                };
+                commands::check::handle(repo.as_deref(), &options, format, self).await
+            }
             Commands::Completions { shell } => commands::completions::handle(shell.as_deref()),
-            Commands::Rules { command } => commands::rules::handle(command, &self).await,
+            Commands::Daemon { command } => commands::daemon::handle(command, self).await,
+            Commands::Help { topic } => commands::help::handle(topic),
+            Commands::Hook { command } => commands::hook::handle(command),
+            Commands::Limits { format } => {
+                let format = &format.unwrap_or_default();
+                commands::limits::handle(format, self).await
+            }
+            Commands::Notify {
+                message,
+                title,
+                sinks,
+                dry_run,
+            } => commands::notify::handle(message.as_deref(), title, sinks, *dry_run, self).await,
+            Commands::PromptSegment => commands::prompt_segment::handle(self).await,
+            Commands::Rules { command } => commands::rules::handle(command, self).await,
             Commands::SatisfyingSort => commands::satisfying_sort::handle().await,
-            Commands::Repos { command } => commands::repos::handle(command, &self).await,
-            Commands::Scans { command } => commands::scans::handle(command, &self).await,
-            Commands::Skill { command } => commands::skill::handle(command.as_ref()),
-            Commands::Update => commands::update::handle().await,
+            Commands::Queue { command } => commands::queue::handle(command, self).await,
+            Commands::Repos { command } => commands::repos::handle(command, self).await,
+            Commands::Scans { command } => commands::scans::handle(command, self).await,
+            Commands::Schema { kind } => commands::schema::handle(*kind),
+            Commands::Selftest { format } => {
+                let format = &format.unwrap_or_default();
+                commands::selftest::handle(format, self).await
+            }
+            Commands::Skill {
+                command,
+                agent,
+                global,
+            } => commands::skill::handle(command.as_ref(), *agent, *global),
+            Commands::Update {
+                version,
+                check,
+                allow_unverified,
+            } => {
+                if *check {
+                    commands::update::check().await
+                } else {
+                    commands::update::handle(version.as_deref(), *allow_unverified).await
+                }
+            }
+            Commands::Usage { reset, format } => {
+                let format = &format.unwrap_or_default();
+                commands::usage::handle(*reset, format)
+            }
             Commands::Version => {
-                console::Term::stdout().write_line(&format!("detail-cli v{VERSION}"))?;
+                output::stdout().write_line(&format!("detail-cli v{VERSION}"))?;
                 Ok(())
             }
         }
     }
 }
 
-#[derive(Clone, clap::ValueEnum)]
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
+    #[default]
     Table,
     Json,
+    Yaml,
 }
 
 #[derive(Subcommand)]
@@ -178,6 +781,35 @@ enum Commands {
         command: commands::bugs::BugCommands,
     },
 
+    /// Fail when a repository has more pending bugs than allowed (for CI)
+    Check {
+        /// Repository in owner/repo format or just repo name.
+        /// If omitted, inferred from the git remote (origin).
+        repo: Option<String>,
+
+        /// Which pending bugs count towards the threshold
+        #[arg(long, value_enum, default_value = "pending")]
+        fail_on: commands::check::FailOn,
+
+        /// Highest count that still passes
+        #[arg(long, default_value = "0")]
+        max: usize,
+
+        /// Only count pending bugs matching this expression, e.g.
+        /// `path:src/** && age>7d`. See `detail help filters`.
+        #[arg(long, value_name = "EXPR")]
+        filter: Option<String>,
+
+        /// Notification sink from [notifications] in config to tell when the
+        /// check fails; repeat for several
+        #[arg(long = "sink", value_name = "NAME")]
+        sinks: Vec<String>,
+
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+
     /// Print shell completion script to stdout
     #[command(long_about = COMPLETIONS_LONG_ABOUT)]
     Completions {
@@ -185,6 +817,58 @@ enum Commands {
         shell: Option<String>,
     },
 
+    /// Keep API responses warm in a background daemon that other commands
+    /// (and editors) query over a local socket before calling the API
+    Daemon {
+        #[command(subcommand)]
+        command: commands::daemon::DaemonCommands,
+    },
+
+    /// Show help for a command, or a guide (`detail help topics` lists them)
+    Help {
+        /// A guide (auth, ci-gating, exports, filters, agents) or a command path
+        /// (e.g. `bugs list`)
+        topic: Vec<String>,
+    },
+
+    /// Install a git pre-push hook that runs `detail check` before each push
+    Hook {
+        #[command(subcommand)]
+        command: commands::hook::HookCommands,
+    },
+
+    /// Show the API rate-limit quota for the current token
+    Limits {
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    /// Send a message to the notification sinks under [notifications] in
+    /// config — the plumbing behind `--sink` on watch, alert and check
+    Notify {
+        /// Message body, or `-` (or nothing) to read it from stdin
+        message: Option<String>,
+
+        /// Message title: the Slack heading, email subject or `{title}` in
+        /// templates
+        #[arg(long, default_value = "Detail")]
+        title: String,
+
+        /// Sink to send to, by name; repeat for several [default: all sinks]
+        #[arg(long = "sink", value_name = "NAME")]
+        sinks: Vec<String>,
+
+        /// Print what each sink would be sent instead of sending it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Print a compact pending-bug summary for the current repo, for shell
+    /// prompts. Reads a local cache only and refreshes it in the background
+    #[command(name = "prompt-segment")]
+    PromptSegment,
+
     /// Create and inspect rules
     Rules {
         #[command(subcommand)]
@@ -195,6 +879,12 @@ enum Commands {
     #[command(name = "satisfying-sort")]
     SatisfyingSort,
 
+    /// Inspect and replay bug reviews queued while offline
+    Queue {
+        #[command(subcommand)]
+        command: commands::queue::QueueCommands,
+    },
+
     /// Manage repos tracked with Detail
     Repos {
         #[command(subcommand)]
@@ -207,14 +897,67 @@ enum Commands {
         command: commands::scans::ScanCommands,
     },
 
+    /// Print the JSON Schema of what `--format json` outputs
+    Schema {
+        /// Output to describe [default: all, keyed by name]
+        #[arg(value_enum)]
+        kind: Option<commands::schema::SchemaKind>,
+    },
+
+    /// Check that the CLI works end to end against the configured API
+    Selftest {
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+
     /// Install Detail skills (default: detail-bugs)
     Skill {
         #[command(subcommand)]
         command: Option<commands::skill::SkillCommands>,
+
+        /// Coding agent to install for: a Claude skill, a Cursor rule, or a
+        /// section of .github/copilot-instructions.md (copilot) or AGENTS.md
+        /// (codex)
+        #[arg(long, value_enum, default_value = "claude", global = true)]
+        agent: commands::skill::Agent,
+
+        /// Install for your user (e.g. ~/.claude/skills/) instead of the
+        /// current repository; works outside a git repository
+        #[arg(long, global = true)]
+        global: bool,
     },
 
     /// Update immediately (auto-update also runs in the background)
-    Update,
+    #[command(visible_alias = "upgrade")]
+    Update {
+        /// Install this release (x.y.z) instead of the newest one, e.g. to
+        /// roll back
+        #[arg(long, value_name = "X.Y.Z")]
+        version: Option<String>,
+
+        /// Only report the current and latest versions; exit with status 5
+        /// when an update is available
+        #[arg(long, conflicts_with_all = ["version", "allow_unverified"])]
+        check: bool,
+
+        /// Install a release even if it publishes no checksum to verify
+        /// its installer against
+        #[arg(long)]
+        allow_unverified: bool,
+    },
+
+    /// Show how you use the CLI: most-run commands, average latency and
+    /// cache savings, from a log kept on this machine only
+    Usage {
+        /// Delete the usage log
+        #[arg(long, conflicts_with = "format")]
+        reset: bool,
+
+        /// Output format [default: table, or `default_format` from config]
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+    },
 
     /// Show version information
     Version,
@@ -254,6 +997,14 @@ mod tests {
         assert!(cli.is_silent());
     }
 
+    #[test]
+    fn silent_when_daemon_status_json() {
+        let cli = Cli::try_parse_from(["detail", "daemon", "status", "--format", "json"]).unwrap();
+        assert!(cli.is_silent());
+        let cli = Cli::try_parse_from(["detail", "daemon", "run"]).unwrap();
+        assert!(!cli.is_silent());
+    }
+
     #[test]
     fn not_silent_when_rules_list_table() {
         let cli =
@@ -429,12 +1180,51 @@ mod tests {
         assert!(!cli.is_silent());
     }
 
+    #[test]
+    fn upgrade_is_an_alias_for_update() {
+        let cli = Cli::try_parse_from(["detail", "upgrade", "--version", "1.2.3"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Update { version: Some(ref v), .. } if v == "1.2.3"
+        ));
+    }
+
+    #[test]
+    fn update_check_conflicts_with_version() {
+        let cli = Cli::try_parse_from(["detail", "update", "--check", "--version", "1.2.3"]);
+        assert!(cli.is_err());
+    }
+
     #[test]
     fn auto_update_disabled_for_update_command() {
         let cli = Cli::try_parse_from(["detail", "update"]).unwrap();
         assert!(!cli.should_run_auto_update());
     }
 
+    #[test]
+    fn usage_log_skips_prompt_segments_and_itself() {
+        let cli = Cli::try_parse_from(["detail", "bugs", "list", "owner/repo"]).unwrap();
+        assert!(cli.records_usage());
+        let cli = Cli::try_parse_from(["detail", "prompt-segment"]).unwrap();
+        assert!(!cli.records_usage());
+        let cli = Cli::try_parse_from(["detail", "usage", "--reset"]).unwrap();
+        assert!(!cli.records_usage());
+    }
+
+    #[test]
+    fn auto_update_disabled_by_flag() {
+        let cli = Cli::try_parse_from(["detail", "bugs", "list", "--no-update-check"]).unwrap();
+        assert!(!cli.should_run_auto_update());
+    }
+
+    #[test]
+    fn rejects_output_width_too_narrow_to_lay_out() {
+        let cli = Cli::try_parse_from(["detail", "repos", "list", "--width", "10"]);
+        assert!(cli.is_err());
+        let cli = Cli::try_parse_from(["detail", "repos", "list", "--width", "100", "--ascii"]);
+        assert!(cli.is_ok_and(|cli| cli.ascii && cli.width == Some(100)));
+    }
+
     #[test]
     fn rejects_bugs_list_limit_above_api_max() {
         let cli = Cli::try_parse_from(["detail", "bugs", "list", "owner/repo", "--limit", "101"]);
@@ -527,6 +1317,20 @@ mod tests {
         assert!(cli.is_err());
     }
 
+    #[test]
+    fn bugs_list_mine_conflicts_with_introduced_by() {
+        assert!(Cli::try_parse_from(["detail", "bugs", "list", "--mine"]).is_ok());
+        let cli = Cli::try_parse_from([
+            "detail",
+            "bugs",
+            "list",
+            "--mine",
+            "--introduced-by",
+            "alice",
+        ]);
+        assert!(cli.is_err());
+    }
+
     #[test]
     fn bugs_list_all_flag_parses() {
         let cli = Cli::try_parse_from(["detail", "bugs", "list", "owner/repo", "--all"]).unwrap();
@@ -583,6 +1387,188 @@ mod tests {
         assert!(cli.is_err());
     }
 
+    #[test]
+    fn bugs_list_ids_only_is_silent() {
+        let cli =
+            Cli::try_parse_from(["detail", "bugs", "list", "owner/repo", "--ids-only"]).unwrap();
+        assert!(cli.is_silent());
+    }
+
+    #[test]
+    fn bugs_list_ids_only_conflicts_with_format() {
+        let cli = Cli::try_parse_from([
+            "detail",
+            "bugs",
+            "list",
+            "owner/repo",
+            "--ids-only",
+            "--format",
+            "json",
+        ]);
+        assert!(cli.is_err());
+    }
+
+    #[test]
+    fn bugs_close_accepts_stdin_marker() {
+        let cli = Cli::try_parse_from([
+            "detail",
+            "bugs",
+            "close",
+            "-",
+            "--state",
+            "dismissed",
+            "--dismissal-reason",
+            "wont-fix",
+        ])
+        .unwrap();
+        if let Commands::Bugs {
+            command: commands::bugs::BugCommands::Close { bug_id, .. },
+        } = &cli.command
+        {
+            assert_eq!(bug_id, "-");
+        } else {
+            panic!("expected bugs close command");
+        }
+    }
+
+    #[test]
+    fn tls_flags_override_config() {
+        let cli = Cli::try_parse_from([
+            "detail",
+            "repos",
+            "list",
+            "--cacert",
+            "/etc/ssl/corp.pem",
+            "--insecure",
+        ])
+        .unwrap();
+        let config = config::storage::Config {
+            ca_cert: Some(PathBuf::from("/etc/ssl/other.pem")),
+            ..config::storage::Config::default()
+        };
+        let options = cli.http_options(&config);
+        assert_eq!(options.ca_cert, Some(PathBuf::from("/etc/ssl/corp.pem")));
+        assert!(options.insecure);
+    }
+
+    #[test]
+    fn timeout_flag_overrides_config() {
+        let config = config::storage::Config {
+            timeout_secs: Some(120),
+            connect_timeout_secs: Some(5),
+            ..config::storage::Config::default()
+        };
+        let cli = Cli::try_parse_from(["detail", "repos", "list"]).unwrap();
+        assert_eq!(
+            cli.http_options(&config).timeout,
+            Some(Duration::from_secs(120))
+        );
+        let cli = Cli::try_parse_from(["detail", "--timeout", "10", "repos", "list"]).unwrap();
+        let options = cli.http_options(&config);
+        assert_eq!(options.timeout, Some(Duration::from_secs(10)));
+        assert_eq!(options.connect_timeout, Some(Duration::from_secs(5)));
+        assert!(Cli::try_parse_from(["detail", "--timeout", "0", "version"]).is_err());
+    }
+
+    #[test]
+    fn profile_is_global() {
+        let cli = Cli::try_parse_from(["detail", "auth", "login", "--profile", "work"]).unwrap();
+        assert_eq!(cli.profile.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn verbose_is_global_and_stackable() {
+        let cli = Cli::try_parse_from(["detail", "bugs", "list", "-vv"]).unwrap();
+        assert_eq!(cli.verbose, 2);
+        let cli = Cli::try_parse_from(["detail", "--verbose", "repos", "list"]).unwrap();
+        assert_eq!(cli.verbose, 1);
+    }
+
+    #[test]
+    fn log_file_is_global() {
+        let cli =
+            Cli::try_parse_from(["detail", "repos", "list", "--log-file", "audit.jsonl"]).unwrap();
+        assert_eq!(cli.log_file, Some(PathBuf::from("audit.jsonl")));
+    }
+
+    #[test]
+    fn events_file_is_global() {
+        let cli = Cli::try_parse_from(["detail", "bugs", "list", "--events-file", "events.jsonl"])
+            .unwrap();
+        assert_eq!(cli.events_file, Some(PathBuf::from("events.jsonl")));
+    }
+
+    #[test]
+    fn check_defaults_to_zero_pending() {
+        let cli = Cli::try_parse_from(["detail", "check", "usedetail/cli"]).unwrap();
+        if let Commands::Check { fail_on, max, .. } = &cli.command {
+            assert!(matches!(fail_on, commands::check::FailOn::Pending));
+            assert_eq!(*max, 0);
+        } else {
+            panic!("expected check command");
+        }
+    }
+
+    #[test]
+    fn queue_drop_requires_key_or_all() {
+        assert!(Cli::try_parse_from(["detail", "queue", "drop"]).is_err());
+        assert!(Cli::try_parse_from(["detail", "queue", "drop", "q_1", "--all"]).is_err());
+        assert!(Cli::try_parse_from(["detail", "queue", "drop", "q_1"]).is_ok());
+        assert!(Cli::try_parse_from(["detail", "queue", "drop", "--all"]).is_ok());
+    }
+
+    #[test]
+    fn bugs_close_dry_run_parses() {
+        let cli = Cli::try_parse_from([
+            "detail",
+            "bugs",
+            "close",
+            "bug_123",
+            "--state",
+            "resolved",
+            "--dry-run",
+        ])
+        .unwrap();
+        if let Commands::Bugs {
+            command: commands::bugs::BugCommands::Close { dry_run, .. },
+        } = &cli.command
+        {
+            assert!(*dry_run);
+        } else {
+            panic!("expected bugs close command");
+        }
+    }
+
+    #[test]
+    fn bugs_export_to_stdout_is_silent() {
+        let cli =
+            Cli::try_parse_from(["detail", "bugs", "export", "owner/repo", "--format", "csv"])
+                .unwrap();
+        assert!(cli.is_silent());
+    }
+
+    #[test]
+    fn bugs_export_to_file_is_not_silent() {
+        let cli = Cli::try_parse_from([
+            "detail",
+            "bugs",
+            "export",
+            "owner/repo",
+            "--format",
+            "sarif",
+            "--output",
+            "bugs.sarif",
+        ])
+        .unwrap();
+        assert!(!cli.is_silent());
+    }
+
+    #[test]
+    fn bugs_export_rejects_unknown_format() {
+        let cli = Cli::try_parse_from(["detail", "bugs", "export", "--format", "xml"]);
+        assert!(cli.is_err());
+    }
+
     #[test]
     fn bugs_list_scan_id_parses() {
         let cli = Cli::try_parse_from([
@@ -1,17 +1,44 @@
 use clap::{CommandFactory, Parser};
 use clap_complete::CompleteEnv;
 use console::Term;
+use detail_cli::commands::palette;
+use detail_cli::exit;
+use detail_cli::output;
 use std::process::ExitCode;
 
+fn report_error(err: &anyhow::Error) -> ExitCode {
+    let printed = output::json_errors()
+        && !exit::is_outcome(err)
+        && serde_json::to_string_pretty(&exit::error_document(err))
+            .is_ok_and(|json| output::write_stdout(&format!("{json}\n")).is_ok());
+    if !printed {
+        let _ = Term::stderr().write_line(&format!("Error: {err:#}"));
+    }
+    ExitCode::from(exit::code(err))
+}
+
+async fn run() -> ExitCode {
+    let cli = if palette::should_open() {
+        match palette::choose().await {
+            Ok(Some(args)) => detail_cli::Cli::parse_from(args),
+            Ok(None) => return ExitCode::SUCCESS,
+            Err(err) => return report_error(&err),
+        }
+    } else {
+        detail_cli::Cli::parse()
+    };
+
+    match cli.run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => report_error(&err),
+    }
+}
+
 #[tokio::main]
 async fn main() -> ExitCode {
     CompleteEnv::with_factory(detail_cli::Cli::command).complete();
 
-    match detail_cli::Cli::parse().run().await {
-        Ok(()) => ExitCode::SUCCESS,
-        Err(err) => {
-            let _ = Term::stderr().write_line(&format!("Error: {err:#}"));
-            ExitCode::FAILURE
-        }
-    }
+    let code = run().await;
+    output::finish_stdout();
+    code
 }
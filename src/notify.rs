@@ -0,0 +1,438 @@
+//! Notification sinks: where `detail notify`, `bugs watch --sink`,
+//! `bugs alert --sink` and `check --sink` deliver messages. `bugs close`
+//! and `bugs digest` post to the single `slack_webhook_url` instead, with
+//! `--notify-slack`.
+//!
+//! Sinks are named under `[notifications]` in config.toml:
+//!
+//! ```toml
+//! [notifications.team-chat]
+//! type = "slack"
+//! url = "https://hooks.slack.com/services/…"
+//!
+//! [notifications.oncall]
+//! type = "webhook"
+//! url = "https://oncall.example.com/hooks/detail"
+//! template = '{"summary": "{title}", "details": "{body}"}'
+//!
+//! [notifications.leads]
+//! type = "email"
+//! to = ["leads@example.com"]
+//! ```
+
+use std::collections::BTreeMap;
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::api::client::{post_json, HttpOptions};
+use crate::config::storage::load_config;
+use crate::exit::Failure;
+use crate::utils::text::truncate_display;
+
+/// Mail transfer program used when an email sink doesn't set `command`.
+const DEFAULT_SENDMAIL: &str = "sendmail";
+
+/// Slack's limits on header and section block text, in characters.
+const SLACK_HEADER_MAX: usize = 150;
+const SLACK_SECTION_MAX: usize = 3000;
+
+/// One `[notifications.<name>]` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Sink {
+    /// A Slack incoming webhook. `template` is the message text, in Slack's
+    /// mrkdwn.
+    Slack {
+        url: String,
+        template: Option<String>,
+    },
+    /// Any endpoint that takes a JSON POST. `template` is the JSON document
+    /// to send; placeholders are filled in inside its strings.
+    Webhook {
+        url: String,
+        template: Option<String>,
+    },
+    /// Mail handed to `sendmail -t` (or `command`). `template` is the body.
+    Email {
+        to: Vec<String>,
+        from: Option<String>,
+        command: Option<String>,
+        template: Option<String>,
+    },
+}
+
+/// A message to deliver. Templates refer to its fields as `{title}`,
+/// `{body}` and `{source}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// The command sending it, e.g. `bugs alert`.
+    pub source: String,
+    pub title: String,
+    /// Plain text; lines are kept.
+    pub body: String,
+}
+
+impl Message {
+    /// Fill in `{title}`, `{body}` and `{source}` in `template`.
+    fn fill(&self, template: &str) -> String {
+        template
+            .replace("{title}", &self.title)
+            .replace("{body}", &self.body)
+            .replace("{source}", &self.source)
+    }
+}
+
+/// What a sink is sent, once its template is applied.
+#[derive(Debug, PartialEq)]
+pub enum Payload {
+    Json(Value),
+    /// A complete message with headers, as `sendmail -t` reads it.
+    Mail(String),
+}
+
+/// Fill placeholders in every string of `value`, so substituted text is
+/// escaped by the JSON encoder rather than spliced into the document.
+fn fill_strings(value: Value, message: &Message) -> Value {
+    match value {
+        Value::String(s) => Value::String(message.fill(&s)),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| fill_strings(item, message))
+                .collect(),
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, item)| (key, fill_strings(item, message)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Render `message` for `sink`.
+pub fn payload(sink: &Sink, message: &Message) -> Result<Payload> {
+    match sink {
+        Sink::Slack { template, .. } => {
+            let text = template.as_deref().map_or_else(
+                || format!("*{}*\n{}", message.title, message.body),
+                |template| message.fill(template),
+            );
+            Ok(Payload::Json(json!({ "text": text })))
+        }
+        Sink::Webhook { template, .. } => {
+            let Some(template) = template else {
+                return Ok(Payload::Json(json!({
+                    "source": message.source,
+                    "title": message.title,
+                    "body": message.body,
+                })));
+            };
+            let document =
+                serde_json::from_str(template).context("Webhook template is not valid JSON")?;
+            Ok(Payload::Json(fill_strings(document, message)))
+        }
+        Sink::Email {
+            to, from, template, ..
+        } => {
+            if to.is_empty() {
+                bail!("Email sink has no recipients in `to`");
+            }
+            let body = template
+                .as_deref()
+                .map_or_else(|| message.body.clone(), |template| message.fill(template));
+            let from = from
+                .as_deref()
+                .map(|from| format!("From: {from}\n"))
+                .unwrap_or_default();
+            // Header values are single lines; a multi-line title would
+            // otherwise start the body early.
+            let subject = message.title.lines().next().unwrap_or_default();
+            Ok(Payload::Mail(format!(
+                "{from}To: {}\nSubject: {subject}\nContent-Type: text/plain; charset=utf-8\n\n{body}\n",
+                to.join(", ")
+            )))
+        }
+    }
+}
+
+/// Pipe `mail` to `command -t`, which reads the recipients from the headers.
+fn sendmail(command: &str, mail: &str) -> Result<()> {
+    let mut child = Command::new(command)
+        .arg("-t")
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run `{command}`"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(mail.as_bytes())
+            .with_context(|| format!("Failed to pass the message to `{command}`"))?;
+    }
+    let status = child
+        .wait()
+        .with_context(|| format!("Failed to run `{command}`"))?;
+    if !status.success() {
+        bail!("`{command}` exited with {status}");
+    }
+    Ok(())
+}
+
+/// Deliver `message` through `sink`.
+pub async fn deliver(sink: &Sink, message: &Message, http: &HttpOptions) -> Result<()> {
+    match (sink, payload(sink, message)?) {
+        (Sink::Slack { url, .. } | Sink::Webhook { url, .. }, Payload::Json(body)) => {
+            post_json(url, &body, http).await
+        }
+        (Sink::Email { command, .. }, Payload::Mail(mail)) => {
+            sendmail(command.as_deref().unwrap_or(DEFAULT_SENDMAIL), &mail)
+        }
+        _ => bail!("Sink and payload don't match"),
+    }
+}
+
+/// The sinks called `names`, or every configured sink when `names` is
+/// empty.
+pub fn select<'a>(
+    sinks: &'a BTreeMap<String, Sink>,
+    names: &'a [String],
+) -> Result<Vec<(&'a str, &'a Sink)>> {
+    if sinks.is_empty() {
+        bail!(Failure::not_found(
+            "No notification sinks configured. Define one under [notifications.<name>] in config.toml."
+        ));
+    }
+    if names.is_empty() {
+        return Ok(sinks
+            .iter()
+            .map(|(name, sink)| (name.as_str(), sink))
+            .collect());
+    }
+    names
+        .iter()
+        .map(|name| match sinks.get(name) {
+            Some(sink) => Ok((name.as_str(), sink)),
+            None => {
+                let known: Vec<&str> = sinks.keys().map(String::as_str).collect();
+                bail!(Failure::not_found(format!(
+                    "Unknown notification sink '{name}'. Known sinks: {}",
+                    known.join(", ")
+                )))
+            }
+        })
+        .collect()
+}
+
+/// Deliver `message` to the sinks called `names` (every sink when empty).
+/// Each sink is tried even if an earlier one fails; the error names every
+/// sink that failed.
+pub async fn send(names: &[String], message: &Message, cli: &crate::Cli) -> Result<()> {
+    let config = load_config()?;
+    let http = cli.http_options(&config);
+    let mut failed = Vec::new();
+    for (name, sink) in select(&config.notifications, names)? {
+        if let Err(e) = deliver(sink, message, &http).await {
+            failed.push(format!("{name}: {e:#}"));
+        }
+    }
+    if !failed.is_empty() {
+        bail!("Failed to notify {}", failed.join("; "));
+    }
+    Ok(())
+}
+
+/// `message` as Slack blocks: the title as a header, the body as a
+/// section, and the sending command as context. `text` is the fallback
+/// shown in notifications.
+pub fn slack_blocks(message: &Message) -> Value {
+    json!({
+        "text": message.title,
+        "blocks": [
+            {
+                "type": "header",
+                "text": {
+                    "type": "plain_text",
+                    "text": truncate_display(&message.title, SLACK_HEADER_MAX),
+                },
+            },
+            {
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": truncate_display(&message.body, SLACK_SECTION_MAX),
+                },
+            },
+            {
+                "type": "context",
+                "elements": [
+                    { "type": "mrkdwn", "text": format!("Sent by `detail {}`", message.source) },
+                ],
+            },
+        ],
+    })
+}
+
+/// `slack_webhook_url` from config, for `--notify-slack`. Checked before the
+/// command acts, so a missing URL doesn't surface only after a close.
+pub fn slack_webhook_url() -> Result<String> {
+    let Some(url) = load_config()?.slack_webhook_url else {
+        bail!(Failure::usage(
+            "--notify-slack needs `slack_webhook_url` in config.toml"
+        ));
+    };
+    Ok(url)
+}
+
+/// Post `message` to the Slack webhook at `url` as blocks.
+pub async fn post_slack(url: &str, message: &Message, cli: &crate::Cli) -> Result<()> {
+    let config = load_config()?;
+    post_json(url, &slack_blocks(message), &cli.http_options(&config))
+        .await
+        .context("Failed to notify Slack")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> Message {
+        Message {
+            source: "bugs alert".into(),
+            title: "acme/api: 12 pending bugs".into(),
+            body: "⚠ acme/api: 12 pending bugs, above 10\n\"quoted\"".into(),
+        }
+    }
+
+    #[test]
+    fn sinks_parse_from_toml() {
+        let sinks: BTreeMap<String, Sink> = toml::from_str(
+            r#"
+            [chat]
+            type = "slack"
+            url = "https://hooks.slack.com/services/T/B/X"
+
+            [leads]
+            type = "email"
+            to = ["leads@example.com"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            sinks["chat"],
+            Sink::Slack {
+                url: "https://hooks.slack.com/services/T/B/X".into(),
+                template: None,
+            }
+        );
+        assert!(matches!(&sinks["leads"], Sink::Email { to, .. } if to.len() == 1));
+    }
+
+    #[test]
+    fn slack_defaults_to_a_bold_title() {
+        let sink = Sink::Slack {
+            url: String::new(),
+            template: None,
+        };
+        assert_eq!(
+            payload(&sink, &message()).unwrap(),
+            Payload::Json(json!({
+                "text": "*acme/api: 12 pending bugs*\n⚠ acme/api: 12 pending bugs, above 10\n\"quoted\""
+            }))
+        );
+    }
+
+    #[test]
+    fn slack_blocks_carry_title_body_and_source() {
+        let blocks = slack_blocks(&message());
+        assert_eq!(blocks["text"], "acme/api: 12 pending bugs");
+        assert_eq!(
+            blocks["blocks"][0]["text"]["text"],
+            "acme/api: 12 pending bugs"
+        );
+        assert_eq!(
+            blocks["blocks"][1]["text"]["text"],
+            "⚠ acme/api: 12 pending bugs, above 10\n\"quoted\""
+        );
+        assert_eq!(
+            blocks["blocks"][2]["elements"][0]["text"],
+            "Sent by `detail bugs alert`"
+        );
+
+        let long = Message {
+            title: "t".repeat(200),
+            ..message()
+        };
+        let header = slack_blocks(&long)["blocks"][0]["text"]["text"].clone();
+        assert_eq!(header.as_str().unwrap().chars().count(), SLACK_HEADER_MAX);
+    }
+
+    #[test]
+    fn webhook_templates_fill_strings_and_keep_json_valid() {
+        let sink = Sink::Webhook {
+            url: String::new(),
+            template: Some(
+                r#"{"summary": "[{source}] {title}", "details": ["{body}"], "n": 1}"#.into(),
+            ),
+        };
+        assert_eq!(
+            payload(&sink, &message()).unwrap(),
+            Payload::Json(json!({
+                "summary": "[bugs alert] acme/api: 12 pending bugs",
+                "details": ["⚠ acme/api: 12 pending bugs, above 10\n\"quoted\""],
+                "n": 1,
+            }))
+        );
+
+        let broken = Sink::Webhook {
+            url: String::new(),
+            template: Some("{title}".into()),
+        };
+        assert!(payload(&broken, &message()).is_err());
+    }
+
+    #[test]
+    fn email_carries_headers_and_body() {
+        let sink = Sink::Email {
+            to: vec!["a@example.com".into(), "b@example.com".into()],
+            from: Some("detail@example.com".into()),
+            command: None,
+            template: Some("From {source}:\n{body}".into()),
+        };
+        let Payload::Mail(mail) = payload(&sink, &message()).unwrap() else {
+            panic!("expected mail");
+        };
+        assert!(mail.starts_with(
+            "From: detail@example.com\nTo: a@example.com, b@example.com\n\
+             Subject: acme/api: 12 pending bugs\n"
+        ));
+        assert!(mail.contains("\n\nFrom bugs alert:\n⚠ acme/api"));
+    }
+
+    #[test]
+    fn select_by_name_or_all() {
+        let sinks: BTreeMap<String, Sink> = [
+            ("a", "https://a.example.com"),
+            ("b", "https://b.example.com"),
+        ]
+        .into_iter()
+        .map(|(name, url)| {
+            let sink = Sink::Webhook {
+                url: url.into(),
+                template: None,
+            };
+            (name.to_string(), sink)
+        })
+        .collect();
+        assert_eq!(select(&sinks, &[]).unwrap().len(), 2);
+        let names = ["b".to_string()];
+        assert_eq!(select(&sinks, &names).unwrap()[0].0, "b");
+        let unknown = ["c".to_string()];
+        let err = select(&sinks, &unknown).unwrap_err();
+        assert!(err.to_string().contains("Known sinks: a, b"));
+        assert!(select(&BTreeMap::new(), &[]).is_err());
+    }
+}
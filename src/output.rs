@@ -1,15 +1,26 @@
 //! CLI output formatting utilities
 
+use std::collections::BTreeMap;
 use std::fmt::Display;
-use std::io::Write as _;
-use std::sync::LazyLock;
+use std::io::{self, BufWriter, ErrorKind, IsTerminal as _, Write};
+use std::path::Path;
+use std::sync::{LazyLock, Mutex, OnceLock, PoisonError};
 
 use anyhow::Result;
+use clap::builder::PossibleValue;
 use console::{style, Term};
+use reqwest::Url;
+use schemars::JsonSchema;
 use serde::Serialize;
+use serde_json::Value;
 use termimad::crossterm::style::Attribute;
 
+use crate::api::types::Bug;
+use crate::jq::Query;
+use crate::text_format::{TextFormatter, TextTable, FORMATTERS};
 use crate::utils::pagination::page_to_offset;
+use crate::utils::term_caps::{self, ColorDepth, TermCaps};
+use crate::utils::text::truncate_display;
 
 static MARKDOWN_SKIN: LazyLock<termimad::MadSkin> = LazyLock::new(|| {
     let mut skin = termimad::MadSkin::default();
@@ -22,6 +33,19 @@ static MARKDOWN_SKIN: LazyLock<termimad::MadSkin> = LazyLock::new(|| {
     skin
 });
 
+/// Markdown rendering for terminals without color: layout only.
+static PLAIN_SKIN: LazyLock<termimad::MadSkin> = LazyLock::new(termimad::MadSkin::no_style);
+
+/// Render markdown with the skin and width `caps` allow.
+fn markdown_text(text: &str, caps: &TermCaps) -> String {
+    let skin = if caps.color == ColorDepth::None {
+        &PLAIN_SKIN
+    } else {
+        &MARKDOWN_SKIN
+    };
+    skin.text(text, Some(caps.layout_width())).to_string()
+}
+
 enum SectionContent {
     KeyValue(Vec<(String, String)>, usize),
     Markdown(String),
@@ -29,7 +53,8 @@ enum SectionContent {
 
 /// Renders detail views as sections with bold headers and terminal-width separators.
 pub struct SectionRenderer {
-    term: Term,
+    term: Stdout,
+    caps: &'static TermCaps,
     sections: Vec<(String, SectionContent)>,
 }
 
@@ -42,7 +67,8 @@ impl Default for SectionRenderer {
 impl SectionRenderer {
     pub fn new() -> Self {
         Self {
-            term: Term::stdout(),
+            term: stdout(),
+            caps: term_caps::get(),
             sections: Vec::new(),
         }
     }
@@ -68,8 +94,7 @@ impl SectionRenderer {
     }
 
     pub fn print(self) -> Result<()> {
-        let width = self.term.size().1.into();
-        let separator = "─".repeat(width);
+        let separator = self.caps.rule();
 
         for (i, (header, content)) in self.sections.iter().enumerate() {
             if !header.is_empty() {
@@ -92,7 +117,7 @@ impl SectionRenderer {
                     }
                 }
                 SectionContent::Markdown(text) => {
-                    write!(&self.term, "{}", MARKDOWN_SKIN.term_text(text))?;
+                    self.term.write_str(&markdown_text(text, self.caps))?;
                 }
             }
             self.term.write_line("")?;
@@ -107,6 +132,64 @@ pub trait Formattable {
     fn to_card(&self) -> (String, Vec<(&'static str, String)>);
 }
 
+impl<T: Formattable> Formattable for &T {
+    fn to_card(&self) -> (String, Vec<(&'static str, String)>) {
+        (*self).to_card()
+    }
+}
+
+/// `--format` for list commands: any `OutputFormat`, or one of the text
+/// formats registered in `text_format::FORMATTERS`.
+#[derive(Clone, Copy)]
+#[allow(
+    variant_size_differences,
+    reason = "a formatter reference is two words; boxing it would lose Copy"
+)]
+pub enum ListFormat {
+    Output(crate::OutputFormat),
+    Text(&'static dyn TextFormatter),
+}
+
+impl Default for ListFormat {
+    fn default() -> Self {
+        Self::Output(crate::OutputFormat::default())
+    }
+}
+
+impl From<&crate::OutputFormat> for ListFormat {
+    fn from(format: &crate::OutputFormat) -> Self {
+        Self::Output(*format)
+    }
+}
+
+impl From<&Self> for ListFormat {
+    fn from(format: &Self) -> Self {
+        *format
+    }
+}
+
+/// Every `ListFormat`, in `--help` order.
+static LIST_FORMATS: LazyLock<Vec<ListFormat>> = LazyLock::new(|| {
+    <crate::OutputFormat as clap::ValueEnum>::value_variants()
+        .iter()
+        .map(ListFormat::from)
+        .chain(FORMATTERS.iter().copied().map(ListFormat::Text))
+        .collect()
+});
+
+impl clap::ValueEnum for ListFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        &LIST_FORMATS
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            Self::Output(format) => clap::ValueEnum::to_possible_value(format),
+            Self::Text(formatter) => Some(PossibleValue::new(formatter.name())),
+        }
+    }
+}
+
 /// Compute the total number of pages for a given item count and page size.
 fn total_pages(total: usize, limit: u32) -> u32 {
     if limit == 0 {
@@ -118,52 +201,652 @@ fn total_pages(total: usize, limit: u32) -> u32 {
         .max(1)
 }
 
+/// Block characters used by `sparkline`, lowest to highest.
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// ASCII stand-ins for `SPARK_LEVELS`, for `--ascii` and non-UTF-8 locales.
+const ASCII_SPARK_LEVELS: [char; 8] = ['_', '.', '-', '~', '=', '+', '*', '#'];
+
+/// Render markdown for the terminal, in the same style as `bugs show`.
+pub fn render_markdown(text: &str) -> String {
+    markdown_text(text, term_caps::get())
+}
+
+/// Render `values` as a sparkline, scaled between the series' own minimum
+/// and maximum, in block characters or, without `unicode`, ASCII. A flat
+/// series renders as a flat line.
+pub fn sparkline(values: &[i64], unicode: bool) -> String {
+    let (Some(&min), Some(&max)) = (values.iter().min(), values.iter().max()) else {
+        return String::new();
+    };
+    let range = max.saturating_sub(min);
+    values
+        .iter()
+        .map(|&v| {
+            // Scale into 0..=7, the index of the highest block character.
+            let level = if range == 0 {
+                0
+            } else {
+                v.saturating_sub(min).saturating_mul(7) / range
+            };
+            let levels = if unicode {
+                &SPARK_LEVELS
+            } else {
+                &ASCII_SPARK_LEVELS
+            };
+            levels[usize::try_from(level).unwrap_or(0).min(7)]
+        })
+        .collect()
+}
+
+/// Whether `--stable-output` is in effect.
+static STABLE_OUTPUT: OnceLock<bool> = OnceLock::new();
+
+/// Make structured output snapshot-safe; see `stabilize`. Only the first
+/// call counts.
+pub fn set_stable_output(enabled: bool) {
+    let _ = STABLE_OUTPUT.set(enabled);
+}
+
+pub fn stable_output() -> bool {
+    STABLE_OUTPUT.get().copied().unwrap_or(false)
+}
+
+/// Whether failures print as JSON on stdout; see `exit::error_document`.
+static JSON_ERRORS: OnceLock<bool> = OnceLock::new();
+
+/// Report a failed command as a JSON error document, for `--format json`.
+/// Only the first call counts.
+pub fn set_json_errors(enabled: bool) {
+    let _ = JSON_ERRORS.set(enabled);
+}
+
+pub fn json_errors() -> bool {
+    JSON_ERRORS.get().copied().unwrap_or(false)
+}
+
+/// The `--jq` expression, if one was passed.
+static JQ: OnceLock<String> = OnceLock::new();
+
+/// Run `expr` over JSON output before printing it; see `crate::jq`. The
+/// caller validates it first. Only the first call counts.
+pub fn set_jq(expr: &str) {
+    let _ = JQ.set(expr.to_string());
+}
+
+/// Fields that differ from run to run for the same data: timings and
+/// request ids. `--stable-output` leaves them out.
+const VOLATILE_FIELDS: [&str; 6] = [
+    "averageMs",
+    "durationMs",
+    "elapsedMs",
+    "fetchedAt",
+    "requestId",
+    "workflowRequestId",
+];
+
+/// `value` with volatile fields removed, object keys in order, and lists of
+/// records (objects with a string `id`) sorted by id. Other lists keep their
+/// order, since it often carries meaning (trends, ranked owners).
+fn stabilize(value: Value) -> Value {
+    match value {
+        Value::Object(fields) => {
+            let mut fields: Vec<(String, Value)> = fields
+                .into_iter()
+                .filter(|(key, _)| !VOLATILE_FIELDS.contains(&key.as_str()))
+                .map(|(key, item)| (key, stabilize(item)))
+                .collect();
+            fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(fields.into_iter().collect())
+        }
+        Value::Array(items) => {
+            let mut items: Vec<Value> = items.into_iter().map(stabilize).collect();
+            let id = |item: &Value| item.get("id").and_then(Value::as_str).map(str::to_owned);
+            if items.iter().all(|item| id(item).is_some()) {
+                items.sort_by_key(id);
+            }
+            Value::Array(items)
+        }
+        other => other,
+    }
+}
+
+/// `value` for a structured `--format`: YAML for `yaml`, pretty JSON
+/// otherwise, or the `--jq` results for `json`. No trailing newline.
+fn render_structured<T: Serialize + ?Sized>(
+    value: &T,
+    format: &crate::OutputFormat,
+) -> Result<String> {
+    if let (Some(expr), crate::OutputFormat::Json) = (JQ.get(), format) {
+        let mut json = serde_json::to_value(value)?;
+        if stable_output() {
+            json = stabilize(json);
+        }
+        return Query::parse(expr)?.render(json);
+    }
+    if stable_output() {
+        return render_text(&stabilize(serde_json::to_value(value)?), format);
+    }
+    render_text(value, format)
+}
+
+fn render_text<T: Serialize + ?Sized>(value: &T, format: &crate::OutputFormat) -> Result<String> {
+    let text = match format {
+        crate::OutputFormat::Yaml => serde_yaml::to_string(value)?,
+        crate::OutputFormat::Json | crate::OutputFormat::Table => {
+            serde_json::to_string_pretty(value)?
+        }
+    };
+    Ok(text.trim_end().to_string())
+}
+
+/// Print `value` for a structured `--format`; see `render_structured`.
+/// Without `--jq` or `--stable-output` to apply first, it's serialized
+/// straight to stdout.
+pub fn write_structured<T: Serialize + ?Sized>(
+    value: &T,
+    format: &crate::OutputFormat,
+) -> Result<()> {
+    let jq = JQ.get().is_some() && matches!(format, crate::OutputFormat::Json);
+    if !jq && !stable_output() {
+        let mut out = stdout();
+        match format {
+            crate::OutputFormat::Yaml => serde_yaml::to_writer(&mut out, value)?,
+            crate::OutputFormat::Json | crate::OutputFormat::Table => {
+                serde_json::to_writer_pretty(&mut out, value)?;
+                out.write_line("")?;
+            }
+        }
+        return Ok(());
+    }
+    let mut text = render_structured(value, format)?;
+    // A `--jq` expression can produce no results; print nothing then.
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    write_stdout(&text)
+}
+
+/// Stdout as `stdout()` writes it. Writes are buffered, so a long export
+/// goes out a buffer at a time as records are rendered; on a terminal each
+/// line is flushed as it ends. A reader closing the pipe early
+/// (`detail bugs export | head`) ends the output for the rest of the
+/// process, but not the command: the reader got what it wanted.
+struct Sink<W: Write> {
+    out: W,
+    line_buffered: bool,
+    closed: bool,
+}
+
+impl<W: Write> Sink<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<()> {
+        if self.closed {
+            return Ok(());
+        }
+        let result = self.out.write_all(buf).and_then(|()| {
+            if self.line_buffered && buf.contains(&b'\n') {
+                self.out.flush()
+            } else {
+                Ok(())
+            }
+        });
+        self.settle(result)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.closed {
+            return Ok(());
+        }
+        let result = self.out.flush();
+        self.settle(result)
+    }
+
+    /// The one place a closed pipe is handled: later writes are dropped.
+    fn settle(&mut self, result: io::Result<()>) -> io::Result<()> {
+        match result {
+            Err(e) if e.kind() == ErrorKind::BrokenPipe => {
+                self.closed = true;
+                Ok(())
+            }
+            other => other,
+        }
+    }
+}
+
+static STDOUT: LazyLock<Mutex<Sink<BufWriter<io::Stdout>>>> = LazyLock::new(|| {
+    let stdout = io::stdout();
+    Mutex::new(Sink {
+        line_buffered: stdout.is_terminal(),
+        out: BufWriter::new(stdout),
+        closed: false,
+    })
+});
+
+/// Handle to the process's stdout, which every command's results go
+/// through; see `Sink`. Interactive prompts and progress stay on stderr.
+#[derive(Clone, Copy)]
+pub struct Stdout(&'static Mutex<Sink<BufWriter<io::Stdout>>>);
+
+/// The process's stdout; see [`Stdout`].
+pub fn stdout() -> Stdout {
+    Stdout(&STDOUT)
+}
+
+impl Stdout {
+    fn with<T>(self, f: impl FnOnce(&mut Sink<BufWriter<io::Stdout>>) -> T) -> T {
+        f(&mut self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    pub fn is_term(self) -> bool {
+        self.with(|sink| sink.line_buffered)
+    }
+
+    pub fn write_str(self, text: &str) -> io::Result<()> {
+        self.with(|sink| sink.write(text.as_bytes()))
+    }
+
+    pub fn write_line(self, line: &str) -> io::Result<()> {
+        self.write_str(&format!("{line}\n"))
+    }
+}
+
+impl Write for Stdout {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.with(|sink| sink.write(buf))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.with(Sink::flush)
+    }
+}
+
+/// Write `content` to stdout.
+pub fn write_stdout(content: &str) -> Result<()> {
+    Ok(stdout().write_str(content)?)
+}
+
+/// Flush stdout before the process exits, noting on stderr when the
+/// reader closed it early.
+pub fn finish_stdout() {
+    let closed = stdout().with(|sink| {
+        let _ = sink.flush();
+        sink.closed
+    });
+    if closed {
+        let _ = Term::stderr().write_line("Output closed by the reader; stopped writing.");
+    }
+}
+
+/// A page of results, as list commands print it for `--format json` and
+/// `yaml`. Fields are in the order earlier releases printed them.
+#[derive(Serialize, JsonSchema)]
+pub struct ListResponse<'a, T> {
+    /// The results on this page.
+    pub items: &'a [T],
+    /// This page's number, from 1.
+    pub page: u32,
+    /// Results across all pages.
+    pub total: usize,
+    pub total_pages: u32,
+}
+
 /// Generic helper to output a list of items in the requested format
 pub fn output_list<T: Formattable + Serialize>(
     items: &[T],
     total: usize,
     page: u32,
     limit: u32,
+    format: impl Into<ListFormat>,
+) -> Result<()> {
+    let total_pages = total_pages(total, limit);
+    let offset: usize = page_to_offset(page, limit).try_into().unwrap_or(usize::MAX);
+    let format = match format.into() {
+        ListFormat::Output(format) => format,
+        ListFormat::Text(formatter) => {
+            return write_stdout(&formatter.render(&TextTable::from_cards(items, offset))?);
+        }
+    };
+
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Yaml => {
+            let response = ListResponse {
+                items,
+                total,
+                page,
+                total_pages,
+            };
+            write_structured(&response, &format)?;
+        }
+        crate::OutputFormat::Table => {
+            let term = stdout();
+            write_cards(term, items, offset)?;
+            term.write_line(&format!("\nPage: {page} of {total_pages}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Print `items` as numbered cards, starting from `offset + 1`.
+fn write_cards<T: Formattable>(term: Stdout, items: &[T], offset: usize) -> Result<()> {
+    let max_key = items
+        .iter()
+        .flat_map(|item| item.to_card().1)
+        .map(|(k, _)| k.len())
+        .max()
+        .unwrap_or(0);
+    // Keep each card line on one terminal row; piped output stays
+    // complete unless `--width` asks otherwise.
+    let width = term_caps::get().width;
+    let fit = |text: &str, indent: usize| {
+        width.map_or_else(
+            || text.to_string(),
+            |width| truncate_display(text, width.saturating_sub(indent)),
+        )
+    };
+    for (i, item) in items.iter().enumerate() {
+        let (header, pairs) = item.to_card();
+        let number = format!("{}. ", offset + i + 1);
+        term.write_line(&format!("{number}{}", fit(&header, number.len())))?;
+        for (k, v) in &pairs {
+            let v = fit(v, 4 + max_key + 2);
+            term.write_line(&format!("    {k:<max_key$}  {v}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// One section of a grouped list, e.g. the bugs in one file.
+#[derive(Serialize)]
+pub struct ListGroup<'a, T> {
+    pub name: String,
+    /// Items in the group, as a subtotal next to the page's `total`.
+    pub total: usize,
+    pub items: Vec<&'a T>,
+}
+
+impl<'a, T> ListGroup<'a, T> {
+    pub fn new(name: impl Into<String>, items: Vec<&'a T>) -> Self {
+        Self {
+            name: name.into(),
+            total: items.len(),
+            items,
+        }
+    }
+}
+
+/// Like `output_list`, but in sections with subtotals: a heading and cards
+/// per group in the table view, a `groups` array in JSON and YAML.
+pub fn output_grouped_list<T: Formattable + Serialize>(
+    groups: &[ListGroup<'_, T>],
+    total: usize,
+    page: u32,
+    limit: u32,
     format: &crate::OutputFormat,
 ) -> Result<()> {
     let total_pages = total_pages(total, limit);
 
     match format {
-        crate::OutputFormat::Json => {
+        crate::OutputFormat::Json | crate::OutputFormat::Yaml => {
             let response = serde_json::json!({
-                "items": items,
+                "groups": groups,
                 "total": total,
                 "page": page,
                 "total_pages": total_pages,
             });
-            Term::stdout().write_line(&serde_json::to_string_pretty(&response)?)?;
+            write_structured(&response, format)?;
         }
         crate::OutputFormat::Table => {
-            let term = Term::stdout();
-            let max_key = items
-                .iter()
-                .flat_map(|item| item.to_card().1)
-                .map(|(k, _)| k.len())
-                .max()
-                .unwrap_or(0);
-            let offset: usize = page_to_offset(page, limit).try_into().unwrap_or(usize::MAX);
-            for (i, item) in items.iter().enumerate() {
-                let (header, pairs) = item.to_card();
-                term.write_line(&format!("{}. {}", offset + i + 1, header))?;
-                for (k, v) in &pairs {
-                    term.write_line(&format!("    {k:<max_key$}  {v}"))?;
-                }
+            let term = stdout();
+            let rule = term_caps::get().rule();
+            let mut offset: usize = page_to_offset(page, limit).try_into().unwrap_or(usize::MAX);
+            for group in groups {
+                term.write_line(&format!("{} ({})", style(&group.name).bold(), group.total))?;
+                term.write_line(&format!("{}", style(&rule).dim()))?;
+                write_cards(term, &group.items, offset)?;
+                term.write_line("")?;
+                offset = offset.saturating_add(group.total);
             }
-            term.write_line(&format!("\nPage: {page} of {total_pages}"))?;
+            term.write_line(&format!("Page: {page} of {total_pages}"))?;
         }
     }
     Ok(())
 }
 
+/// A zero-based line and UTF-16 character offset, as in LSP.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// LSP `DiagnosticSeverity`: security vulnerabilities are errors, other
+/// bugs warnings.
+const LSP_SEVERITY_ERROR: u8 = 1;
+const LSP_SEVERITY_WARNING: u8 = 2;
+
+/// One bug as an LSP `Diagnostic`.
+#[derive(Debug, Serialize)]
+pub struct Diagnostic {
+    /// The API locates bugs by file only, so this is the file's first line.
+    pub range: Range,
+    pub severity: u8,
+    /// The bug ID, for `detail bugs show` and `detail bugs close`.
+    pub code: String,
+    pub source: &'static str,
+    pub message: String,
+}
+
+/// A file's diagnostics, shaped like LSP's `PublishDiagnosticsParams` so
+/// editor plugins can forward them unchanged.
+///
+/// `bugs export --format lsp-diagnostics` prints a JSON array of these, one
+/// per file. This is an interop format: fields may be added, but existing
+/// ones keep their names and meaning.
+#[derive(Debug, Serialize)]
+pub struct FileDiagnostics {
+    /// A `file://` URI under `root` when one is known, else the path the API
+    /// reported, relative to the repository root.
+    pub uri: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Group `bugs` into diagnostics per file. Bugs without a file are left
+/// out, since editors can only show diagnostics on a document.
+pub fn lsp_diagnostics(bugs: &[Bug], root: Option<&Path>) -> Vec<FileDiagnostics> {
+    let mut by_file: BTreeMap<&str, Vec<Diagnostic>> = BTreeMap::new();
+    for bug in bugs {
+        let Some(path) = bug.file_path.as_deref() else {
+            continue;
+        };
+        let severity = if bug.is_security_vulnerability == Some(true) {
+            LSP_SEVERITY_ERROR
+        } else {
+            LSP_SEVERITY_WARNING
+        };
+        by_file.entry(path).or_default().push(Diagnostic {
+            range: Range::default(),
+            severity,
+            code: bug.id.to_string(),
+            source: "detail",
+            message: bug.title.clone(),
+        });
+    }
+    by_file
+        .into_iter()
+        .map(|(path, diagnostics)| FileDiagnostics {
+            uri: root
+                .and_then(|root| Url::from_file_path(root.join(path)).ok())
+                .map_or_else(|| path.to_string(), String::from),
+            diagnostics,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // ── Sink ─────────────────────────────────────────────────────────
+
+    /// Accepts `budget` bytes, then fails like a closed pipe.
+    struct ClosingPipe {
+        written: Vec<u8>,
+        budget: usize,
+    }
+
+    impl Write for ClosingPipe {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.written.len() >= self.budget {
+                return Err(io::Error::from(ErrorKind::BrokenPipe));
+            }
+            let n = buf.len().min(self.budget - self.written.len());
+            self.written.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn sink(budget: usize) -> Sink<ClosingPipe> {
+        Sink {
+            out: ClosingPipe {
+                written: Vec::new(),
+                budget,
+            },
+            line_buffered: false,
+            closed: false,
+        }
+    }
+
+    #[test]
+    fn sink_stops_quietly_on_a_closed_pipe() {
+        let mut sink = sink(10);
+        sink.write(b"first record\n").unwrap();
+        assert!(sink.closed);
+        // Later records are dropped rather than failing the command.
+        sink.write(b"second record\n").unwrap();
+        sink.flush().unwrap();
+        assert_eq!(sink.out.written, b"first reco");
+    }
+
+    #[test]
+    fn sink_passes_everything_through_an_open_pipe() {
+        let mut sink = sink(usize::MAX);
+        sink.write(b"one\n").unwrap();
+        sink.write(b"two\n").unwrap();
+        assert!(!sink.closed);
+        assert_eq!(sink.out.written, b"one\ntwo\n");
+    }
+
+    #[test]
+    fn line_buffered_sink_flushes_each_finished_line() {
+        let mut sink = Sink {
+            out: BufWriter::new(Vec::new()),
+            line_buffered: true,
+            closed: false,
+        };
+        sink.write(b"partial").unwrap();
+        assert!(sink.out.get_ref().is_empty());
+        sink.write(b" line\n").unwrap();
+        assert_eq!(sink.out.get_ref(), b"partial line\n");
+    }
+
+    /// Fails every write, like a full disk.
+    struct Full;
+
+    impl Write for Full {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn sink_reports_other_write_errors() {
+        let mut sink = Sink {
+            out: Full,
+            line_buffered: false,
+            closed: false,
+        };
+        assert!(sink.write(b"x").is_err());
+        assert!(!sink.closed);
+    }
+
+    // ── lsp_diagnostics ──────────────────────────────────────────────
+
+    #[test]
+    fn diagnostics_are_grouped_by_file() {
+        let bugs: Vec<Bug> = serde_json::from_value(serde_json::json!([
+            { "id": "bug_1", "title": "SQL injection", "summary": "", "createdAt": 0,
+              "repoId": "repo_1", "filePath": "src/login.rs", "isSecurityVulnerability": true,
+              "linkedIssues": [] },
+            { "id": "bug_2", "title": "Off-by-one", "summary": "", "createdAt": 0,
+              "repoId": "repo_1", "filePath": "src/login.rs", "linkedIssues": [] },
+            { "id": "bug_3", "title": "No file", "summary": "", "createdAt": 0,
+              "repoId": "repo_1", "linkedIssues": [] }
+        ]))
+        .unwrap();
+        let files = lsp_diagnostics(&bugs, None);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].uri, "src/login.rs");
+        let severities: Vec<u8> = files[0].diagnostics.iter().map(|d| d.severity).collect();
+        assert_eq!(severities, [LSP_SEVERITY_ERROR, LSP_SEVERITY_WARNING]);
+        assert_eq!(files[0].diagnostics[1].code, "bug_2");
+    }
+
+    // ── render_structured ────────────────────────────────────────────
+
+    #[test]
+    fn yaml_uses_the_same_envelope_as_json() {
+        let envelope = serde_json::json!({ "items": ["a"], "total": 1 });
+        assert_eq!(
+            render_structured(&envelope, &crate::OutputFormat::Yaml).unwrap(),
+            "items:\n- a\ntotal: 1"
+        );
+        assert_eq!(
+            render_structured(&envelope, &crate::OutputFormat::Json).unwrap(),
+            "{\n  \"items\": [\n    \"a\"\n  ],\n  \"total\": 1\n}"
+        );
+    }
+
+    // ── stabilize ────────────────────────────────────────────────────
+
+    #[test]
+    fn stabilize_drops_volatile_fields_and_sorts_records() {
+        let value = serde_json::json!({
+            "items": [
+                { "id": "bug_2", "workflowRequestId": "wr_9", "tags": ["b", "a"] },
+                { "id": "bug_1", "durationMs": 812 },
+            ],
+            "trend": [3, 1, 2],
+        });
+        assert_eq!(
+            stabilize(value),
+            serde_json::json!({
+                "items": [{ "id": "bug_1" }, { "id": "bug_2", "tags": ["b", "a"] }],
+                "trend": [3, 1, 2],
+            })
+        );
+    }
+
+    #[test]
+    fn stabilize_keeps_lists_without_ids_in_order() {
+        let value = serde_json::json!([{ "name": "b" }, { "name": "a" }, { "id": "x" }]);
+        assert_eq!(stabilize(value.clone()), value);
+    }
+
     // ── total_pages ──────────────────────────────────────────────────
 
     #[test]
@@ -197,6 +880,33 @@ mod tests {
         assert_eq!(total_pages(10, 0), 1);
     }
 
+    // ── sparkline ────────────────────────────────────────────────────
+
+    #[test]
+    fn sparkline_scales_between_min_and_max() {
+        assert_eq!(sparkline(&[0, 7, 14], true), "▁▄█");
+    }
+
+    #[test]
+    fn sparkline_offset_series_uses_full_range() {
+        assert_eq!(sparkline(&[10, 12, 11], true), "▁█▄");
+    }
+
+    #[test]
+    fn sparkline_flat_series_is_flat() {
+        assert_eq!(sparkline(&[5, 5, 5], true), "▁▁▁");
+    }
+
+    #[test]
+    fn sparkline_empty_is_empty() {
+        assert_eq!(sparkline(&[], true), "");
+    }
+
+    #[test]
+    fn sparkline_falls_back_to_ascii() {
+        assert_eq!(sparkline(&[0, 7, 14], false), "_~#");
+    }
+
     // ── SectionRenderer builder ──────────────────────────────────────
 
     #[test]
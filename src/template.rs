@@ -0,0 +1,165 @@
+//! `--template` for `bugs list` and `bugs show`: print each bug through a
+//! minijinja template, e.g. `{{id}}\t{{title}}\t{{file_path}}`.
+//!
+//! Templates see every field of the bug as in `--format json`, with names
+//! in snake_case (`file_path`, `is_security_vulnerability`, `review.state`),
+//! plus `state`: pending, resolved or dismissed. Missing and null fields
+//! render empty. `\t`, `\n` and `\\` in the template stand for a tab, a
+//! newline and a backslash, since shells pass them through as typed.
+
+use anyhow::{anyhow, Result};
+use minijinja::{Environment, UndefinedBehavior};
+use serde_json::Value;
+
+use crate::api::types::Bug;
+use crate::exit::Failure;
+use crate::export::bug_state;
+
+const NAME: &str = "template";
+
+/// A compiled `--template`.
+pub struct Template {
+    env: Environment<'static>,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut env = Environment::new();
+        // `review.state` on a pending bug, which has no review, is empty
+        // rather than an error.
+        env.set_undefined_behavior(UndefinedBehavior::Chainable);
+        env.add_template_owned(NAME, unescape(source))
+            .map_err(|e| anyhow!("{e}"))?;
+        Ok(Self { env })
+    }
+
+    /// `bug` through the template, without a trailing newline.
+    pub fn render(&self, bug: &Bug) -> Result<String> {
+        let template = self.env.get_template(NAME)?;
+        template
+            .render(context(bug)?)
+            .map_err(|e| anyhow!("Failed to render --template: {e}"))
+    }
+}
+
+/// Parse a `--template` flag value, reporting a bad template as a usage
+/// error.
+pub fn parse_flag(value: Option<&str>) -> Result<Option<Template>> {
+    value
+        .map(|source| {
+            Template::parse(source)
+                .map_err(|e| Failure::usage(format!("Invalid --template: {e}")).into())
+        })
+        .transpose()
+}
+
+/// `source` with `\t`, `\n` and `\\` replaced by what they stand for. Any
+/// other backslash is kept as typed.
+fn unescape(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// `name` in snake_case: `filePath` becomes `file_path`.
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('_');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// `value` with snake_case keys and without nulls, which minijinja would
+/// print as `none`.
+fn template_value(value: Value) -> Value {
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .filter(|(_, item)| !item.is_null())
+                .map(|(key, item)| (snake_case(&key), template_value(item)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(template_value).collect()),
+        other => other,
+    }
+}
+
+/// What a template sees for `bug`.
+fn context(bug: &Bug) -> Result<Value> {
+    let mut value = serde_json::to_value(bug)?;
+    if let Value::Object(fields) = &mut value {
+        fields.insert("state".to_string(), serde_json::to_value(bug_state(bug))?);
+    }
+    Ok(template_value(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_support::test_bug;
+
+    fn bug() -> Bug {
+        test_bug(json!({
+            "title": "Null deref", "filePath": "src/main.rs", "isSecurityVulnerability": true
+        }))
+    }
+
+    #[test]
+    fn renders_fields_in_snake_case_with_escapes() {
+        let template = Template::parse(r"{{id}}\t{{title}}\t{{file_path}}").unwrap();
+        assert_eq!(
+            template.render(&bug()).unwrap(),
+            "bug_1\tNull deref\tsrc/main.rs"
+        );
+    }
+
+    #[test]
+    fn nulls_and_missing_fields_render_empty() {
+        let template = Template::parse("[{{commit_sha}}][{{review.state}}][{{state}}]").unwrap();
+        assert_eq!(template.render(&bug()).unwrap(), "[][][pending]");
+    }
+
+    #[test]
+    fn conditionals_see_typed_values() {
+        let template =
+            Template::parse("{% if is_security_vulnerability %}VULN {% endif %}{{id}}").unwrap();
+        assert_eq!(template.render(&bug()).unwrap(), "VULN bug_1");
+    }
+
+    #[test]
+    fn bad_templates_are_usage_errors() {
+        assert!(parse_flag(None).unwrap().is_none());
+        let err = parse_flag(Some("{{ id ")).err().unwrap();
+        assert!(err.to_string().starts_with("Invalid --template"), "{err}");
+    }
+
+    #[test]
+    fn unescape_keeps_other_backslashes() {
+        assert_eq!(unescape(r"a\tb\nc\\d\qe\"), "a\tb\nc\\d\\qe\\");
+    }
+}
@@ -0,0 +1,16 @@
+//! Fixtures shared by unit tests across modules.
+
+use crate::api::types::Bug;
+
+/// A bug for tests: `bug_1` in `repo_1`, pending, created at the epoch,
+/// with `fields` (camelCase, as the API sends them) laid over it.
+pub fn test_bug(fields: serde_json::Value) -> Bug {
+    let mut bug = serde_json::json!({
+        "id": "bug_1", "title": "t", "summary": "s", "createdAt": 0,
+        "repoId": "repo_1", "linkedIssues": []
+    });
+    if let (Some(bug), Some(fields)) = (bug.as_object_mut(), fields.as_object()) {
+        bug.extend(fields.clone());
+    }
+    serde_json::from_value(bug).unwrap()
+}
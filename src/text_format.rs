@@ -0,0 +1,212 @@
+//! Niche plain-text table formats for list commands, e.g. org-mode tables
+//! for Emacs. Each is a `TextFormatter` in `FORMATTERS`; `--format` offers
+//! every registered one by name (see `output::ListFormat`), so adding a
+//! format means adding a formatter here and nothing in `output_list`.
+
+use std::fmt::Write as _;
+use std::iter;
+
+use anyhow::Result;
+use unicode_width::UnicodeWidthStr as _;
+
+use crate::output::Formattable;
+
+/// List items as rows: a number and title column, then every card field
+/// seen, in first-seen order. Items without a field get an empty cell.
+pub struct TextTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl TextTable {
+    /// Rows for `items`, numbered from `offset + 1`.
+    pub fn from_cards<T: Formattable>(items: &[T], offset: usize) -> Self {
+        let cards: Vec<(String, Vec<(&'static str, String)>)> =
+            items.iter().map(Formattable::to_card).collect();
+        let mut keys: Vec<&'static str> = Vec::new();
+        for (key, _) in cards.iter().flat_map(|(_, pairs)| pairs) {
+            if !keys.contains(key) {
+                keys.push(*key);
+            }
+        }
+        let rows = cards
+            .iter()
+            .enumerate()
+            .map(|(i, (title, pairs))| {
+                let mut row = vec![(offset + i + 1).to_string(), title.clone()];
+                row.extend(keys.iter().map(|key| {
+                    pairs
+                        .iter()
+                        .find(|(k, _)| k == key)
+                        .map_or_else(String::new, |(_, value)| value.clone())
+                }));
+                row
+            })
+            .collect();
+        let mut headers = vec!["#".to_string(), "Title".to_string()];
+        headers.extend(keys.iter().map(ToString::to_string));
+        Self { headers, rows }
+    }
+}
+
+/// Renders a `TextTable` as a document in some markup language.
+pub trait TextFormatter: Sync {
+    /// The `--format` value that selects it.
+    fn name(&self) -> &'static str;
+
+    fn render(&self, table: &TextTable) -> Result<String>;
+}
+
+/// Every text format `--format` offers on list commands.
+pub static FORMATTERS: [&dyn TextFormatter; 2] = [&OrgMode, &RestructuredText];
+
+/// `text` on one line, as every table cell must be.
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// An org-mode table, aligned so it reads well before Emacs realigns it.
+pub struct OrgMode;
+
+impl OrgMode {
+    /// `text` safe in an org table cell: `|` would start a new column.
+    fn cell(text: &str) -> String {
+        single_line(text).replace('|', "\\vert{}")
+    }
+}
+
+impl TextFormatter for OrgMode {
+    fn name(&self) -> &'static str {
+        "org-mode"
+    }
+
+    fn render(&self, table: &TextTable) -> Result<String> {
+        let headers: Vec<String> = table.headers.iter().map(|h| Self::cell(h)).collect();
+        let rows: Vec<Vec<String>> = table
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| Self::cell(cell)).collect())
+            .collect();
+        let widths: Vec<usize> = (0..headers.len())
+            .map(|column| {
+                iter::once(&headers)
+                    .chain(&rows)
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| cell.width())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let line = |cells: &[String]| {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| {
+                    let pad = width.saturating_sub(cell.width());
+                    format!(" {cell}{} ", " ".repeat(pad))
+                })
+                .collect();
+            padded.join("|")
+        };
+        let rule: Vec<String> = widths.iter().map(|width| "-".repeat(width + 2)).collect();
+
+        let mut out = String::new();
+        writeln!(out, "|{}|", line(&headers))?;
+        writeln!(out, "|{}|", rule.join("+"))?;
+        for row in &rows {
+            writeln!(out, "|{}|", line(row))?;
+        }
+        Ok(out)
+    }
+}
+
+/// A reStructuredText `list-table` directive.
+pub struct RestructuredText;
+
+impl TextFormatter for RestructuredText {
+    fn name(&self) -> &'static str {
+        "rst"
+    }
+
+    fn render(&self, table: &TextTable) -> Result<String> {
+        let mut out = String::from(".. list-table::\n   :header-rows: 1\n");
+        for row in iter::once(&table.headers).chain(&table.rows) {
+            out.push('\n');
+            for (i, cell) in row.iter().enumerate() {
+                let bullet = if i == 0 { "   * -" } else { "     -" };
+                let cell = single_line(cell);
+                if cell.is_empty() {
+                    writeln!(out, "{bullet}")?;
+                } else {
+                    writeln!(out, "{bullet} {cell}")?;
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Item(&'static str, Vec<(&'static str, String)>);
+
+    impl Formattable for Item {
+        fn to_card(&self) -> (String, Vec<(&'static str, String)>) {
+            (self.0.to_string(), self.1.clone())
+        }
+    }
+
+    fn sample_table() -> TextTable {
+        TextTable::from_cards(
+            &[
+                Item("Null deref | crash", vec![("ID", "bug_1".to_string())]),
+                Item(
+                    "Off-by-one",
+                    vec![
+                        ("ID", "bug_2".to_string()),
+                        ("File", "src/a.rs".to_string()),
+                    ],
+                ),
+            ],
+            10,
+        )
+    }
+
+    #[test]
+    fn table_has_a_column_per_field_seen() {
+        let table = sample_table();
+        assert_eq!(table.headers, ["#", "Title", "ID", "File"]);
+        assert_eq!(table.rows[0], ["11", "Null deref | crash", "bug_1", ""]);
+        assert_eq!(table.rows[1][3], "src/a.rs");
+    }
+
+    #[test]
+    fn org_mode_table_is_aligned_and_escaped() {
+        assert_eq!(
+            OrgMode.render(&sample_table()).unwrap(),
+            "\
+| #  | Title                    | ID    | File     |
+|----+--------------------------+-------+----------|
+| 11 | Null deref \\vert{} crash | bug_1 |          |
+| 12 | Off-by-one               | bug_2 | src/a.rs |
+"
+        );
+    }
+
+    #[test]
+    fn rst_list_table_has_a_header_row() {
+        let rst = RestructuredText.render(&sample_table()).unwrap();
+        assert!(rst.starts_with(".. list-table::\n   :header-rows: 1\n\n   * - #\n     - Title\n"));
+        assert!(rst.contains("   * - 11\n     - Null deref | crash\n     - bug_1\n     -\n"));
+    }
+
+    #[test]
+    fn formatter_names_are_unique() {
+        let mut names: Vec<&str> = FORMATTERS.iter().map(|f| f.name()).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), FORMATTERS.len());
+    }
+}
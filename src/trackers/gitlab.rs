@@ -0,0 +1,242 @@
+//! GitLab.com and self-managed GitLab, through the REST API v4.
+//!
+//! Works without configuration on gitlab.com given `GITLAB_TOKEN`; a
+//! `[gitlab]` table in config.toml points elsewhere or adds labels:
+//!
+//! ```toml
+//! [gitlab]
+//! base_url = "https://gitlab.acme.com"
+//! labels = ["detail"]
+//! ```
+
+use std::env;
+
+use anyhow::{bail, Result};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{CreatedIssue, IssueDraft};
+use crate::api::client::{post_json_authorized, HttpOptions};
+use crate::api::types::BugReviewState;
+use crate::exit::Failure;
+
+/// Environment variable read when `token` isn't set in `[gitlab]`.
+const TOKEN_ENV: &str = "GITLAB_TOKEN";
+
+const DEFAULT_BASE_URL: &str = "https://gitlab.com";
+
+/// The `[gitlab]` table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitlabSettings {
+    /// Instance URL [default: https://gitlab.com].
+    pub base_url: Option<String>,
+    /// Personal, project or group access token with the `api` scope.
+    pub token: Option<String>,
+    /// Labels added to every exported issue, before the derived ones.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    /// Make issues for security vulnerabilities confidential [default: true].
+    pub confidential_vulns: Option<bool>,
+}
+
+impl GitlabSettings {
+    fn base_url(&self) -> &str {
+        self.base_url
+            .as_deref()
+            .map_or(DEFAULT_BASE_URL, |base| base.trim().trim_end_matches('/'))
+    }
+
+    fn authorization(&self) -> Result<String> {
+        let Some(token) = self
+            .token
+            .clone()
+            .or_else(|| env::var(TOKEN_ENV).ok())
+            .filter(|token| !token.trim().is_empty())
+        else {
+            bail!(Failure::usage(format!(
+                "No GitLab token. Set `token` under [gitlab] in config.toml or {TOKEN_ENV}."
+            )));
+        };
+        Ok(format!("Bearer {token}"))
+    }
+}
+
+/// Whether `project` is a full project path: `group/project`, with any
+/// subgroups in between.
+fn is_project_path(project: &str) -> bool {
+    let segments: Vec<&str> = project.split('/').collect();
+    segments.len() >= 2
+        && segments.iter().all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        })
+}
+
+/// Labels for `draft`. The API has no severity, so `severity::high` marks
+/// security vulnerabilities and `severity::medium` everything else, the same
+/// split `--format lsp` makes between errors and warnings. Bugs no longer
+/// pending also carry their state, and dismissed ones the reason.
+fn labels(settings: &GitlabSettings, draft: &IssueDraft) -> Vec<String> {
+    let (kind, severity) = if draft.security {
+        ("security", "severity::high")
+    } else {
+        ("bug", "severity::medium")
+    };
+    let mut derived = vec![kind.to_string(), severity.to_string()];
+    match draft.state {
+        BugReviewState::Pending => {}
+        BugReviewState::Resolved => derived.push("detail::resolved".to_string()),
+        BugReviewState::Dismissed => {
+            derived.push("detail::dismissed".to_string());
+            if let Some(reason) = draft.dismissal_reason {
+                derived.push(format!(
+                    "dismissed::{}",
+                    reason.to_string().replace('_', "-")
+                ));
+            }
+        }
+    }
+    let mut labels = settings.labels.clone();
+    for label in derived {
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    labels
+}
+
+/// The create-issue request for `draft`.
+pub fn request_body(settings: &GitlabSettings, draft: &IssueDraft) -> Value {
+    json!({
+        "title": draft.title,
+        "description": draft.body,
+        // The API takes labels as one comma-separated string.
+        "labels": labels(settings, draft).join(","),
+        "confidential": draft.security && settings.confidential_vulns.unwrap_or(true),
+    })
+}
+
+#[derive(Deserialize)]
+struct Created {
+    iid: u64,
+    web_url: String,
+}
+
+/// Create an issue for `draft` in `project` (`group/project`).
+pub async fn create_issue(
+    settings: &GitlabSettings,
+    project: &str,
+    draft: &IssueDraft,
+    http: &HttpOptions,
+) -> Result<CreatedIssue> {
+    let project = check_project(project)?;
+    let authorization = settings.authorization()?;
+    let url = format!(
+        "{}/api/v4/projects/{}/issues",
+        settings.base_url(),
+        utf8_percent_encode(project, NON_ALPHANUMERIC)
+    );
+    let created: Created =
+        post_json_authorized(&url, &request_body(settings, draft), &authorization, http).await?;
+    Ok(CreatedIssue {
+        key: format!("{project}#{}", created.iid),
+        url: created.web_url,
+    })
+}
+
+/// `project` without surrounding slashes, if it's a project path.
+pub fn check_project(project: &str) -> Result<&str> {
+    let project = project.trim_matches('/');
+    if !is_project_path(project) {
+        bail!(Failure::usage(format!(
+            "'{project}' isn't a GitLab project path (e.g. acme/api)"
+        )));
+    }
+    Ok(project)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::BugDismissalReason;
+
+    fn draft(security: bool) -> IssueDraft {
+        IssueDraft {
+            title: "Token leaks into logs".into(),
+            body: "The token is logged.".into(),
+            security,
+            state: BugReviewState::Pending,
+            dismissal_reason: None,
+        }
+    }
+
+    #[test]
+    fn vulnerabilities_are_labelled_and_confidential() {
+        let settings = GitlabSettings {
+            labels: vec!["detail".into()],
+            ..GitlabSettings::default()
+        };
+        assert_eq!(
+            request_body(&settings, &draft(true)),
+            json!({
+                "title": "Token leaks into logs",
+                "description": "The token is logged.",
+                "labels": "detail,security,severity::high",
+                "confidential": true,
+            })
+        );
+        let body = request_body(&settings, &draft(false));
+        assert_eq!(body["labels"], "detail,bug,severity::medium");
+        assert_eq!(body["confidential"], false);
+
+        let open = GitlabSettings {
+            confidential_vulns: Some(false),
+            ..GitlabSettings::default()
+        };
+        assert_eq!(request_body(&open, &draft(true))["confidential"], false);
+    }
+
+    #[test]
+    fn closed_bugs_carry_their_state_and_reason() {
+        let mut dismissed = draft(false);
+        dismissed.state = BugReviewState::Dismissed;
+        dismissed.dismissal_reason = Some(BugDismissalReason::WontFix);
+        assert_eq!(
+            labels(&GitlabSettings::default(), &dismissed),
+            [
+                "bug",
+                "severity::medium",
+                "detail::dismissed",
+                "dismissed::wont-fix"
+            ]
+        );
+    }
+
+    #[test]
+    fn project_paths_need_a_namespace() {
+        assert_eq!(check_project("acme/api").unwrap(), "acme/api");
+        assert_eq!(
+            check_project("/acme/tools/api.rs/").unwrap(),
+            "acme/tools/api.rs"
+        );
+        assert!(check_project("api").is_err());
+        assert!(check_project("acme//api").is_err());
+        assert!(check_project("acme/my api").is_err());
+    }
+
+    #[test]
+    fn defaults_to_gitlab_com() {
+        assert_eq!(GitlabSettings::default().base_url(), "https://gitlab.com");
+        let settings = GitlabSettings {
+            base_url: Some("https://gitlab.acme.com/".into()),
+            token: Some("glpat-x".into()),
+            ..GitlabSettings::default()
+        };
+        assert_eq!(settings.base_url(), "https://gitlab.acme.com");
+        assert_eq!(settings.authorization().unwrap(), "Bearer glpat-x");
+    }
+}
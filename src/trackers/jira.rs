@@ -0,0 +1,244 @@
+//! Jira Cloud and Data Center, through the REST API v2.
+//!
+//! Configured under `[jira]` in config.toml:
+//!
+//! ```toml
+//! [jira]
+//! base_url = "https://acme.atlassian.net"
+//! email = "me@acme.com"          # Cloud; leave out for a Data Center token
+//! issue_type = "Bug"
+//! vuln_issue_type = "Security"
+//! vuln_priority = "Highest"
+//! ```
+//!
+//! The API token is `token` there or `JIRA_API_TOKEN`.
+
+use std::env;
+
+use anyhow::{bail, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{CreatedIssue, IssueDraft};
+use crate::api::client::{post_json_authorized, HttpOptions};
+use crate::exit::Failure;
+
+/// Environment variable read when `token` isn't set in `[jira]`.
+const TOKEN_ENV: &str = "JIRA_API_TOKEN";
+
+/// Issue type used when `issue_type` isn't set.
+const DEFAULT_ISSUE_TYPE: &str = "Bug";
+
+/// The `[jira]` table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JiraSettings {
+    /// Site URL, e.g. `https://acme.atlassian.net`.
+    pub base_url: String,
+    /// Account email for Jira Cloud's basic auth. Without it the token is
+    /// sent as a bearer token, as Data Center personal access tokens are.
+    pub email: Option<String>,
+    pub token: Option<String>,
+    /// Issue type for bugs [default: Bug].
+    pub issue_type: Option<String>,
+    /// Priority for bugs; Jira's default when unset.
+    pub priority: Option<String>,
+    /// Issue type for security vulnerabilities [default: `issue_type`].
+    pub vuln_issue_type: Option<String>,
+    /// Priority for security vulnerabilities [default: `priority`].
+    pub vuln_priority: Option<String>,
+    /// Labels added to every exported issue; vulnerabilities also get
+    /// `security`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+}
+
+impl JiraSettings {
+    fn base_url(&self) -> Result<&str> {
+        let base = self.base_url.trim().trim_end_matches('/');
+        if base.is_empty() {
+            bail!(Failure::usage(
+                "Jira isn't configured. Set `base_url` under [jira] in config.toml."
+            ));
+        }
+        Ok(base)
+    }
+
+    fn issue_type(&self, security: bool) -> &str {
+        security
+            .then_some(self.vuln_issue_type.as_deref())
+            .flatten()
+            .or(self.issue_type.as_deref())
+            .unwrap_or(DEFAULT_ISSUE_TYPE)
+    }
+
+    fn priority(&self, security: bool) -> Option<&str> {
+        security
+            .then_some(self.vuln_priority.as_deref())
+            .flatten()
+            .or(self.priority.as_deref())
+    }
+
+    /// `Authorization` header value: basic auth with `email`, else bearer.
+    fn authorization(&self) -> Result<String> {
+        let Some(token) = self
+            .token
+            .clone()
+            .or_else(|| env::var(TOKEN_ENV).ok())
+            .filter(|token| !token.trim().is_empty())
+        else {
+            bail!(Failure::usage(format!(
+                "No Jira API token. Set `token` under [jira] in config.toml or {TOKEN_ENV}."
+            )));
+        };
+        Ok(match &self.email {
+            Some(email) => format!("Basic {}", STANDARD.encode(format!("{email}:{token}"))),
+            None => format!("Bearer {token}"),
+        })
+    }
+}
+
+/// Whether `key` looks like a Jira project key: an uppercase letter, then
+/// uppercase letters, digits or underscores.
+fn is_project_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// The create-issue request for `draft` in `project`.
+pub fn request_body(settings: &JiraSettings, project: &str, draft: &IssueDraft) -> Result<Value> {
+    if !is_project_key(project) {
+        bail!(Failure::usage(format!(
+            "'{project}' isn't a Jira project key (e.g. PAY)"
+        )));
+    }
+    let mut labels = settings.labels.clone();
+    if draft.security && !labels.iter().any(|label| label == "security") {
+        labels.push("security".to_string());
+    }
+    let mut fields = json!({
+        "project": { "key": project },
+        "summary": draft.title,
+        "description": draft.body,
+        "issuetype": { "name": settings.issue_type(draft.security) },
+        "labels": labels,
+    });
+    if let (Some(priority), Some(fields)) =
+        (settings.priority(draft.security), fields.as_object_mut())
+    {
+        fields.insert("priority".to_string(), json!({ "name": priority }));
+    }
+    Ok(json!({ "fields": fields }))
+}
+
+#[derive(Deserialize)]
+struct Created {
+    key: String,
+}
+
+/// Create an issue for `draft` in `project`.
+pub async fn create_issue(
+    settings: &JiraSettings,
+    project: &str,
+    draft: &IssueDraft,
+    http: &HttpOptions,
+) -> Result<CreatedIssue> {
+    let base = settings.base_url()?;
+    let body = request_body(settings, project, draft)?;
+    let authorization = settings.authorization()?;
+    let created: Created = post_json_authorized(
+        &format!("{base}/rest/api/2/issue"),
+        &body,
+        &authorization,
+        http,
+    )
+    .await?;
+    Ok(CreatedIssue {
+        url: format!("{base}/browse/{}", created.key),
+        key: created.key,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::BugReviewState;
+
+    fn draft(security: bool) -> IssueDraft {
+        IssueDraft {
+            title: "Token leaks into logs".into(),
+            body: "The token is logged.".into(),
+            security,
+            state: BugReviewState::Pending,
+            dismissal_reason: None,
+        }
+    }
+
+    fn settings() -> JiraSettings {
+        toml::from_str(
+            r#"
+            base_url = "https://acme.atlassian.net/"
+            email = "me@acme.com"
+            token = "secret"
+            priority = "Medium"
+            vuln_issue_type = "Security"
+            vuln_priority = "Highest"
+            labels = ["detail"]
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn vulnerabilities_get_their_own_type_priority_and_label() {
+        let body = request_body(&settings(), "PAY", &draft(true)).unwrap();
+        assert_eq!(
+            body,
+            json!({ "fields": {
+                "project": { "key": "PAY" },
+                "summary": "Token leaks into logs",
+                "description": "The token is logged.",
+                "issuetype": { "name": "Security" },
+                "priority": { "name": "Highest" },
+                "labels": ["detail", "security"],
+            }})
+        );
+    }
+
+    #[test]
+    fn other_bugs_use_the_defaults() {
+        let body = request_body(&settings(), "PAY", &draft(false)).unwrap();
+        assert_eq!(body["fields"]["issuetype"]["name"], "Bug");
+        assert_eq!(body["fields"]["priority"]["name"], "Medium");
+        assert_eq!(body["fields"]["labels"], json!(["detail"]));
+
+        let bare = JiraSettings::default();
+        let body = request_body(&bare, "PAY", &draft(true)).unwrap();
+        assert_eq!(body["fields"]["issuetype"]["name"], "Bug");
+        assert!(body["fields"].get("priority").is_none());
+    }
+
+    #[test]
+    fn project_keys_are_checked() {
+        assert!(is_project_key("PAY"));
+        assert!(is_project_key("PAY_2"));
+        assert!(!is_project_key("pay"));
+        assert!(!is_project_key("2PAY"));
+        assert!(request_body(&settings(), "acme/api", &draft(false)).is_err());
+    }
+
+    #[test]
+    fn email_selects_basic_auth() {
+        let mut settings = settings();
+        assert_eq!(
+            settings.authorization().unwrap(),
+            format!("Basic {}", STANDARD.encode("me@acme.com:secret"))
+        );
+        settings.email = None;
+        assert_eq!(settings.authorization().unwrap(), "Bearer secret");
+        assert_eq!(settings.base_url().unwrap(), "https://acme.atlassian.net");
+    }
+}
@@ -0,0 +1,73 @@
+//! External issue trackers `detail bugs export-issue` can file bugs in.
+
+pub mod gitlab;
+pub mod jira;
+
+use crate::api::types::{Bug, BugDismissalReason, BugReviewState};
+use crate::export::bug_state;
+
+/// What an exported issue says, before a tracker's own formatting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueDraft {
+    pub title: String,
+    /// The bug's summary followed by where it is and which bug it came from.
+    pub body: String,
+    pub security: bool,
+    /// Review state in Detail; usually pending when exporting.
+    pub state: BugReviewState,
+    pub dismissal_reason: Option<BugDismissalReason>,
+}
+
+impl IssueDraft {
+    pub fn from_bug(bug: &Bug) -> Self {
+        let mut details = Vec::new();
+        if let Some(path) = &bug.file_path {
+            details.push(format!("File: {path}"));
+        }
+        if let Some(sha) = &bug.commit_sha {
+            details.push(format!("Commit: {sha}"));
+        }
+        details.push(format!("Detail bug: {}", bug.id));
+        Self {
+            title: bug.title.clone(),
+            body: format!("{}\n\n{}", bug.summary.trim_end(), details.join("\n")),
+            security: bug.is_security_vulnerability == Some(true),
+            state: bug_state(bug),
+            dismissal_reason: bug
+                .review
+                .as_ref()
+                .and_then(|review| review.dismissal_reason),
+        }
+    }
+}
+
+/// An issue a tracker created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatedIssue {
+    /// The tracker's own key, e.g. `PAY-231`.
+    pub key: String,
+    pub url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draft_lists_location_and_origin_after_the_summary() {
+        let bug: Bug = serde_json::from_value(serde_json::json!({
+            "id": "bug_1", "title": "Token leaks into logs", "summary": "The token is logged.\n",
+            "createdAt": 0, "repoId": "repo_1", "linkedIssues": [],
+            "filePath": "src/auth.rs", "isSecurityVulnerability": true
+        }))
+        .unwrap();
+        let draft = IssueDraft::from_bug(&bug);
+        assert_eq!(draft.title, "Token leaks into logs");
+        assert_eq!(
+            draft.body,
+            "The token is logged.\n\nFile: src/auth.rs\nDetail bug: bug_1"
+        );
+        assert!(draft.security);
+        assert_eq!(draft.state, BugReviewState::Pending);
+    }
+}
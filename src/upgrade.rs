@@ -1,13 +1,51 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write as _};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt as _;
+use std::process;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use anyhow::{Context, Result};
-use axoupdater::{AxoUpdater, UpdateResult};
+use anyhow::{bail, Context, Result};
+use axoupdater::{AxoUpdater, UpdateRequest, UpdateResult};
 use console::{style, Term};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
 
+use crate::api::client::{download_release_asset, HttpOptions};
 use crate::config::storage;
+use crate::exit::Failure;
+use crate::output::render_markdown;
 
 const UPDATE_CHECK_INTERVAL: u64 = 3600; // 1 hour in seconds
 
+/// Set to anything but empty, `0` or `false` to skip auto-update, e.g. in
+/// CI containers without a config file.
+pub const NO_UPDATE_ENV: &str = "DETAIL_NO_UPDATE";
+
+/// Release assets are published under `<this>/v<version>/`.
+const RELEASES_URL: &str = concat!(env!("CARGO_PKG_REPOSITORY"), "/releases/download");
+
+/// Release notes for `v<version>` are at `<this>/v<version>`.
+const RELEASE_NOTES_URL: &str = concat!(env!("CARGO_PKG_REPOSITORY"), "/releases/tag");
+
+/// GitHub's REST endpoint for a repository, e.g. `<this>/usedetail/cli`.
+const GITHUB_API_REPOS_URL: &str = "https://api.github.com/repos";
+
+/// Release notes longer than this are cut short after an update, with a
+/// link to the rest.
+const RELEASE_NOTES_MAX_LINES: usize = 30;
+
+/// The `sha256sum`-format checksum list published with every release.
+const CHECKSUMS_ASSET: &str = "sha256.sum";
+
+/// The installer script the updater downloads and runs on this platform.
+const INSTALLER_ASSET: &str = if cfg!(windows) {
+    "detail-cli-installer.ps1"
+} else {
+    "detail-cli-installer.sh"
+};
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum ManualUpdateOutcome {
     Updated {
@@ -18,6 +56,20 @@ pub enum ManualUpdateOutcome {
     Unavailable,
 }
 
+/// The outcome of `detail update --check`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct UpdateCheck {
+    pub current: String,
+    pub latest: String,
+    pub update_available: bool,
+}
+
+impl UpdateCheck {
+    pub fn release_notes_url(&self) -> String {
+        format!("{RELEASE_NOTES_URL}/v{}", self.latest)
+    }
+}
+
 fn version_strings(result: &UpdateResult) -> (String, String) {
     let old_version = result
         .old_version
@@ -27,22 +79,25 @@ fn version_strings(result: &UpdateResult) -> (String, String) {
     (old_version, new_version)
 }
 
-pub(crate) fn print_update_success_message(term: &Term, old_version: &str, new_version: &str) {
-    let _ = term.write_line("");
-    let _ = term.write_line(&format!("{}", style("─".repeat(60)).dim()));
-    let _ = term.write_line(&format!(
+pub(crate) fn print_update_success_message(
+    term: &mut impl io::Write,
+    old_version: &str,
+    new_version: &str,
+) {
+    let rule = style("─".repeat(60)).dim();
+    let _ = writeln!(term);
+    let _ = writeln!(term, "{rule}");
+    let _ = writeln!(
+        term,
         "{}",
         style(format!(
             "✓ Updated Detail CLI from v{old_version} to v{new_version}"
         ))
         .green()
-    ));
-    let _ = term.write_line(&format!(
-        "{}",
-        style("  Changes will apply on next run").dim()
-    ));
-    let _ = term.write_line(&format!("{}", style("─".repeat(60)).dim()));
-    let _ = term.write_line("");
+    );
+    let _ = writeln!(term, "{}", style("  Changes will apply on next run").dim());
+    let _ = writeln!(term, "{rule}");
+    let _ = writeln!(term);
 }
 
 const fn should_check_for_updates(config: &storage::Config, now: u64) -> bool {
@@ -56,6 +111,17 @@ const fn should_check_for_updates(config: &storage::Config, now: u64) -> bool {
     }
 }
 
+/// Whether an environment flag's value means "on".
+fn env_flag_set(value: &str) -> bool {
+    let value = value.trim();
+    !(value.is_empty() || value == "0" || value.eq_ignore_ascii_case("false"))
+}
+
+/// Whether `DETAIL_NO_UPDATE` turns auto-update off.
+pub fn disabled_by_env() -> bool {
+    env::var(NO_UPDATE_ENV).is_ok_and(|value| env_flag_set(&value))
+}
+
 fn now_unix_seconds() -> Result<u64> {
     Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
 }
@@ -66,6 +132,173 @@ fn load_configured_updater() -> Option<AxoUpdater> {
     Some(updater)
 }
 
+/// The digest listed for `file` in a `sha256sum`-format checksum list.
+fn expected_digest<'a>(checksums: &'a str, file: &str) -> Option<&'a str> {
+    checksums.lines().find_map(|line| {
+        let (digest, name) = line.split_once(char::is_whitespace)?;
+        // `sha256sum` marks binary-mode entries with a leading `*`.
+        (name.trim_start().trim_start_matches('*') == file).then_some(digest)
+    })
+}
+
+/// `spec` as a release version: `x.y.z`, optionally with a `v` prefix or a
+/// pre-release suffix (`1.4.0-rc.1`).
+fn release_version(spec: &str) -> Option<&str> {
+    let version = spec.trim().trim_start_matches('v');
+    let core = version.split_once('-').map_or(version, |(core, _)| core);
+    let parts: Vec<&str> = core.split('.').collect();
+    (parts.len() == 3
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit())))
+    .then_some(version)
+}
+
+/// The installer published for `version`, checked against the release's
+/// checksum list. `Ok(None)` when the release lists no checksum for it;
+/// a mismatch is always an error.
+async fn verified_installer(version: &str) -> Result<Option<Vec<u8>>> {
+    let options = HttpOptions::from_config(&storage::load_config().unwrap_or_default());
+    let base = format!("{RELEASES_URL}/v{version}");
+
+    let Some(checksums) =
+        download_release_asset(&format!("{base}/{CHECKSUMS_ASSET}"), &options).await?
+    else {
+        return Ok(None);
+    };
+    let checksums = String::from_utf8(checksums).context("Release checksum list is not UTF-8")?;
+    let Some(expected) = expected_digest(&checksums, INSTALLER_ASSET) else {
+        return Ok(None);
+    };
+
+    let installer = download_release_asset(&format!("{base}/{INSTALLER_ASSET}"), &options)
+        .await?
+        .with_context(|| format!("Release v{version} has no {INSTALLER_ASSET}"))?;
+    check_digest(&installer, expected)
+        .with_context(|| format!("Refusing to install {INSTALLER_ASSET} from v{version}"))?;
+    Ok(Some(installer))
+}
+
+/// Fail unless `bytes` hash to the hex SHA-256 digest `expected`.
+fn check_digest(bytes: &[u8], expected: &str) -> Result<()> {
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if !actual.eq_ignore_ascii_case(expected) {
+        bail!("Checksum mismatch (expected {expected}, got {actual})");
+    }
+    Ok(())
+}
+
+/// Save a verified installer for the updater to run, in a file created
+/// fresh so nothing could have been written there first. The updater
+/// executes it directly on Unix, so it's made executable. Returns the
+/// path, which the updater needs as UTF-8.
+fn write_installer(installer: &[u8]) -> Result<String> {
+    let path = env::temp_dir().join(format!("{}-{INSTALLER_ASSET}", process::id()));
+    let path = path
+        .to_str()
+        .with_context(|| format!("Installer path {} is not valid UTF-8", path.display()))?
+        .to_string();
+    let _ = fs::remove_file(&path);
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o700);
+    options
+        .open(&path)
+        .and_then(|mut file| {
+            file.write_all(installer)?;
+            file.sync_all()
+        })
+        .with_context(|| format!("Failed to write installer to {path}"))?;
+    Ok(path)
+}
+
+/// Run the updater with the installer saved at `path`, removing it after.
+async fn run_installer(updater: &mut AxoUpdater, path: &str) -> Result<Option<UpdateResult>> {
+    updater.configure_installer_path(path);
+    let result = updater.run().await;
+    let _ = fs::remove_file(path);
+    Ok(result?)
+}
+
+/// What `run_verified` does with a release that publishes no checksum for
+/// its installer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnverifiedRelease {
+    /// Fail, saying how to install it anyway (`detail update`).
+    Refuse,
+    /// Leave it uninstalled (the background check).
+    Skip,
+    /// Let the updater download and run it (`--allow-unverified`).
+    Install,
+}
+
+/// Whether to install v`version` although it publishes no checksum, or an
+/// error if `unverified` refuses it.
+fn install_unverified(
+    version: &str,
+    target: Option<&str>,
+    unverified: UnverifiedRelease,
+) -> Result<bool> {
+    match unverified {
+        UnverifiedRelease::Refuse => {
+            let pin = target
+                .map(|v| format!(" --version {v}"))
+                .unwrap_or_default();
+            bail!(
+                "Release v{version} publishes no checksum for {INSTALLER_ASSET}; \
+                 refusing to install it. Run `detail update{pin} --allow-unverified` to install anyway"
+            );
+        }
+        UnverifiedRelease::Skip => Ok(false),
+        UnverifiedRelease::Install => Ok(true),
+    }
+}
+
+/// Install `target` (or, without one, the newest release if it's newer
+/// than this one), running the installer only after checking it against
+/// the published checksums: the bytes checked are the bytes run.
+/// Releases without a checksum are handled as `unverified` says.
+async fn run_verified(
+    updater: &mut AxoUpdater,
+    target: Option<&str>,
+    unverified: UnverifiedRelease,
+) -> Result<Option<UpdateResult>> {
+    let version = if let Some(target) = target {
+        if target == crate::VERSION {
+            return Ok(None);
+        }
+        target.to_string()
+    } else {
+        if !updater.is_update_needed().await? {
+            return Ok(None);
+        }
+        let Some(version) = updater.query_new_version().await?.map(ToString::to_string) else {
+            return Ok(None);
+        };
+        version
+    };
+
+    let installer = verified_installer(&version).await?;
+    // Pin the updater to the release just checked: the requested one, or
+    // the newest as of the check, in case another was published since.
+    updater.configure_version_specifier(UpdateRequest::SpecificVersion(version.clone()));
+
+    if let Some(installer) = installer {
+        let path = write_installer(&installer)?;
+        return run_installer(updater, &path).await;
+    }
+    if !install_unverified(&version, target, unverified)? {
+        return Ok(None);
+    }
+    let _ = Term::stderr().write_line(&format!(
+        "{} installing v{version} without checksum verification (--allow-unverified).",
+        style("Warning:").yellow().bold()
+    ));
+    updater.use_release_installer();
+    Ok(updater.run().await?)
+}
+
 fn record_update_check_now() -> Result<()> {
     let now = now_unix_seconds()?;
     storage::update_config(|config| {
@@ -104,17 +337,31 @@ pub async fn auto_update() -> Result<()> {
     }
 
     // Perform the update with the lock held to prevent concurrent
-    // binary replacement.
+    // binary replacement. A release that can't be reached or verified is
+    // skipped quietly; an explicit `detail update` reports why.
     if let Some(mut updater) = load_configured_updater() {
-        if let Ok(Some(result)) = updater.run().await {
-            print_update_success(&result);
+        if let Ok(Some(result)) = run_verified(&mut updater, None, UnverifiedRelease::Skip).await {
+            print_update_success(&result).await;
         }
     }
 
     Ok(())
 }
 
-pub async fn update_now() -> Result<ManualUpdateOutcome> {
+/// Update now: to `version` if given (which may be older than this one),
+/// otherwise to the newest release.
+pub async fn update_now(
+    version: Option<&str>,
+    allow_unverified: bool,
+) -> Result<ManualUpdateOutcome> {
+    let target = version
+        .map(|spec| {
+            release_version(spec).with_context(|| {
+                Failure::usage(format!("Invalid version '{spec}' (expected x.y.z)"))
+            })
+        })
+        .transpose()?;
+
     // Acquire the update lock to prevent concurrent binary writes.
     // Block (rather than skip) since this is an explicit user request.
     let _lock = storage::acquire_update_lock()?;
@@ -125,8 +372,12 @@ pub async fn update_now() -> Result<ManualUpdateOutcome> {
         return Ok(ManualUpdateOutcome::Unavailable);
     };
 
-    Ok(updater
-        .run()
+    let unverified = if allow_unverified {
+        UnverifiedRelease::Install
+    } else {
+        UnverifiedRelease::Refuse
+    };
+    Ok(run_verified(&mut updater, target, unverified)
         .await
         .context("Failed to run updater for Detail CLI")?
         .map_or(ManualUpdateOutcome::AlreadyUpToDate, |result| {
@@ -138,27 +389,186 @@ pub async fn update_now() -> Result<ManualUpdateOutcome> {
         }))
 }
 
-fn print_update_success(result: &UpdateResult) {
+/// Look up the newest release without installing it. `None` when this
+/// installation can't self-update.
+pub async fn check_latest() -> Result<Option<UpdateCheck>> {
+    let Some(mut updater) = load_configured_updater() else {
+        return Ok(None);
+    };
+    let update_available = updater
+        .is_update_needed()
+        .await
+        .context("Failed to check for Detail CLI updates")?;
+    let latest = updater
+        .query_new_version()
+        .await
+        .context("Failed to check for Detail CLI updates")?
+        .map_or_else(|| crate::VERSION.to_string(), ToString::to_string);
+    Ok(Some(UpdateCheck {
+        current: crate::VERSION.to_string(),
+        latest,
+        update_available,
+    }))
+}
+
+/// The markdown notes of the GitHub release for `version`, if it has any.
+async fn fetch_release_notes(version: &str) -> Result<Option<String>> {
+    let Some(repo) = env!("CARGO_PKG_REPOSITORY").strip_prefix("https://github.com/") else {
+        return Ok(None);
+    };
+    let url = format!("{GITHUB_API_REPOS_URL}/{repo}/releases/tags/v{version}");
+    let options = HttpOptions::from_config(&storage::load_config().unwrap_or_default());
+    let Some(body) = download_release_asset(&url, &options).await? else {
+        return Ok(None);
+    };
+    let release: Value = serde_json::from_slice(&body).context("Invalid release JSON")?;
+    Ok(release
+        .get("body")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|notes| !notes.is_empty())
+        .map(ToString::to_string))
+}
+
+/// `notes` cut to `RELEASE_NOTES_MAX_LINES`, ending with a link to the
+/// full notes when anything was cut.
+fn abridge_release_notes(notes: &str, version: &str) -> String {
+    let lines: Vec<&str> = notes.lines().collect();
+    if lines.len() <= RELEASE_NOTES_MAX_LINES {
+        return notes.to_string();
+    }
+    let kept = lines
+        .get(..RELEASE_NOTES_MAX_LINES)
+        .unwrap_or_default()
+        .join("\n");
+    format!("{kept}\n\n*…and more: {RELEASE_NOTES_URL}/v{version}*")
+}
+
+/// Show what changed in `version`, below the update banner. Best effort:
+/// notes that can't be fetched are skipped silently.
+pub(crate) async fn print_release_notes(term: &mut impl io::Write, version: &str) {
+    let Ok(Some(notes)) = fetch_release_notes(version).await else {
+        return;
+    };
+    let _ = writeln!(
+        term,
+        "{}",
+        style(format!("What's new in v{version}")).bold()
+    );
+    let _ = write!(
+        term,
+        "{}",
+        render_markdown(&abridge_release_notes(&notes, version))
+    );
+    let _ = writeln!(term);
+}
+
+async fn print_update_success(result: &UpdateResult) {
     let (old_version, new_version) = version_strings(result);
-    print_update_success_message(&Term::stderr(), &old_version, &new_version);
+    let mut term = Term::stderr();
+    print_update_success_message(&mut term, &old_version, &new_version);
+    print_release_notes(&mut term, &new_version).await;
 }
 
 #[cfg(test)]
 mod tests {
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt as _;
+
     use crate::config::storage::Config;
 
     use super::*;
 
     fn base_config() -> Config {
         Config {
-            api_url: None,
-            app_url: None,
             check_for_updates: true,
-            last_update_check: None,
-            api_token: None,
+            ..Config::default()
         }
     }
 
+    #[test]
+    fn release_version_accepts_semver_forms() {
+        assert_eq!(release_version("1.4.0"), Some("1.4.0"));
+        assert_eq!(release_version("v1.4.0"), Some("1.4.0"));
+        assert_eq!(release_version("1.4.0-rc.1"), Some("1.4.0-rc.1"));
+        assert_eq!(release_version("1.4"), None);
+        assert_eq!(release_version("1.4.x"), None);
+        assert_eq!(release_version("latest"), None);
+    }
+
+    #[test]
+    fn long_release_notes_are_abridged() {
+        let short = "## Fixes\n- one";
+        assert_eq!(abridge_release_notes(short, "1.0.0"), short);
+
+        let long: Vec<String> = (0..50).map(|i| format!("- change {i}")).collect();
+        let abridged = abridge_release_notes(&long.join("\n"), "1.0.0");
+        assert!(abridged.contains("- change 29"));
+        assert!(!abridged.contains("- change 30"));
+        assert!(abridged.ends_with("/releases/tag/v1.0.0*"));
+    }
+
+    #[test]
+    fn release_notes_link_to_the_latest_tag() {
+        let check = UpdateCheck {
+            current: "0.2.6".to_string(),
+            latest: "0.3.0".to_string(),
+            update_available: true,
+        };
+        assert_eq!(
+            check.release_notes_url(),
+            "https://github.com/usedetail/cli/releases/tag/v0.3.0"
+        );
+    }
+
+    #[test]
+    fn expected_digest_reads_sha256sum_lines() {
+        let checksums = "\
+            abc123  detail-cli-x86_64-unknown-linux-gnu.tar.xz\n\
+            def456 *detail-cli-installer.sh\n";
+        assert_eq!(
+            expected_digest(checksums, "detail-cli-installer.sh"),
+            Some("def456")
+        );
+        assert_eq!(
+            expected_digest(checksums, "detail-cli-x86_64-unknown-linux-gnu.tar.xz"),
+            Some("abc123")
+        );
+        assert_eq!(expected_digest(checksums, "detail-cli-installer.ps1"), None);
+    }
+
+    #[test]
+    fn check_digest_accepts_only_the_listed_hash() {
+        let installer = b"#!/bin/sh\necho install\n";
+        let digest = format!("{:x}", Sha256::digest(installer));
+        assert!(check_digest(installer, &digest).is_ok());
+        assert!(check_digest(installer, &digest.to_uppercase()).is_ok());
+        let err = check_digest(b"#!/bin/sh\necho tampered\n", &digest).unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+    }
+
+    #[test]
+    fn background_check_skips_a_release_without_checksums() {
+        assert!(!install_unverified("1.2.0", None, UnverifiedRelease::Skip).unwrap());
+        assert!(install_unverified("1.2.0", None, UnverifiedRelease::Install).unwrap());
+        let err = install_unverified("1.2.0", Some("1.2.0"), UnverifiedRelease::Refuse)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("detail update --version 1.2.0 --allow-unverified"));
+    }
+
+    #[test]
+    fn written_installer_holds_the_verified_bytes() {
+        let path = write_installer(b"verified").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"verified");
+        #[cfg(unix)]
+        {
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn should_skip_when_updates_disabled() {
         let mut config = base_config();
@@ -166,6 +576,16 @@ mod tests {
         assert!(!should_check_for_updates(&config, 10_000));
     }
 
+    #[test]
+    fn no_update_env_accepts_common_truthy_values() {
+        assert!(env_flag_set("1"));
+        assert!(env_flag_set("true"));
+        assert!(env_flag_set("yes"));
+        assert!(!env_flag_set(""));
+        assert!(!env_flag_set("0"));
+        assert!(!env_flag_set("FALSE"));
+    }
+
     #[test]
     fn should_check_on_first_run_when_enabled() {
         let config = base_config();
@@ -0,0 +1,35 @@
+//! The process-wide cap on API requests in flight, set with
+//! `--concurrency` or `concurrency` in config. Every API call waits for a
+//! slot, so nested parallel work (pages within repos, bulk closes) can't
+//! exceed it either.
+
+use std::sync::OnceLock;
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Requests in flight when neither `--concurrency` nor `concurrency` is set.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+static LIMIT: OnceLock<usize> = OnceLock::new();
+static SLOTS: OnceLock<Semaphore> = OnceLock::new();
+
+/// Set the cap. Only the first call counts, so call it before any request.
+pub fn set_limit(limit: usize) {
+    let _ = LIMIT.set(limit.max(1));
+}
+
+/// How many requests may be in flight, and so how many items parallel
+/// operations should work on at once.
+pub fn limit() -> usize {
+    *LIMIT.get_or_init(|| DEFAULT_CONCURRENCY)
+}
+
+/// Wait for a free slot; it's released when the permit is dropped.
+pub async fn acquire() -> Option<SemaphorePermit<'static>> {
+    // The semaphore is never closed, so this only fails in theory.
+    SLOTS
+        .get_or_init(|| Semaphore::new(limit()))
+        .acquire()
+        .await
+        .ok()
+}
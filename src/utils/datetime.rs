@@ -1,27 +1,142 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use chrono_tz::Tz;
+
+/// Whether `--relative-dates` (or `relative_dates` in config) is in effect.
+static RELATIVE_DATES: OnceLock<bool> = OnceLock::new();
+
+/// Show creation times as "3 days ago". Only the first call counts.
+pub fn set_relative_dates(enabled: bool) {
+    let _ = RELATIVE_DATES.set(enabled);
+}
+
+fn relative_dates() -> bool {
+    RELATIVE_DATES.get().copied().unwrap_or(false)
+}
+
+/// Zone timestamps are shown in: `local` or an IANA name like
+/// `Europe/Berlin` or `UTC`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Timezone {
+    /// The machine's local timezone.
+    #[default]
+    Local,
+    Named(Tz),
+}
+
+impl FromStr for Timezone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("local") {
+            return Ok(Self::Local);
+        }
+        s.parse::<Tz>()
+            .map(Self::Named)
+            .map_err(|err| format!("{err} (expected `local` or an IANA name like `Europe/Berlin`)"))
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => f.write_str("local"),
+            Self::Named(tz) => f.write_str(tz.name()),
+        }
+    }
+}
+
+/// Zone chosen with `--tz` (or `timezone` in config).
+static TIMEZONE: OnceLock<Timezone> = OnceLock::new();
 
-/// Format a UTC timestamp (in milliseconds) in the machine's local timezone.
+/// Show timestamps in `zone`. Only the first call counts.
+pub fn set_timezone(zone: Timezone) {
+    let _ = TIMEZONE.set(zone);
+}
+
+fn timezone() -> Timezone {
+    TIMEZONE.get().copied().unwrap_or_default()
+}
+
+/// Format a UTC timestamp (in milliseconds) in the `--tz` zone, by default
+/// the machine's local one.
 fn format_timestamp(timestamp_ms: i64, fmt: &str) -> String {
+    format_timestamp_in(timestamp_ms, fmt, timezone())
+}
+
+fn format_timestamp_in(timestamp_ms: i64, fmt: &str, zone: Timezone) -> String {
     // `from_timestamp_millis` floors toward negative infinity, so timestamps
     // in (-1000, 0) correctly land in the second before the epoch instead of
     // collapsing to epoch via integer-division truncation.
     DateTime::from_timestamp_millis(timestamp_ms).map_or_else(
         || "-".into(),
-        |dt| dt.with_timezone(&Local).format(fmt).to_string(),
+        |dt| match zone {
+            Timezone::Local => dt.with_timezone(&Local).format(fmt).to_string(),
+            Timezone::Named(tz) => dt.with_timezone(&tz).format(fmt).to_string(),
+        },
     )
 }
 
-/// Format a timestamp (in milliseconds) as a local date string (YYYY-MM-DD)
+/// Format a timestamp (in milliseconds) as a date string (YYYY-MM-DD)
 pub fn format_date(timestamp_ms: i64) -> String {
     format_timestamp(timestamp_ms, "%Y-%m-%d")
 }
 
-/// Format a timestamp (in milliseconds) as a local datetime string.
+/// Format a timestamp (in milliseconds) as a datetime string with its zone.
 pub fn format_datetime(timestamp_ms: i64) -> String {
     format_timestamp(timestamp_ms, "%Y-%m-%d %H:%M:%S %Z")
 }
 
+/// `n unit` with the unit pluralized as needed, e.g. `1 day`, `3 days`.
+fn count(n: i64, unit: &str) -> String {
+    if n == 1 {
+        format!("1 {unit}")
+    } else {
+        format!("{n} {unit}s")
+    }
+}
+
+/// How long before `now` the timestamp (in milliseconds) was, in words:
+/// `just now`, `5 minutes ago`, `3 days ago`, `2 years ago`. Rounds down
+/// to the largest whole unit; months are 30 days and years 365. Times
+/// after `now` read `in 3 days`.
+pub fn humanize_since(timestamp_ms: i64, now: DateTime<Utc>) -> String {
+    let Some(then) = DateTime::from_timestamp_millis(timestamp_ms) else {
+        return "-".into();
+    };
+    let elapsed = now.signed_duration_since(then);
+    let span = elapsed.abs();
+    let amount = match span.num_days() {
+        0 if span.num_hours() > 0 => count(span.num_hours(), "hour"),
+        0 if span.num_minutes() > 0 => count(span.num_minutes(), "minute"),
+        0 => return "just now".into(),
+        days @ 1..30 => count(days, "day"),
+        days @ 30..365 => count(days / 30, "month"),
+        days => count(days / 365, "year"),
+    };
+    if elapsed < Duration::zero() {
+        format!("in {amount}")
+    } else {
+        format!("{amount} ago")
+    }
+}
+
+/// `absolute(timestamp_ms)`, or how long ago it was with
+/// `--relative-dates`. For creation times in list and show output; exports
+/// keep absolute dates, since they're read later.
+pub fn format_relative_or(timestamp_ms: i64, absolute: fn(i64) -> String) -> String {
+    if relative_dates() {
+        humanize_since(timestamp_ms, Utc::now())
+    } else {
+        absolute(timestamp_ms)
+    }
+}
+
 /// Parse a `--since` / `--until` value into a UTC instant relative to `now`.
 ///
 /// Accepted forms:
@@ -53,7 +168,7 @@ pub fn parse_time_spec(s: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
 /// Parse `<n><unit>` where unit is one of `s|m|h|d|w` (case-insensitive).
 /// Returns `None` when the input doesn't match — callers fall back to
 /// absolute-date parsing.
-fn parse_relative_duration(s: &str) -> Option<Duration> {
+pub fn parse_relative_duration(s: &str) -> Option<Duration> {
     let unit = s.chars().last()?;
     if !unit.is_ascii_alphabetic() {
         return None;
@@ -135,6 +250,82 @@ mod tests {
         assert_ne!(format_datetime(-1), format_datetime(0));
     }
 
+    // ── timezone ─────────────────────────────────────────────────────
+
+    #[test]
+    fn timezone_parses_local_and_iana_names() {
+        assert_eq!("local".parse(), Ok(Timezone::Local));
+        assert_eq!("LOCAL".parse(), Ok(Timezone::Local));
+        assert_eq!("UTC".parse(), Ok(Timezone::Named(Tz::UTC)));
+        assert_eq!(
+            "Europe/Berlin".parse(),
+            Ok(Timezone::Named(Tz::Europe__Berlin))
+        );
+        assert!("Mars/Olympus".parse::<Timezone>().is_err());
+    }
+
+    #[test]
+    fn format_timestamp_in_named_zone() {
+        // 2025-01-15 00:00:00 UTC
+        let ts = 1_736_899_200_000;
+        let fmt = "%Y-%m-%d %H:%M:%S %Z";
+        assert_eq!(
+            format_timestamp_in(ts, fmt, Timezone::Named(Tz::UTC)),
+            "2025-01-15 00:00:00 UTC"
+        );
+        assert_eq!(
+            format_timestamp_in(ts, fmt, Timezone::Named(Tz::America__New_York)),
+            "2025-01-14 19:00:00 EST"
+        );
+        assert_eq!(
+            format_timestamp_in(ts, fmt, Timezone::Local),
+            expected_local(ts, fmt)
+        );
+    }
+
+    // ── humanize_since ───────────────────────────────────────────────
+
+    /// `fixed_now()` minus `seconds`, in milliseconds.
+    fn ago(seconds: i64) -> i64 {
+        (fixed_now().timestamp() - seconds) * 1000
+    }
+
+    #[test]
+    fn humanize_since_under_a_minute_is_just_now() {
+        assert_eq!(humanize_since(ago(0), fixed_now()), "just now");
+        assert_eq!(humanize_since(ago(59), fixed_now()), "just now");
+    }
+
+    #[test]
+    fn humanize_since_rounds_down_to_the_largest_unit() {
+        assert_eq!(humanize_since(ago(60), fixed_now()), "1 minute ago");
+        assert_eq!(humanize_since(ago(59 * 60), fixed_now()), "59 minutes ago");
+        assert_eq!(
+            humanize_since(ago(2 * 3600 + 59), fixed_now()),
+            "2 hours ago"
+        );
+        assert_eq!(humanize_since(ago(86_400), fixed_now()), "1 day ago");
+        assert_eq!(
+            humanize_since(ago(3 * 86_400 + 3600), fixed_now()),
+            "3 days ago"
+        );
+        assert_eq!(humanize_since(ago(45 * 86_400), fixed_now()), "1 month ago");
+        assert_eq!(
+            humanize_since(ago(800 * 86_400), fixed_now()),
+            "2 years ago"
+        );
+    }
+
+    #[test]
+    fn humanize_since_future_reads_in() {
+        assert_eq!(humanize_since(ago(-2 * 86_400), fixed_now()), "in 2 days");
+    }
+
+    #[test]
+    fn humanize_since_out_of_range_returns_dash() {
+        assert_eq!(humanize_since(i64::MIN, fixed_now()), "-");
+    }
+
     // ── parse_time_spec ──────────────────────────────────────────────
 
     fn fixed_now() -> DateTime<Utc> {
@@ -0,0 +1,28 @@
+//! File-writing helpers.
+
+use std::fs::{self, File};
+use std::io::Write as _;
+use std::path::Path;
+use std::process;
+
+use anyhow::{Context, Result};
+
+/// Write `content` to `path` through a temporary file in the same
+/// directory and a rename, so readers never see a half-written file.
+pub fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let name = path
+        .file_name()
+        .with_context(|| format!("{} is not a file path", path.display()))?;
+    let temp = path.with_file_name(format!(".{}.{}.tmp", name.to_string_lossy(), process::id()));
+    let written = File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp, path));
+    if let Err(err) = written {
+        let _ = fs::remove_file(&temp);
+        return Err(err).with_context(|| format!("Failed to write {}", path.display()));
+    }
+    Ok(())
+}
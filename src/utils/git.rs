@@ -1,6 +1,9 @@
 use anyhow::{bail, Result};
+use std::path::PathBuf;
 use std::process::Command;
 
+use crate::exit::Failure;
+
 /// Extract `owner/repo` from a GitHub remote URL.
 ///
 /// Supports HTTPS (`https://github.com/owner/repo.git`),
@@ -95,16 +98,133 @@ pub fn infer_repo_from_git_remote() -> Result<String> {
     )
 }
 
+/// The top-level directory of the git work tree containing the current
+/// directory, or `None` outside one.
+pub fn repo_root() -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .ok()?;
+    let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !root.is_empty()).then(|| PathBuf::from(root))
+}
+
+/// Where git looks for hooks in the current repository, honoring
+/// `core.hooksPath` and worktrees, or `None` outside a repository.
+pub fn hooks_dir() -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .output()
+        .ok()?;
+    let dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !dir.is_empty()).then(|| PathBuf::from(dir))
+}
+
+/// The current directory relative to the top of the work tree, with a
+/// trailing `/` (empty at the top), or `None` outside a work tree.
+pub fn repo_prefix() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-prefix"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The abbreviated hash of `HEAD`, or `None` outside a repository or before
+/// the first commit.
+pub fn head_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    let sha = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !sha.is_empty()).then_some(sha)
+}
+
+/// Whether commit `sha` is in the local repository's object store.
+pub fn has_commit(sha: &str) -> bool {
+    Command::new("git")
+        .args(["cat-file", "-e", &format!("{sha}^{{commit}}")])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
 /// If `explicit` is `Some`, return it. Otherwise try to infer from the git
 /// remote. Wraps the inference error to tell the user to supply the argument.
 pub fn resolve_repo_arg(explicit: Option<&str>) -> Result<String> {
     explicit.map_or_else(infer_repo_from_git_remote, |r| Ok(r.to_string()))
 }
 
+/// A `git config` value, or `None` when it's unset or git isn't available.
+fn git_config(key: &str) -> Option<String> {
+    let output = Command::new("git").args(["config", key]).output().ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+/// The author strings a commit by this identity may carry: the name, the
+/// email, its local part, and the login in a GitHub noreply address
+/// (`12345+login@users.noreply.github.com`).
+fn author_aliases(name: Option<&str>, email: Option<&str>) -> Vec<String> {
+    let mut aliases: Vec<String> = name.into_iter().map(str::to_string).collect();
+    if let Some(email) = email {
+        aliases.push(email.to_string());
+        if let Some((local, _)) = email.split_once('@') {
+            let login = local.split_once('+').map_or(local, |(_, login)| login);
+            aliases.push(local.to_string());
+            aliases.push(login.to_string());
+        }
+    }
+    aliases.sort_unstable_by_key(|alias| alias.to_ascii_lowercase());
+    aliases.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    aliases
+}
+
+/// The author strings that identify the local git user (`user.name` and
+/// `user.email`), for `bugs list --mine`.
+pub fn local_author_aliases() -> Result<Vec<String>> {
+    let aliases = author_aliases(
+        git_config("user.name").as_deref(),
+        git_config("user.email").as_deref(),
+    );
+    if aliases.is_empty() {
+        bail!(Failure::usage(
+            "--mine needs git's user.name or user.email; set one with `git config --global user.email <email>`, or pass --introduced-by"
+        ));
+    }
+    Ok(aliases)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // ── author_aliases ──────────────────────────────────────────────
+
+    #[test]
+    fn author_aliases_include_noreply_login() {
+        assert_eq!(
+            author_aliases(
+                Some("Alice Smith"),
+                Some("12345+alice@users.noreply.github.com")
+            ),
+            [
+                "12345+alice",
+                "12345+alice@users.noreply.github.com",
+                "alice",
+                "Alice Smith",
+            ]
+        );
+        assert_eq!(
+            author_aliases(None, Some("Alice@acme.com")),
+            ["Alice", "Alice@acme.com"]
+        );
+        assert!(author_aliases(None, None).is_empty());
+    }
+
     // ── parse_github_remote_url ─────────────────────────────────────
 
     #[test]
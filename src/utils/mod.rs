@@ -1,4 +1,9 @@
+pub mod concurrency;
 pub mod datetime;
+pub mod files;
 pub mod git;
 pub mod pagination;
 pub mod repos;
+pub mod secret;
+pub mod term_caps;
+pub mod text;
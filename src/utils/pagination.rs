@@ -1,8 +1,83 @@
+use std::future::Future;
+
+use anyhow::{bail, Result};
+use futures_util::{stream, Stream, StreamExt as _, TryStreamExt as _};
+
+use crate::utils::concurrency;
+
 /// Convert page number and limit to offset for pagination
 pub const fn page_to_offset(page: u32, limit: u32) -> u32 {
     (page - 1).saturating_mul(limit)
 }
 
+/// Offsets of the pages after the first, stepping by the first page's
+/// length (the server may cap pages below the requested size).
+fn remaining_offsets(first_page_len: usize, total: usize) -> Vec<u32> {
+    if first_page_len == 0 {
+        return Vec::new();
+    }
+    (first_page_len..total)
+        .step_by(first_page_len)
+        .map_while(|offset| u32::try_from(offset).ok())
+        .collect()
+}
+
+/// Fetch a whole collection. `fetch(offset)` returns one page and the
+/// collection's total; the first page is fetched alone to learn the total,
+/// then the rest concurrently (up to `--concurrency` at a time), stitched
+/// back in order. A total over `max_items` fails after the first page
+/// rather than fetching without end.
+pub async fn fetch_all_pages<T, F, Fut>(max_items: usize, mut fetch: F) -> Result<Vec<T>>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<(Vec<T>, usize)>>,
+{
+    let (mut items, total) = fetch(0).await?;
+    if total > max_items {
+        bail!("The API reported {total} results, more than the {max_items} fetched at most");
+    }
+    let offsets = remaining_offsets(items.len(), total);
+    let pages: Vec<(Vec<T>, usize)> = stream::iter(offsets)
+        .map(fetch)
+        .buffered(concurrency::limit())
+        .try_collect()
+        .await?;
+    for (page, _) in pages {
+        items.extend(page);
+    }
+    Ok(items)
+}
+
+/// Walk a collection lazily, one page at a time. `fetch(offset)` returns
+/// one page and the collection's total, as for `fetch_all_pages`; the next
+/// page is requested only once the stream has yielded the previous one.
+pub fn paged<T, F, Fut>(fetch: F) -> impl Stream<Item = Result<T>>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<(Vec<T>, usize)>>,
+{
+    stream::try_unfold((fetch, Some(0)), |(mut fetch, offset)| async move {
+        let Some(offset) = offset else {
+            return anyhow::Ok(None);
+        };
+        let (items, total) = fetch(offset).await?;
+        let fetched = usize::try_from(offset)
+            .unwrap_or(usize::MAX)
+            .saturating_add(items.len());
+        // An empty page ends the walk even if the total promised more.
+        let next = if items.is_empty() || fetched >= total {
+            None
+        } else {
+            u32::try_from(fetched).ok()
+        };
+        anyhow::Ok(Some((
+            stream::iter(items.into_iter().map(anyhow::Ok)),
+            (fetch, next),
+        )))
+    })
+    .try_flatten()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -26,4 +101,62 @@ mod tests {
     fn page_to_offset_limit_one() {
         assert_eq!(page_to_offset(5, 1), 4);
     }
+
+    #[test]
+    fn remaining_offsets_step_by_first_page() {
+        assert_eq!(remaining_offsets(100, 350), [100, 200, 300]);
+        assert!(remaining_offsets(100, 100).is_empty());
+        assert!(remaining_offsets(0, 50).is_empty());
+    }
+
+    #[tokio::test]
+    async fn fetch_all_pages_keeps_page_order() {
+        let items: Vec<u32> = (0..250).collect();
+        let all = fetch_all_pages(1000, |offset| {
+            let start = usize::try_from(offset).unwrap();
+            let page = items[start..(start + 100).min(items.len())].to_vec();
+            async move { Ok((page, 250)) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(all, items);
+    }
+
+    #[tokio::test]
+    async fn fetch_all_pages_refuses_totals_over_the_cap() {
+        let mut calls = 0;
+        let result: Result<Vec<u32>> = fetch_all_pages(100, |_| {
+            calls += 1;
+            async { Ok((vec![0; 100], 250)) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[tokio::test]
+    async fn paged_fetches_pages_as_the_stream_is_read() {
+        let items: Vec<u32> = (0..250).collect();
+        let mut offsets = Vec::new();
+        let all: Vec<u32> = paged(|offset| {
+            offsets.push(offset);
+            let start = usize::try_from(offset).unwrap();
+            let page = items[start..(start + 100).min(items.len())].to_vec();
+            async move { Ok((page, 250)) }
+        })
+        .try_collect()
+        .await
+        .unwrap();
+        assert_eq!(all, items);
+        assert_eq!(offsets, [0, 100, 200]);
+    }
+
+    #[tokio::test]
+    async fn paged_stops_on_an_empty_page() {
+        let all: Vec<u32> = paged(|_| async { Ok((Vec::new(), 10)) })
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(all.is_empty());
+    }
 }
@@ -1,33 +1,106 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+
 use anyhow::{bail, Context, Result};
+use console::{style, Term};
+use serde::{Deserialize, Serialize};
 
 use crate::api::client::ApiClient;
-use crate::api::types::{Repo, RepoId};
+use crate::api::types::{Repo, RepoId, ReposResponse};
+use crate::config::storage::{cache_dir, load_config, RepoShortcut};
+use crate::exit::Failure;
+
+async fn fetch_repo_page(client: &ApiClient, offset: u32) -> Result<ReposResponse> {
+    client
+        .list_repos(client.pagination().page_size, offset)
+        .await
+        .context("Failed to fetch repositories while resolving identifier")
+}
 
-/// Page size used when paginating through repos to resolve identifiers.
-const REPO_PAGE_SIZE: u32 = 100;
+/// How long a cached repo list is used before it is fetched again.
+const REPO_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// The repo list as last fetched, stored in the user cache directory so
+/// every `bugs` invocation doesn't paginate the whole list again.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedRepos {
+    /// Token and org the list was fetched with; see `request_scope`.
+    scope: String,
+    /// Epoch millis.
+    fetched_at: i64,
+    repos: Vec<Repo>,
+}
 
-/// Fetch all repos by paginating through the API.
-pub async fn fetch_all_repos(client: &ApiClient) -> Result<Vec<Repo>> {
-    let mut all_repos = Vec::new();
-    let mut offset = 0;
+fn repo_cache_path() -> Result<PathBuf> {
+    cache_dir().map(|dir| dir.join("repos.json"))
+}
 
-    loop {
-        let repos = client
-            .list_repos(REPO_PAGE_SIZE, offset)
-            .await
-            .context("Failed to fetch repositories while resolving identifier")?;
+/// The cached repo list, if it belongs to `scope` and is younger than
+/// `REPO_CACHE_TTL` at `now`.
+fn fresh_cached_repos(contents: &str, scope: &str, now: i64) -> Option<Vec<Repo>> {
+    let cached: CachedRepos = serde_json::from_str(contents).ok()?;
+    let ttl = i64::try_from(REPO_CACHE_TTL.as_millis()).unwrap_or(i64::MAX);
+    let age = now.checked_sub(cached.fetched_at)?;
+    (cached.scope == scope && (0..ttl).contains(&age)).then_some(cached.repos)
+}
+
+/// Fetch every repo, starting from `first` when the first page is already
+/// in hand, and refresh the cache. Writing the cache is best effort:
+/// failing to save it only costs a refetch next time.
+async fn fetch_and_cache_repos(
+    client: &ApiClient,
+    first: Option<ReposResponse>,
+) -> Result<Vec<Repo>> {
+    let repos = client
+        .list_all_repos_from(first)
+        .await
+        .context("Failed to fetch repositories while resolving identifier")?;
+    let cached = CachedRepos {
+        scope: client.cache_scope().to_string(),
+        fetched_at: chrono::Utc::now().timestamp_millis(),
+        repos,
+    };
+    if let (Ok(path), Ok(json)) = (repo_cache_path(), serde_json::to_string(&cached)) {
+        let _ = fs::write(path, json);
+    }
+    Ok(cached.repos)
+}
 
-        let total = usize::try_from(repos.total.max(0)).unwrap_or(0);
-        let page_size = repos.repos.len();
-        all_repos.extend(repos.repos);
+/// The cached repo list, unless it is stale or `--refresh` was passed.
+fn read_repo_cache(client: &ApiClient) -> Option<Vec<Repo>> {
+    if client.refresh() {
+        return None;
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    let contents = fs::read_to_string(repo_cache_path().ok()?).ok()?;
+    fresh_cached_repos(&contents, client.cache_scope(), now)
+}
 
-        if page_size == 0 || all_repos.len() >= total {
-            break;
-        }
-        offset += u32::try_from(page_size).unwrap_or(REPO_PAGE_SIZE);
+/// All accessible repos, from the cache when it is fresh and `--refresh`
+/// wasn't passed. The flag says whether the list came from the cache.
+async fn cached_repos(client: &ApiClient) -> Result<(Vec<Repo>, bool)> {
+    if let Some(repos) = read_repo_cache(client) {
+        return Ok((repos, true));
     }
+    Ok((fetch_and_cache_repos(client, None).await?, false))
+}
 
-    Ok(all_repos)
+/// Resolve identifiers against the cached repo list, refetching it once if
+/// any don't match: the repo may have been added since the list was cached.
+async fn resolve_with_repo_cache<T>(
+    client: &ApiClient,
+    resolve: impl Fn(&[Repo]) -> Result<T>,
+) -> Result<T> {
+    let (repos, from_cache) = cached_repos(client).await?;
+    match resolve(&repos) {
+        Ok(resolved) => Ok(resolved),
+        Err(_) if from_cache => resolve(&fetch_and_cache_repos(client, None).await?),
+        Err(err) => Err(err),
+    }
 }
 
 /// Validate that a slash-containing identifier has exactly one slash with
@@ -35,9 +108,9 @@ pub async fn fetch_all_repos(client: &ApiClient) -> Result<Vec<Repo>> {
 pub fn validate_owner_repo_format(identifier: &str) -> Result<()> {
     let parts: Vec<&str> = identifier.split('/').collect();
     if parts.len() != 2 || parts[0].trim().is_empty() || parts[1].trim().is_empty() {
-        bail!(
+        bail!(Failure::usage(
             "Invalid repository format. Please use owner/repo (e.g., 'usedetail/cli') or just the repo name. Run 'detail repos list' to see your repositories."
-        );
+        ));
     }
     Ok(())
 }
@@ -48,9 +121,9 @@ pub fn match_repo_by_name(name: &str, repos: &[Repo]) -> Result<RepoId> {
     let matching: Vec<_> = repos.iter().filter(|r| r.name == name).collect();
 
     match matching.len() {
-        0 => bail!(
+        0 => bail!(Failure::not_found(format!(
             "Repository '{name}' not found. Run 'detail repos list' to see your repositories."
-        ),
+        ))),
         1 => Ok(matching[0].id.clone()),
         _ => {
             let repo_list: Vec<String> = matching
@@ -67,10 +140,195 @@ pub fn match_repo_by_name(name: &str, repos: &[Repo]) -> Result<RepoId> {
     }
 }
 
-/// Resolve owner/repo or repo name to repo ID, searching across all accessible repos.
+/// Expand an `@name` shortcut from the `[shortcuts]` config table into the
+/// repo identifiers it maps to. Identifiers without a leading `@` pass
+/// through unchanged as a single-element list.
+pub fn expand_repo_shortcut(
+    identifier: &str,
+    shortcuts: &BTreeMap<String, RepoShortcut>,
+) -> Result<Vec<String>> {
+    let identifier = identifier.trim();
+    let Some(name) = identifier.strip_prefix('@') else {
+        return Ok(vec![identifier.to_string()]);
+    };
+    let Some(shortcut) = shortcuts.get(name) else {
+        let known: Vec<String> = shortcuts.keys().map(|k| format!("@{k}")).collect();
+        if known.is_empty() {
+            bail!(Failure::not_found(format!(
+                "Unknown repo shortcut '{identifier}'. Define it under [shortcuts] in config.toml."
+            )));
+        }
+        bail!(Failure::not_found(format!(
+            "Unknown repo shortcut '{identifier}'. Known shortcuts: {}",
+            known.join(", ")
+        )));
+    };
+    if shortcut.repos().is_empty() {
+        bail!("Repo shortcut '{identifier}' does not map to any repositories.");
+    }
+    Ok(shortcut.repos().to_vec())
+}
+
+/// Resolve owner/repo, repo name, or an `@name` shortcut to the repos it
+/// refers to, searching across all accessible repos. Each entry pairs the
+/// expanded identifier (for messages) with its repo ID.
+pub async fn resolve_repo_ids(
+    client: &ApiClient,
+    repo_identifier: &str,
+) -> Result<Vec<(String, RepoId)>> {
+    let config = load_config()?;
+    let identifiers = expand_repo_shortcut(repo_identifier, &config.shortcuts)?;
+    resolve_identifiers(client, &identifiers).await
+}
+
+async fn resolve_identifiers(
+    client: &ApiClient,
+    identifiers: &[String],
+) -> Result<Vec<(String, RepoId)>> {
+    resolve_with_repo_cache(client, |repos| {
+        identifiers
+            .iter()
+            .map(|identifier| {
+                let id = resolve_repo_id_from_repos(repos, identifier)?;
+                Ok((identifier.clone(), id))
+            })
+            .collect()
+    })
+    .await
+}
+
+/// `resolve_repo_ids`, also running `prefetch` with the repo's ID when
+/// that saves a round trip: with a cold repo cache, an `owner/repo`
+/// identifier is looked up in the first page of repos, and once found
+/// `prefetch` runs while the remaining pages are fetched for the cache.
+/// Otherwise `prefetch` doesn't run and the result holds `None`.
+pub async fn resolve_repo_ids_prefetching<T, Fut>(
+    client: &ApiClient,
+    repo_identifier: &str,
+    prefetch: impl FnOnce(RepoId) -> Fut,
+) -> Result<(Vec<(String, RepoId)>, Option<T>)>
+where
+    Fut: Future<Output = T>,
+{
+    let config = load_config()?;
+    let identifiers = expand_repo_shortcut(repo_identifier, &config.shortcuts)?;
+    let Some(identifier) = prefetch_candidate(&identifiers, read_repo_cache(client).is_some())
+    else {
+        return Ok((resolve_identifiers(client, &identifiers).await?, None));
+    };
+
+    let first = fetch_repo_page(client, 0).await?;
+    let (id, prefetched) = prefetch_from_first_page(
+        identifier,
+        first,
+        |first| fetch_and_cache_repos(client, Some(first)),
+        prefetch,
+    )
+    .await?;
+    Ok((vec![(identifier.clone(), id)], prefetched))
+}
+
+/// The identifier worth prefetching for: a lone `owner/repo` when the repo
+/// cache is cold. A warm cache resolves without a request, and a bare name
+/// can't be matched until every page is in, since it may be ambiguous.
+fn prefetch_candidate(identifiers: &[String], cache_warm: bool) -> Option<&String> {
+    match identifiers {
+        [identifier] if identifier.contains('/') && !cache_warm => Some(identifier),
+        _ => None,
+    }
+}
+
+/// Resolve `identifier` starting from the `first` page of repos. When it's
+/// there, `prefetch` runs alongside `fetch_rest`; otherwise the rest of the
+/// list is fetched first and `prefetch` doesn't run.
+async fn prefetch_from_first_page<T, Fut, RestFut>(
+    identifier: &str,
+    first: ReposResponse,
+    fetch_rest: impl FnOnce(ReposResponse) -> RestFut,
+    prefetch: impl FnOnce(RepoId) -> Fut,
+) -> Result<(RepoId, Option<T>)>
+where
+    Fut: Future<Output = T>,
+    RestFut: Future<Output = Result<Vec<Repo>>>,
+{
+    match resolve_repo_id_from_repos(&first.repos, identifier) {
+        Ok(id) => {
+            // Only the cache needs the rest of the list now, so failing to
+            // fetch it doesn't fail the command.
+            let (_, prefetched) = tokio::join!(fetch_rest(first), prefetch(id.clone()));
+            Ok((id, Some(prefetched)))
+        }
+        Err(_) => {
+            let repos = fetch_rest(first).await?;
+            let id = resolve_repo_id_from_repos(&repos, identifier)?;
+            Ok((id, None))
+        }
+    }
+}
+
+/// Resolve owner/repo, repo name, or a single-repo `@name` shortcut to a repo
+/// ID, searching across all accessible repos.
 pub async fn resolve_repo_id(client: &ApiClient, repo_identifier: &str) -> Result<RepoId> {
-    let repos = fetch_all_repos(client).await?;
-    resolve_repo_id_from_repos(&repos, repo_identifier)
+    let config = load_config()?;
+    let identifier = single_repo_identifier(repo_identifier, &config.shortcuts)?;
+    resolve_with_repo_cache(client, |repos| {
+        resolve_repo_id_from_repos(repos, &identifier)
+    })
+    .await
+}
+
+/// A repository that failed during a multi-repo operation.
+pub struct RepoFailure {
+    pub repo: String,
+    pub error: anyhow::Error,
+}
+
+/// Render failures as an indented `  - repo: error` list.
+pub fn format_repo_failures(failures: &[RepoFailure]) -> String {
+    failures
+        .iter()
+        .map(|f| format!("  - {}: {:#}", f.repo, f.error))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Report per-repo failures after partial results have been printed.
+///
+/// The failure list goes to stderr so `--format json` output stays valid,
+/// and a partial `Failure` is returned so the exit code reflects it without
+/// a JSON error document following the results.
+pub fn report_repo_failures(failures: &[RepoFailure], total: usize) -> Result<()> {
+    if failures.is_empty() {
+        return Ok(());
+    }
+    let term = Term::stderr();
+    term.write_line("")?;
+    term.write_line(&format!(
+        "{}",
+        style(format!("Failed repositories ({}):", failures.len())).red()
+    ))?;
+    term.write_line(&format_repo_failures(failures))?;
+    bail!(Failure::partial(format!(
+        "{} of {total} repositories failed",
+        failures.len()
+    )))
+}
+
+/// Expand `identifier` and require that it names exactly one repository —
+/// for commands that operate on a single repo.
+fn single_repo_identifier(
+    identifier: &str,
+    shortcuts: &BTreeMap<String, RepoShortcut>,
+) -> Result<String> {
+    let mut expanded = expand_repo_shortcut(identifier, shortcuts)?;
+    match expanded.pop() {
+        Some(only) if expanded.is_empty() => Ok(only),
+        Some(_) | None => bail!(Failure::usage(format!(
+            "Repo shortcut '{}' expands to {} repositories, but this command takes a single repository.",
+            identifier.trim(),
+            expanded.len() + 1
+        ))),
+    }
 }
 
 pub fn resolve_repo_id_from_repos(repos: &[Repo], repo_identifier: &str) -> Result<RepoId> {
@@ -90,9 +348,12 @@ pub fn resolve_repo_id_from_repos(repos: &[Repo], repo_identifier: &str) -> Resu
             .iter()
             .find(|r| r.full_name == normalized)
             .map(|r| r.id.clone())
-            .context(format!(
-                "Repository '{normalized}' not found. Make sure you have access to this repository."
-            ))
+            .ok_or_else(|| {
+                Failure::not_found(format!(
+                    "Repository '{normalized}' not found. Make sure you have access to this repository."
+                ))
+                .into()
+            })
     } else {
         match_repo_by_name(identifier, repos)
     }
@@ -100,8 +361,11 @@ pub fn resolve_repo_id_from_repos(repos: &[Repo], repo_identifier: &str) -> Resu
 
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
+
     use super::*;
     use crate::api::types::Repo;
+    use crate::exit;
 
     fn sample_repos() -> Vec<Repo> {
         vec![
@@ -126,6 +390,103 @@ mod tests {
         ]
     }
 
+    // ── repo cache ───────────────────────────────────────────────────
+
+    fn cache_json(scope: &str, fetched_at: i64) -> String {
+        serde_json::to_string(&CachedRepos {
+            scope: scope.into(),
+            fetched_at,
+            repos: sample_repos(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn fresh_cache_for_same_token_is_used() {
+        let repos = fresh_cached_repos(&cache_json("tok", 1_000), "tok", 61_000).unwrap();
+        assert_eq!(repos.len(), 3);
+    }
+
+    #[test]
+    fn stale_or_foreign_cache_is_ignored() {
+        let ttl = i64::try_from(REPO_CACHE_TTL.as_millis()).unwrap();
+        assert!(fresh_cached_repos(&cache_json("tok", 0), "tok", ttl).is_none());
+        assert!(fresh_cached_repos(&cache_json("other", 0), "tok", 1).is_none());
+        // A clock that went backwards doesn't make the cache fresh forever.
+        assert!(fresh_cached_repos(&cache_json("tok", 10_000), "tok", 0).is_none());
+        assert!(fresh_cached_repos("not json", "tok", 0).is_none());
+    }
+
+    // ── prefetching ─────────────────────────────────────────────────
+
+    fn first_page(repos: Vec<Repo>) -> ReposResponse {
+        serde_json::from_value(serde_json::json!({
+            "repos": repos,
+            "total": 3,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn prefetch_candidate_is_a_lone_owner_repo_with_a_cold_cache() {
+        let one = vec!["usedetail/cli".to_string()];
+        assert_eq!(prefetch_candidate(&one, false), Some(&one[0]));
+        // Warm cache: resolution needs no request, so nothing to overlap.
+        assert_eq!(prefetch_candidate(&one, true), None);
+        // Bare names may be ambiguous until every page is in.
+        assert_eq!(prefetch_candidate(&["cli".to_string()], false), None);
+        let many = vec!["usedetail/cli".to_string(), "acme/cli".to_string()];
+        assert_eq!(prefetch_candidate(&many, false), None);
+    }
+
+    #[tokio::test]
+    async fn prefetches_when_the_repo_is_on_the_first_page() {
+        let rest_fetched = &Cell::new(false);
+        let (id, prefetched) = prefetch_from_first_page(
+            "usedetail/cli",
+            first_page(sample_repos()[..1].to_vec()),
+            |_| async move {
+                rest_fetched.set(true);
+                Ok(sample_repos())
+            },
+            |id| async move { format!("bugs of {id}") },
+        )
+        .await
+        .unwrap();
+        assert_eq!(id.to_string(), "repo_1");
+        assert_eq!(prefetched.as_deref(), Some("bugs of repo_1"));
+        assert!(rest_fetched.get(), "the cache still gets the full list");
+    }
+
+    #[tokio::test]
+    async fn refetches_without_prefetch_when_the_repo_is_not_on_the_first_page() {
+        let prefetched = &Cell::new(false);
+        let (id, result) = prefetch_from_first_page(
+            "usedetail/web",
+            first_page(sample_repos()[..1].to_vec()),
+            |_| async { Ok(sample_repos()) },
+            |_| async move { prefetched.set(true) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(id.to_string(), "repo_3");
+        assert!(result.is_none());
+        assert!(!prefetched.get());
+    }
+
+    #[tokio::test]
+    async fn missing_repo_fails_after_the_full_refetch() {
+        let err = prefetch_from_first_page(
+            "usedetail/missing",
+            first_page(sample_repos()[..1].to_vec()),
+            |_| async { Ok(sample_repos()) },
+            |_| async {},
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("not found"));
+    }
+
     // ── validate_owner_repo_format ───────────────────────────────────
 
     #[test]
@@ -207,6 +568,112 @@ mod tests {
         assert!(err.to_string().contains("not found"));
     }
 
+    // ── expand_repo_shortcut ────────────────────────────────────────
+
+    fn sample_shortcuts() -> BTreeMap<String, RepoShortcut> {
+        BTreeMap::from([
+            (
+                "core".to_string(),
+                RepoShortcut::One("usedetail/cli".into()),
+            ),
+            (
+                "frontend".to_string(),
+                RepoShortcut::Many(vec!["usedetail/web".into(), "acme/cli".into()]),
+            ),
+            ("empty".to_string(), RepoShortcut::Many(vec![])),
+        ])
+    }
+
+    #[test]
+    fn expand_plain_identifier_passes_through() {
+        let expanded = expand_repo_shortcut(" usedetail/cli ", &sample_shortcuts()).unwrap();
+        assert_eq!(expanded, vec!["usedetail/cli"]);
+    }
+
+    #[test]
+    fn expand_single_repo_shortcut() {
+        let expanded = expand_repo_shortcut("@core", &sample_shortcuts()).unwrap();
+        assert_eq!(expanded, vec!["usedetail/cli"]);
+    }
+
+    #[test]
+    fn expand_multi_repo_shortcut_preserves_order() {
+        let expanded = expand_repo_shortcut("@frontend", &sample_shortcuts()).unwrap();
+        assert_eq!(expanded, vec!["usedetail/web", "acme/cli"]);
+    }
+
+    #[test]
+    fn expand_unknown_shortcut_lists_known_names() {
+        let err = expand_repo_shortcut("@nope", &sample_shortcuts()).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("Unknown repo shortcut '@nope'"), "got: {msg}");
+        assert!(msg.contains("@core, @empty, @frontend"), "got: {msg}");
+    }
+
+    #[test]
+    fn expand_unknown_shortcut_without_config_points_at_config() {
+        let err = expand_repo_shortcut("@nope", &BTreeMap::new()).unwrap_err();
+        assert!(err.to_string().contains("[shortcuts]"));
+    }
+
+    #[test]
+    fn expand_empty_shortcut_errors() {
+        let err = expand_repo_shortcut("@empty", &sample_shortcuts()).unwrap_err();
+        assert!(err.to_string().contains("does not map to any repositories"));
+    }
+
+    // ── single_repo_identifier ──────────────────────────────────────
+
+    #[test]
+    fn single_repo_accepts_single_repo_shortcut() {
+        let id = single_repo_identifier("@core", &sample_shortcuts()).unwrap();
+        assert_eq!(id, "usedetail/cli");
+    }
+
+    #[test]
+    fn single_repo_rejects_multi_repo_shortcut() {
+        let err = single_repo_identifier("@frontend", &sample_shortcuts()).unwrap_err();
+        assert!(err.to_string().contains("expands to 2 repositories"));
+    }
+
+    // ── format_repo_failures ────────────────────────────────────────
+
+    #[test]
+    fn format_repo_failures_lists_each_repo_with_error_chain() {
+        let failures = vec![
+            RepoFailure {
+                repo: "acme/api".into(),
+                error: anyhow::anyhow!("API error: 500 Internal Server Error")
+                    .context("Failed to fetch bugs"),
+            },
+            RepoFailure {
+                repo: "acme/web".into(),
+                error: anyhow::anyhow!("timed out"),
+            },
+        ];
+        assert_eq!(
+            format_repo_failures(&failures),
+            "  - acme/api: Failed to fetch bugs: API error: 500 Internal Server Error\n  - acme/web: timed out"
+        );
+    }
+
+    #[test]
+    fn report_repo_failures_is_ok_when_nothing_failed() {
+        assert!(report_repo_failures(&[], 3).is_ok());
+    }
+
+    #[test]
+    fn report_repo_failures_is_an_outcome_so_json_output_stays_one_document() {
+        let failures = vec![RepoFailure {
+            repo: "acme/api".into(),
+            error: anyhow::anyhow!("timed out"),
+        }];
+        let err = report_repo_failures(&failures, 2).unwrap_err();
+        assert_eq!(err.to_string(), "1 of 2 repositories failed");
+        assert!(exit::is_outcome(&err));
+        assert_eq!(exit::code(&err), exit::API_ERROR);
+    }
+
     // ── resolve_repo_id_from_repos ──────────────────────────────────
 
     #[test]
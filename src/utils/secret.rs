@@ -0,0 +1,163 @@
+//! Hidden entry of secrets (API tokens, signing secrets) at a terminal.
+//!
+//! Input is read key by key in raw mode so Ctrl-C and Ctrl-D can be told
+//! apart from text, and the terminal is restored by a drop guard however
+//! entry ends. Terminals crossterm can't drive fall back to reading a line
+//! with echo turned off.
+
+use std::io;
+
+use anyhow::{bail, Context, Result};
+use console::Term;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+
+/// Raw mode for as long as this lives. Restoring on drop covers early
+/// returns and panics as well as the normal path.
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        enable_raw_mode()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+    }
+}
+
+/// What a key press does to the entry.
+#[derive(Debug, PartialEq, Eq)]
+enum Entry {
+    Continue,
+    Submit,
+    Cancel,
+}
+
+/// Apply `key` to `buffer`. Ctrl-C cancels; Ctrl-D cancels an empty entry
+/// and submits otherwise, like end of input; Ctrl-U clears the line.
+fn apply_key(buffer: &mut String, key: KeyEvent) -> Entry {
+    if key.kind == KeyEventKind::Release {
+        return Entry::Continue;
+    }
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Char('c') if ctrl => Entry::Cancel,
+        KeyCode::Char('d') if ctrl && buffer.is_empty() => Entry::Cancel,
+        KeyCode::Char('d') if ctrl => Entry::Submit,
+        KeyCode::Char('u') if ctrl => {
+            buffer.clear();
+            Entry::Continue
+        }
+        KeyCode::Enter => Entry::Submit,
+        KeyCode::Backspace => {
+            buffer.pop();
+            Entry::Continue
+        }
+        KeyCode::Char(c) if !ctrl => {
+            buffer.push(c);
+            Entry::Continue
+        }
+        _ => Entry::Continue,
+    }
+}
+
+/// Read keys into a line until it's submitted (`Some`) or cancelled
+/// (`None`). Errors, such as a terminal without raw mode, are returned for
+/// the caller to fall back on.
+fn read_keys() -> io::Result<Option<String>> {
+    let _raw = RawMode::enable()?;
+    let mut buffer = String::new();
+    loop {
+        match event::read()? {
+            Event::Key(key) => match apply_key(&mut buffer, key) {
+                Entry::Continue => {}
+                Entry::Submit => return Ok(Some(buffer)),
+                Entry::Cancel => return Ok(None),
+            },
+            Event::Paste(text) => buffer.push_str(&text),
+            _ => {}
+        }
+    }
+}
+
+/// Prompt on stderr with `prompt` and read a line without echoing it.
+pub fn read_hidden_line(prompt: &str) -> Result<String> {
+    let term = Term::stderr();
+    term.write_str(prompt)?;
+    let Ok(entered) = read_keys() else {
+        // No raw mode or key events here; echo-off line input still keeps
+        // the secret off the screen.
+        return term
+            .read_secure_line()
+            .context("Failed to read from the terminal");
+    };
+    // Raw mode is off again; finish the prompt line.
+    term.write_line("")?;
+    match entered {
+        Some(line) => Ok(line),
+        None => bail!("Cancelled"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(buffer: &mut String, code: KeyCode, modifiers: KeyModifiers) -> Entry {
+        apply_key(buffer, KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn typing_and_backspace_edit_the_line() {
+        let mut buffer = String::new();
+        for c in "dtl_x".chars() {
+            assert_eq!(
+                press(&mut buffer, KeyCode::Char(c), KeyModifiers::NONE),
+                Entry::Continue
+            );
+        }
+        press(&mut buffer, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(buffer, "dtl_");
+        assert_eq!(
+            press(&mut buffer, KeyCode::Char('X'), KeyModifiers::SHIFT),
+            Entry::Continue
+        );
+        assert_eq!(buffer, "dtl_X");
+        assert_eq!(
+            press(&mut buffer, KeyCode::Enter, KeyModifiers::NONE),
+            Entry::Submit
+        );
+    }
+
+    #[test]
+    fn control_keys_cancel_submit_or_clear() {
+        let mut buffer = String::from("dtl_");
+        assert_eq!(
+            press(&mut buffer, KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Entry::Cancel
+        );
+        assert_eq!(
+            press(&mut buffer, KeyCode::Char('d'), KeyModifiers::CONTROL),
+            Entry::Submit
+        );
+        press(&mut buffer, KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert!(buffer.is_empty());
+        assert_eq!(
+            press(&mut buffer, KeyCode::Char('d'), KeyModifiers::CONTROL),
+            Entry::Cancel
+        );
+    }
+
+    #[test]
+    fn key_releases_are_ignored() {
+        let mut buffer = String::new();
+        let mut key = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE);
+        key.kind = KeyEventKind::Release;
+        assert_eq!(apply_key(&mut buffer, key), Entry::Continue);
+        assert!(buffer.is_empty());
+    }
+}
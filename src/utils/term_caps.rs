@@ -0,0 +1,267 @@
+//! What the terminal on stdout can show, worked out once per run from the
+//! environment and the `--ascii`, `--no-color` and `--width` flags. Renderers
+//! ask `get()` instead of probing `Term::stdout()` themselves, so the flags
+//! apply to every command the same way.
+
+use std::env;
+use std::sync::OnceLock;
+
+use console::Term;
+
+/// Columns assumed for rules and wrapping when stdout isn't a terminal and
+/// `--width` isn't given.
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// How many colors the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+/// Flags that override what detection finds.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Overrides {
+    /// `--ascii`: draw with plain ASCII only.
+    pub ascii: bool,
+    /// `--no-color`: no colors or text styles.
+    pub no_color: bool,
+    /// `--width`: lay out for this many columns.
+    pub width: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TermCaps {
+    pub color: ColorDepth,
+    /// Box-drawing and block characters render correctly.
+    pub unicode: bool,
+    /// OSC 8 hyperlinks are understood rather than printed as noise.
+    pub hyperlinks: bool,
+    /// Inline images (kitty or iTerm2 protocol) are supported.
+    pub images: bool,
+    /// Columns to lay out for; `None` when piped without `--width`, so
+    /// output isn't cut to a width nobody sees.
+    pub width: Option<usize>,
+}
+
+/// `TERM_PROGRAM` values of terminals known to handle OSC 8 links.
+const HYPERLINK_PROGRAMS: [&str; 4] = ["iTerm.app", "WezTerm", "vscode", "ghostty"];
+
+/// `TERM_PROGRAM` values of terminals known to show inline images.
+const IMAGE_PROGRAMS: [&str; 3] = ["iTerm.app", "WezTerm", "ghostty"];
+
+/// First VTE release with OSC 8 support (0.50), as `VTE_VERSION` spells it.
+const VTE_HYPERLINKS_SINCE: u32 = 5000;
+
+impl TermCaps {
+    /// Work out capabilities from whether stdout is a terminal, its width,
+    /// and environment variables read through `var`.
+    pub fn detect(
+        is_term: bool,
+        term_width: usize,
+        var: impl Fn(&str) -> Option<String>,
+        overrides: Overrides,
+    ) -> Self {
+        let var = |name: &str| var(name).filter(|value| !value.is_empty());
+        let term = var("TERM").unwrap_or_default();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+        let dumb = term == "dumb";
+        let kitty = term == "xterm-kitty";
+
+        let color = if overrides.no_color || var("NO_COLOR").is_some() || !is_term || dumb {
+            ColorDepth::None
+        } else if var("COLORTERM").is_some_and(|v| v == "truecolor" || v == "24bit") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        };
+
+        // The first locale variable that's set wins, as in the C library.
+        // With none set, assume a modern terminal.
+        let unicode = !overrides.ascii
+            && !dumb
+            && ["LC_ALL", "LC_CTYPE", "LANG"]
+                .into_iter()
+                .find_map(&var)
+                .is_none_or(|locale| {
+                    let locale = locale.to_ascii_lowercase();
+                    locale.contains("utf-8") || locale.contains("utf8")
+                });
+
+        let hyperlinks = is_term
+            && !dumb
+            && (kitty
+                || HYPERLINK_PROGRAMS.contains(&program.as_str())
+                || var("WT_SESSION").is_some()
+                || var("VTE_VERSION")
+                    .and_then(|v| v.parse::<u32>().ok())
+                    .is_some_and(|v| v >= VTE_HYPERLINKS_SINCE));
+
+        let images = is_term && (kitty || IMAGE_PROGRAMS.contains(&program.as_str()));
+
+        Self {
+            color,
+            unicode,
+            hyperlinks,
+            images,
+            width: overrides.width.or_else(|| is_term.then_some(term_width)),
+        }
+    }
+
+    /// Columns for full-width elements such as section rules.
+    pub const fn layout_width(&self) -> usize {
+        match self.width {
+            Some(width) => width,
+            None => DEFAULT_WIDTH,
+        }
+    }
+
+    /// A horizontal rule across the layout width.
+    pub fn rule(&self) -> String {
+        let line = if self.unicode { "─" } else { "-" };
+        line.repeat(self.layout_width())
+    }
+
+    /// `text` linked to `url` where the terminal supports it, plain `text`
+    /// otherwise.
+    pub fn link(&self, text: &str, url: &str) -> String {
+        if self.hyperlinks {
+            format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+static CAPS: OnceLock<TermCaps> = OnceLock::new();
+
+fn detect_stdout(overrides: Overrides) -> TermCaps {
+    let term = Term::stdout();
+    TermCaps::detect(
+        term.is_term(),
+        usize::from(term.size().1),
+        |name| env::var(name).ok(),
+        overrides,
+    )
+}
+
+/// Detect capabilities with `overrides` applied. Only the first call counts,
+/// so call it before anything is printed.
+pub fn init(overrides: Overrides) {
+    let caps = CAPS.get_or_init(|| detect_stdout(overrides));
+    if caps.color == ColorDepth::None {
+        console::set_colors_enabled(false);
+    }
+    if overrides.no_color || env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        console::set_colors_enabled_stderr(false);
+    }
+}
+
+/// The capabilities for this run, detected without overrides if `init`
+/// wasn't called.
+pub fn get() -> &'static TermCaps {
+    CAPS.get_or_init(|| detect_stdout(Overrides::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(is_term: bool, vars: &[(&str, &str)], overrides: Overrides) -> TermCaps {
+        TermCaps::detect(
+            is_term,
+            120,
+            |name| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| (*value).to_string())
+            },
+            overrides,
+        )
+    }
+
+    #[test]
+    fn color_depth_follows_colorterm_and_term() {
+        let none = Overrides::default();
+        let truecolor = detect(true, &[("COLORTERM", "truecolor")], none);
+        assert_eq!(truecolor.color, ColorDepth::TrueColor);
+        let ansi256 = detect(true, &[("TERM", "xterm-256color")], none);
+        assert_eq!(ansi256.color, ColorDepth::Ansi256);
+        assert_eq!(detect(true, &[], none).color, ColorDepth::Ansi16);
+    }
+
+    #[test]
+    fn color_is_off_when_piped_dumb_or_disabled() {
+        let none = Overrides::default();
+        assert_eq!(detect(false, &[], none).color, ColorDepth::None);
+        let dumb = detect(true, &[("TERM", "dumb")], none);
+        assert_eq!(dumb.color, ColorDepth::None);
+        let no_color = detect(true, &[("NO_COLOR", "1")], none);
+        assert_eq!(no_color.color, ColorDepth::None);
+        let flag = Overrides {
+            no_color: true,
+            ..Overrides::default()
+        };
+        assert_eq!(detect(true, &[], flag).color, ColorDepth::None);
+    }
+
+    #[test]
+    fn unicode_follows_the_first_locale_set() {
+        let none = Overrides::default();
+        assert!(detect(true, &[], none).unicode);
+        assert!(detect(true, &[("LANG", "en_US.UTF-8")], none).unicode);
+        assert!(!detect(true, &[("LANG", "C")], none).unicode);
+        let vars = [("LC_ALL", "POSIX"), ("LANG", "en_US.UTF-8")];
+        assert!(!detect(true, &vars, none).unicode);
+    }
+
+    #[test]
+    fn ascii_flag_turns_off_unicode() {
+        let ascii = Overrides {
+            ascii: true,
+            ..Overrides::default()
+        };
+        let caps = detect(true, &[("LANG", "en_US.UTF-8")], ascii);
+        assert!(!caps.unicode);
+        assert_eq!(caps.rule(), "-".repeat(120));
+    }
+
+    #[test]
+    fn hyperlinks_only_in_known_terminals() {
+        let none = Overrides::default();
+        let iterm = [("TERM_PROGRAM", "iTerm.app")];
+        assert!(detect(true, &iterm, none).hyperlinks);
+        assert!(!detect(false, &iterm, none).hyperlinks);
+        assert!(detect(true, &[("VTE_VERSION", "6003")], none).hyperlinks);
+        assert!(!detect(true, &[("VTE_VERSION", "4200")], none).hyperlinks);
+        assert!(!detect(true, &[], none).hyperlinks);
+    }
+
+    #[test]
+    fn link_is_plain_text_without_hyperlink_support() {
+        let caps = detect(true, &[], Overrides::default());
+        assert_eq!(caps.link("ENG-1", "https://x.test/1"), "ENG-1");
+        let caps = detect(true, &[("TERM", "xterm-kitty")], Overrides::default());
+        assert_eq!(
+            caps.link("ENG-1", "https://x.test/1"),
+            "\x1b]8;;https://x.test/1\x1b\\ENG-1\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn width_is_unset_when_piped_unless_given() {
+        let none = Overrides::default();
+        assert_eq!(detect(true, &[], none).width, Some(120));
+        assert_eq!(detect(false, &[], none).width, None);
+        assert_eq!(detect(false, &[], none).layout_width(), DEFAULT_WIDTH);
+        let width = Overrides {
+            width: Some(60),
+            ..Overrides::default()
+        };
+        assert_eq!(detect(false, &[], width).width, Some(60));
+    }
+}
@@ -0,0 +1,61 @@
+//! Width-aware text helpers for terminal rendering.
+
+use unicode_segmentation::UnicodeSegmentation as _;
+use unicode_width::UnicodeWidthStr as _;
+
+const ELLIPSIS: &str = "…";
+
+/// Shorten `s` to at most `width` terminal columns, ending in `…` when
+/// anything was cut. Cuts only between grapheme clusters, so multi-byte and
+/// double-width characters (CJK, emoji, combining marks) are never split.
+pub fn truncate_display(s: &str, width: usize) -> String {
+    if s.width() <= width {
+        return s.to_string();
+    }
+    // Leave a column for the ellipsis.
+    let budget = width.saturating_sub(ELLIPSIS.width());
+    let mut out = String::new();
+    let mut used = 0;
+    for grapheme in s.graphemes(true) {
+        let grapheme_width = grapheme.width();
+        if used + grapheme_width > budget {
+            break;
+        }
+        out.push_str(grapheme);
+        used += grapheme_width;
+    }
+    if width > 0 {
+        out.push_str(ELLIPSIS);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_strings_are_unchanged() {
+        assert_eq!(truncate_display("Null deref", 10), "Null deref");
+        assert_eq!(truncate_display("", 0), "");
+    }
+
+    #[test]
+    fn long_strings_end_in_an_ellipsis_within_width() {
+        assert_eq!(
+            truncate_display("Null pointer dereference", 10),
+            "Null poin…"
+        );
+        assert_eq!(truncate_display("abc", 1), "…");
+        assert_eq!(truncate_display("abc", 0), "");
+    }
+
+    #[test]
+    fn wide_and_combining_characters_are_not_split() {
+        // Each CJK character is two columns wide.
+        assert_eq!(truncate_display("日本語のタイトル", 7), "日本語…");
+        // "é" as e + combining acute accent stays one grapheme.
+        assert_eq!(truncate_display("cafe\u{301} crème", 5), "cafe\u{301}…");
+        assert_eq!(truncate_display("🦀🦀🦀", 4), "🦀…");
+    }
+}
@@ -0,0 +1,45 @@
+//! Tests for the crate used as a library, outside the `detail` binary.
+//!
+//! These change the process environment, so they live in their own test
+//! binary rather than next to the CLI tests, which pass it on to `detail`.
+
+#![cfg_attr(
+    test,
+    allow(
+        clippy::unwrap_used,
+        clippy::tests_outside_test_module,
+        reason = "unwrap and top-level #[test] functions are idiomatic in integration tests"
+    )
+)]
+
+use std::env;
+use std::fs;
+use std::process;
+
+#[test]
+fn create_client_uses_the_profile_from_detail_profile() {
+    let config_dir = env::temp_dir().join(format!("detail-library-{}", process::id()));
+    let _ = fs::remove_dir_all(&config_dir);
+    fs::create_dir_all(config_dir.join("detail-cli")).unwrap();
+    // Only the top-level login has a token, so a client can be created only
+    // if the profile is ignored.
+    fs::write(
+        config_dir.join("detail-cli").join("config.toml"),
+        "api_token = \"dtl_live_default\"\n\n[profiles.work]\n",
+    )
+    .unwrap();
+    env::set_var("XDG_CONFIG_HOME", &config_dir);
+    env::remove_var("DETAIL_API_TOKEN");
+    env::set_var("DETAIL_PROFILE", "work");
+
+    let err = detail_cli::create_client().err();
+    let _ = fs::remove_dir_all(&config_dir);
+
+    let message = err.map(|err| format!("{err:#}"));
+    assert!(
+        message
+            .as_deref()
+            .is_some_and(|message| message.contains("profile 'work'")),
+        "expected a missing-token error for profile 'work', got {message:?}"
+    );
+}